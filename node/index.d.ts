@@ -14,7 +14,7 @@ export interface TransformOptions {
   /** Whether to enable various draft syntax. */
   drafts?: Drafts,
   /** Whether to compile this file as a CSS module. */
-  cssModules?: boolean,
+  cssModules?: boolean | CSSModulesConfig,
   /**
    * Whether to analyze dependencies (e.g. `@import` and `url()`).
    * When enabled, `@import` rules are removed, and `url()` dependencies
@@ -32,10 +32,40 @@ export interface TransformOptions {
    * to be unused. These will be removed during minification. Note that these are not
    * selectors but individual names (without any . or # prefixes).
    */
-  unusedSymbols?: string[]
+  unusedSymbols?: string[],
+  /**
+   * Whether to ignore invalid rules and declarations rather than erroring.
+   * When enabled, warnings are returned, and the invalid rule or declaration is
+   * omitted from the output code.
+   */
+  errorRecovery?: boolean,
+  /**
+   * Whether to return the style sheet AST as JSON. The AST reflects the style sheet
+   * after minification, and uses the same structure as the Rust `StyleSheet` type.
+   */
+  ast?: boolean
 }
 
-export type BundleOptions = Omit<TransformOptions, 'code'>;
+export interface BundleOptions extends Omit<TransformOptions, 'code'> {
+  /** Whether to return the import graph of the bundle. */
+  graph?: boolean
+}
+
+export interface BundleAsyncOptions extends BundleOptions {
+  /** Custom functions to read and resolve files. By default, files are read from the file system. */
+  resolver?: Resolver
+}
+
+export interface Resolver {
+  /** Reads the contents of the given file path. */
+  read?: (filePath: string) => string | Promise<string>,
+  /**
+   * Resolves an `@import` or `composes` specifier to a file path, relative to the file it was referenced from.
+   * By default, relative paths and packages in `node_modules` are supported. When a custom `read` function
+   * is provided, only relative paths are supported by default.
+   */
+  resolve?: (specifier: string, originatingFile: string) => string | Promise<string>
+}
 
 export interface Drafts {
   /** Whether to enable CSS nesting. */
@@ -44,6 +74,24 @@ export interface Drafts {
   customMedia?: boolean
 }
 
+export interface CSSModulesConfig {
+  /**
+   * The pattern to use when renaming class names and other identifiers, e.g. `[name]__[local]--[hash:5]`.
+   * Supported placeholders are `[name]`, `[local]`, `[hash]`, and `[content-hash]`. Default is `[hash]_[local]`.
+   */
+  pattern?: string,
+  /**
+   * Whether to scope dashed identifiers, e.g. CSS variables, `@property` rules, and `@custom-media` names.
+   * Names declared in other files may be referenced with `var(--foo from "file.css")`, or `var(--foo from global)`.
+   */
+  dashedIdents?: boolean,
+  /**
+   * Whether to require that every selector contains at least one local class name or id.
+   * Selectors that only reference global names, e.g. `div > a`, result in an error.
+   */
+  pure?: boolean
+}
+
 export interface PseudoClasses {
   hover?: string,
   active?: string,
@@ -59,8 +107,72 @@ export interface TransformResult {
   map: Buffer | void,
   /** CSS module exports, if enabled. */
   exports: CSSModuleExports | void,
+  /** CSS module references to other files, e.g. via `var(--foo from "file.css")`, if enabled. */
+  references: CSSModuleReferences | void,
   /** `@import` and `url()` dependencies, if enabled. */
-  dependencies: Dependency[] | void
+  dependencies: Dependency[] | void,
+  /** Warnings that occurred during compilation. */
+  warnings: Warning[],
+  /** The style sheet AST, if enabled. */
+  ast: object | void,
+  /** The import graph of the bundle, if enabled. */
+  graph: BundleGraph | void
+}
+
+export interface BundleGraph {
+  /** The files included in the bundle. The entry is always the first file. */
+  files: BundleGraphFile[],
+  /** The `@import` rules and `composes` references between files. */
+  edges: BundleGraphEdge[],
+  /** Indexes of the files in the order they are concatenated in the bundle. */
+  order: number[]
+}
+
+export interface BundleGraphFile {
+  /** The path of the file. */
+  path: string,
+  /** The conditions the rules of the file are wrapped in, combined from all `@import` rules leading to it. */
+  conditions: ImportConditions
+}
+
+export interface BundleGraphEdge {
+  /** The index of the file containing the reference. */
+  from: number,
+  /** The index of the referenced file. */
+  to: number,
+  /** The kind of reference. */
+  kind: 'import' | 'composes' | 'value',
+  /** The specifier of the referenced file, as written in the source. */
+  specifier: string,
+  /** The conditions of the `@import` rule. */
+  conditions: ImportConditions,
+  /** The location of the reference. */
+  loc: ErrorLocation
+}
+
+export interface ImportConditions {
+  /** The cascade layer name, or an empty string for an anonymous layer. */
+  layer: string | null,
+  /** A media query. */
+  media: string | null,
+  /** A `supports()` condition. */
+  supports: string | null
+}
+
+export interface Warning {
+  /** The warning message. */
+  message: string,
+  /** The location where the warning occurred, if available. */
+  loc: ErrorLocation | null
+}
+
+export interface ErrorLocation {
+  /** The filename in which the warning occurred. */
+  filename: string,
+  /** The line number, starting from 0. */
+  line: number,
+  /** The column number, starting from 1. */
+  column: number
 }
 
 export type CSSModuleExports = {
@@ -77,6 +189,11 @@ export interface CSSModuleExport {
   composes: CSSModuleReference[]
 }
 
+export type CSSModuleReferences = {
+  /** Maps placeholder names to references. */
+  [name: string]: DependencyCSSModuleReference
+};
+
 export type CSSModuleReference = LocalCSSModuleReference | GlobalCSSModuleReference | DependencyCSSModuleReference;
 
 export interface LocalCSSModuleReference {
@@ -168,6 +285,25 @@ export interface TransformAttributeResult {
   dependencies: Dependency[] | void  
 }
 
+export interface PrintAstOptions {
+  /** The style sheet AST to print, as returned by `transform` or `bundle` with the `ast` option enabled. */
+  ast: object,
+  /** Whether to enable minification. */
+  minify?: boolean,
+  /** The browser targets for the generated code. */
+  targets?: Targets,
+  /** Whether to analyze `url()` dependencies. See `TransformOptions.analyzeDependencies`. */
+  analyzeDependencies?: boolean,
+  /** Replaces user action pseudo classes with class names that can be applied from JavaScript. */
+  pseudoClasses?: PseudoClasses
+}
+
+/**
+ * Prints a style sheet AST, e.g. after modifying the `ast` returned by `transform`, back to CSS.
+ * The AST is not minified again, and source maps are not supported.
+ */
+export declare function printAst(options: PrintAstOptions): TransformResult;
+
 /**
  * Compiles a single CSS declaration list, such as an inline style attribute in HTML.
  */
@@ -183,3 +319,9 @@ export declare function browserslistToTargets(browserslist: string[]): Targets;
  * Bundles a CSS file and its dependencies, inlining @import rules.
  */
 export declare function bundle(options: BundleOptions): TransformResult;
+
+/**
+ * Bundles a CSS file and its dependencies asynchronously, inlining @import rules.
+ * Files are read and resolved using the provided resolver functions, if any.
+ */
+export declare function bundleAsync(options: BundleAsyncOptions): Promise<TransformResult>;
@@ -23,3 +23,33 @@ if (process.env.CSS_TRANSFORMER_WASM) {
 }
 
 module.exports.browserslistToTargets = require('./browserslistToTargets');
+
+if (module.exports.bundleAsync) {
+  const native = module.exports;
+  const bundleAsync = native.bundleAsync;
+
+  // Calls the given resolver function, which may return a promise,
+  // and passes the result or error back to the native bundler.
+  const complete = (request, fn) => {
+    Promise.resolve()
+      .then(fn)
+      .then(
+        res => native.completeRequest(request, null, res),
+        err => native.completeRequest(request, String(err && err.message || err), null)
+      );
+  };
+
+  module.exports = Object.assign({}, native, {
+    bundleAsync({resolver = {}, ...options}) {
+      return new Promise((resolve, reject) => {
+        bundleAsync(
+          options,
+          resolver.read && ((_, request, filePath) => complete(request, () => resolver.read(filePath))),
+          resolver.resolve && ((_, request, specifier, originatingFile) => complete(request, () => resolver.resolve(specifier, originatingFile))),
+          (_, err, res) => err ? reject(err) : resolve(res)
+        );
+      });
+    }
+  });
+  delete module.exports.completeRequest;
+}
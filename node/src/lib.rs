@@ -2,18 +2,27 @@
 #[global_allocator]
 static GLOBAL: jemallocator::Jemalloc = jemallocator::Jemalloc;
 
-use parcel_css::bundler::{BundleErrorKind, Bundler, FileProvider, SourceProvider};
-use parcel_css::css_modules::CssModuleExports;
+#[cfg(not(target_arch = "wasm32"))]
+use parcel_css::bundler::{resolve_node_style, AsyncSourceProvider, BoxFuture};
+use parcel_css::bundler::{Bundle, BundleErrorKind, BundleGraph, Bundler, FileProvider, SourceProvider};
+use parcel_css::css_modules::{CssModuleExports, CssModuleReferences, Pattern, PatternParseError};
 use parcel_css::dependencies::Dependency;
 use parcel_css::error::{Error, ErrorLocation, MinifyErrorKind, ParserError, PrinterErrorKind};
 use parcel_css::stylesheet::{
   MinifyOptions, ParserOptions, PrinterOptions, PseudoClasses, StyleAttribute, StyleSheet,
 };
 use parcel_css::targets::Browsers;
+#[cfg(not(target_arch = "wasm32"))]
+use parcel_css::traits::IntoOwned;
 use parcel_sourcemap::SourceMap;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::path::Path;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+  path::PathBuf,
+  sync::{Arc, Mutex},
+};
 
 // ---------------------------------------------
 
@@ -32,6 +41,15 @@ pub fn transform(config_val: JsValue) -> Result<JsValue, JsValue> {
   res.serialize(&serializer).map_err(JsValue::from)
 }
 
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(js_name = "printAst")]
+pub fn print_ast(config_val: JsValue) -> Result<JsValue, JsValue> {
+  let config: PrintConfig = from_value(config_val).map_err(JsValue::from)?;
+  let res = compile_ast(&config)?;
+  let serializer = Serializer::new().serialize_maps_as_objects(true);
+  res.serialize(&serializer).map_err(JsValue::from)
+}
+
 #[cfg(target_arch = "wasm32")]
 #[wasm_bindgen(js_name = "transformStyleAttribute")]
 pub fn transform_style_attribute(config_val: JsValue) -> Result<JsValue, JsValue> {
@@ -45,7 +63,9 @@ pub fn transform_style_attribute(config_val: JsValue) -> Result<JsValue, JsValue
 // ---------------------------------------------
 
 #[cfg(not(target_arch = "wasm32"))]
-use napi::{CallContext, JsObject, JsUnknown};
+use napi::threadsafe_function::{ThreadSafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+#[cfg(not(target_arch = "wasm32"))]
+use napi::{CallContext, Env, JsExternal, JsFunction, JsObject, JsString, JsUndefined, JsUnknown, ValueType};
 #[cfg(not(target_arch = "wasm32"))]
 use napi_derive::{js_function, module_exports};
 
@@ -67,28 +87,52 @@ struct TransformResult {
   #[serde(with = "serde_bytes")]
   map: Option<Vec<u8>>,
   exports: Option<CssModuleExports>,
+  references: Option<CssModuleReferences>,
   dependencies: Option<Vec<Dependency>>,
+  warnings: Vec<Warning>,
+  ast: Option<serde_json::Value>,
+  graph: Option<BundleGraph>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Warning {
+  message: String,
+  loc: Option<ErrorLocation>,
+}
+
+impl<T: std::fmt::Display> From<&Error<T>> for Warning {
+  fn from(e: &Error<T>) -> Warning {
+    Warning {
+      message: e.kind.to_string(),
+      loc: e.loc.clone(),
+    }
+  }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 impl TransformResult {
-  fn into_js(self, ctx: CallContext) -> napi::Result<JsUnknown> {
+  fn into_js(self, env: &Env) -> napi::Result<JsUnknown> {
     // Manually construct buffers so we avoid a copy and work around
     // https://github.com/napi-rs/napi-rs/issues/1124.
-    let mut obj = ctx.env.create_object()?;
-    let buf = ctx.env.create_buffer_with_data(self.code)?;
+    let mut obj = env.create_object()?;
+    let buf = env.create_buffer_with_data(self.code)?;
     obj.set_named_property("code", buf.into_raw())?;
     obj.set_named_property(
       "map",
       if let Some(map) = self.map {
-        let buf = ctx.env.create_buffer_with_data(map)?;
+        let buf = env.create_buffer_with_data(map)?;
         buf.into_raw().into_unknown()
       } else {
-        ctx.env.get_null()?.into_unknown()
+        env.get_null()?.into_unknown()
       },
     )?;
-    obj.set_named_property("exports", ctx.env.to_js_value(&self.exports)?)?;
-    obj.set_named_property("dependencies", ctx.env.to_js_value(&self.dependencies)?)?;
+    obj.set_named_property("exports", env.to_js_value(&self.exports)?)?;
+    obj.set_named_property("references", env.to_js_value(&self.references)?)?;
+    obj.set_named_property("dependencies", env.to_js_value(&self.dependencies)?)?;
+    obj.set_named_property("warnings", env.to_js_value(&self.warnings)?)?;
+    obj.set_named_property("ast", env.to_js_value(&self.ast)?)?;
+    obj.set_named_property("graph", env.to_js_value(&self.graph)?)?;
     Ok(obj.into_unknown())
   }
 }
@@ -102,11 +146,24 @@ fn transform(ctx: CallContext) -> napi::Result<JsUnknown> {
   let res = compile(code, &config);
 
   match res {
-    Ok(res) => res.into_js(ctx),
+    Ok(res) => res.into_js(ctx.env),
     Err(err) => err.throw(ctx, Some(code)),
   }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+#[js_function(1)]
+fn print_ast(ctx: CallContext) -> napi::Result<JsUnknown> {
+  let opts = ctx.get::<JsObject>(0)?;
+  let config: PrintConfig = ctx.env.from_js_value(opts)?;
+  let res = compile_ast(&config);
+
+  match res {
+    Ok(res) => res.into_js(ctx.env),
+    Err(err) => err.throw(ctx, None),
+  }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 #[js_function(1)]
 fn transform_style_attribute(ctx: CallContext) -> napi::Result<JsUnknown> {
@@ -116,7 +173,7 @@ fn transform_style_attribute(ctx: CallContext) -> napi::Result<JsUnknown> {
   let res = compile_attr(code, &config);
 
   match res {
-    Ok(res) => res.into_js(ctx),
+    Ok(res) => res.into_js(ctx.env),
     Err(err) => err.throw(ctx, Some(code)),
   }
 }
@@ -130,7 +187,7 @@ fn bundle(ctx: CallContext) -> napi::Result<JsUnknown> {
   let res = compile_bundle(&fs, &config);
 
   match res {
-    Ok(res) => res.into_js(ctx),
+    Ok(res) => res.into_js(ctx.env),
     Err(err) => {
       let code = match &err {
         CompileError::ParseError(Error {
@@ -151,7 +208,215 @@ fn bundle(ctx: CallContext) -> napi::Result<JsUnknown> {
         }) => Some(fs.read(Path::new(filename))?),
         _ => None,
       };
-      err.throw(ctx, code)
+      err.throw(ctx, code.as_deref())
+    }
+  }
+}
+
+/// Bundles asynchronously on a separate thread, calling the `read` and `resolve` JS functions
+/// (if provided) to load files. The result is passed to the `callback` function once done.
+/// This is wrapped in a promise based API in `index.js`.
+#[cfg(not(target_arch = "wasm32"))]
+#[js_function(4)]
+fn bundle_async(ctx: CallContext) -> napi::Result<JsUndefined> {
+  let opts = ctx.get::<JsObject>(0)?;
+  let config: BundleConfig = ctx.env.from_js_value(opts)?;
+
+  let read = match get_function(&ctx, 1)? {
+    Some(read) => Some(ctx.env.create_threadsafe_function(
+      &read,
+      0,
+      |ctx: ThreadSafeCallContext<(Arc<Request>, String)>| {
+        let (request, file) = ctx.value;
+        Ok(vec![
+          ctx.env.create_external(request, None)?.into_unknown(),
+          ctx.env.create_string_from_std(file)?.into_unknown(),
+        ])
+      },
+    )?),
+    None => None,
+  };
+
+  let resolve = match get_function(&ctx, 2)? {
+    Some(resolve) => Some(ctx.env.create_threadsafe_function(
+      &resolve,
+      0,
+      |ctx: ThreadSafeCallContext<(Arc<Request>, String, String)>| {
+        let (request, specifier, originating_file) = ctx.value;
+        Ok(vec![
+          ctx.env.create_external(request, None)?.into_unknown(),
+          ctx.env.create_string_from_std(specifier)?.into_unknown(),
+          ctx.env.create_string_from_std(originating_file)?.into_unknown(),
+        ])
+      },
+    )?),
+    None => None,
+  };
+
+  let callback = ctx.get::<JsFunction>(3)?;
+  let callback = ctx.env.create_threadsafe_function(
+    &callback,
+    0,
+    |ctx: ThreadSafeCallContext<Result<TransformResult, CompileError<'static>>>| match ctx.value {
+      Ok(res) => Ok(vec![ctx.env.get_null()?.into_unknown(), res.into_js(&ctx.env)?]),
+      Err(err) => Ok(vec![err.into_js_error(&ctx.env, None)?]),
+    },
+  )?;
+
+  std::thread::spawn(move || {
+    let fs = JsSourceProvider { read, resolve };
+    let res = block_on(compile_bundle_async(&fs, &config)).map_err(|err| err.into_owned());
+    callback.call(Ok(res), ThreadsafeFunctionCallMode::Blocking);
+  });
+
+  ctx.env.get_undefined()
+}
+
+/// Completes a pending `read` or `resolve` request of `bundleAsync` with either an error message or a result.
+#[cfg(not(target_arch = "wasm32"))]
+#[js_function(3)]
+fn complete_request(ctx: CallContext) -> napi::Result<JsUndefined> {
+  let external = ctx.get::<JsExternal>(0)?;
+  let request = ctx.env.get_value_external::<Arc<Request>>(&external)?;
+  let result = match get_string(&ctx, 1)? {
+    Some(err) => Err(err),
+    None => match get_string(&ctx, 2)? {
+      Some(result) => Ok(result),
+      None => Err("Expected a string".into()),
+    },
+  };
+  request.complete(result);
+  ctx.env.get_undefined()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn get_function(ctx: &CallContext, index: usize) -> napi::Result<Option<JsFunction>> {
+  let value = ctx.get::<JsUnknown>(index)?;
+  if value.get_type()? == ValueType::Function {
+    Ok(Some(unsafe { value.cast::<JsFunction>() }))
+  } else {
+    Ok(None)
+  }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn get_string(ctx: &CallContext, index: usize) -> napi::Result<Option<String>> {
+  let value = ctx.get::<JsUnknown>(index)?;
+  if value.get_type()? == ValueType::String {
+    Ok(Some(unsafe { value.cast::<JsString>() }.into_utf8()?.into_owned()?))
+  } else {
+    Ok(None)
+  }
+}
+
+/// A pending call to a JS function, which is completed via `completeRequest`.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+struct Request {
+  state: Mutex<(Option<Result<String, String>>, Option<std::task::Waker>)>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Request {
+  fn complete(&self, result: Result<String, String>) {
+    let mut state = self.state.lock().unwrap();
+    state.0 = Some(result);
+    if let Some(waker) = state.1.take() {
+      waker.wake();
+    }
+  }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+struct RequestFuture(Arc<Request>);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl std::future::Future for RequestFuture {
+  type Output = std::io::Result<String>;
+
+  fn poll(self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<Self::Output> {
+    let mut state = self.0.state.lock().unwrap();
+    match state.0.take() {
+      Some(result) => {
+        std::task::Poll::Ready(result.map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err)))
+      }
+      None => {
+        state.1 = Some(cx.waker().clone());
+        std::task::Poll::Pending
+      }
+    }
+  }
+}
+
+/// A source provider that calls the `read` and `resolve` functions passed to `bundleAsync`,
+/// and falls back to the file system otherwise. Packages in `node_modules` are only resolved
+/// when files are also read from the file system, since a custom `read` function may not
+/// correspond to the files on disk.
+#[cfg(not(target_arch = "wasm32"))]
+struct JsSourceProvider {
+  read: Option<ThreadsafeFunction<(Arc<Request>, String)>>,
+  resolve: Option<ThreadsafeFunction<(Arc<Request>, String, String)>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl AsyncSourceProvider for JsSourceProvider {
+  fn read<'a>(&'a self, file: &'a Path) -> BoxFuture<'a, std::io::Result<String>> {
+    match &self.read {
+      Some(read) => {
+        let request = Arc::new(Request::default());
+        read.call(
+          Ok((request.clone(), file.to_string_lossy().into_owned())),
+          ThreadsafeFunctionCallMode::NonBlocking,
+        );
+        Box::pin(RequestFuture(request))
+      }
+      None => Box::pin(std::future::ready(std::fs::read_to_string(file))),
+    }
+  }
+
+  fn resolve<'a>(
+    &'a self,
+    specifier: &'a str,
+    originating_file: &'a Path,
+  ) -> BoxFuture<'a, std::io::Result<PathBuf>> {
+    match &self.resolve {
+      Some(resolve) => {
+        let request = Arc::new(Request::default());
+        resolve.call(
+          Ok((
+            request.clone(),
+            specifier.to_owned(),
+            originating_file.to_string_lossy().into_owned(),
+          )),
+          ThreadsafeFunctionCallMode::NonBlocking,
+        );
+        let future = RequestFuture(request);
+        Box::pin(async move { future.await.map(PathBuf::from) })
+      }
+      None if self.read.is_none() => Box::pin(std::future::ready(resolve_node_style(specifier, originating_file))),
+      None => Box::pin(std::future::ready(Ok(originating_file.with_file_name(specifier)))),
+    }
+  }
+}
+
+/// Runs a future to completion on the current thread.
+#[cfg(not(target_arch = "wasm32"))]
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+  struct ThreadWaker(std::thread::Thread);
+
+  impl std::task::Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+      self.0.unpark()
+    }
+  }
+
+  let waker = Arc::new(ThreadWaker(std::thread::current())).into();
+  let mut cx = std::task::Context::from_waker(&waker);
+  let mut future = Box::pin(future);
+  loop {
+    match future.as_mut().poll(&mut cx) {
+      std::task::Poll::Ready(output) => return output,
+      std::task::Poll::Pending => std::thread::park(),
     }
   }
 }
@@ -160,8 +425,11 @@ fn bundle(ctx: CallContext) -> napi::Result<JsUnknown> {
 #[module_exports]
 fn init(mut exports: JsObject) -> napi::Result<()> {
   exports.create_named_method("transform", transform)?;
+  exports.create_named_method("printAst", print_ast)?;
   exports.create_named_method("transformStyleAttribute", transform_style_attribute)?;
   exports.create_named_method("bundle", bundle)?;
+  exports.create_named_method("bundleAsync", bundle_async)?;
+  exports.create_named_method("completeRequest", complete_request)?;
 
   Ok(())
 }
@@ -178,10 +446,22 @@ struct Config {
   pub minify: Option<bool>,
   pub source_map: Option<bool>,
   pub drafts: Option<Drafts>,
-  pub css_modules: Option<bool>,
+  pub css_modules: Option<CssModulesOption>,
   pub analyze_dependencies: Option<bool>,
   pub pseudo_classes: Option<OwnedPseudoClasses>,
   pub unused_symbols: Option<HashSet<String>>,
+  pub error_recovery: Option<bool>,
+  pub ast: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PrintConfig {
+  pub ast: serde_json::Value,
+  pub targets: Option<Browsers>,
+  pub minify: Option<bool>,
+  pub analyze_dependencies: Option<bool>,
+  pub pseudo_classes: Option<OwnedPseudoClasses>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -192,10 +472,45 @@ struct BundleConfig {
   pub minify: Option<bool>,
   pub source_map: Option<bool>,
   pub drafts: Option<Drafts>,
-  pub css_modules: Option<bool>,
+  pub css_modules: Option<CssModulesOption>,
   pub analyze_dependencies: Option<bool>,
   pub pseudo_classes: Option<OwnedPseudoClasses>,
   pub unused_symbols: Option<HashSet<String>>,
+  pub error_recovery: Option<bool>,
+  pub ast: Option<bool>,
+  pub graph: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum CssModulesOption {
+  Bool(bool),
+  Config(CssModulesConfig),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CssModulesConfig {
+  pattern: Option<String>,
+  dashed_idents: Option<bool>,
+  pure: Option<bool>,
+}
+
+impl CssModulesOption {
+  fn to_config<'i>(&self) -> Result<Option<parcel_css::css_modules::Config>, CompileError<'i>> {
+    match self {
+      CssModulesOption::Bool(true) => Ok(Some(parcel_css::css_modules::Config::default())),
+      CssModulesOption::Bool(false) => Ok(None),
+      CssModulesOption::Config(config) => Ok(Some(parcel_css::css_modules::Config {
+        pattern: match config.pattern.as_ref() {
+          Some(pattern) => Pattern::parse(pattern)?,
+          None => Pattern::default(),
+        },
+        dashed_idents: config.dashed_idents.unwrap_or_default(),
+        pure: config.pure.unwrap_or_default(),
+      })),
+    }
+  }
 }
 
 #[derive(Debug, Deserialize)]
@@ -237,8 +552,12 @@ fn compile<'i>(code: &'i str, config: &Config) -> Result<TransformResult, Compil
     ParserOptions {
       nesting: matches!(drafts, Some(d) if d.nesting),
       custom_media: matches!(drafts, Some(d) if d.custom_media),
-      css_modules: config.css_modules.unwrap_or(false),
-      source_index: 0,
+      css_modules: match &config.css_modules {
+        Some(css_modules) => css_modules.to_config()?,
+        None => None,
+      },
+      error_recovery: config.error_recovery.unwrap_or(false),
+      ..ParserOptions::default()
     },
   )?;
   stylesheet.minify(MinifyOptions {
@@ -246,6 +565,12 @@ fn compile<'i>(code: &'i str, config: &Config) -> Result<TransformResult, Compil
     unused_symbols: config.unused_symbols.clone().unwrap_or_default(),
   })?;
 
+  let ast = if config.ast.unwrap_or(false) {
+    Some(serde_json::to_value(&stylesheet)?)
+  } else {
+    None
+  };
+
   let mut source_map = if config.source_map.unwrap_or(false) {
     let mut sm = SourceMap::new("/");
     sm.add_source(&config.filename);
@@ -273,7 +598,38 @@ fn compile<'i>(code: &'i str, config: &Config) -> Result<TransformResult, Compil
     code: res.code.into_bytes(),
     map,
     exports: res.exports,
+    references: res.references,
     dependencies: res.dependencies,
+    warnings: stylesheet
+      .parser_warnings
+      .iter()
+      .map(Warning::from)
+      .chain(res.warnings.iter().map(Warning::from))
+      .collect(),
+    ast,
+    graph: None,
+  })
+}
+
+fn compile_ast<'i>(config: &PrintConfig) -> Result<TransformResult, CompileError<'i>> {
+  let stylesheet = StyleSheet::deserialize(&config.ast)?;
+  let res = stylesheet.to_css(PrinterOptions {
+    minify: config.minify.unwrap_or(false),
+    source_map: None,
+    targets: config.targets,
+    analyze_dependencies: config.analyze_dependencies.unwrap_or(false),
+    pseudo_classes: config.pseudo_classes.as_ref().map(|p| p.into()),
+  })?;
+
+  Ok(TransformResult {
+    code: res.code.into_bytes(),
+    map: None,
+    exports: res.exports,
+    references: res.references,
+    dependencies: res.dependencies,
+    warnings: res.warnings.iter().map(Warning::from).collect(),
+    ast: None,
+    graph: None,
   })
 }
 
@@ -284,22 +640,60 @@ fn compile_bundle<'i>(fs: &'i FileProvider, config: &BundleConfig) -> Result<Tra
     None
   };
 
+  let parser_options = bundle_parser_options(config)?;
+  let mut bundler = Bundler::new(fs, source_map.as_mut(), parser_options);
+  let bundle = bundler.bundle(Path::new(&config.filename))?;
+  finish_bundle(bundle, source_map, config)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn compile_bundle_async<'i>(
+  fs: &'i JsSourceProvider,
+  config: &BundleConfig,
+) -> Result<TransformResult, CompileError<'i>> {
+  let mut source_map = if config.source_map.unwrap_or(false) {
+    Some(SourceMap::new("/"))
+  } else {
+    None
+  };
+
+  let parser_options = bundle_parser_options(config)?;
+  let mut bundler = Bundler::new(fs, source_map.as_mut(), parser_options);
+  let bundle = bundler.bundle_async(Path::new(&config.filename)).await?;
+  finish_bundle(bundle, source_map, config)
+}
+
+fn bundle_parser_options<'i>(config: &BundleConfig) -> Result<ParserOptions, CompileError<'i>> {
   let drafts = config.drafts.as_ref();
-  let parser_options = ParserOptions {
+  Ok(ParserOptions {
     nesting: matches!(drafts, Some(d) if d.nesting),
     custom_media: matches!(drafts, Some(d) if d.custom_media),
-    css_modules: config.css_modules.unwrap_or(false),
+    css_modules: match &config.css_modules {
+      Some(css_modules) => css_modules.to_config()?,
+      None => None,
+    },
+    error_recovery: config.error_recovery.unwrap_or(false),
     ..ParserOptions::default()
-  };
-
-  let mut bundler = Bundler::new(fs, source_map.as_mut(), parser_options);
-  let mut stylesheet = bundler.bundle(Path::new(&config.filename))?;
+  })
+}
 
+fn finish_bundle<'i>(
+  bundle: Bundle<'i>,
+  mut source_map: Option<SourceMap>,
+  config: &BundleConfig,
+) -> Result<TransformResult, CompileError<'i>> {
+  let Bundle { mut stylesheet, graph } = bundle;
   stylesheet.minify(MinifyOptions {
     targets: config.targets,
     unused_symbols: config.unused_symbols.clone().unwrap_or_default(),
   })?;
 
+  let ast = if config.ast.unwrap_or(false) {
+    Some(serde_json::to_value(&stylesheet)?)
+  } else {
+    None
+  };
+
   let res = stylesheet.to_css(PrinterOptions {
     minify: config.minify.unwrap_or(false),
     source_map: source_map.as_mut(),
@@ -318,7 +712,20 @@ fn compile_bundle<'i>(fs: &'i FileProvider, config: &BundleConfig) -> Result<Tra
     code: res.code.into_bytes(),
     map,
     exports: res.exports,
+    references: res.references,
     dependencies: res.dependencies,
+    warnings: stylesheet
+      .parser_warnings
+      .iter()
+      .map(Warning::from)
+      .chain(res.warnings.iter().map(Warning::from))
+      .collect(),
+    ast,
+    graph: if config.graph.unwrap_or(false) {
+      Some(graph)
+    } else {
+      None
+    },
   })
 }
 
@@ -358,13 +765,13 @@ struct AttrResult {
 
 #[cfg(not(target_arch = "wasm32"))]
 impl AttrResult {
-  fn into_js(self, ctx: CallContext) -> napi::Result<JsUnknown> {
+  fn into_js(self, env: &Env) -> napi::Result<JsUnknown> {
     // Manually construct buffers so we avoid a copy and work around
     // https://github.com/napi-rs/napi-rs/issues/1124.
-    let mut obj = ctx.env.create_object()?;
-    let buf = ctx.env.create_buffer_with_data(self.code)?;
+    let mut obj = env.create_object()?;
+    let buf = env.create_buffer_with_data(self.code)?;
     obj.set_named_property("code", buf.into_raw())?;
-    obj.set_named_property("dependencies", ctx.env.to_js_value(&self.dependencies)?)?;
+    obj.set_named_property("dependencies", env.to_js_value(&self.dependencies)?)?;
     Ok(obj.into_unknown())
   }
 }
@@ -394,6 +801,8 @@ enum CompileError<'i> {
   PrinterError(Error<PrinterErrorKind>),
   SourceMapError(parcel_sourcemap::SourceMapError),
   BundleError(Error<BundleErrorKind<'i>>),
+  PatternError(PatternParseError),
+  SerdeError(serde_json::Error),
 }
 
 impl<'i> std::fmt::Display for CompileError<'i> {
@@ -403,6 +812,8 @@ impl<'i> std::fmt::Display for CompileError<'i> {
       CompileError::MinifyError(err) => err.kind.fmt(f),
       CompileError::PrinterError(err) => err.kind.fmt(f),
       CompileError::BundleError(err) => err.kind.fmt(f),
+      CompileError::PatternError(err) => err.fmt(f),
+      CompileError::SerdeError(err) => err.fmt(f),
       CompileError::SourceMapError(err) => write!(f, "{}", err.to_string()), // TODO: switch to `fmt::Display` once parcel_sourcemap supports this
     }
   }
@@ -411,13 +822,29 @@ impl<'i> std::fmt::Display for CompileError<'i> {
 impl<'i> CompileError<'i> {
   #[cfg(not(target_arch = "wasm32"))]
   fn throw(self, ctx: CallContext, code: Option<&str>) -> napi::Result<JsUnknown> {
+    match self {
+      CompileError::ParseError(_)
+      | CompileError::PrinterError(_)
+      | CompileError::MinifyError(_)
+      | CompileError::BundleError(_) => {
+        let obj = self.into_js_error(ctx.env, code)?;
+        ctx.env.throw(obj)?;
+        Ok(ctx.env.get_undefined()?.into_unknown())
+      }
+      _ => Err(self.into()),
+    }
+  }
+
+  /// Converts the error to a JS error object, including location information if available.
+  #[cfg(not(target_arch = "wasm32"))]
+  fn into_js_error(self, env: &Env, code: Option<&str>) -> napi::Result<JsUnknown> {
     let reason = self.to_string();
     let data = match &self {
-      CompileError::ParseError(Error { kind, .. }) => ctx.env.to_js_value(kind)?,
-      CompileError::PrinterError(Error { kind, .. }) => ctx.env.to_js_value(kind)?,
-      CompileError::MinifyError(Error { kind, .. }) => ctx.env.to_js_value(kind)?,
-      CompileError::BundleError(Error { kind, .. }) => ctx.env.to_js_value(kind)?,
-      _ => ctx.env.get_null()?.into_unknown(),
+      CompileError::ParseError(Error { kind, .. }) => env.to_js_value(kind)?,
+      CompileError::PrinterError(Error { kind, .. }) => env.to_js_value(kind)?,
+      CompileError::MinifyError(Error { kind, .. }) => env.to_js_value(kind)?,
+      CompileError::BundleError(Error { kind, .. }) => env.to_js_value(kind)?,
+      _ => env.get_null()?.into_unknown(),
     };
 
     match self {
@@ -426,28 +853,41 @@ impl<'i> CompileError<'i> {
       | CompileError::MinifyError(Error { loc, .. })
       | CompileError::BundleError(Error { loc, .. }) => {
         // Generate an error with location information.
-        let syntax_error = ctx.env.get_global()?.get_named_property::<napi::JsFunction>("SyntaxError")?;
-        let reason = ctx.env.create_string_from_std(reason)?;
+        let syntax_error = env.get_global()?.get_named_property::<napi::JsFunction>("SyntaxError")?;
+        let reason = env.create_string_from_std(reason)?;
         let mut obj = syntax_error.new(&[reason])?;
         if let Some(loc) = loc {
-          let line = ctx.env.create_int32((loc.line + 1) as i32)?;
-          let col = ctx.env.create_int32(loc.column as i32)?;
-          let filename = ctx.env.create_string_from_std(loc.filename)?;
+          let line = env.create_int32((loc.line + 1) as i32)?;
+          let col = env.create_int32(loc.column as i32)?;
+          let filename = env.create_string_from_std(loc.filename)?;
           obj.set_named_property("fileName", filename)?;
           if let Some(code) = code {
-            let source = ctx.env.create_string(code)?;
+            let source = env.create_string(code)?;
             obj.set_named_property("source", source)?;
           }
-          let mut loc = ctx.env.create_object()?;
+          let mut loc = env.create_object()?;
           loc.set_named_property("line", line)?;
           loc.set_named_property("column", col)?;
           obj.set_named_property("loc", loc)?;
         }
         obj.set_named_property("data", data)?;
-        ctx.env.throw(obj)?;
-        Ok(ctx.env.get_undefined()?.into_unknown())
+        Ok(obj.into_unknown())
       }
-      _ => Err(self.into()),
+      _ => Ok(env.create_error(self.into())?.into_unknown()),
+    }
+  }
+
+  /// Converts the error to an owned version that does not borrow from the source code.
+  #[cfg(not(target_arch = "wasm32"))]
+  fn into_owned(self) -> CompileError<'static> {
+    match self {
+      CompileError::ParseError(err) => CompileError::ParseError(err.into_owned()),
+      CompileError::MinifyError(err) => CompileError::MinifyError(err),
+      CompileError::PrinterError(err) => CompileError::PrinterError(err),
+      CompileError::SourceMapError(err) => CompileError::SourceMapError(err),
+      CompileError::BundleError(err) => CompileError::BundleError(err.into_owned()),
+      CompileError::PatternError(err) => CompileError::PatternError(err),
+      CompileError::SerdeError(err) => CompileError::SerdeError(err),
     }
   }
 }
@@ -476,6 +916,18 @@ impl<'i> From<parcel_sourcemap::SourceMapError> for CompileError<'i> {
   }
 }
 
+impl<'i> From<PatternParseError> for CompileError<'i> {
+  fn from(e: PatternParseError) -> CompileError<'i> {
+    CompileError::PatternError(e)
+  }
+}
+
+impl<'i> From<serde_json::Error> for CompileError<'i> {
+  fn from(e: serde_json::Error) -> CompileError<'i> {
+    CompileError::SerdeError(e)
+  }
+}
+
 impl<'i> From<Error<BundleErrorKind<'i>>> for CompileError<'i> {
   fn from(e: Error<BundleErrorKind<'i>>) -> CompileError<'i> {
     CompileError::BundleError(e)
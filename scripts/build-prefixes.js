@@ -202,6 +202,9 @@ for (let feature of cssFeatures) {
 // No browser supports custom media queries yet.
 addValue(compat, {}, 'custom-media-queries');
 
+// No browser supports @scope yet.
+addValue(compat, {}, 'css-scope');
+
 let mdnFeatures = {
   doublePositionGradients: mdn.css.types.image.gradient['radial-gradient'].doubleposition.__compat.support,
   clamp: mdn.css.types.clamp.__compat.support,
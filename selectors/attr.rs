@@ -7,6 +7,11 @@ use cssparser::ToCss;
 use std::fmt;
 
 #[derive(Clone, Eq, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(bound = "", rename_all = "camelCase")
+)]
 pub struct AttrSelectorWithOptionalNamespace<'i, Impl: SelectorImpl<'i>> {
   pub namespace: Option<NamespaceConstraint<(Impl::NamespacePrefix, Impl::NamespaceUrl)>>,
   pub local_name: Impl::LocalName,
@@ -25,6 +30,11 @@ impl<'i, Impl: SelectorImpl<'i>> AttrSelectorWithOptionalNamespace<'i, Impl> {
 }
 
 #[derive(Clone, Eq, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum NamespaceConstraint<NamespaceUrl> {
   Any,
 
@@ -33,8 +43,14 @@ pub enum NamespaceConstraint<NamespaceUrl> {
 }
 
 #[derive(Clone, Eq, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum ParsedAttrSelectorOperation<AttrValue> {
   Exists,
+  #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
   WithValue {
     operator: AttrSelectorOperator,
     case_sensitivity: ParsedCaseSensitivity,
@@ -69,6 +85,11 @@ impl<AttrValue> AttrSelectorOperation<AttrValue> {
 }
 
 #[derive(Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "kebab-case")
+)]
 pub enum AttrSelectorOperator {
   Equal,
   Includes,
@@ -125,6 +146,11 @@ impl AttrSelectorOperator {
 pub static SELECTOR_WHITESPACE: &[char] = &[' ', '\t', '\n', '\r', '\x0C'];
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "kebab-case")
+)]
 pub enum ParsedCaseSensitivity {
   // 's' was specified.
   ExplicitCaseSensitive,
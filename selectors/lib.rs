@@ -16,6 +16,8 @@ extern crate fxhash;
 extern crate log;
 extern crate phf;
 extern crate precomputed_hash;
+#[cfg(feature = "serde")]
+extern crate serde;
 extern crate smallvec;
 
 pub mod attr;
@@ -221,7 +221,7 @@ macro_rules! with_all_bounds {
             /// non tree-structural pseudo-classes
             /// (see: https://drafts.csswg.org/selectors/#structural-pseudos)
             type NonTSPseudoClass: $($CommonBounds)* + NonTSPseudoClass<'i, Impl = Self>;
-            type VendorPrefix: Sized + Eq + Clone + ToCss;
+            type VendorPrefix: $($CommonBounds)* + Sized + ToCss;
 
             /// pseudo-elements
             type PseudoElement: $($CommonBounds)* + PseudoElement<'i, Impl = Self>;
@@ -243,11 +243,18 @@ macro_rules! with_bounds {
     }
 }
 
+#[cfg(not(feature = "serde"))]
 with_bounds! {
     [Clone + Eq]
     [From<CowRcStr<'i>>]
 }
 
+#[cfg(feature = "serde")]
+with_bounds! {
+    [Clone + Eq + serde::Serialize + for<'de> serde::Deserialize<'de>]
+    [From<CowRcStr<'i>>]
+}
+
 pub trait Parser<'i> {
   type Impl: SelectorImpl<'i>;
   type Error: 'i + From<SelectorParseErrorKind<'i>>;
@@ -336,6 +343,7 @@ pub trait Parser<'i> {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(bound = ""))]
 pub struct SelectorList<'i, Impl: SelectorImpl<'i>>(pub SmallVec<[Selector<'i, Impl>; 1]>);
 
 /// How to treat invalid selectors in a selector list.
@@ -827,6 +835,17 @@ impl<'i, Impl: SelectorImpl<'i>> Selector<'i, Impl> {
     Selector(spec, components)
   }
 
+  /// Consumes the selector, returning its specificity and flags along with its
+  /// components in matching order.
+  pub fn into_raw_parts(self) -> (SpecificityAndFlags, Vec<Component<'i, Impl>>) {
+    (self.0, self.1)
+  }
+
+  /// Creates a selector from the parts returned by `into_raw_parts`.
+  pub fn from_raw_parts(spec: SpecificityAndFlags, components: Vec<Component<'i, Impl>>) -> Self {
+    Selector(spec, components)
+  }
+
   /// Returns count of simple selectors and combinators in the Selector.
   #[inline]
   pub fn len(&self) -> usize {
@@ -878,6 +897,65 @@ impl<'i, Impl: SelectorImpl<'i>> Selector<'i, Impl> {
   }
 }
 
+#[cfg(feature = "serde")]
+impl<'i, Impl: SelectorImpl<'i>> serde::Serialize for Selector<'i, Impl> {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    use serde::ser::SerializeSeq;
+
+    // Compound selectors are stored in matching order (right to left), but the simple
+    // selectors within each compound are stored in parse order. Serialize everything in
+    // parse order so that the components can be pushed back into a SelectorBuilder.
+    let mut seq = serializer.serialize_seq(Some(self.len()))?;
+    let mut combinators = self.iter_raw_match_order().rev().filter_map(|c| c.as_combinator());
+    let compounds = self.iter_raw_match_order().as_slice().split(|c| c.is_combinator()).rev();
+    for (i, compound) in compounds.enumerate() {
+      if i > 0 {
+        if let Some(combinator) = combinators.next() {
+          seq.serialize_element(&Component::<Impl>::Combinator(combinator))?;
+        }
+      }
+      for component in compound {
+        seq.serialize_element(component)?;
+      }
+    }
+    seq.end()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'i, 'de, Impl: SelectorImpl<'i>> serde::Deserialize<'de> for Selector<'i, Impl> {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: serde::Deserializer<'de>,
+  {
+    let components = Vec::<Component<'i, Impl>>::deserialize(deserializer)?;
+    let mut builder = SelectorBuilder::default();
+    let mut has_pseudo_element = false;
+    let mut slotted = false;
+    let mut part = false;
+    for component in components {
+      match component {
+        Component::Combinator(combinator) => builder.push_combinator(combinator),
+        component => {
+          match component {
+            Component::PseudoElement(_) => has_pseudo_element = true,
+            Component::Slotted(_) => slotted = true,
+            Component::Part(_) => part = true,
+            _ => {}
+          }
+          builder.push_simple_selector(component);
+        }
+      }
+    }
+
+    let (spec, components) = builder.build(has_pseudo_element, slotted, part);
+    Ok(Selector(spec, components))
+  }
+}
+
 #[derive(Clone)]
 pub struct SelectorIter<'a, 'i, Impl: 'a + SelectorImpl<'i>> {
   iter: slice::Iter<'a, Component<'i, Impl>>,
@@ -1012,6 +1090,11 @@ impl<'a, 'i, Impl: SelectorImpl<'i>> Iterator for AncestorIter<'a, 'i, Impl> {
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "kebab-case")
+)]
 pub enum Combinator {
   Child,        //  >
   Descendant,   // space
@@ -1068,6 +1151,11 @@ impl Combinator {
 ///
 /// [1] https://bugzilla.mozilla.org/show_bug.cgi?id=1357973
 #[derive(Clone, Eq, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(bound = "", tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Component<'i, Impl: SelectorImpl<'i>> {
   Combinator(Combinator),
 
@@ -1079,14 +1167,17 @@ pub enum Component<'i, Impl: SelectorImpl<'i>> {
   ExplicitUniversalType,
   LocalName(LocalName<'i, Impl>),
 
+  #[cfg_attr(feature = "serde", serde(rename = "id"))]
   ID(Impl::Identifier),
   Class(Impl::Identifier),
 
+  #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
   AttributeInNoNamespaceExists {
     local_name: Impl::LocalName,
     local_name_lower: Impl::LocalName,
   },
   // Used only when local_name is already lowercase.
+  #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
   AttributeInNoNamespace {
     local_name: Impl::LocalName,
     operator: AttrSelectorOperator,
@@ -1112,6 +1203,7 @@ pub enum Component<'i, Impl: SelectorImpl<'i>> {
   FirstOfType,
   LastOfType,
   OnlyOfType,
+  #[cfg_attr(feature = "serde", serde(rename = "non-ts-pseudo-class"))]
   NonTSPseudoClass(Impl::NonTSPseudoClass),
   /// The ::slotted() pseudo-element:
   ///
@@ -1303,6 +1395,11 @@ impl<'i, Impl: SelectorImpl<'i>> Component<'i, Impl> {
 }
 
 #[derive(Clone, Eq, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(bound = "", rename_all = "camelCase")
+)]
 pub struct LocalName<'i, Impl: SelectorImpl<'i>> {
   pub name: Impl::LocalName,
   pub lower_name: Impl::LocalName,
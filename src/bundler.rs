@@ -6,8 +6,38 @@
 //! and returns a [StyleSheet](super::stylesheet::StyleSheet) containing the rules from all
 //! of the dependencies of the entry file, recursively.
 //!
+//! The specifiers of dependencies are resolved to file paths by the source provider as well. By default,
+//! they are resolved relative to the importing file, and [FileProvider](FileProvider) additionally looks
+//! up packages in `node_modules`. Custom providers may override this, e.g. to support aliases.
+//! [MemoryProvider](MemoryProvider) can be used to bundle files held in memory instead.
+//! Files can also be loaded asynchronously using an [AsyncSourceProvider](AsyncSourceProvider) with
+//! [bundle_async](Bundler::bundle_async).
+//!
+//! Relative `url()` references in bundled files are rewritten so that they continue to point at the
+//! same assets once inlined into the entry file. By default, they are made relative to the directory of
+//! the entry, but they can also be rewritten to absolute paths from a project root. See
+//! [BundleOptions](BundleOptions) for details.
+//!
 //! Rules are bundled following `@import` order, and wrapped in the necessary `@media`, `@supports`,
-//! and `@layer` rules as appropriate to preserve the authored behavior.
+//! and `@layer` rules as appropriate to preserve the authored behavior. Imports of remote style sheets
+//! (e.g. `https:` or `data:` urls) cannot be bundled, so they are kept and hoisted to the top of the
+//! output instead, with the conditions of the files importing them applied.
+//!
+//! Multiple entries can be bundled together using [bundle_entries](Bundler::bundle_entries), which
+//! loads each file once and can optionally extract files shared between the entries into separate
+//! style sheets.
+//!
+//! Small assets referenced via `url()`, such as icons or fonts, can be inlined as `data:` URIs
+//! using the [inline_asset_limit](BundleOptions::inline_asset_limit) option.
+//!
+//! The import graph of the bundle, including the conditions of each @import rule and the order in which
+//! files were concatenated, is returned along with the bundled style sheet as a [Bundle](Bundle).
+//!
+//! When the `css_modules` option is enabled, files referenced by the `composes` property are also
+//! included in the bundle, before the file that references them. These files are compiled with their
+//! own CSS module scope, and references to them are resolved to the compiled class names. Files that
+//! values are imported from via `@value` or `:import` rules are included in the same way, and the
+//! imported values are substituted with the values exported by those files.
 //!
 //! # Example
 //!
@@ -20,11 +50,12 @@
 //!
 //! let fs = FileProvider::new();
 //! let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
-//! let stylesheet = bundler.bundle(Path::new("style.css")).unwrap();
+//! let stylesheet = bundler.bundle(Path::new("style.css")).unwrap().stylesheet;
 //! ```
 
 use crate::{
-  error::ErrorLocation,
+  css_modules::import_placeholder,
+  error::{format_import_chain, ErrorLocation, Warning, WarningKind},
   rules::{
     layer::{LayerBlockRule, LayerName},
     Location,
@@ -33,23 +64,33 @@ use crate::{
 use crate::{
   error::{Error, ParserError},
   media_query::MediaList,
+  properties::{css_modules::ComposesFrom, Property},
   rules::{
+    icss::{replace_imported_values, IcssImport, ValueRule},
     import::ImportRule,
     media::MediaRule,
     supports::{SupportsCondition, SupportsRule},
     CssRule, CssRuleList,
   },
-  stylesheet::{ParserOptions, StyleSheet},
+  stylesheet::{ParserOptions, PrinterOptions, StyleSheet},
+  traits::{IntoOwned, ToCss, Visit, Visitor},
+  values::{string::CowArcStr, url::Url},
 };
 use dashmap::DashMap;
+use data_encoding::BASE64;
 use parcel_sourcemap::SourceMap;
 use rayon::prelude::*;
 use serde::Serialize;
 use std::{
-  collections::HashSet,
+  borrow::Cow,
+  collections::{hash_map::DefaultHasher, HashMap, HashSet},
   fs,
-  path::{Path, PathBuf},
+  future::Future,
+  hash::{Hash, Hasher},
+  path::{Component, Path, PathBuf},
+  pin::Pin,
   sync::Mutex,
+  task::{Context, Poll},
 };
 
 /// A Bundler combines a CSS file and all imported dependencies together into
@@ -60,12 +101,215 @@ pub struct Bundler<'a, 's, P> {
   source_indexes: DashMap<PathBuf, u32>,
   stylesheets: Mutex<Vec<BundleStyleSheet<'a>>>,
   options: ParserOptions,
+  bundle_options: BundleOptions,
+  entry_dir: PathBuf,
+  keep_parsed: bool,
+  warnings: Mutex<Vec<Warning>>,
+}
+
+/// Options for a [Bundler](Bundler).
+#[derive(Debug, Clone, Default)]
+pub struct BundleOptions {
+  /// How relative `url()` references in bundled files are rewritten.
+  pub url_rebase: UrlRebase,
+  /// How circular `@import` rules are handled.
+  pub circular_imports: CircularImports,
+  /// Whether files imported by multiple entries are extracted into shared style sheets.
+  /// Only applies when bundling with [bundle_entries](Bundler::bundle_entries).
+  pub extract_shared: bool,
+  /// Whether parsed files are cached between calls to [bundle](Bundler::bundle), so that
+  /// rebundling only reads and parses the files that were [invalidated](Bundler::invalidate).
+  pub cache: bool,
+  /// The maximum size in bytes of assets referenced via relative `url()`s to inline as `data:` URIs.
+  /// Assets are read using [read_asset](SourceProvider::read_asset). Urls with a query string or
+  /// fragment, and files with an unknown extension, are not inlined. Disabled by default.
+  pub inline_asset_limit: Option<usize>,
+}
+
+/// Controls how relative `url()` references are rewritten when files are inlined into the bundle.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UrlRebase {
+  /// Urls are rewritten to be relative to the directory of the entry file. This is the default.
+  Entry,
+  /// Urls are rewritten to absolute paths from the given project root directory, e.g. `/images/logo.png`.
+  /// Urls that point outside of the root are rewritten relative to the entry file instead.
+  Root(PathBuf),
+  /// Urls are left unchanged.
+  Preserve,
+}
+
+impl Default for UrlRebase {
+  fn default() -> UrlRebase {
+    UrlRebase::Entry
+  }
+}
+
+/// Controls how circular `@import` rules are handled while bundling.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CircularImports {
+  /// A warning is emitted, and the @import rule that closes the cycle is ignored, as in browsers.
+  /// This is the default.
+  Warn,
+  /// Bundling fails with a [CircularImport](BundleErrorKind::CircularImport) error.
+  Error,
+}
+
+impl Default for CircularImports {
+  fn default() -> CircularImports {
+    CircularImports::Warn
+  }
+}
+
+/// The result of bundling an entry with [bundle](Bundler::bundle) or [bundle_async](Bundler::bundle_async).
+#[derive(Debug)]
+pub struct Bundle<'i> {
+  /// The bundled style sheet.
+  pub stylesheet: StyleSheet<'i>,
+  /// The import graph of the bundle.
+  pub graph: BundleGraph,
+}
+
+/// The result of bundling multiple entries with [bundle_entries](Bundler::bundle_entries).
+#[derive(Debug)]
+pub struct MultiEntryBundle<'i> {
+  /// The bundled style sheet of each entry, in the order the entries were given.
+  pub entries: Vec<StyleSheet<'i>>,
+  /// Style sheets containing files shared between entries, if the
+  /// [extract_shared](BundleOptions::extract_shared) option is enabled.
+  pub shared: Vec<StyleSheet<'i>>,
+  /// Describes which style sheets each entry needs.
+  pub manifest: BundleManifest,
+  /// The import graph of each entry, in the order the entries were given.
+  pub graphs: Vec<BundleGraph>,
+}
+
+/// Describes the style sheets produced by [bundle_entries](Bundler::bundle_entries).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BundleManifest {
+  /// The manifest of each entry, in the order the entries were given.
+  pub entries: Vec<EntryManifest>,
+  /// The manifest of each shared style sheet.
+  pub shared: Vec<SharedManifest>,
+}
+
+/// Describes the style sheets needed by an entry.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EntryManifest {
+  /// The path of the entry file.
+  pub path: PathBuf,
+  /// Indexes of the shared style sheets to load before the style sheet of the entry, in order.
+  pub shared: Vec<usize>,
+}
+
+/// Describes a style sheet shared between entries.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SharedManifest {
+  /// The files included in the style sheet, in order.
+  pub files: Vec<PathBuf>,
+  /// Indexes of the entries that depend on the style sheet.
+  pub entries: Vec<usize>,
+}
+
+/// The import graph of a bundle, as returned in a [Bundle](Bundle).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BundleGraph {
+  /// The files included in the bundle. The entry is always the first file.
+  pub files: Vec<BundleGraphFile>,
+  /// The @import rules and `composes` references between files.
+  pub edges: Vec<BundleGraphEdge>,
+  /// Indexes of the files in the order they are concatenated in the bundle.
+  pub order: Vec<usize>,
+}
+
+/// A file included in a [BundleGraph](BundleGraph).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BundleGraphFile {
+  /// The path of the file.
+  pub path: PathBuf,
+  /// The conditions the rules of the file are wrapped in, combined from all @import rules leading to it.
+  pub conditions: ImportConditions,
+}
+
+/// A reference from one file in a [BundleGraph](BundleGraph) to another.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BundleGraphEdge {
+  /// The index of the file containing the reference.
+  pub from: usize,
+  /// The index of the referenced file.
+  pub to: usize,
+  /// The kind of reference.
+  pub kind: BundleGraphEdgeKind,
+  /// The specifier of the referenced file, as written in the source.
+  pub specifier: String,
+  /// The conditions of the @import rule. These are always empty for `composes` references.
+  pub conditions: ImportConditions,
+  /// The location of the reference.
+  pub loc: ErrorLocation,
+}
+
+/// The kind of a [BundleGraphEdge](BundleGraphEdge).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BundleGraphEdgeKind {
+  /// An @import rule.
+  Import,
+  /// A reference via the CSS modules `composes` property.
+  Composes,
+  /// A CSS modules `@value` or ICSS `:import` rule importing values from another file.
+  Value,
+}
+
+/// The conditions of an @import rule, serialized as CSS.
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct ImportConditions {
+  /// The cascade layer name, or an empty string for an anonymous layer.
+  pub layer: Option<String>,
+  /// A media query.
+  pub media: Option<String>,
+  /// A `supports()` condition.
+  pub supports: Option<String>,
+}
+
+impl ImportConditions {
+  fn new(
+    layer: &Option<Option<LayerName<'_>>>,
+    media: &MediaList<'_>,
+    supports: &Option<SupportsCondition<'_>>,
+  ) -> ImportConditions {
+    ImportConditions {
+      layer: layer.as_ref().map(|layer| match layer {
+        Some(name) => name.to_css_string(PrinterOptions::default()).unwrap(),
+        None => String::new(),
+      }),
+      media: if media.media_queries.is_empty() {
+        None
+      } else {
+        Some(media.to_css_string(PrinterOptions::default()).unwrap())
+      },
+      supports: supports
+        .as_ref()
+        .map(|supports| supports.to_css_string(PrinterOptions::default()).unwrap()),
+    }
+  }
 }
 
 #[derive(Debug)]
 struct BundleStyleSheet<'i> {
   stylesheet: Option<StyleSheet<'i>>,
+  /// A copy of the file as originally parsed, kept when bundling multiple entries or when the `cache`
+  /// option is enabled, so that files are only parsed once. `stylesheet` is modified while bundling.
+  cache: Option<CachedStyleSheet<'i>>,
+  /// Whether the file may have changed since it was cached, and must be read again.
+  stale: bool,
+  /// Whether the file is included in the bundle of the current entry.
+  included: bool,
   dependencies: Vec<u32>,
+  /// Source indexes of files referenced via the CSS modules `composes` property.
+  css_modules_deps: Vec<u32>,
+  /// The @import rules and `composes` references of this file, as originally written, for the [BundleGraph].
+  edges: Vec<Edge<'i>>,
+  /// The source index of the file that defines the CSS module scope for this file.
+  css_module_scope: u32,
   parent_source_index: u32,
   parent_dep_index: u32,
   layer: Option<Option<LayerName<'i>>>,
@@ -80,50 +324,325 @@ struct BundleStyleSheet<'i> {
 /// file system.
 pub trait SourceProvider: Send + Sync {
   /// Reads the contents of the given file path to a string.
-  fn read<'a>(&'a self, file: &Path) -> std::io::Result<&'a str>;
+  ///
+  /// Contents borrowed from the provider are parsed without copying. Owned contents are only kept
+  /// until they are parsed, and the resulting style sheet is converted to an owned value.
+  fn read<'a>(&'a self, file: &Path) -> std::io::Result<Cow<'a, str>>;
+
+  /// Resolves the given `@import` or `composes` specifier to a file path, relative to the
+  /// file it was referenced from. This can be overridden to implement aliases, for example.
+  ///
+  /// The default implementation resolves the specifier relative to the directory of the
+  /// originating file. See [resolve_node_style](resolve_node_style) for an implementation
+  /// that also looks up packages in `node_modules`, with the `node-resolve` feature.
+  fn resolve(&self, specifier: &str, originating_file: &Path) -> std::io::Result<PathBuf> {
+    Ok(originating_file.with_file_name(specifier))
+  }
+
+  /// Reads the contents of the given asset, e.g. an image or font, to inline it as a `data:` URI.
+  /// See [inline_asset_limit](BundleOptions::inline_asset_limit).
+  ///
+  /// The default implementation reads the asset as text using [read](SourceProvider::read),
+  /// so this should be overridden to support binary files.
+  fn read_asset(&self, file: &Path) -> std::io::Result<Vec<u8>> {
+    self.read(file).map(|source| source.into_owned().into_bytes())
+  }
+}
+
+/// A boxed future, as returned by an [AsyncSourceProvider](AsyncSourceProvider).
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A trait to asynchronously provide the contents of files to a Bundler, via
+/// [bundle_async](Bundler::bundle_async).
+///
+/// Unlike [SourceProvider](SourceProvider), the contents of files are returned as owned strings,
+/// so this can be implemented on top of a JavaScript callback or a virtual file system that loads
+/// files lazily.
+pub trait AsyncSourceProvider: Send + Sync {
+  /// Reads the contents of the given file path to a string.
+  fn read<'a>(&'a self, file: &'a Path) -> BoxFuture<'a, std::io::Result<String>>;
+
+  /// Resolves the given `@import` or `composes` specifier to a file path, relative to the
+  /// file it was referenced from. See [SourceProvider::resolve](SourceProvider::resolve).
+  fn resolve<'a>(
+    &'a self,
+    specifier: &'a str,
+    originating_file: &'a Path,
+  ) -> BoxFuture<'a, std::io::Result<PathBuf>> {
+    Box::pin(std::future::ready(Ok(originating_file.with_file_name(specifier))))
+  }
+
+  /// Reads the contents of the given asset. See [SourceProvider::read_asset](SourceProvider::read_asset).
+  fn read_asset<'a>(&'a self, file: &'a Path) -> BoxFuture<'a, std::io::Result<Vec<u8>>> {
+    Box::pin(async move { self.read(file).await.map(String::into_bytes) })
+  }
 }
 
-/// Provides an implementation of [SourceProvider](SourceProvider)
-/// that reads files from the file system.
-pub struct FileProvider {
-  inputs: Mutex<Vec<*mut String>>,
+/// Resolves a specifier similarly to the Node.js module resolution algorithm.
+///
+/// The specifier is first resolved relative to the directory of the originating file. If it is
+/// not a relative path (i.e. starting with `./`, `../` or `/`) and that file does not exist, it is
+/// looked up as a package in the `node_modules` directories of each ancestor directory instead.
+/// A `~` prefix, as used by webpack, skips the relative lookup.
+///
+/// If the `package.json` of a package has an `exports` field, it is used to resolve both the package
+/// itself and paths within it, including subpath patterns (e.g. `"./themes/*"`). Conditional exports
+/// are resolved using the `style` condition, falling back to `default`. Subpaths that are not exported
+/// result in an error. Otherwise, when a package itself is referenced (e.g. `@import "normalize.css"`),
+/// the `style` field is used as the entry point, followed by the `main` field if it points to a CSS file,
+/// and finally `index.css`. Paths within a package (e.g. `@import "pkg/theme.css"`) are resolved
+/// relative to the package directory.
+///
+/// Aliases such as `~/` or `@/`, which are configured per project in other tools, are not supported,
+/// and `~/` specifiers result in an error. These can be implemented by overriding
+/// [resolve](SourceProvider::resolve) and falling back to this function.
+///
+/// This function accesses the file system, and is only available with the `node-resolve` feature.
+#[cfg(feature = "node-resolve")]
+pub fn resolve_node_style(specifier: &str, originating_file: &Path) -> std::io::Result<PathBuf> {
+  if let Some(module) = specifier.strip_prefix('~') {
+    if module.starts_with('/') {
+      return Err(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!(
+          "Cannot resolve '{}': aliases are not supported, use a custom resolver instead",
+          specifier
+        ),
+      ));
+    }
+
+    return resolve_node_module(module, originating_file)?.ok_or_else(|| {
+      std::io::Error::new(std::io::ErrorKind::NotFound, format!("Cannot find module '{}'", module))
+    });
+  }
+
+  let relative = originating_file.with_file_name(specifier);
+  if specifier.starts_with("./")
+    || specifier.starts_with("../")
+    || specifier.starts_with('/')
+    || relative.is_file()
+  {
+    return Ok(relative);
+  }
+
+  Ok(resolve_node_module(specifier, originating_file)?.unwrap_or(relative))
+}
+
+#[cfg(feature = "node-resolve")]
+#[derive(serde::Deserialize)]
+struct PackageJson {
+  style: Option<String>,
+  main: Option<String>,
+  exports: Option<serde_json::Value>,
+}
+
+#[cfg(feature = "node-resolve")]
+fn resolve_node_module(specifier: &str, originating_file: &Path) -> std::io::Result<Option<PathBuf>> {
+  // Scoped packages include two path segments in the package name, e.g. `@scope/pkg`.
+  let (name, subpath) = match specifier.split_once('/') {
+    Some((scope, rest)) if scope.starts_with('@') => match rest.split_once('/') {
+      Some((name, subpath)) => (format!("{}/{}", scope, name), Some(subpath)),
+      None => (specifier.to_owned(), None),
+    },
+    Some((name, subpath)) => (name.to_owned(), Some(subpath)),
+    None => (specifier.to_owned(), None),
+  };
+  let subpath = subpath.filter(|s| !s.is_empty());
+
+  for dir in originating_file.ancestors().skip(1) {
+    let package_dir = dir.join("node_modules").join(&name);
+    if !package_dir.is_dir() {
+      continue;
+    }
+
+    let package_json = package_dir.join("package.json");
+    let package: Option<PackageJson> = if package_json.is_file() {
+      let package = serde_json::from_str(&fs::read_to_string(&package_json)?)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+      Some(package)
+    } else {
+      None
+    };
+
+    if let Some(exports) = package.as_ref().and_then(|package| package.exports.as_ref()) {
+      let subpath = subpath.map_or_else(|| ".".to_owned(), |subpath| format!("./{}", subpath));
+      return match resolve_package_exports(exports, &subpath) {
+        Some(target) => Ok(Some(package_dir.join(target))),
+        None => Err(std::io::Error::new(
+          std::io::ErrorKind::NotFound,
+          format!("Package subpath '{}' is not exported from package '{}'", subpath, name),
+        )),
+      };
+    }
+
+    if let Some(subpath) = subpath {
+      return Ok(Some(package_dir.join(subpath)));
+    }
+
+    let entry =
+      package.and_then(|package| package.style.or_else(|| package.main.filter(|main| main.ends_with(".css"))));
+
+    return Ok(Some(package_dir.join(entry.as_deref().unwrap_or("index.css"))));
+  }
+
+  Ok(None)
+}
+
+/// Resolves a subpath (e.g. `.` or `./theme.css`) using the `exports` field of a `package.json`.
+#[cfg(feature = "node-resolve")]
+fn resolve_package_exports(exports: &serde_json::Value, subpath: &str) -> Option<String> {
+  use serde_json::Value;
+
+  // An `exports` field without subpath keys defines the main entry point only.
+  let subpaths = match exports {
+    Value::Object(map) if map.keys().any(|key| key.starts_with('.')) => map,
+    _ if subpath == "." => return resolve_export_target(exports, None),
+    _ => return None,
+  };
+
+  if let Some(target) = subpaths.get(subpath) {
+    return resolve_export_target(target, None);
+  }
+
+  // Subpath patterns contain a single `*`, which may match any string. The longest matching
+  // prefix takes precedence.
+  subpaths
+    .iter()
+    .filter_map(|(key, target)| {
+      let (prefix, suffix) = key.split_once('*')?;
+      let matched = subpath.strip_prefix(prefix)?.strip_suffix(suffix)?;
+      Some((prefix.len(), matched, target))
+    })
+    .max_by_key(|(len, _, _)| *len)
+    .and_then(|(_, matched, target)| resolve_export_target(target, Some(matched)))
+}
+
+#[cfg(feature = "node-resolve")]
+fn resolve_export_target(target: &serde_json::Value, matched: Option<&str>) -> Option<String> {
+  use serde_json::Value;
+
+  match target {
+    Value::String(target) => match matched {
+      Some(matched) => Some(target.replace('*', matched)),
+      None => Some(target.clone()),
+    },
+    Value::Array(targets) => targets.iter().find_map(|target| resolve_export_target(target, matched)),
+    Value::Object(conditions) => ["style", "default"]
+      .iter()
+      .find_map(|condition| resolve_export_target(conditions.get(*condition)?, matched)),
+    _ => None,
+  }
 }
 
+/// Provides an implementation of [SourceProvider](SourceProvider)
+/// that reads files from the file system. With the `node-resolve` feature,
+/// specifiers are resolved using [resolve_node_style](resolve_node_style).
+///
+/// The contents of files are not kept by the provider, so it can be reused
+/// to rebundle after files change, e.g. in watch mode.
+#[derive(Debug, Default)]
+pub struct FileProvider;
+
 impl FileProvider {
   /// Creates a new FileProvider.
   pub fn new() -> FileProvider {
-    FileProvider {
-      inputs: Mutex::new(Vec::new()),
-    }
+    FileProvider
   }
 }
 
-unsafe impl Sync for FileProvider {}
-unsafe impl Send for FileProvider {}
-
 impl SourceProvider for FileProvider {
-  fn read<'a>(&'a self, file: &Path) -> std::io::Result<&'a str> {
-    let source = fs::read_to_string(file)?;
-    let ptr = Box::into_raw(Box::new(source));
-    self.inputs.lock().unwrap().push(ptr);
-    // SAFETY: this is safe because the pointer is not dropped
-    // until the FileProvider is, and we never remove from the
-    // list of pointers stored in the vector.
-    Ok(unsafe { &*ptr })
+  fn read<'a>(&'a self, file: &Path) -> std::io::Result<Cow<'a, str>> {
+    Ok(Cow::Owned(fs::read_to_string(file)?))
+  }
+
+  #[cfg(feature = "node-resolve")]
+  fn resolve(&self, specifier: &str, originating_file: &Path) -> std::io::Result<PathBuf> {
+    resolve_node_style(specifier, originating_file)
+  }
+
+  fn read_asset(&self, file: &Path) -> std::io::Result<Vec<u8>> {
+    fs::read(file)
+  }
+}
+
+/// Provides an implementation of [SourceProvider](SourceProvider) that reads files
+/// from memory, e.g. for tests or virtual files.
+///
+/// Paths are normalized, so specifiers are resolved relative to the importing file
+/// (including `..` segments) without accessing the file system.
+///
+/// # Example
+///
+/// ```
+/// use std::path::Path;
+/// use parcel_css::{
+///   bundler::{Bundler, MemoryProvider},
+///   stylesheet::{ParserOptions, PrinterOptions}
+/// };
+///
+/// let mut fs = MemoryProvider::new();
+/// fs.insert("/src/index.css", r#"@import "../theme/colors.css"; .a { color: red }"#);
+/// fs.insert("/theme/colors.css", ".b { color: green }");
+///
+/// let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+/// let stylesheet = bundler.bundle(Path::new("/src/index.css")).unwrap().stylesheet;
+/// let res = stylesheet.to_css(PrinterOptions { minify: true, ..PrinterOptions::default() }).unwrap();
+/// assert_eq!(res.code, ".b{color:green}.a{color:red}");
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct MemoryProvider {
+  files: HashMap<PathBuf, String>,
+}
+
+impl MemoryProvider {
+  /// Creates a new, empty MemoryProvider.
+  pub fn new() -> MemoryProvider {
+    MemoryProvider::default()
+  }
+
+  /// Adds a file with the given path and contents, replacing any existing file at that path.
+  pub fn insert<P: AsRef<Path>, S: Into<String>>(&mut self, path: P, source: S) {
+    self.files.insert(normalize_path(path.as_ref()), source.into());
+  }
+
+  /// Removes the file with the given path, returning its contents if it existed.
+  pub fn remove<P: AsRef<Path>>(&mut self, path: P) -> Option<String> {
+    self.files.remove(&normalize_path(path.as_ref()))
+  }
+}
+
+impl<P: AsRef<Path>, S: Into<String>> FromIterator<(P, S)> for MemoryProvider {
+  fn from_iter<I: IntoIterator<Item = (P, S)>>(iter: I) -> Self {
+    let mut provider = MemoryProvider::new();
+    for (path, source) in iter {
+      provider.insert(path, source);
+    }
+    provider
   }
 }
 
-impl Drop for FileProvider {
-  fn drop(&mut self) {
-    for ptr in self.inputs.lock().unwrap().iter() {
-      std::mem::drop(unsafe { Box::from_raw(*ptr) })
+impl SourceProvider for MemoryProvider {
+  fn read<'a>(&'a self, file: &Path) -> std::io::Result<Cow<'a, str>> {
+    match self.files.get(&normalize_path(file)) {
+      Some(source) => Ok(Cow::Borrowed(source)),
+      None => Err(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        format!("File not found: {}", file.display()),
+      )),
     }
   }
+
+  fn resolve(&self, specifier: &str, originating_file: &Path) -> std::io::Result<PathBuf> {
+    Ok(normalize_path(&originating_file.with_file_name(specifier)))
+  }
 }
 
 /// An error that could occur during bundling.
 #[derive(Debug, Serialize)]
 pub enum BundleErrorKind<'i> {
+  /// A circular `@import` was encountered. Contains the locations of the @import rules
+  /// that form the cycle, in order.
+  CircularImport(Vec<ErrorLocation>),
   /// An I/O error occurred.
   IOError(#[serde(skip)] std::io::Error),
   /// A parser error occurred.
@@ -134,6 +653,8 @@ pub enum BundleErrorKind<'i> {
   UnsupportedLayerCombination,
   /// Unsupported media query boolean logic was encountered.
   UnsupportedMediaBooleanLogic,
+  /// A CSS modules value imported via `@value` or `:import` is not exported by the referenced file.
+  UnresolvedValue(String),
 }
 
 impl<'i> From<Error<ParserError<'i>>> for Error<BundleErrorKind<'i>> {
@@ -149,11 +670,30 @@ impl<'i> std::fmt::Display for BundleErrorKind<'i> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     use BundleErrorKind::*;
     match self {
+      CircularImport(chain) => write!(f, "Circular @import: {}", format_import_chain(chain)),
       IOError(err) => write!(f, "IO error: {}", err),
       ParserError(err) => err.fmt(f),
       UnsupportedImportCondition => write!(f, "Unsupported import condition"),
       UnsupportedLayerCombination => write!(f, "Unsupported layer combination in @import"),
       UnsupportedMediaBooleanLogic => write!(f, "Unsupported boolean logic in @import media query"),
+      UnresolvedValue(name) => write!(f, "Imported value `{}` is not exported by the referenced file", name),
+    }
+  }
+}
+
+impl<'i, 'any> IntoOwned<'any> for BundleErrorKind<'i> {
+  type Owned = BundleErrorKind<'any>;
+
+  fn into_owned(self) -> Self::Owned {
+    use BundleErrorKind::*;
+    match self {
+      CircularImport(chain) => CircularImport(chain),
+      IOError(err) => IOError(err),
+      ParserError(err) => ParserError(err.into_owned()),
+      UnsupportedImportCondition => UnsupportedImportCondition,
+      UnsupportedLayerCombination => UnsupportedLayerCombination,
+      UnsupportedMediaBooleanLogic => UnsupportedMediaBooleanLogic,
+      UnresolvedValue(name) => UnresolvedValue(name),
     }
   }
 }
@@ -166,24 +706,326 @@ impl<'i> BundleErrorKind<'i> {
   }
 }
 
-impl<'a, 's, P: SourceProvider> Bundler<'a, 's, P> {
+impl<'a, 's, P> Bundler<'a, 's, P> {
   /// Creates a new Bundler using the given source provider.
   /// If a source map is given, the content of each source file included in the bundle will
   /// be added accordingly.
   pub fn new(fs: &'a P, source_map: Option<&'s mut SourceMap>, options: ParserOptions) -> Self {
+    Self::with_options(fs, source_map, options, BundleOptions::default())
+  }
+
+  /// Creates a new Bundler using the given source provider and bundling options.
+  pub fn with_options(
+    fs: &'a P,
+    source_map: Option<&'s mut SourceMap>,
+    options: ParserOptions,
+    bundle_options: BundleOptions,
+  ) -> Self {
     Bundler {
       source_map: source_map.map(Mutex::new),
       fs,
       source_indexes: DashMap::new(),
       stylesheets: Mutex::new(Vec::new()),
       options,
+      bundle_options,
+      entry_dir: PathBuf::new(),
+      keep_parsed: false,
+      warnings: Mutex::new(Vec::new()),
+    }
+  }
+
+  /// Marks the given file as changed, so that the next bundle reads it again when the `cache`
+  /// option is enabled. The file is only parsed again if its contents actually changed.
+  /// The path must match the one returned by [SourceProvider::resolve](SourceProvider::resolve).
+  pub fn invalidate(&mut self, file: &Path) {
+    if let Some(source_index) = self.source_indexes.get(file) {
+      self.stylesheets.get_mut().unwrap()[*source_index as usize].stale = true;
+    }
+  }
+
+  /// Marks all files as changed, so that the next bundle reads all of them again. Only the files
+  /// whose contents changed are parsed again.
+  pub fn invalidate_all(&mut self) {
+    for stylesheet in self.stylesheets.get_mut().unwrap() {
+      stylesheet.stale = true;
+    }
+  }
+
+  /// Returns all files the bundler has attempted to load so far, including files that
+  /// failed to load or parse. This is useful to know which files to watch after an error.
+  pub fn files(&self) -> Vec<PathBuf> {
+    let mut files: Vec<(PathBuf, u32)> = self
+      .source_indexes
+      .iter()
+      .map(|entry| (entry.key().clone(), *entry.value()))
+      .collect();
+    files.sort_by_key(|(_, source_index)| *source_index);
+    files.into_iter().map(|(file, _)| file).collect()
+  }
+}
+
+impl<'a, 's, P: AsyncSourceProvider> Bundler<'a, 's, P> {
+  /// Bundles the given entry file and all dependencies into a single style sheet, reading
+  /// and resolving files asynchronously using an [AsyncSourceProvider](AsyncSourceProvider).
+  ///
+  /// Files are loaded concurrently, one level of the import graph at a time. Once all files
+  /// have been loaded, they are bundled as with [bundle](Bundler::bundle).
+  pub async fn bundle_async<'e>(&mut self, entry: &'e Path) -> Result<Bundle<'a>, Error<BundleErrorKind<'a>>> {
+    let mut sources = HashMap::new();
+    let mut resolved = HashMap::new();
+    let mut asset_paths = Vec::new();
+    let inline_assets = self.bundle_options.inline_asset_limit.is_some();
+    let mut queue = vec![entry.to_owned()];
+    let mut visited: HashSet<PathBuf> = queue.iter().cloned().collect();
+    while !queue.is_empty() {
+      // Files cached by a previous bundle are only read again if they were invalidated.
+      let mut dependencies = Vec::new();
+      let mut files = Vec::new();
+      for file in queue.drain(..) {
+        match self.cached_dependencies(&file, inline_assets) {
+          Some(deps) => dependencies.push((file, deps)),
+          None => files.push(file),
+        }
+      }
+
+      // Files are parsed as soon as they are loaded to find their dependencies, and cached
+      // so that they are not parsed again when bundled.
+      let contents = join_all(files.iter().map(|file| self.fs.read(file)).collect()).await;
+      for (file, code) in files.into_iter().zip(contents) {
+        if let Ok(code) = &code {
+          if let Some(deps) = self.parse_loaded(&file, code, inline_assets) {
+            dependencies.push((file.clone(), deps));
+          }
+        }
+        sources.insert(file, code);
+      }
+
+      let mut specifiers = Vec::new();
+      for (file, deps) in dependencies {
+        for specifier in deps.specifiers {
+          let key = (file.clone(), specifier);
+          if !resolved.contains_key(&key) && !specifiers.contains(&key) {
+            specifiers.push(key);
+          }
+        }
+        for path in deps.assets {
+          if !asset_paths.contains(&path) {
+            asset_paths.push(path);
+          }
+        }
+      }
+
+      let paths = join_all(
+        specifiers
+          .iter()
+          .map(|(file, specifier)| self.fs.resolve(specifier, file))
+          .collect(),
+      )
+      .await;
+
+      for (key, path) in specifiers.into_iter().zip(paths) {
+        if let Ok(path) = &path {
+          if visited.insert(path.clone()) {
+            queue.push(path.clone());
+          }
+        }
+        resolved.insert(key, path);
+      }
+    }
+
+    // Now that all files are loaded, bundle them synchronously.
+    let contents = join_all(asset_paths.iter().map(|path| self.fs.read_asset(path)).collect()).await;
+    let assets = asset_paths.into_iter().zip(contents).collect();
+
+    let loaded = LoadedSources {
+      sources,
+      resolved,
+      assets,
+    };
+    let result = self.bundle_with(entry, &loaded);
+    if !self.bundle_options.cache {
+      for stylesheet in self.stylesheets.get_mut().unwrap() {
+        stylesheet.cache = None;
+      }
+    }
+    result
+  }
+}
+
+impl<'a, 's, P: SourceProvider> Bundler<'a, 's, P> {
+  /// Bundles the given entry file and all dependencies into a single style sheet, and returns it
+  /// along with the import graph of the bundle.
+  ///
+  /// Warnings emitted while bundling, e.g. for relative urls that could not be rebased
+  /// unambiguously, are returned as part of the [ToCssResult](super::stylesheet::ToCssResult).
+  pub fn bundle<'e>(&mut self, entry: &'e Path) -> Result<Bundle<'a>, Error<BundleErrorKind<'a>>> {
+    let sources = ProviderSources(self.fs);
+    self.bundle_with(entry, &sources)
+  }
+
+  /// Bundles multiple entry files, returning a style sheet for each of them.
+  ///
+  /// The import graph is shared between entries, so files imported by several entries are only
+  /// read and parsed once. Source indexes are also shared, so all returned style sheets can be
+  /// printed with the same source map.
+  ///
+  /// When the [extract_shared](BundleOptions::extract_shared) option is enabled, files that are
+  /// bundled identically into multiple entries are moved into shared style sheets instead. The
+  /// returned [BundleManifest](BundleManifest) lists the shared style sheets that each entry
+  /// depends on, which must be loaded in order before the style sheet of the entry itself.
+  /// Files are only extracted if this preserves the cascade order of each entry, i.e. if they
+  /// are concatenated before all other rules of the entry. Entries that import external style
+  /// sheets are never split, because the @import rules must remain at the top of the entry.
+  pub fn bundle_entries(&mut self, entries: &[&Path]) -> Result<MultiEntryBundle<'a>, Error<BundleErrorKind<'a>>> {
+    self.keep_parsed = entries.len() > 1;
+    let sources = ProviderSources(self.fs);
+    let bundles: Result<Vec<_>, _> = entries.iter().map(|entry| self.bundle_entry(entry, &sources)).collect();
+    self.keep_parsed = false;
+    if !self.bundle_options.cache {
+      for stylesheet in self.stylesheets.get_mut().unwrap() {
+        stylesheet.cache = None;
+      }
+    }
+    let bundles = bundles?;
+
+    let chunks = if self.bundle_options.extract_shared {
+      extract_shared(&bundles, self.options.css_modules.is_some())
+    } else {
+      Vec::new()
+    };
+
+    let chunk_indexes: HashMap<FragmentKey, usize> = chunks
+      .iter()
+      .enumerate()
+      .flat_map(|(index, chunk)| chunk.keys.iter().map(move |key| (*key, index)))
+      .collect();
+
+    let sources = self.sources();
+    let mut manifest = BundleManifest {
+      entries: Vec::new(),
+      shared: chunks
+        .iter()
+        .map(|chunk| {
+          let mut files = Vec::new();
+          for (source_index, _) in &chunk.keys {
+            let file = PathBuf::from(&sources[*source_index as usize]);
+            if !files.contains(&file) {
+              files.push(file);
+            }
+          }
+
+          SharedManifest {
+            files,
+            entries: chunk.entries.clone(),
+          }
+        })
+        .collect(),
+    };
+
+    let mut shared_rules: Vec<Vec<CssRule<'a>>> = chunks.iter().map(|_| Vec::new()).collect();
+    let mut shared_scopes: Vec<Option<(u32, Option<Vec<u32>>)>> = chunks.iter().map(|_| None).collect();
+    let mut stylesheets = Vec::new();
+    let mut graphs = Vec::new();
+    for (index, (entry, bundle)) in entries.iter().zip(bundles).enumerate() {
+      let mut shared = Vec::new();
+      let mut rules = Vec::new();
+      for (key, fragment) in fragment_keys(&bundle.fragments).into_iter().zip(bundle.fragments) {
+        match chunk_indexes.get(&key) {
+          Some(&chunk) if chunks[chunk].entries.contains(&index) => {
+            if !shared.contains(&chunk) {
+              shared.push(chunk);
+            }
+
+            // Shared fragments are identical in all entries, so they are taken from the first one.
+            if chunks[chunk].entries[0] == index {
+              shared_rules[chunk].extend(fragment.rules);
+              if shared_scopes[chunk].is_none() {
+                shared_scopes[chunk] = Some((bundle.source_index, bundle.css_module_scopes.clone()));
+              }
+            }
+          }
+          _ => rules.extend(fragment.rules),
+        }
+      }
+
+      manifest.entries.push(EntryManifest {
+        path: entry.to_path_buf(),
+        shared,
+      });
+      graphs.push(bundle.graph);
+
+      stylesheets.push(self.build_stylesheet(
+        rules,
+        bundle.imports,
+        bundle.source_index,
+        bundle.css_module_scopes,
+        bundle.parser_warnings,
+        bundle.warnings,
+      ));
     }
+
+    let shared = shared_rules
+      .into_iter()
+      .zip(shared_scopes)
+      .map(|(rules, scopes)| {
+        let (source_index, css_module_scopes) = scopes.unwrap();
+        self.build_stylesheet(
+          rules,
+          Vec::new(),
+          source_index,
+          css_module_scopes,
+          Vec::new(),
+          Vec::new(),
+        )
+      })
+      .collect();
+
+    Ok(MultiEntryBundle {
+      entries: stylesheets,
+      shared,
+      manifest,
+      graphs,
+    })
+  }
+}
+
+impl<'a, 's, P: Sync> Bundler<'a, 's, P> {
+  /// Bundles the given entry file and all dependencies into a single style sheet, reading files from the given sources.
+  fn bundle_with<S: Sources<'a>>(
+    &mut self,
+    entry: &Path,
+    sources: &S,
+  ) -> Result<Bundle<'a>, Error<BundleErrorKind<'a>>> {
+    let bundle = self.bundle_entry(entry, sources)?;
+    let rules = bundle.fragments.into_iter().flat_map(|fragment| fragment.rules).collect();
+    let stylesheet = self.build_stylesheet(
+      rules,
+      bundle.imports,
+      bundle.source_index,
+      bundle.css_module_scopes,
+      bundle.parser_warnings,
+      bundle.warnings,
+    );
+    Ok(Bundle {
+      stylesheet,
+      graph: bundle.graph,
+    })
   }
 
-  /// Bundles the given entry file and all dependencies into a single style sheet.
-  pub fn bundle<'e>(&mut self, entry: &'e Path) -> Result<StyleSheet<'a>, Error<BundleErrorKind<'a>>> {
+  /// Loads the given entry and all of its dependencies, and concatenates them into fragments.
+  fn bundle_entry<S: Sources<'a>>(
+    &mut self,
+    entry: &Path,
+    sources: &S,
+  ) -> Result<EntryBundle<'a>, Error<BundleErrorKind<'a>>> {
+    self.entry_dir = normalize_path(entry.parent().unwrap_or_else(|| Path::new("")));
+    for stylesheet in self.stylesheets.get_mut().unwrap() {
+      stylesheet.included = false;
+    }
+
     // Phase 1: load and parse all files. This is done in parallel.
-    self.load_file(
+    let source_index = self.load_file(
+      sources,
       &entry,
       ImportRule {
         url: "".into(),
@@ -196,24 +1038,160 @@ impl<'a, 's, P: SourceProvider> Bundler<'a, 's, P> {
           column: 1,
         },
       },
+      None,
     )?;
 
     // Phase 2: determine the order that the files should be concatenated.
-    self.order();
+    self.order(source_index)?;
 
-    // Phase 3: concatenate.
-    let mut rules: Vec<CssRule<'a>> = Vec::new();
-    self.inline(&mut rules);
+    // Phase 3: concatenate. The graph is built first, since the conditions of each file are moved
+    // into the concatenated rules, but its order is only known afterwards.
+    let (mut graph, graph_indexes) = self.build_graph(source_index);
+    let mut fragments = Vec::new();
+    let mut imports = Vec::new();
+    self.inline(source_index, &mut fragments, &mut imports);
 
-    let sources = self
-      .stylesheets
-      .get_mut()
-      .unwrap()
+    // The rules of a file are in its last fragment, which follows the fragments of its @layer statements.
+    let mut seen = HashSet::new();
+    graph.order = fragments
+      .iter()
+      .rev()
+      .filter(|fragment| seen.insert(fragment.source_index))
+      .map(|fragment| graph_indexes[&fragment.source_index])
+      .collect();
+    graph.order.reverse();
+
+    let stylesheets = self.stylesheets.get_mut().unwrap();
+    let css_module_scopes = if self.options.css_modules.is_some() {
+      Some(stylesheets.iter().map(|s| s.css_module_scope).collect())
+    } else {
+      None
+    };
+
+    // Invalid rules and declarations that were skipped in any file of the bundle, due to the `error_recovery` option.
+    let parser_warnings = stylesheets
       .iter()
-      .flat_map(|s| s.stylesheet.as_ref().unwrap().sources.iter().cloned())
+      .filter(|s| s.included)
+      .filter_map(|s| s.stylesheet.as_ref())
+      .flat_map(|s| s.parser_warnings.iter().cloned())
       .collect();
 
-    Ok(StyleSheet::new(sources, CssRuleList(rules), self.options.clone()))
+    Ok(EntryBundle {
+      source_index,
+      fragments,
+      imports,
+      graph,
+      css_module_scopes,
+      parser_warnings,
+      warnings: std::mem::take(self.warnings.get_mut().unwrap()),
+    })
+  }
+
+  /// Builds the import graph of the given entry, without the concatenation order. Also returns
+  /// the index of each file in the graph by source index.
+  fn build_graph(&mut self, entry: u32) -> (BundleGraph, HashMap<u32, usize>) {
+    let stylesheets = &*self.stylesheets.get_mut().unwrap();
+    let filename = |source_index: u32| &stylesheets[source_index as usize].stylesheet.as_ref().unwrap().sources[0];
+
+    // Files are listed in the order they are first referenced, starting with the entry.
+    let mut source_indexes = Vec::new();
+    visit(stylesheets, entry, &mut source_indexes);
+    let indexes: HashMap<u32, usize> = source_indexes.iter().enumerate().map(|(i, s)| (*s, i)).collect();
+
+    let mut files = Vec::new();
+    let mut edges = Vec::new();
+    for source_index in &source_indexes {
+      let stylesheet = &stylesheets[*source_index as usize];
+      files.push(BundleGraphFile {
+        path: PathBuf::from(filename(*source_index)),
+        conditions: ImportConditions::new(&stylesheet.layer, &stylesheet.media, &stylesheet.supports),
+      });
+
+      for edge in &stylesheet.edges {
+        edges.push(BundleGraphEdge {
+          from: indexes[source_index],
+          to: indexes[&edge.source_index],
+          kind: edge.kind,
+          specifier: edge.rule.url.to_string(),
+          conditions: ImportConditions::new(&edge.rule.layer, &edge.rule.media, &edge.rule.supports),
+          loc: ErrorLocation::new(edge.rule.loc, filename(*source_index).clone()),
+        });
+      }
+    }
+
+    fn visit(stylesheets: &[BundleStyleSheet<'_>], source_index: u32, dest: &mut Vec<u32>) {
+      if dest.contains(&source_index) {
+        return;
+      }
+
+      dest.push(source_index);
+      for edge in &stylesheets[source_index as usize].edges {
+        visit(stylesheets, edge.source_index, dest);
+      }
+    }
+
+    let graph = BundleGraph {
+      files,
+      edges,
+      order: Vec::new(),
+    };
+    (graph, indexes)
+  }
+
+  /// Creates a style sheet from bundled rules. The source file names and content hashes
+  /// of all loaded files are included, so that source indexes in the rules remain valid.
+  fn build_stylesheet(
+    &mut self,
+    mut rules: Vec<CssRule<'a>>,
+    imports: Vec<CssRule<'a>>,
+    entry: u32,
+    css_module_scopes: Option<Vec<u32>>,
+    parser_warnings: Vec<Error<ParserError<'a>>>,
+    warnings: Vec<Warning>,
+  ) -> StyleSheet<'a> {
+    // External @import rules must precede all other rules, except for @layer statements.
+    let index = rules
+      .iter()
+      .position(|rule| !matches!(rule, CssRule::LayerStatement(_)))
+      .unwrap_or(rules.len());
+    rules.splice(index..index, imports);
+
+    let stylesheets = self.stylesheets.get_mut().unwrap();
+    let content_hashes = stylesheets
+      .iter()
+      .map(|s| match &s.stylesheet {
+        Some(stylesheet) => stylesheet.content_hashes.clone(),
+        None => Some(vec![String::new()]),
+      })
+      .collect::<Option<Vec<Vec<String>>>>()
+      .map(|hashes| hashes.into_iter().flatten().collect());
+
+    // Files loaded for later entries are not part of this bundle, so their scope does not matter.
+    let css_module_scopes = css_module_scopes.map(|mut scopes: Vec<u32>| {
+      scopes.extend(scopes.len() as u32..stylesheets.len() as u32);
+      scopes
+    });
+
+    let mut stylesheet = StyleSheet::new(self.sources(), CssRuleList(rules), self.options.clone());
+    stylesheet.content_hashes = content_hashes;
+    stylesheet.css_module_scopes = css_module_scopes;
+    stylesheet.css_module_entry = entry;
+    stylesheet.parser_warnings = parser_warnings;
+    stylesheet.warnings = warnings;
+    stylesheet
+  }
+
+  fn sources(&self) -> Vec<String> {
+    let stylesheets = self.stylesheets.lock().unwrap();
+    stylesheets
+      .iter()
+      .enumerate()
+      .map(|(source_index, s)| match &s.stylesheet {
+        Some(stylesheet) => stylesheet.sources[0].clone(),
+        // Files that failed to load in a previous bundle are not part of this one.
+        None => self.find_filename(source_index as u32),
+      })
+      .collect()
   }
 
   fn find_filename(&self, source_index: u32) -> String {
@@ -222,14 +1200,21 @@ impl<'a, 's, P: SourceProvider> Bundler<'a, 's, P> {
     entry.key().to_str().unwrap().into()
   }
 
-  fn load_file(&self, file: &Path, rule: ImportRule<'a>) -> Result<u32, Error<BundleErrorKind<'a>>> {
+  fn load_file<S: Sources<'a>>(
+    &self,
+    sources: &S,
+    file: &Path,
+    rule: ImportRule<'a>,
+    css_module_scope: Option<u32>,
+  ) -> Result<u32, Error<BundleErrorKind<'a>>> {
     // Check if we already loaded this file.
     let mut stylesheets = self.stylesheets.lock().unwrap();
-    let source_index = match self.source_indexes.get(file) {
-      Some(source_index) => {
+    let source_index = self.source_indexes.get(file).map(|source_index| *source_index);
+    let (source_index, css_module_scope) = match source_index {
+      Some(source_index) if stylesheets[source_index as usize].included => {
         // If we already loaded this file, combine the media queries and supports conditions
         // from this import rule with the existing ones using a logical or operator.
-        let entry = &mut stylesheets[*source_index as usize];
+        let entry = &mut stylesheets[source_index as usize];
 
         // We cannot combine a media query and a supports query from different @import rules.
         // e.g. @import "a.css" print; @import "a.css" supports(color: red);
@@ -271,48 +1256,92 @@ impl<'a, 's, P: SourceProvider> Bundler<'a, 's, P> {
           }
         }
 
-        return Ok(*source_index);
+        return Ok(source_index);
       }
-      None => {
-        let source_index = stylesheets.len() as u32;
-        self.source_indexes.insert(file.to_owned(), source_index);
-
-        stylesheets.push(BundleStyleSheet {
-          stylesheet: None,
-          layer: rule.layer.clone(),
-          media: rule.media.clone(),
-          supports: rule.supports.clone(),
-          loc: rule.loc.clone(),
-          dependencies: Vec::new(),
-          parent_source_index: 0,
-          parent_dep_index: 0,
-        });
+      source_index => {
+        // Files loaded for a previous entry keep their source index, so that
+        // source indexes are consistent between the bundles of all entries.
+        let source_index = source_index.unwrap_or_else(|| self.add_file(&mut stylesheets, file));
 
-        source_index
+        // Files without a parent scope (i.e. the entry, and files referenced via `composes`) define their own.
+        let css_module_scope = css_module_scope.unwrap_or(source_index);
+
+        let entry = &mut stylesheets[source_index as usize];
+        entry.included = true;
+        entry.layer = rule.layer.clone();
+        entry.media = rule.media.clone();
+        entry.supports = rule.supports.clone();
+        entry.loc = rule.loc;
+        entry.dependencies.clear();
+        entry.css_modules_deps.clear();
+        entry.edges.clear();
+        entry.css_module_scope = css_module_scope;
+        entry.parent_source_index = u32::MAX;
+        entry.parent_dep_index = 0;
+
+        (source_index, css_module_scope)
       }
     };
 
-    drop(stylesheets); // ensure we aren't holding the lock anymore
-
-    let code = self.fs.read(file).map_err(|e| Error {
-      kind: BundleErrorKind::IOError(e),
-      loc: Some(ErrorLocation::new(rule.loc, self.find_filename(rule.loc.source_index))),
-    })?;
+    // Reuse the cached style sheet if the file was already loaded for a previous entry or bundle.
+    let filename = file.to_str().unwrap();
+    let entry = &stylesheets[source_index as usize];
+    let cached = match &entry.cache {
+      Some(cache) if !entry.stale => Some(cache.to_stylesheet(filename, &self.options)),
+      _ => None,
+    };
 
-    let mut opts = self.options.clone();
-    opts.source_index = source_index;
+    drop(stylesheets); // ensure we aren't holding the lock anymore
 
-    let filename = file.to_str().unwrap();
-    if let Some(source_map) = &self.source_map {
-      let mut source_map = source_map.lock().unwrap();
-      let source_index = source_map.add_source(filename);
-      let _ = source_map.set_source_content(source_index as usize, code);
-    }
+    let mut stylesheet = match cached {
+      Some(stylesheet) => stylesheet,
+      None => {
+        let code = sources.read(file).map_err(|e| Error {
+          kind: BundleErrorKind::IOError(e),
+          loc: Some(ErrorLocation::new(rule.loc, self.find_filename(rule.loc.source_index))),
+        })?;
+
+        // Files that were invalidated are only parsed again if their contents changed.
+        let hash = hash_source(code.as_str());
+        let mut stylesheets = self.stylesheets.lock().unwrap();
+        let entry = &mut stylesheets[source_index as usize];
+        let cached = match &entry.cache {
+          Some(cache) if cache.hash == hash => Some(cache.to_stylesheet(filename, &self.options)),
+          _ => None,
+        };
+        if cached.is_some() {
+          entry.stale = false;
+        }
+        drop(stylesheets);
 
-    let mut stylesheet = StyleSheet::parse(filename, code, opts)?;
+        match cached {
+          Some(stylesheet) => stylesheet,
+          None => {
+            let stylesheet = self.parse_file(filename, code, source_index)?;
+            let entry = &mut self.stylesheets.lock().unwrap()[source_index as usize];
+            entry.stale = false;
+            entry.cache = if self.keep_parsed || self.bundle_options.cache {
+              Some(CachedStyleSheet::new(hash, &stylesheet))
+            } else {
+              None
+            };
+            stylesheet
+          }
+        }
+      }
+    };
+    if let Some(limit) = self.bundle_options.inline_asset_limit {
+      let dir = normalize_path(file.parent().unwrap_or_else(|| Path::new("")));
+      stylesheet.visit(&mut AssetInliner {
+        fs: sources,
+        dir: &dir,
+        limit,
+      });
+    }
+    self.rebase_urls(&mut stylesheet, file, source_index);
 
     // Collect and load dependencies for this stylesheet in parallel.
-    let dependencies: Result<Vec<u32>, _> = stylesheet
+    let dependencies: Result<Vec<(u32, Edge<'a>)>, _> = stylesheet
       .rules
       .0
       .par_iter_mut()
@@ -327,8 +1356,6 @@ impl<'a, 's, P: SourceProvider> Bundler<'a, 's, P> {
         }
 
         if let CssRule::Import(import) = r {
-          let path = file.with_file_name(&*import.url);
-
           // Combine media queries and supports conditions from parent
           // stylesheet with @import rule using a logical and operator.
           let mut media = rule.media.clone();
@@ -367,35 +1394,445 @@ impl<'a, 's, P: SourceProvider> Bundler<'a, 's, P> {
             import.layer.clone()
           };
 
+          let supports = combine_supports(rule.supports.clone(), &import.supports);
+
+          // Remote style sheets cannot be bundled. Keep the @import rule with the combined
+          // conditions instead, so that it can be hoisted to the top of the bundle.
+          if is_external_import(&import.url) {
+            import.media = media;
+            import.supports = supports;
+            import.layer = layer.or_else(|| rule.layer.clone());
+            return None;
+          }
+
+          let path = match sources.resolve(&import.url, file) {
+            Ok(path) => path,
+            Err(e) => {
+              return Some(Err(Error {
+                kind: BundleErrorKind::IOError(e),
+                loc: Some(ErrorLocation::new(
+                  import.loc,
+                  self.find_filename(import.loc.source_index),
+                )),
+              }))
+            }
+          };
+
           let result = self.load_file(
+            sources,
             &path,
             ImportRule {
               layer,
               media,
-              supports: combine_supports(rule.supports.clone(), &import.supports),
+              supports,
               url: "".into(),
               loc: import.loc,
             },
+            Some(css_module_scope),
           );
 
-          Some(result)
+          Some(result.map(|dep_source_index| {
+            let edge = Edge {
+              source_index: dep_source_index,
+              kind: BundleGraphEdgeKind::Import,
+              rule: import.clone(),
+            };
+            (dep_source_index, edge)
+          }))
         } else {
           None
         }
       })
       .collect();
 
+    let (dependencies, mut edges): (Vec<u32>, Vec<Edge<'a>>) = dependencies?.into_iter().unzip();
+
+    // Load files referenced by the CSS modules `composes` property in parallel, and resolve
+    // the references to the source index of the loaded file.
+    let mut css_modules_deps = Vec::new();
+    if self.options.css_modules.is_some() {
+      let deps: Result<Vec<(u32, Edge<'a>)>, _> = stylesheet
+        .rules
+        .0
+        .par_iter_mut()
+        .filter_map(|r| match r {
+          CssRule::Style(style) => Some(style),
+          _ => None,
+        })
+        .flat_map(|style| {
+          let declarations = &mut style.declarations;
+          declarations
+            .declarations
+            .par_iter_mut()
+            .chain(declarations.important_declarations.par_iter_mut())
+        })
+        .filter_map(|property| {
+          let composes = match property {
+            Property::Composes(composes) => composes,
+            _ => return None,
+          };
+
+          let loc = Location {
+            source_index,
+            line: composes.loc.line,
+            column: composes.loc.column,
+          };
+
+          let specifier = match &composes.from {
+            Some(ComposesFrom::File(f)) => f.clone(),
+            _ => return None,
+          };
+
+          let path = match sources.resolve(&specifier, file) {
+            Ok(path) => path,
+            Err(e) => {
+              return Some(Err(Error {
+                kind: BundleErrorKind::IOError(e),
+                loc: Some(ErrorLocation::new(loc, self.find_filename(source_index))),
+              }))
+            }
+          };
+
+          let result = self.load_file(
+            sources,
+            &path,
+            ImportRule {
+              layer: None,
+              media: MediaList::new(),
+              supports: None,
+              url: "".into(),
+              loc,
+            },
+            None,
+          );
+
+          Some(result.map(|dep_source_index| {
+            composes.from = Some(ComposesFrom::SourceIndex(dep_source_index));
+            let edge = Edge {
+              source_index: dep_source_index,
+              kind: BundleGraphEdgeKind::Composes,
+              rule: ImportRule {
+                layer: None,
+                media: MediaList::new(),
+                supports: None,
+                url: specifier,
+                loc,
+              },
+            };
+            (dep_source_index, edge)
+          }))
+        })
+        .collect();
+
+      for (dep_source_index, edge) in deps? {
+        if dep_source_index != source_index {
+          if !css_modules_deps.contains(&dep_source_index) {
+            css_modules_deps.push(dep_source_index);
+          }
+          edges.push(edge);
+        }
+      }
+
+      // Load files that values are imported from via `@value` or `:import` rules in parallel. These are
+      // included in the bundle like files referenced via `composes`, and the placeholders substituted for
+      // the imported values while parsing are replaced with the values exported by the loaded file.
+      let deps: Result<Vec<(usize, u32, Edge<'a>)>, _> = stylesheet
+        .rules
+        .0
+        .par_iter_mut()
+        .enumerate()
+        .filter_map(|(index, r)| match r {
+          CssRule::IcssImport(import) => Some((index, &*import)),
+          _ => None,
+        })
+        .map(|(index, import)| {
+          let path = sources.resolve(&import.specifier, file).map_err(|e| Error {
+            kind: BundleErrorKind::IOError(e),
+            loc: Some(ErrorLocation::new(import.loc, self.find_filename(source_index))),
+          })?;
+
+          let rule = ImportRule {
+            layer: None,
+            media: MediaList::new(),
+            supports: None,
+            url: import.specifier.clone(),
+            loc: import.loc,
+          };
+          let dep_source_index = self.load_file(sources, &path, rule.clone(), None)?;
+          let edge = Edge {
+            source_index: dep_source_index,
+            kind: BundleGraphEdgeKind::Value,
+            rule,
+          };
+          Ok((index, dep_source_index, edge))
+        })
+        .collect();
+
+      let mut values = HashMap::new();
+      let mut resolved = HashMap::new();
+      for (index, dep_source_index, edge) in deps? {
+        let import = match &stylesheet.rules.0[index] {
+          CssRule::IcssImport(import) => import,
+          _ => unreachable!(),
+        };
+
+        // The imported file has no values yet if it is still being loaded, i.e. it imports this file.
+        let exported = self.exported_values(dep_source_index);
+        let mut rules = Vec::new();
+        for IcssImport { local, imported } in &import.imports {
+          let value = match exported.get(imported) {
+            Some(value) => value.clone(),
+            None => {
+              return Err(Error {
+                kind: BundleErrorKind::UnresolvedValue(imported.to_string()),
+                loc: Some(ErrorLocation::new(import.loc, self.find_filename(source_index))),
+              })
+            }
+          };
+
+          values.insert(import_placeholder(&import.specifier, imported), value.to_string());
+          rules.push(CssRule::Value(ValueRule {
+            name: local.clone(),
+            value,
+            loc: import.loc,
+          }));
+        }
+        resolved.insert(index, rules);
+
+        if dep_source_index != source_index {
+          if !css_modules_deps.contains(&dep_source_index) {
+            css_modules_deps.push(dep_source_index);
+          }
+          edges.push(edge);
+        }
+      }
+
+      // Replace the imports with the resolved values, so that they are exported as is.
+      if !resolved.is_empty() {
+        let rules = std::mem::take(&mut stylesheet.rules.0);
+        for (index, rule) in rules.into_iter().enumerate() {
+          match resolved.remove(&index) {
+            Some(values) => stylesheet.rules.0.extend(values),
+            None => stylesheet.rules.0.push(rule),
+          }
+        }
+        replace_imported_values(&mut stylesheet.rules, &values, &self.options);
+      }
+    }
+
     let entry = &mut self.stylesheets.lock().unwrap()[source_index as usize];
     entry.stylesheet = Some(stylesheet);
-    entry.dependencies = dependencies?;
+    entry.dependencies = dependencies;
+    entry.css_modules_deps = css_modules_deps;
+    entry.edges = edges;
 
     Ok(source_index)
   }
 
-  fn order(&mut self) {
-    process(self.stylesheets.get_mut().unwrap(), 0, &mut HashSet::new());
+  /// Returns the source index of the given file, adding it to the list of files if it was not loaded before.
+  fn add_file(&self, stylesheets: &mut Vec<BundleStyleSheet<'a>>, file: &Path) -> u32 {
+    if let Some(source_index) = self.source_indexes.get(file) {
+      return *source_index;
+    }
+
+    let source_index = stylesheets.len() as u32;
+    self.source_indexes.insert(file.to_owned(), source_index);
+    stylesheets.push(BundleStyleSheet {
+      stylesheet: None,
+      cache: None,
+      stale: false,
+      included: false,
+      layer: None,
+      media: MediaList::new(),
+      supports: None,
+      loc: Location {
+        source_index,
+        line: 0,
+        column: 1,
+      },
+      dependencies: Vec::new(),
+      css_modules_deps: Vec::new(),
+      edges: Vec::new(),
+      css_module_scope: source_index,
+      parent_source_index: u32::MAX,
+      parent_dep_index: 0,
+    });
+    source_index
+  }
+
+  /// Returns the dependencies of a file cached by a previous bundle, unless it was invalidated since.
+  fn cached_dependencies(&self, file: &Path, assets: bool) -> Option<SourceDependencies> {
+    let source_index = *self.source_indexes.get(file)?;
+    let stylesheets = self.stylesheets.lock().unwrap();
+    let entry = &stylesheets[source_index as usize];
+    match &entry.cache {
+      Some(cache) if !entry.stale => {
+        let mut stylesheet = cache.to_stylesheet(file.to_str().unwrap(), &self.options);
+        Some(stylesheet_dependencies(file, &mut stylesheet, &self.options, assets))
+      }
+      _ => None,
+    }
+  }
+
+  /// Parses a file loaded by [bundle_async](Bundler::bundle_async) and caches it, so that it is not
+  /// parsed again when bundled. Returns the dependencies of the file, or `None` if it failed to parse,
+  /// in which case the error is reported when the file is bundled.
+  fn parse_loaded(&self, file: &Path, code: &str, assets: bool) -> Option<SourceDependencies> {
+    let source_index = self.add_file(&mut self.stylesheets.lock().unwrap(), file);
+    let filename = file.to_str().unwrap();
+    let hash = hash_source(code);
+
+    // Files that were invalidated are only parsed again if their contents changed.
+    let cached = match &self.stylesheets.lock().unwrap()[source_index as usize].cache {
+      Some(cache) if cache.hash == hash => Some(cache.to_stylesheet(filename, &self.options)),
+      _ => None,
+    };
+
+    let mut stylesheet = match cached {
+      Some(stylesheet) => stylesheet,
+      None => {
+        let stylesheet = self
+          .parse_file(filename, Source::Owned(Cow::Borrowed(code)), source_index)
+          .ok()?;
+        self.stylesheets.lock().unwrap()[source_index as usize].cache =
+          Some(CachedStyleSheet::new(hash, &stylesheet));
+        stylesheet
+      }
+    };
+
+    self.stylesheets.lock().unwrap()[source_index as usize].stale = false;
+    Some(stylesheet_dependencies(file, &mut stylesheet, &self.options, assets))
+  }
+
+  /// Parses the given source code, and adds it to the source map.
+  fn parse_file(
+    &self,
+    filename: &str,
+    code: Source<'a, '_>,
+    source_index: u32,
+  ) -> Result<StyleSheet<'a>, Error<BundleErrorKind<'a>>> {
+    let mut opts = self.options.clone();
+    opts.source_index = source_index;
+
+    if let Some(source_map) = &self.source_map {
+      let mut source_map = source_map.lock().unwrap();
+      let source_index = source_map.add_source(filename);
+      let _ = source_map.set_source_content(source_index as usize, code.as_str());
+    }
+
+    match code {
+      Source::Borrowed(code) => Ok(StyleSheet::parse(filename, code, opts)?),
+      Source::Owned(code) => match StyleSheet::parse(filename, &code, opts) {
+        Ok(stylesheet) => Ok(stylesheet.into_owned()),
+        Err(err) => Err(err.into_owned().into()),
+      },
+    }
+  }
+
+  /// Returns the values exported by a loaded file via `@value` and `:export` rules.
+  fn exported_values(&self, source_index: u32) -> HashMap<CowArcStr<'a>, CowArcStr<'a>> {
+    let mut values = HashMap::new();
+    let stylesheets = self.stylesheets.lock().unwrap();
+    let stylesheet = match &stylesheets[source_index as usize].stylesheet {
+      Some(stylesheet) => stylesheet,
+      None => return values,
+    };
+
+    for rule in &stylesheet.rules.0 {
+      match rule {
+        CssRule::Value(rule) => {
+          values.insert(rule.name.clone(), rule.value.clone());
+        }
+        CssRule::IcssExport(rule) => {
+          for export in &rule.exports {
+            values.insert(export.name.clone(), export.value.clone());
+          }
+        }
+        _ => {}
+      }
+    }
+
+    values
+  }
+
+  /// Rewrites relative urls in the given style sheet so that they resolve to the same
+  /// files once inlined into the entry, according to the `url_rebase` option.
+  fn rebase_urls(&self, stylesheet: &mut StyleSheet<'a>, file: &Path, source_index: u32) {
+    let dir = normalize_path(file.parent().unwrap_or_else(|| Path::new("")));
+    let root = match &self.bundle_options.url_rebase {
+      UrlRebase::Preserve => return,
+      UrlRebase::Entry if dir == self.entry_dir => return,
+      UrlRebase::Entry => None,
+      UrlRebase::Root(root) => Some(normalize_path(root)),
+    };
+
+    let mut rebaser = UrlRebaser {
+      dir: &dir,
+      entry_dir: &self.entry_dir,
+      root: root.as_deref(),
+      in_custom_property: false,
+      ambiguous: Vec::new(),
+    };
+    stylesheet.visit(&mut rebaser);
+
+    if !rebaser.ambiguous.is_empty() {
+      let filename = file.to_str().unwrap();
+      let mut warnings = self.warnings.lock().unwrap();
+      for (url, loc) in rebaser.ambiguous {
+        warnings.push(Warning {
+          kind: WarningKind::AmbiguousUrlInCustomProperty { url },
+          loc: Some(ErrorLocation::new(
+            Location {
+              source_index,
+              line: loc.line,
+              column: loc.column,
+            },
+            filename.into(),
+          )),
+        })
+      }
+    }
+  }
+
+  fn order(&mut self, source_index: u32) -> Result<(), Error<BundleErrorKind<'a>>> {
+    let mut cycles = Vec::new();
+    process(
+      self.stylesheets.get_mut().unwrap(),
+      source_index,
+      &mut HashSet::new(),
+      &mut Vec::new(),
+      &mut cycles,
+    );
+
+    for cycle in cycles {
+      let chain: Vec<ErrorLocation> = cycle
+        .into_iter()
+        .map(|loc| ErrorLocation::new(loc, self.find_filename(loc.source_index)))
+        .collect();
+      let loc = chain.last().cloned();
+      match self.bundle_options.circular_imports {
+        CircularImports::Error => {
+          return Err(Error {
+            kind: BundleErrorKind::CircularImport(chain),
+            loc,
+          })
+        }
+        CircularImports::Warn => self.warnings.get_mut().unwrap().push(Warning {
+          kind: WarningKind::CircularImport { chain },
+          loc,
+        }),
+      }
+    }
 
-    fn process(stylesheets: &mut Vec<BundleStyleSheet<'_>>, source_index: u32, visited: &mut HashSet<u32>) {
+    /// Visits the dependencies of the given file. `chain` contains the @import rules that led to it,
+    /// as pairs of source index and dependency index, and any cycles found are added to `cycles`.
+    fn process(
+      stylesheets: &mut Vec<BundleStyleSheet<'_>>,
+      source_index: u32,
+      visited: &mut HashSet<u32>,
+      chain: &mut Vec<(u32, u32)>,
+      cycles: &mut Vec<Vec<Location>>,
+    ) {
       if visited.contains(&source_index) {
         return;
       }
@@ -404,34 +1841,95 @@ impl<'a, 's, P: SourceProvider> Bundler<'a, 's, P> {
 
       for dep_index in 0..stylesheets[source_index as usize].dependencies.len() {
         let dep_source_index = stylesheets[source_index as usize].dependencies[dep_index];
-        let mut resolved = &mut stylesheets[dep_source_index as usize];
+
+        // Browsers ignore an @import of a style sheet that is already being imported.
+        let cycle_start = if dep_source_index == source_index {
+          Some(chain.len())
+        } else {
+          chain.iter().position(|(s, _)| *s == dep_source_index)
+        };
+        if let Some(start) = cycle_start {
+          cycles.push(
+            chain[start..]
+              .iter()
+              .chain(std::iter::once(&(source_index, dep_index as u32)))
+              .map(|(s, d)| import_loc(&stylesheets[*s as usize], *d))
+              .collect(),
+          );
+          continue;
+        }
+
+        let resolved = &mut stylesheets[dep_source_index as usize];
 
         // In browsers, every instance of an @import is evaluated, so we preserve the last.
         resolved.parent_dep_index = dep_index as u32;
         resolved.parent_source_index = source_index;
 
-        process(stylesheets, dep_source_index, visited);
+        chain.push((source_index, dep_index as u32));
+        process(stylesheets, dep_source_index, visited, chain, cycles);
+        chain.pop();
+      }
+
+      // Files referenced via `composes` are ordered after @import dependencies, but before the rules of this file.
+      let num_imports = stylesheets[source_index as usize].dependencies.len();
+      for i in 0..stylesheets[source_index as usize].css_modules_deps.len() {
+        let dep_source_index = stylesheets[source_index as usize].css_modules_deps[i];
+        let resolved = &mut stylesheets[dep_source_index as usize];
+        resolved.parent_dep_index = (num_imports + i) as u32;
+        resolved.parent_source_index = source_index;
+
+        // Files referenced via `composes` start a new @import chain.
+        process(stylesheets, dep_source_index, visited, &mut Vec::new(), cycles);
       }
     }
+
+    /// Returns the location of the @import rule for the given dependency index.
+    /// This is only used for error handling, so it's ok if this is a bit slow.
+    fn import_loc(stylesheet: &BundleStyleSheet<'_>, dep_index: u32) -> Location {
+      stylesheet
+        .stylesheet
+        .as_ref()
+        .unwrap()
+        .rules
+        .0
+        .iter()
+        .filter_map(|rule| match rule {
+          CssRule::Import(import) if !is_external_import(&import.url) => Some(import.loc),
+          _ => None,
+        })
+        .nth(dep_index as usize)
+        .unwrap()
+    }
+
+    Ok(())
   }
 
-  fn inline(&mut self, dest: &mut Vec<CssRule<'a>>) {
-    process(self.stylesheets.get_mut().unwrap(), 0, dest);
+  fn inline(&mut self, source_index: u32, dest: &mut Vec<Fragment<'a>>, imports: &mut Vec<CssRule<'a>>) {
+    process(self.stylesheets.get_mut().unwrap(), source_index, dest, imports);
 
-    fn process<'a>(stylesheets: &mut Vec<BundleStyleSheet<'a>>, source_index: u32, dest: &mut Vec<CssRule<'a>>) {
+    fn process<'a>(
+      stylesheets: &mut Vec<BundleStyleSheet<'a>>,
+      source_index: u32,
+      dest: &mut Vec<Fragment<'a>>,
+      imports: &mut Vec<CssRule<'a>>,
+    ) {
       let stylesheet = &mut stylesheets[source_index as usize];
       let mut rules = std::mem::take(&mut stylesheet.stylesheet.as_mut().unwrap().rules.0);
 
       let mut dep_index = 0;
       for rule in &mut rules {
         match rule {
+          CssRule::Import(import) if is_external_import(&import.url) => {
+            // External imports are hoisted, in the order they are encountered.
+            imports.push(std::mem::replace(rule, CssRule::Ignored));
+          }
           CssRule::Import(_) => {
             let dep_source_index = stylesheets[source_index as usize].dependencies[dep_index as usize];
             let resolved = &stylesheets[dep_source_index as usize];
 
             // Include the dependency if this is the last instance as computed earlier.
             if resolved.parent_source_index == source_index && resolved.parent_dep_index == dep_index {
-              process(stylesheets, dep_source_index, dest);
+              process(stylesheets, dep_source_index, dest, imports);
             }
 
             *rule = CssRule::Ignored;
@@ -441,13 +1939,27 @@ impl<'a, 's, P: SourceProvider> Bundler<'a, 's, P> {
             // @layer rules are the only rules that may appear before an @import.
             // We must preserve this order to ensure correctness.
             let layer = std::mem::replace(rule, CssRule::Ignored);
-            dest.push(layer);
+            dest.push(Fragment {
+              source_index,
+              rules: vec![layer],
+            });
           }
           CssRule::Ignored => {}
           _ => break,
         }
       }
 
+      // Include files referenced via the CSS modules `composes` property before the rules of this file,
+      // so that the composing class names have a higher precedence.
+      let num_imports = stylesheets[source_index as usize].dependencies.len();
+      let css_modules_deps = std::mem::take(&mut stylesheets[source_index as usize].css_modules_deps);
+      for (i, dep_source_index) in css_modules_deps.into_iter().enumerate() {
+        let resolved = &stylesheets[dep_source_index as usize];
+        if resolved.parent_source_index == source_index && resolved.parent_dep_index == (num_imports + i) as u32 {
+          process(stylesheets, dep_source_index, dest, imports);
+        }
+      }
+
       // Wrap rules in the appropriate @media and @supports rules.
       let stylesheet = &mut stylesheets[source_index as usize];
       if !stylesheet.media.media_queries.is_empty() {
@@ -474,126 +1986,1094 @@ impl<'a, 's, P: SourceProvider> Bundler<'a, 's, P> {
         })]
       }
 
-      dest.extend(rules);
+      dest.push(Fragment { source_index, rules });
     }
   }
 }
 
-fn combine_supports<'a>(
-  a: Option<SupportsCondition<'a>>,
-  b: &Option<SupportsCondition<'a>>,
-) -> Option<SupportsCondition<'a>> {
-  if let Some(mut a) = a {
-    if let Some(b) = b {
-      a.and(b)
+/// A parsed file, along with a hash of its contents.
+#[derive(Debug)]
+struct CachedStyleSheet<'i> {
+  hash: u64,
+  rules: CssRuleList<'i>,
+  content_hashes: Option<Vec<String>>,
+  parser_warnings: Vec<Error<ParserError<'i>>>,
+}
+
+impl<'i> CachedStyleSheet<'i> {
+  fn new(hash: u64, stylesheet: &StyleSheet<'i>) -> CachedStyleSheet<'i> {
+    CachedStyleSheet {
+      hash,
+      rules: stylesheet.rules.clone(),
+      content_hashes: stylesheet.content_hashes.clone(),
+      parser_warnings: stylesheet.parser_warnings.clone(),
     }
-    Some(a)
-  } else {
-    b.clone()
+  }
+
+  fn to_stylesheet(&self, filename: &str, options: &ParserOptions) -> StyleSheet<'i> {
+    let mut stylesheet = StyleSheet::new(vec![filename.into()], self.rules.clone(), options.clone());
+    stylesheet.content_hashes = self.content_hashes.clone();
+    stylesheet.parser_warnings = self.parser_warnings.clone();
+    stylesheet
   }
 }
 
-#[cfg(test)]
-mod tests {
-  use super::*;
-  use crate::{
-    stylesheet::{MinifyOptions, PrinterOptions},
-    targets::Browsers,
-  };
-  use indoc::indoc;
-  use std::collections::HashMap;
+fn hash_source(code: &str) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  code.hash(&mut hasher);
+  hasher.finish()
+}
 
-  struct TestProvider {
-    map: HashMap<PathBuf, String>,
-  }
+/// An @import rule or `composes` reference of a file, recorded for the [BundleGraph].
+#[derive(Debug)]
+struct Edge<'i> {
+  /// The source index of the referenced file.
+  source_index: u32,
+  kind: BundleGraphEdgeKind,
+  rule: ImportRule<'i>,
+}
 
-  impl SourceProvider for TestProvider {
-    fn read<'a>(&'a self, file: &Path) -> std::io::Result<&'a str> {
-      Ok(self.map.get(file).unwrap())
+/// The rules contributed by a file to a bundle. A file contributes a fragment for each @layer statement
+/// preceding its @import rules, followed by a fragment containing the rest of its rules.
+struct Fragment<'i> {
+  source_index: u32,
+  rules: Vec<CssRule<'i>>,
+}
+
+/// Identifies a fragment across entries by the source index of the file,
+/// and the index of the fragment among those of the same file.
+type FragmentKey = (u32, usize);
+
+/// The bundled fragments of an entry, before they are combined into a style sheet.
+struct EntryBundle<'i> {
+  source_index: u32,
+  fragments: Vec<Fragment<'i>>,
+  imports: Vec<CssRule<'i>>,
+  graph: BundleGraph,
+  css_module_scopes: Option<Vec<u32>>,
+  parser_warnings: Vec<Error<ParserError<'i>>>,
+  warnings: Vec<Warning>,
+}
+
+/// A group of fragments shared between the same entries.
+struct Chunk {
+  keys: Vec<FragmentKey>,
+  entries: Vec<usize>,
+}
+
+fn fragment_keys(fragments: &[Fragment<'_>]) -> Vec<FragmentKey> {
+  let mut counts = HashMap::new();
+  fragments
+    .iter()
+    .map(|fragment| {
+      let count = counts.entry(fragment.source_index).or_insert(0);
+      *count += 1;
+      (fragment.source_index, *count - 1)
+    })
+    .collect()
+}
+
+/// Determines which fragments can be shared between entries. A fragment is shared when it is
+/// identical in all entries that include it, and it is grouped with the fragments included by
+/// exactly the same entries. To preserve the cascade order, the shared fragments must form a prefix
+/// of each entry, and the fragments of each group must be contiguous and in the same order.
+/// Fragments that break these rules are not shared, and the rules are checked again until they hold.
+fn extract_shared(bundles: &[EntryBundle<'_>], css_modules: bool) -> Vec<Chunk> {
+  // Entries that import external style sheets must keep them at the top, so they cannot load shared
+  // style sheets first.
+  let keys: Vec<Option<Vec<FragmentKey>>> = bundles
+    .iter()
+    .map(|bundle| {
+      if bundle.imports.is_empty() {
+        Some(fragment_keys(&bundle.fragments))
+      } else {
+        None
+      }
+    })
+    .collect();
+
+  let mut owners: HashMap<FragmentKey, Vec<(usize, usize)>> = HashMap::new();
+  for (entry, keys) in keys.iter().enumerate() {
+    for (index, key) in keys.iter().flatten().enumerate() {
+      owners.entry(*key).or_default().push((entry, index));
     }
   }
 
-  macro_rules! fs(
-    { $($key:literal: $value:expr),* } => {
-      {
-        #[allow(unused_mut)]
-        let mut m = HashMap::new();
-        $(
-          m.insert(PathBuf::from($key), $value.to_owned());
-        )*
-        TestProvider {
-          map: m
+  // Fragments of CSS modules are compiled within the scope of the entry, unless referenced via `composes`.
+  let scope = |entry: usize, source_index: u32| {
+    bundles[entry]
+      .css_module_scopes
+      .as_ref()
+      .map(|scopes| scopes[source_index as usize])
+  };
+
+  let mut shared: HashSet<FragmentKey> = owners
+    .iter()
+    .filter(|(key, owners)| {
+      let (first, first_index) = owners[0];
+      owners.len() > 1
+        && owners[1..].iter().all(|(entry, index)| {
+          bundles[*entry].fragments[*index].rules == bundles[first].fragments[first_index].rules
+            && (!css_modules || scope(*entry, key.0) == scope(first, key.0))
+        })
+    })
+    .map(|(key, _)| *key)
+    .collect();
+
+  loop {
+    // Shared fragments must be concatenated before all other fragments of an entry.
+    let mut changed = false;
+    for keys in keys.iter().flatten() {
+      let mut prefix = true;
+      for key in keys {
+        if !shared.contains(key) {
+          prefix = false;
+        } else if !prefix {
+          shared.remove(key);
+          changed = true;
         }
       }
-    };
-  );
-
-  fn bundle(fs: TestProvider, entry: &str) -> String {
-    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
-    let stylesheet = bundler.bundle(Path::new(entry)).unwrap();
-    stylesheet.to_css(PrinterOptions::default()).unwrap().code
-  }
+    }
 
-  fn bundle_css_module(fs: TestProvider, entry: &str) -> String {
-    let mut bundler = Bundler::new(
-      &fs,
-      None,
-      ParserOptions {
-        css_modules: true,
-        ..ParserOptions::default()
-      },
-    );
-    let stylesheet = bundler.bundle(Path::new(entry)).unwrap();
-    stylesheet.to_css(PrinterOptions::default()).unwrap().code
-  }
+    if changed {
+      continue;
+    }
 
-  fn bundle_custom_media(fs: TestProvider, entry: &str) -> String {
-    let mut bundler = Bundler::new(
-      &fs,
-      None,
-      ParserOptions {
-        custom_media: true,
-        ..ParserOptions::default()
-      },
-    );
-    let mut stylesheet = bundler.bundle(Path::new(entry)).unwrap();
-    let targets = Some(Browsers {
-      safari: Some(13 << 16),
-      ..Browsers::default()
-    });
-    stylesheet
-      .minify(MinifyOptions {
-        targets,
-        ..MinifyOptions::default()
-      })
-      .unwrap();
-    stylesheet
-      .to_css(PrinterOptions {
-        targets,
-        ..PrinterOptions::default()
-      })
-      .unwrap()
-      .code
-  }
+    // Group the shared fragments by the entries that include them, in the order of the first entry.
+    let mut chunks: Vec<Chunk> = Vec::new();
+    let mut chunk_indexes = HashMap::new();
+    for keys in keys.iter().flatten() {
+      for key in keys.iter().take_while(|key| shared.contains(key)) {
+        let entries: Vec<usize> = owners[key].iter().map(|(entry, _)| *entry).collect();
+        let index = *chunk_indexes.entry(entries.clone()).or_insert_with(|| {
+          chunks.push(Chunk {
+            keys: Vec::new(),
+            entries,
+          });
+          chunks.len() - 1
+        });
 
-  fn error_test(fs: TestProvider, entry: &str) {
-    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
-    let res = bundler.bundle(Path::new(entry));
-    match res {
-      Ok(_) => unreachable!(),
-      Err(e) => assert!(matches!(e.kind, BundleErrorKind::UnsupportedLayerCombination)),
+        if !chunks[index].keys.contains(key) {
+          chunks[index].keys.push(*key);
+        }
+      }
     }
-  }
 
-  #[test]
-  fn test_bundle() {
-    let res = bundle(
+    let chunk_of: HashMap<FragmentKey, usize> = chunks
+      .iter()
+      .enumerate()
+      .flat_map(|(index, chunk)| chunk.keys.iter().map(move |key| (*key, index)))
+      .collect();
+
+    // Each entry must include every group it depends on as a contiguous run in the same order.
+    let mut conflict = None;
+    'entries: for keys in keys.iter().flatten() {
+      let mut seen = HashSet::new();
+      let mut current = None;
+      let mut position = 0;
+      for key in keys.iter().take_while(|key| shared.contains(key)) {
+        let chunk = chunk_of[key];
+        if current != Some(chunk) {
+          let complete = current.map_or(true, |current| position == chunks[current].keys.len());
+          if !complete || !seen.insert(chunk) {
+            conflict = Some(*key);
+            break 'entries;
+          }
+          current = Some(chunk);
+          position = 0;
+        }
+
+        if chunks[chunk].keys[position] != *key {
+          conflict = Some(*key);
+          break 'entries;
+        }
+        position += 1;
+      }
+    }
+
+    match conflict {
+      Some(key) => {
+        shared.remove(&key);
+      }
+      None => return chunks,
+    }
+  }
+}
+
+/// Reads and resolves files while bundling, either from a [SourceProvider], or from the files
+/// loaded up front by [bundle_async](Bundler::bundle_async).
+trait Sources<'a>: Sync {
+  fn read<'b>(&'b self, file: &Path) -> std::io::Result<Source<'a, 'b>>;
+  fn resolve(&self, specifier: &str, originating_file: &Path) -> std::io::Result<PathBuf>;
+  fn read_asset(&self, file: &Path) -> std::io::Result<Vec<u8>>;
+}
+
+/// The source code of a file read while bundling.
+enum Source<'a, 'b> {
+  /// Source code that lives as long as the bundler, which parsed style sheets may borrow from.
+  Borrowed(&'a str),
+  /// Source code that is dropped once parsed, so the style sheet is converted to an owned value.
+  Owned(Cow<'b, str>),
+}
+
+impl<'a, 'b> Source<'a, 'b> {
+  fn as_str(&self) -> &str {
+    match self {
+      Source::Borrowed(code) => code,
+      Source::Owned(code) => code,
+    }
+  }
+}
+
+/// Reads files from a [SourceProvider].
+struct ProviderSources<'a, P>(&'a P);
+
+impl<'a, P: SourceProvider> Sources<'a> for ProviderSources<'a, P> {
+  fn read<'b>(&'b self, file: &Path) -> std::io::Result<Source<'a, 'b>> {
+    match self.0.read(file)? {
+      Cow::Borrowed(code) => Ok(Source::Borrowed(code)),
+      Cow::Owned(code) => Ok(Source::Owned(Cow::Owned(code))),
+    }
+  }
+
+  fn resolve(&self, specifier: &str, originating_file: &Path) -> std::io::Result<PathBuf> {
+    self.0.resolve(specifier, originating_file)
+  }
+
+  fn read_asset(&self, file: &Path) -> std::io::Result<Vec<u8>> {
+    self.0.read_asset(file)
+  }
+}
+
+/// Sources loaded by [bundle_async](Bundler::bundle_async), which are provided to the synchronous bundler.
+struct LoadedSources {
+  sources: HashMap<PathBuf, std::io::Result<String>>,
+  resolved: HashMap<(PathBuf, String), std::io::Result<PathBuf>>,
+  /// Assets that may be inlined, if the `inline_asset_limit` option is enabled.
+  assets: HashMap<PathBuf, std::io::Result<Vec<u8>>>,
+}
+
+impl<'a> Sources<'a> for LoadedSources {
+  fn read<'b>(&'b self, file: &Path) -> std::io::Result<Source<'a, 'b>> {
+    match self.sources.get(file) {
+      Some(Ok(code)) => Ok(Source::Owned(Cow::Borrowed(code))),
+      Some(Err(err)) => Err(clone_io_error(err)),
+      None => Err(std::io::Error::new(std::io::ErrorKind::NotFound, "File was not loaded")),
+    }
+  }
+
+  fn resolve(&self, specifier: &str, originating_file: &Path) -> std::io::Result<PathBuf> {
+    match self.resolved.get(&(originating_file.to_owned(), specifier.to_owned())) {
+      Some(Ok(path)) => Ok(path.clone()),
+      Some(Err(err)) => Err(clone_io_error(err)),
+      None => Err(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        "Specifier was not resolved",
+      )),
+    }
+  }
+
+  fn read_asset(&self, file: &Path) -> std::io::Result<Vec<u8>> {
+    match self.assets.get(file) {
+      Some(Ok(data)) => Ok(data.clone()),
+      Some(Err(err)) => Err(clone_io_error(err)),
+      None => Err(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        "Asset was not loaded",
+      )),
+    }
+  }
+}
+
+fn clone_io_error(err: &std::io::Error) -> std::io::Error {
+  std::io::Error::new(err.kind(), err.to_string())
+}
+
+/// The files referenced by a file, found before it is bundled when loading files asynchronously.
+struct SourceDependencies {
+  /// The specifiers of the files that will be bundled along with the source, i.e. the `@import` rules,
+  /// and the `composes` properties and imported values when CSS modules are enabled.
+  specifiers: Vec<String>,
+  /// The paths of the assets referenced by the source that may be inlined.
+  assets: Vec<PathBuf>,
+}
+
+/// Finds the dependencies of a parsed file, before it is bundled.
+fn stylesheet_dependencies(
+  file: &Path,
+  stylesheet: &mut StyleSheet<'_>,
+  options: &ParserOptions,
+  assets: bool,
+) -> SourceDependencies {
+  let mut dependencies = SourceDependencies {
+    specifiers: Vec::new(),
+    assets: Vec::new(),
+  };
+
+  for rule in &stylesheet.rules.0 {
+    match rule {
+      CssRule::Import(import) if !is_external_import(&import.url) => {
+        dependencies.specifiers.push(import.url.to_string())
+      }
+      CssRule::IcssImport(import) => dependencies.specifiers.push(import.specifier.to_string()),
+      CssRule::Style(style) if options.css_modules.is_some() => {
+        let declarations = &style.declarations;
+        for property in declarations.declarations.iter().chain(&declarations.important_declarations) {
+          if let Property::Composes(composes) = property {
+            if let Some(ComposesFrom::File(file)) = &composes.from {
+              dependencies.specifiers.push(file.to_string());
+            }
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+
+  if assets {
+    let dir = normalize_path(file.parent().unwrap_or_else(|| Path::new("")));
+    let mut collector = AssetCollector {
+      dir: &dir,
+      paths: Vec::new(),
+    };
+    stylesheet.visit(&mut collector);
+    dependencies.assets = collector.paths;
+  }
+
+  dependencies
+}
+
+/// Polls the given futures concurrently, and resolves to their outputs in order.
+fn join_all<T>(futures: Vec<BoxFuture<'_, T>>) -> JoinAll<'_, T> {
+  JoinAll {
+    outputs: futures.iter().map(|_| None).collect(),
+    futures: futures.into_iter().map(Some).collect(),
+  }
+}
+
+struct JoinAll<'a, T> {
+  futures: Vec<Option<BoxFuture<'a, T>>>,
+  outputs: Vec<Option<T>>,
+}
+
+// The futures are boxed, and the outputs are never pinned.
+impl<'a, T> Unpin for JoinAll<'a, T> {}
+
+impl<'a, T> Future for JoinAll<'a, T> {
+  type Output = Vec<T>;
+
+  fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+    let this = self.get_mut();
+    let mut pending = false;
+    for (future, output) in this.futures.iter_mut().zip(this.outputs.iter_mut()) {
+      if let Some(f) = future {
+        match f.as_mut().poll(cx) {
+          Poll::Ready(value) => {
+            *output = Some(value);
+            *future = None;
+          }
+          Poll::Pending => pending = true,
+        }
+      }
+    }
+
+    if pending {
+      Poll::Pending
+    } else {
+      Poll::Ready(this.outputs.iter_mut().map(|output| output.take().unwrap()).collect())
+    }
+  }
+}
+
+/// Returns whether the given `@import` specifier refers to a remote or inline style sheet that
+/// cannot be bundled, i.e. an absolute url with a scheme such as `https:` or `data:`, or a
+/// protocol-relative url.
+fn is_external_import(specifier: &str) -> bool {
+  if specifier.starts_with("//") {
+    return true;
+  }
+
+  // Single letter schemes are not allowed, so that Windows drive letters are treated as file paths.
+  match specifier.split_once(':') {
+    Some((scheme, _)) => {
+      scheme.len() > 1
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+          .chars()
+          .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    }
+    None => false,
+  }
+}
+
+struct UrlRebaser<'a> {
+  /// The directory of the file being rebased.
+  dir: &'a Path,
+  entry_dir: &'a Path,
+  root: Option<&'a Path>,
+  in_custom_property: bool,
+  /// Rebased urls within custom properties, which may be resolved differently depending on where they are used.
+  ambiguous: Vec<(String, cssparser::SourceLocation)>,
+}
+
+impl<'a, 'i> Visitor<'i> for UrlRebaser<'a> {
+  fn visit_property(&mut self, property: &mut Property<'i>) {
+    self.in_custom_property = matches!(property, Property::Custom(_));
+    property.visit_children(self);
+    self.in_custom_property = false;
+  }
+
+  fn visit_url(&mut self, url: &mut Url<'i>) {
+    if url.url.is_empty() || url.url.starts_with('#') || url.is_absolute() {
+      return;
+    }
+
+    // Preserve any query string or fragment as is.
+    let (path, suffix) = url
+      .url
+      .split_at(url.url.find(|c| c == '?' || c == '#').unwrap_or(url.url.len()));
+    let resolved = normalize_path(&self.dir.join(path));
+    let from_root = self
+      .root
+      .and_then(|root| relative_path(root, &resolved))
+      .filter(|path| !path.starts_with("../"))
+      .map(|path| format!("/{}", path));
+    let rebased = match from_root.or_else(|| relative_path(self.entry_dir, &resolved)) {
+      Some(rebased) => format!("{}{}", rebased, suffix),
+      None => return,
+    };
+
+    if rebased != *url.url {
+      if self.in_custom_property {
+        self.ambiguous.push((url.url.to_string(), url.loc));
+      }
+      url.url = rebased.into();
+    }
+  }
+}
+
+/// Replaces relative urls of small assets with `data:` URIs.
+struct AssetInliner<'a, S> {
+  fs: &'a S,
+  /// The directory of the file being processed.
+  dir: &'a Path,
+  limit: usize,
+}
+
+impl<'a, 'b, 'i, S: Sources<'b>> Visitor<'i> for AssetInliner<'a, S> {
+  fn visit_url(&mut self, url: &mut Url<'i>) {
+    let (path, mime_type) = match asset_path(self.dir, url) {
+      Some(asset) => asset,
+      None => return,
+    };
+
+    // Assets that cannot be read are left for other tools to resolve.
+    match self.fs.read_asset(&path) {
+      Ok(data) if data.len() <= self.limit => url.url = data_uri(mime_type, &data).into(),
+      _ => {}
+    }
+  }
+}
+
+/// Collects the paths of the assets that an [AssetInliner] may inline, so that they can be loaded in advance.
+struct AssetCollector<'a> {
+  dir: &'a Path,
+  paths: Vec<PathBuf>,
+}
+
+impl<'a, 'i> Visitor<'i> for AssetCollector<'a> {
+  fn visit_url(&mut self, url: &mut Url<'i>) {
+    if let Some((path, _)) = asset_path(self.dir, url) {
+      self.paths.push(path);
+    }
+  }
+}
+
+/// Resolves a url relative to the given directory, and returns the path
+/// and mime type of the asset if it may be inlined.
+fn asset_path(dir: &Path, url: &Url<'_>) -> Option<(PathBuf, &'static str)> {
+  // Urls with a query string or fragment may refer to part of a file, e.g. an SVG sprite.
+  if url.url.is_empty() || url.is_absolute() || url.url.contains(|c| c == '?' || c == '#') {
+    return None;
+  }
+
+  let path = normalize_path(&dir.join(url.url.as_ref()));
+  let mime_type = asset_mime_type(&path)?;
+  Some((path, mime_type))
+}
+
+/// Returns the mime type of an asset that can be inlined, based on its file extension.
+fn asset_mime_type(path: &Path) -> Option<&'static str> {
+  let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+  let mime_type = match extension.as_str() {
+    "png" => "image/png",
+    "jpg" | "jpeg" => "image/jpeg",
+    "gif" => "image/gif",
+    "webp" => "image/webp",
+    "avif" => "image/avif",
+    "bmp" => "image/bmp",
+    "ico" => "image/x-icon",
+    "svg" => "image/svg+xml",
+    "woff" => "font/woff",
+    "woff2" => "font/woff2",
+    "ttf" => "font/ttf",
+    "otf" => "font/otf",
+    "eot" => "application/vnd.ms-fontobject",
+    _ => return None,
+  };
+  Some(mime_type)
+}
+
+/// Creates a `data:` URI for the given asset. SVG is URL-encoded, which is
+/// smaller than base64 for text. Other assets are base64-encoded.
+fn data_uri(mime_type: &str, data: &[u8]) -> String {
+  if mime_type == "image/svg+xml" {
+    if let Ok(svg) = std::str::from_utf8(data) {
+      let mut uri = format!("data:{},", mime_type);
+      for byte in svg.trim().bytes() {
+        match byte {
+          b'a'..=b'z'
+          | b'A'..=b'Z'
+          | b'0'..=b'9'
+          | b'-'
+          | b'.'
+          | b'_'
+          | b'~'
+          | b'!'
+          | b'$'
+          | b'&'
+          | b'*'
+          | b'+'
+          | b','
+          | b';'
+          | b'='
+          | b':'
+          | b'@'
+          | b'/'
+          | b'\'' => uri.push(byte as char),
+          _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+      }
+      return uri;
+    }
+  }
+
+  format!("data:{};base64,{}", mime_type, BASE64.encode(data))
+}
+
+/// Lexically normalizes a path, resolving `.` and `..` components without accessing the file system.
+fn normalize_path(path: &Path) -> PathBuf {
+  let mut normalized = PathBuf::new();
+  for component in path.components() {
+    match component {
+      Component::CurDir => {}
+      Component::ParentDir => match normalized.components().next_back() {
+        Some(Component::Normal(_)) => {
+          normalized.pop();
+        }
+        Some(Component::RootDir) | Some(Component::Prefix(_)) => {}
+        _ => normalized.push(".."),
+      },
+      component => normalized.push(component),
+    }
+  }
+  normalized
+}
+
+/// Returns a url path to the given file, relative to the given directory. Both paths must be normalized.
+/// Returns `None` if the paths do not share the same root, e.g. if only one of them is absolute.
+fn relative_path(dir: &Path, file: &Path) -> Option<String> {
+  if dir.has_root() != file.has_root() {
+    return None;
+  }
+
+  let mut dir_components = dir.components().peekable();
+  let mut file_components = file.components().peekable();
+  while dir_components.peek().is_some() && dir_components.peek() == file_components.peek() {
+    dir_components.next();
+    file_components.next();
+  }
+
+  let mut segments = Vec::new();
+  for component in dir_components {
+    match component {
+      Component::Normal(_) => segments.push(".."),
+      // The remaining directory path must not contain a different root or prefix, or `..` components
+      // that we cannot reverse.
+      _ => return None,
+    }
+  }
+
+  for component in file_components {
+    match component {
+      Component::Normal(segment) => segments.push(segment.to_str()?),
+      // Normalized paths only start with `..` components, e.g. a file above a relative directory.
+      Component::ParentDir => segments.push(".."),
+      _ => return None,
+    }
+  }
+
+  Some(segments.join("/"))
+}
+
+fn combine_supports<'a>(
+  a: Option<SupportsCondition<'a>>,
+  b: &Option<SupportsCondition<'a>>,
+) -> Option<SupportsCondition<'a>> {
+  if let Some(mut a) = a {
+    if let Some(b) = b {
+      a.and(b)
+    }
+    Some(a)
+  } else {
+    b.clone()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    css_modules::{CssModuleExport, CssModuleExports, CssModuleReference},
+    stylesheet::{MinifyOptions, ToCssResult},
+    targets::Browsers,
+  };
+  use indoc::indoc;
+  use std::collections::HashMap;
+  use std::sync::Arc;
+
+  struct TestProvider {
+    map: HashMap<PathBuf, String>,
+  }
+
+  impl SourceProvider for TestProvider {
+    fn read<'a>(&'a self, file: &Path) -> std::io::Result<Cow<'a, str>> {
+      Ok(Cow::Borrowed(self.map.get(file).unwrap()))
+    }
+  }
+
+  /// Resolves `~/` specifiers relative to the `/src` directory.
+  struct AliasProvider(TestProvider);
+
+  impl SourceProvider for AliasProvider {
+    fn read<'a>(&'a self, file: &Path) -> std::io::Result<Cow<'a, str>> {
+      self.0.read(file)
+    }
+
+    fn resolve(&self, specifier: &str, originating_file: &Path) -> std::io::Result<PathBuf> {
+      match specifier.strip_prefix("~/") {
+        Some(path) => Ok(Path::new("/src").join(path)),
+        None => Ok(originating_file.with_file_name(specifier)),
+      }
+    }
+  }
+
+  /// Provides the files of a TestProvider asynchronously, yielding once before each read.
+  struct AsyncTestProvider(TestProvider);
+
+  impl AsyncSourceProvider for AsyncTestProvider {
+    fn read<'a>(&'a self, file: &'a Path) -> BoxFuture<'a, std::io::Result<String>> {
+      Box::pin(async move {
+        YieldNow(false).await;
+        match self.0.map.get(file) {
+          Some(code) => Ok(code.clone()),
+          None => Err(std::io::Error::new(std::io::ErrorKind::NotFound, "File not found")),
+        }
+      })
+    }
+  }
+
+  /// Provides files that can be changed between bundles, and records every file that is read.
+  struct ChangingProvider {
+    files: Mutex<HashMap<PathBuf, &'static str>>,
+    reads: Mutex<Vec<PathBuf>>,
+  }
+
+  impl ChangingProvider {
+    fn set(&self, file: &str, code: &'static str) {
+      self.files.lock().unwrap().insert(PathBuf::from(file), code);
+    }
+
+    fn take_reads(&self) -> Vec<PathBuf> {
+      std::mem::take(&mut *self.reads.lock().unwrap())
+    }
+  }
+
+  impl SourceProvider for ChangingProvider {
+    fn read<'a>(&'a self, file: &Path) -> std::io::Result<Cow<'a, str>> {
+      self.reads.lock().unwrap().push(file.to_owned());
+      Ok(Cow::Borrowed(self.files.lock().unwrap()[file]))
+    }
+  }
+
+  impl AsyncSourceProvider for ChangingProvider {
+    fn read<'a>(&'a self, file: &'a Path) -> BoxFuture<'a, std::io::Result<String>> {
+      self.reads.lock().unwrap().push(file.to_owned());
+      Box::pin(std::future::ready(Ok(self.files.lock().unwrap()[file].to_owned())))
+    }
+  }
+
+  struct YieldNow(bool);
+
+  impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+      if self.0 {
+        return Poll::Ready(());
+      }
+
+      self.0 = true;
+      cx.waker().wake_by_ref();
+      Poll::Pending
+    }
+  }
+
+  fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(std::thread::Thread);
+
+    impl std::task::Wake for ThreadWaker {
+      fn wake(self: Arc<Self>) {
+        self.0.unpark()
+      }
+    }
+
+    let waker = Arc::new(ThreadWaker(std::thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+      match future.as_mut().poll(&mut cx) {
+        Poll::Ready(output) => return output,
+        Poll::Pending => std::thread::park(),
+      }
+    }
+  }
+
+  macro_rules! fs(
+    { $($key:literal: $value:expr),* } => {
+      {
+        #[allow(unused_mut)]
+        let mut m = HashMap::new();
+        $(
+          m.insert(PathBuf::from($key), $value.to_owned());
+        )*
+        TestProvider {
+          map: m
+        }
+      }
+    };
+  );
+
+  fn bundle(fs: TestProvider, entry: &str) -> String {
+    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+    let stylesheet = bundler.bundle(Path::new(entry)).unwrap().stylesheet;
+    stylesheet.to_css(PrinterOptions::default()).unwrap().code
+  }
+
+  fn bundle_css_module(fs: TestProvider, entry: &str) -> String {
+    let mut bundler = Bundler::new(
+      &fs,
+      None,
+      ParserOptions {
+        css_modules: Some(Default::default()),
+        ..ParserOptions::default()
+      },
+    );
+    let stylesheet = bundler.bundle(Path::new(entry)).unwrap().stylesheet;
+    stylesheet.to_css(PrinterOptions::default()).unwrap().code
+  }
+
+  fn bundle_css_module_exports(fs: TestProvider, entry: &str) -> (String, CssModuleExports) {
+    let mut bundler = Bundler::new(
+      &fs,
+      None,
+      ParserOptions {
+        css_modules: Some(Default::default()),
+        ..ParserOptions::default()
+      },
+    );
+    let stylesheet = bundler.bundle(Path::new(entry)).unwrap().stylesheet;
+    let res = stylesheet.to_css(PrinterOptions::default()).unwrap();
+    (res.code, res.exports.unwrap())
+  }
+
+  fn bundle_custom_media(fs: TestProvider, entry: &str) -> String {
+    let mut bundler = Bundler::new(
+      &fs,
+      None,
+      ParserOptions {
+        custom_media: true,
+        ..ParserOptions::default()
+      },
+    );
+    let mut stylesheet = bundler.bundle(Path::new(entry)).unwrap().stylesheet;
+    let targets = Some(Browsers {
+      safari: Some(13 << 16),
+      ..Browsers::default()
+    });
+    stylesheet
+      .minify(MinifyOptions {
+        targets,
+        ..MinifyOptions::default()
+      })
+      .unwrap();
+    stylesheet
+      .to_css(PrinterOptions {
+        targets,
+        ..PrinterOptions::default()
+      })
+      .unwrap()
+      .code
+  }
+
+  fn error_test(fs: TestProvider, entry: &str) {
+    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+    let res = bundler.bundle(Path::new(entry));
+    match res {
+      Ok(_) => unreachable!(),
+      Err(e) => assert!(matches!(e.kind, BundleErrorKind::UnsupportedLayerCombination)),
+    }
+  }
+
+  #[test]
+  fn test_bundle() {
+    let res = bundle(
+      fs! {
+        "/a.css": r#"
+        @import "b.css";
+        .a { color: red }
+      "#,
+        "/b.css": r#"
+        .b { color: green }
+      "#
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      res,
+      indoc! { r#"
+      .b {
+        color: green;
+      }
+      
+      .a {
+        color: red;
+      }
+    "#}
+    );
+
+    let res = bundle(
+      fs! {
+        "/a.css": r#"
+        @import "b.css" print;
+        .a { color: red }
+      "#,
+        "/b.css": r#"
+        .b { color: green }
+      "#
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      res,
+      indoc! { r#"
+      @media print {
+        .b {
+          color: green;
+        }
+      }
+      
+      .a {
+        color: red;
+      }
+    "#}
+    );
+
+    let res = bundle(
+      fs! {
+        "/a.css": r#"
+        @import "b.css" supports(color: green);
+        .a { color: red }
+      "#,
+        "/b.css": r#"
+        .b { color: green }
+      "#
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      res,
+      indoc! { r#"
+      @supports (color: green) {
+        .b {
+          color: green;
+        }
+      }
+      
+      .a {
+        color: red;
+      }
+    "#}
+    );
+
+    let res = bundle(
+      fs! {
+        "/a.css": r#"
+        @import "b.css" supports(color: green) print;
+        .a { color: red }
+      "#,
+        "/b.css": r#"
+        .b { color: green }
+      "#
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      res,
+      indoc! { r#"
+      @supports (color: green) {
+        @media print {
+          .b {
+            color: green;
+          }
+        }
+      }
+      
+      .a {
+        color: red;
+      }
+    "#}
+    );
+
+    let res = bundle(
+      fs! {
+        "/a.css": r#"
+        @import "b.css" print;
+        @import "b.css" screen;
+        .a { color: red }
+      "#,
+        "/b.css": r#"
+        .b { color: green }
+      "#
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      res,
+      indoc! { r#"
+      @media print, screen {
+        .b {
+          color: green;
+        }
+      }
+      
+      .a {
+        color: red;
+      }
+    "#}
+    );
+
+    let res = bundle(
+      fs! {
+        "/a.css": r#"
+        @import "b.css" supports(color: red);
+        @import "b.css" supports(foo: bar);
+        .a { color: red }
+      "#,
+        "/b.css": r#"
+        .b { color: green }
+      "#
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      res,
+      indoc! { r#"
+      @supports ((color: red) or (foo: bar)) {
+        .b {
+          color: green;
+        }
+      }
+      
+      .a {
+        color: red;
+      }
+    "#}
+    );
+
+    let res = bundle(
+      fs! {
+        "/a.css": r#"
+        @import "b.css" print;
+        .a { color: red }
+      "#,
+        "/b.css": r#"
+        @import "c.css" (color);
+        .b { color: yellow }
+      "#,
+        "/c.css": r#"
+        .c { color: green }
+      "#
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      res,
+      indoc! { r#"
+      @media print and (color) {
+        .c {
+          color: green;
+        }
+      }
+      
+      @media print {
+        .b {
+          color: #ff0;
+        }
+      }
+
+      .a {
+        color: red;
+      }
+    "#}
+    );
+
+    let res = bundle(
       fs! {
         "/a.css": r#"
         @import "b.css";
         .a { color: red }
       "#,
         "/b.css": r#"
+        @import "c.css";
+      "#,
+        "/c.css": r#"
+        @import "a.css";
+        .c { color: green }
+      "#
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      res,
+      indoc! { r#"
+      .c {
+        color: green;
+      }
+
+      .a {
+        color: red;
+      }
+    "#}
+    );
+
+    let res = bundle(
+      fs! {
+        "/a.css": r#"
+        @import "b/c.css";
+        .a { color: red }
+      "#,
+        "/b/c.css": r#"
         .b { color: green }
       "#
       },
@@ -615,11 +3095,202 @@ mod tests {
     let res = bundle(
       fs! {
         "/a.css": r#"
-        @import "b.css" print;
+        @import "./b/c.css";
+        .a { color: red }
+      "#,
+        "/b/c.css": r#"
+        .b { color: green }
+      "#
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      res,
+      indoc! { r#"
+      .b {
+        color: green;
+      }
+      
+      .a {
+        color: red;
+      }
+    "#}
+    );
+
+    let res = bundle_css_module(
+      fs! {
+        "/a.css": r#"
+        @import "b.css";
         .a { color: red }
       "#,
         "/b.css": r#"
+        .a { color: green }
+      "#
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      res,
+      indoc! { r#"
+      ._6lixEq_a {
+        color: green;
+      }
+
+      ._6lixEq_a {
+        color: red;
+      }
+    "#}
+    );
+
+    let (code, exports) = bundle_css_module_exports(
+      fs! {
+        "/a.css": r#"
+        .a {
+          composes: b from "b.css";
+          color: red;
+        }
+
+        .c {
+          composes: b c from "b.css";
+        }
+      "#,
+        "/b.css": r#"
+        @import "d.css";
         .b { color: green }
+        .c { color: blue }
+      "#,
+        "/d.css": r#"
+        .d { color: yellow }
+      "#
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      code,
+      indoc! { r#"
+      ._9z6RGq_d {
+        color: #ff0;
+      }
+
+      ._9z6RGq_b {
+        color: green;
+      }
+
+      ._9z6RGq_c {
+        color: #00f;
+      }
+
+      ._6lixEq_a {
+        color: red;
+      }
+
+      ._6lixEq_c {
+      }
+    "#}
+    );
+    let mut expected = HashMap::new();
+    expected.insert(
+      "a".into(),
+      CssModuleExport {
+        name: "_6lixEq_a".into(),
+        composes: vec![CssModuleReference::Local {
+          name: "_9z6RGq_b".into(),
+        }],
+        is_referenced: false,
+      },
+    );
+    expected.insert(
+      "c".into(),
+      CssModuleExport {
+        name: "_6lixEq_c".into(),
+        composes: vec![
+          CssModuleReference::Local {
+            name: "_9z6RGq_b".into(),
+          },
+          CssModuleReference::Local {
+            name: "_9z6RGq_c".into(),
+          },
+        ],
+        is_referenced: false,
+      },
+    );
+    assert_eq!(exports, expected);
+
+    let (code, exports) = bundle_css_module_exports(
+      fs! {
+        "/a.css": r#"
+        @value primary, secondary as accent from "colors.css";
+
+        :import("theme.css") {
+          bg: background;
+        }
+
+        a {
+          color: primary;
+          border-color: accent;
+          background: bg;
+        }
+      "#,
+        "/colors.css": r#"
+        @value primary: red;
+        @value secondary: #00f;
+      "#,
+        "/theme.css": r#"
+        :export {
+          background: green;
+        }
+      "#
+      },
+      "/a.css",
+    );
+    assert_eq!(
+      code,
+      indoc! { r#"
+      a {
+        color: red;
+        border-color: #00f;
+        background: green;
+      }
+    "#}
+    );
+    assert_eq!(exports["primary"].name, "red");
+    assert_eq!(exports["accent"].name, "#00f");
+    assert_eq!(exports["bg"].name, "green");
+
+    let fs = fs! {
+      "/a.css": r#"
+      @value missing from "b.css";
+      a { color: missing }
+    "#,
+      "/b.css": r#"
+      @value primary: red;
+    "#
+    };
+    let mut bundler = Bundler::new(
+      &fs,
+      None,
+      ParserOptions {
+        css_modules: Some(Default::default()),
+        ..ParserOptions::default()
+      },
+    );
+    let err = bundler.bundle(Path::new("/a.css")).unwrap_err();
+    assert!(matches!(err.kind, BundleErrorKind::UnresolvedValue(name) if name == "missing"));
+
+    let res = bundle_custom_media(
+      fs! {
+        "/a.css": r#"
+        @import "media.css";
+        @import "b.css";
+        .a { color: red }
+      "#,
+        "/media.css": r#"
+        @custom-media --foo print;
+      "#,
+        "/b.css": r#"
+        @media (--foo) {
+          .a { color: green }
+        }
       "#
       },
       "/a.css",
@@ -628,11 +3299,11 @@ mod tests {
       res,
       indoc! { r#"
       @media print {
-        .b {
+        .a {
           color: green;
         }
       }
-      
+
       .a {
         color: red;
       }
@@ -642,7 +3313,7 @@ mod tests {
     let res = bundle(
       fs! {
         "/a.css": r#"
-        @import "b.css" supports(color: green);
+        @import "b.css" layer(foo);
         .a { color: red }
       "#,
         "/b.css": r#"
@@ -654,7 +3325,7 @@ mod tests {
     assert_eq!(
       res,
       indoc! { r#"
-      @supports (color: green) {
+      @layer foo {
         .b {
           color: green;
         }
@@ -669,7 +3340,7 @@ mod tests {
     let res = bundle(
       fs! {
         "/a.css": r#"
-        @import "b.css" supports(color: green) print;
+        @import "b.css" layer;
         .a { color: red }
       "#,
         "/b.css": r#"
@@ -681,11 +3352,9 @@ mod tests {
     assert_eq!(
       res,
       indoc! { r#"
-      @supports (color: green) {
-        @media print {
-          .b {
-            color: green;
-          }
+      @layer {
+        .b {
+          color: green;
         }
       }
       
@@ -698,12 +3367,15 @@ mod tests {
     let res = bundle(
       fs! {
         "/a.css": r#"
-        @import "b.css" print;
-        @import "b.css" screen;
+        @import "b.css" layer(foo);
         .a { color: red }
       "#,
         "/b.css": r#"
+        @import "c.css" layer(bar);
         .b { color: green }
+      "#,
+        "/c.css": r#"
+        .c { color: green }
       "#
       },
       "/a.css",
@@ -711,7 +3383,13 @@ mod tests {
     assert_eq!(
       res,
       indoc! { r#"
-      @media print, screen {
+      @layer foo.bar {
+        .c {
+          color: green;
+        }
+      }
+
+      @layer foo {
         .b {
           color: green;
         }
@@ -726,9 +3404,8 @@ mod tests {
     let res = bundle(
       fs! {
         "/a.css": r#"
-        @import "b.css" supports(color: red);
-        @import "b.css" supports(foo: bar);
-        .a { color: red }
+        @import "b.css" layer(foo);
+        @import "b.css" layer(foo);
       "#,
         "/b.css": r#"
         .b { color: green }
@@ -739,30 +3416,40 @@ mod tests {
     assert_eq!(
       res,
       indoc! { r#"
-      @supports ((color: red) or (foo: bar)) {
+      @layer foo {
         .b {
           color: green;
         }
       }
-      
-      .a {
-        color: red;
-      }
     "#}
     );
 
     let res = bundle(
       fs! {
         "/a.css": r#"
-        @import "b.css" print;
-        .a { color: red }
+        @layer bar, foo;
+        @import "b.css" layer(foo);
+        
+        @layer bar {
+          div {
+            background: red;
+          }
+        }
       "#,
         "/b.css": r#"
-        @import "c.css" (color);
-        .b { color: yellow }
+        @layer qux, baz;
+        @import "c.css" layer(baz);
+        
+        @layer qux {
+          div {
+            background: green;
+          }
+        }
       "#,
         "/c.css": r#"
-        .c { color: green }
+        div {
+          background: yellow;
+        }      
       "#
       },
       "/a.css",
@@ -770,183 +3457,203 @@ mod tests {
     assert_eq!(
       res,
       indoc! { r#"
-      @media print and (color) {
-        .c {
-          color: green;
+      @layer bar, foo;
+      @layer foo.qux, foo.baz;
+
+      @layer foo.baz {
+        div {
+          background: #ff0;
+        }
+      }
+
+      @layer foo {
+        @layer qux {
+          div {
+            background: green;
+          }
         }
       }
       
-      @media print {
-        .b {
-          color: #ff0;
+      @layer bar {
+        div {
+          background: red;
         }
       }
-
-      .a {
-        color: red;
-      }
     "#}
     );
 
-    let res = bundle(
+    error_test(
       fs! {
         "/a.css": r#"
-        @import "b.css";
-        .a { color: red }
+        @import "b.css" layer(foo);
+        @import "b.css" layer(bar);
       "#,
         "/b.css": r#"
-        @import "c.css";
-      "#,
-        "/c.css": r#"
-        @import "a.css";
-        .c { color: green }
+        .b { color: red }
       "#
       },
       "/a.css",
     );
-    assert_eq!(
-      res,
-      indoc! { r#"
-      .c {
-        color: green;
-      }
 
-      .a {
-        color: red;
-      }
-    "#}
+    error_test(
+      fs! {
+        "/a.css": r#"
+        @import "b.css" layer;
+        @import "b.css" layer;
+      "#,
+        "/b.css": r#"
+        .b { color: red }
+      "#
+      },
+      "/a.css",
     );
 
-    let res = bundle(
+    error_test(
       fs! {
         "/a.css": r#"
-        @import "b/c.css";
+        @import "b.css" layer;
         .a { color: red }
       "#,
-        "/b/c.css": r#"
+        "/b.css": r#"
+        @import "c.css" layer;
         .b { color: green }
+      "#,
+        "/c.css": r#"
+        .c { color: green }
       "#
       },
       "/a.css",
     );
-    assert_eq!(
-      res,
-      indoc! { r#"
-      .b {
-        color: green;
-      }
-      
-      .a {
-        color: red;
-      }
-    "#}
-    );
 
-    let res = bundle(
+    error_test(
       fs! {
         "/a.css": r#"
-        @import "./b/c.css";
+        @import "b.css" layer;
         .a { color: red }
       "#,
-        "/b/c.css": r#"
+        "/b.css": r#"
+        @import "c.css" layer(foo);
         .b { color: green }
+      "#,
+        "/c.css": r#"
+        .c { color: green }
       "#
       },
       "/a.css",
     );
-    assert_eq!(
-      res,
-      indoc! { r#"
-      .b {
-        color: green;
-      }
-      
-      .a {
-        color: red;
-      }
-    "#}
-    );
 
-    let res = bundle_css_module(
+    let res = bundle(
       fs! {
-        "/a.css": r#"
+        "/index.css": r#"
+        @import "a.css";
         @import "b.css";
-        .a { color: red }
+      "#,
+        "/a.css": r#"
+        @import "./c.css";
+        body { background: red; }
       "#,
         "/b.css": r#"
-        .a { color: green }
+        @import "./c.css";
+        body { color: red; }
+      "#,
+        "/c.css": r#"
+        body {
+          background: white;
+          color: black; 
+        }
       "#
       },
-      "/a.css",
+      "/index.css",
     );
     assert_eq!(
       res,
       indoc! { r#"
-      ._6lixEq_a {
-        color: green;
+      body {
+        background: red;
       }
 
-      ._6lixEq_a {
+      body {
+        background: #fff;
+        color: #000;
+      }
+
+      body {
         color: red;
       }
     "#}
     );
 
-    let res = bundle_custom_media(
+    let res = bundle(
       fs! {
-        "/a.css": r#"
-        @import "media.css";
+        "/index.css": r#"
+        @import "a.css";
         @import "b.css";
-        .a { color: red }
+        @import "a.css";
       "#,
-        "/media.css": r#"
-        @custom-media --foo print;
+        "/a.css": r#"
+        body { background: green; }
       "#,
         "/b.css": r#"
-        @media (--foo) {
-          .a { color: green }
-        }
+        body { background: red; }
       "#
       },
-      "/a.css",
+      "/index.css",
     );
     assert_eq!(
       res,
       indoc! { r#"
-      @media print {
-        .a {
-          color: green;
-        }
+      body {
+        background: red;
       }
 
-      .a {
-        color: red;
+      body {
+        background: green;
       }
     "#}
     );
 
-    let res = bundle(
-      fs! {
-        "/a.css": r#"
-        @import "b.css" layer(foo);
-        .a { color: red }
+    let fs = AliasProvider(fs! {
+      "/src/components/button.css": r#"
+        @import "~/styles/theme.css";
+        .button { composes: base from "~/styles/base.css"; color: red }
       "#,
-        "/b.css": r#"
-        .b { color: green }
+      "/src/styles/theme.css": r#"
+        .theme { color: green }
+      "#,
+      "/src/styles/base.css": r#"
+        .base { color: yellow }
       "#
+    });
+    let mut bundler = Bundler::new(
+      &fs,
+      None,
+      ParserOptions {
+        css_modules: Some(Default::default()),
+        ..ParserOptions::default()
       },
-      "/a.css",
     );
+    let stylesheet = bundler.bundle(Path::new("/src/components/button.css")).unwrap().stylesheet;
+    let res = stylesheet.to_css(PrinterOptions::default()).unwrap();
     assert_eq!(
-      res,
+      stylesheet.sources,
+      vec![
+        "/src/components/button.css",
+        "/src/styles/theme.css",
+        "/src/styles/base.css"
+      ]
+    );
+    assert_eq!(
+      res.code,
       indoc! { r#"
-      @layer foo {
-        .b {
-          color: green;
-        }
+      .tui4Fq_theme {
+        color: green;
       }
-      
-      .a {
+
+      ._3GPtnG_base {
+        color: #ff0;
+      }
+
+      .tui4Fq_button {
         color: red;
       }
     "#}
@@ -955,10 +3662,14 @@ mod tests {
     let res = bundle(
       fs! {
         "/a.css": r#"
-        @import "b.css" layer;
+        @layer base;
+        @import url("https://fonts.googleapis.com/css2?family=Roboto");
+        @import "b.css" print;
         .a { color: red }
       "#,
         "/b.css": r#"
+        @import "//cdn.example.com/x.css" supports(display: grid) (orientation: landscape);
+        @import "data:text/css,.d{color:blue}" layer(remote);
         .b { color: green }
       "#
       },
@@ -967,262 +3678,732 @@ mod tests {
     assert_eq!(
       res,
       indoc! { r#"
-      @layer {
+      @layer base;
+      @import "https://fonts.googleapis.com/css2?family=Roboto";
+      @import "//cdn.example.com/x.css" supports(display: grid) print and (orientation: landscape);
+      @import "data:text/css,.d{color:blue}" layer(remote) print;
+
+      @media print {
         .b {
           color: green;
         }
       }
-      
+
       .a {
         color: red;
       }
     "#}
     );
 
-    let res = bundle(
+    let circular_fs = || {
       fs! {
         "/a.css": r#"
-        @import "b.css" layer(foo);
+        @import "b.css";
         .a { color: red }
       "#,
         "/b.css": r#"
-        @import "c.css" layer(bar);
+        @import "c.css";
         .b { color: green }
       "#,
         "/c.css": r#"
-        .c { color: green }
+        @import "b.css";
+        .c { color: blue }
       "#
+      }
+    };
+
+    let fs = circular_fs();
+    let mut bundler = Bundler::with_options(
+      &fs,
+      None,
+      ParserOptions::default(),
+      BundleOptions {
+        circular_imports: CircularImports::Error,
+        ..BundleOptions::default()
       },
-      "/a.css",
     );
+    let err = bundler.bundle(Path::new("/a.css")).unwrap_err();
+    let chain = match &err.kind {
+      BundleErrorKind::CircularImport(chain) => chain,
+      _ => unreachable!(),
+    };
+    assert_eq!(
+      chain.iter().map(|loc| loc.filename.as_str()).collect::<Vec<_>>(),
+      vec!["/b.css", "/c.css"]
+    );
+    assert_eq!(err.loc, Some(chain[1].clone()));
+    assert_eq!(err.kind.to_string(), "Circular @import: /b.css -> /c.css -> /b.css");
+
+    let fs = circular_fs();
+    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+    let res = bundler
+      .bundle(Path::new("/a.css"))
+      .unwrap()
+      .stylesheet
+      .to_css(PrinterOptions::default())
+      .unwrap();
     assert_eq!(
-      res,
+      res.code,
       indoc! { r#"
-      @layer foo.bar {
-        .c {
-          color: green;
-        }
+      .c {
+        color: #00f;
       }
 
-      @layer foo {
-        .b {
-          color: green;
-        }
+      .b {
+        color: green;
       }
-      
+
       .a {
         color: red;
       }
     "#}
     );
+    assert_eq!(res.warnings.len(), 1);
+    assert!(matches!(res.warnings[0].kind, WarningKind::CircularImport { .. }));
 
-    let res = bundle(
-      fs! {
-        "/a.css": r#"
-        @import "b.css" layer(foo);
-        @import "b.css" layer(foo);
+    // Invalid rules are skipped in every file with error recovery, and returned along with the bundle.
+    let fs = fs! {
+      "/a.css": r#"
+        @import "b.css";
+        .a { color: red }
       "#,
-        "/b.css": r#"
-        .b { color: green }
+      "/b.css": r#"
+        ..b { color: green }
+        .c { color: blue }
       "#
-      },
-      "/a.css",
-    );
+    };
+    let options = ParserOptions {
+      error_recovery: true,
+      ..ParserOptions::default()
+    };
+    let mut bundler = Bundler::new(&fs, None, options);
+    let stylesheet = bundler.bundle(Path::new("/a.css")).unwrap().stylesheet;
+    assert_eq!(stylesheet.parser_warnings.len(), 1);
+    assert_eq!(stylesheet.parser_warnings[0].loc.as_ref().unwrap().filename, "/b.css");
+    let res = stylesheet.to_css(PrinterOptions::default()).unwrap();
     assert_eq!(
-      res,
+      res.code,
       indoc! { r#"
-      @layer foo {
-        .b {
-          color: green;
-        }
+      .c {
+        color: #00f;
+      }
+
+      .a {
+        color: red;
       }
     "#}
     );
-
-    let res = bundle(
-      fs! {
-        "/a.css": r#"
-        @layer bar, foo;
-        @import "b.css" layer(foo);
-        
-        @layer bar {
-          div {
-            background: red;
-          }
-        }
+
+    let async_fs = AsyncTestProvider(fs! {
+      "/a.css": r#"
+        @import "b.css";
+        @import "c.css" print;
+        .a { color: red }
       "#,
-        "/b.css": r#"
-        @layer qux, baz;
-        @import "c.css" layer(baz);
-        
-        @layer qux {
-          div {
-            background: green;
-          }
-        }
+      "/b.css": r#"
+        @import "c.css" print;
+        .b { color: green }
       "#,
-        "/c.css": r#"
-        div {
-          background: yellow;
-        }      
+      "/c.css": r#"
+        .c { color: yellow }
       "#
-      },
-      "/a.css",
-    );
+    });
+    let mut bundler = Bundler::new(&async_fs, None, ParserOptions::default());
+    let future = bundler.bundle_async(Path::new("/a.css"));
+    fn assert_send<T: Send>(_: &T) {}
+    assert_send(&future);
+    let res = block_on(future).unwrap().stylesheet.to_css(PrinterOptions::default()).unwrap();
     assert_eq!(
-      res,
+      res.code,
       indoc! { r#"
-      @layer bar, foo;
-      @layer foo.qux, foo.baz;
-
-      @layer foo.baz {
-        div {
-          background: #ff0;
-        }
+      .b {
+        color: green;
       }
 
-      @layer foo {
-        @layer qux {
-          div {
-            background: green;
-          }
+      @media print {
+        .c {
+          color: #ff0;
         }
       }
-      
-      @layer bar {
-        div {
-          background: red;
-        }
+
+      .a {
+        color: red;
       }
     "#}
     );
 
-    error_test(
+    let async_fs = AsyncTestProvider(fs! {
+      "/a.css": r#"
+        @import "missing.css";
+      "#
+    });
+    let mut bundler = Bundler::new(&async_fs, None, ParserOptions::default());
+    let err = block_on(bundler.bundle_async(Path::new("/a.css"))).unwrap_err();
+    assert!(matches!(err.kind, BundleErrorKind::IOError(_)));
+    assert_eq!(err.loc.unwrap().filename, "/a.css");
+
+    // Files loaded asynchronously are parsed once, and cached by the bundler.
+    let fs = ChangingProvider {
+      files: Mutex::new(HashMap::new()),
+      reads: Mutex::new(Vec::new()),
+    };
+    fs.set("/a.css", r#"@import "b.css"; .a { color: red }"#);
+    fs.set("/b.css", ".b { color: green }");
+    let bundle_options = BundleOptions {
+      cache: true,
+      ..BundleOptions::default()
+    };
+    let mut bundler = Bundler::with_options(&fs, None, ParserOptions::default(), bundle_options);
+    let rebundle = |bundler: &mut Bundler<ChangingProvider>| {
+      let stylesheet = block_on(bundler.bundle_async(Path::new("/a.css"))).unwrap().stylesheet;
+      let mut reads = fs.take_reads();
+      reads.sort();
+      let options = PrinterOptions {
+        minify: true,
+        ..PrinterOptions::default()
+      };
+      (stylesheet.to_css(options).unwrap().code, reads)
+    };
+    let paths = |paths: &[&str]| paths.iter().map(PathBuf::from).collect::<Vec<_>>();
+
+    let (code, reads) = rebundle(&mut bundler);
+    assert_eq!(code, ".b{color:green}.a{color:red}");
+    assert_eq!(reads, paths(&["/a.css", "/b.css"]));
+    let mut files = bundler.files();
+    files.sort();
+    assert_eq!(files, paths(&["/a.css", "/b.css"]));
+
+    let (code, reads) = rebundle(&mut bundler);
+    assert_eq!(code, ".b{color:green}.a{color:red}");
+    assert!(reads.is_empty());
+
+    fs.set("/b.css", ".b { color: yellow }");
+    bundler.invalidate(Path::new("/b.css"));
+    let (code, reads) = rebundle(&mut bundler);
+    assert_eq!(code, ".b{color:#ff0}.a{color:red}");
+    assert_eq!(reads, paths(&["/b.css"]));
+
+    let mut fs: MemoryProvider = vec![
+      (
+        "/src/a.css",
+        r#"@import "./nested/../b.css"; @import "../lib/c.css"; .a { color: red }"#,
+      ),
+      ("/src/b.css", ".b { color: green }"),
+    ]
+    .into_iter()
+    .collect();
+    fs.insert("/lib/c.css", ".c { color: blue }");
+    let res = Bundler::new(&fs, None, ParserOptions::default())
+      .bundle(Path::new("/src/a.css"))
+      .unwrap()
+      .stylesheet
+      .to_css(PrinterOptions {
+        minify: true,
+        ..PrinterOptions::default()
+      })
+      .unwrap();
+    assert_eq!(res.code, ".b{color:green}.c{color:#00f}.a{color:red}");
+
+    fs.remove("/lib/c.css");
+    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+    let err = bundler.bundle(Path::new("/src/a.css")).unwrap_err();
+    assert!(matches!(err.kind, BundleErrorKind::IOError(_)));
+
+    fn bundle_entries(
+      fs: TestProvider,
+      entries: &[&str],
+      extract_shared: bool,
+    ) -> (Vec<String>, Vec<String>, BundleManifest) {
+      let bundle_options = BundleOptions {
+        extract_shared,
+        ..BundleOptions::default()
+      };
+      let mut bundler = Bundler::with_options(&fs, None, ParserOptions::default(), bundle_options);
+      let entries: Vec<&Path> = entries.iter().map(Path::new).collect();
+      let res = bundler.bundle_entries(&entries).unwrap();
+      let print = |stylesheets: Vec<StyleSheet>| {
+        stylesheets
+          .iter()
+          .map(|stylesheet| {
+            let options = PrinterOptions {
+              minify: true,
+              ..PrinterOptions::default()
+            };
+            stylesheet.to_css(options).unwrap().code
+          })
+          .collect()
+      };
+      (print(res.entries), print(res.shared), res.manifest)
+    }
+
+    let entries_fs = || {
       fs! {
+        "/reset.css": ".reset { color: green }",
+        "/base.css": ".base { color: red }",
         "/a.css": r#"
-        @import "b.css" layer(foo);
-        @import "b.css" layer(bar);
+        @import "reset.css";
+        @import "base.css";
+        .a { color: blue }
       "#,
         "/b.css": r#"
-        .b { color: red }
+        @import "reset.css";
+        @import "base.css";
+        .b { color: yellow }
+      "#,
+        "/c.css": r#"
+        @import "base.css";
+        .c { color: green }
       "#
-      },
-      "/a.css",
+      }
+    };
+    let (entries, shared, manifest) = bundle_entries(entries_fs(), &["/a.css", "/b.css", "/c.css"], false);
+    assert_eq!(
+      entries,
+      vec![
+        ".reset{color:green}.base{color:red}.a{color:#00f}",
+        ".reset{color:green}.base{color:red}.b{color:#ff0}",
+        ".base{color:red}.c{color:green}",
+      ]
     );
+    assert!(shared.is_empty());
+    assert!(manifest.entries.iter().all(|entry| entry.shared.is_empty()));
 
-    error_test(
+    let (entries, shared, manifest) = bundle_entries(entries_fs(), &["/a.css", "/b.css", "/c.css"], true);
+    assert_eq!(entries, vec![".a{color:#00f}", ".b{color:#ff0}", ".c{color:green}"]);
+    assert_eq!(shared, vec![".reset{color:green}", ".base{color:red}"]);
+    assert_eq!(
+      manifest,
+      BundleManifest {
+        entries: vec![
+          EntryManifest {
+            path: "/a.css".into(),
+            shared: vec![0, 1]
+          },
+          EntryManifest {
+            path: "/b.css".into(),
+            shared: vec![0, 1]
+          },
+          EntryManifest {
+            path: "/c.css".into(),
+            shared: vec![1]
+          },
+        ],
+        shared: vec![
+          SharedManifest {
+            files: vec!["/reset.css".into()],
+            entries: vec![0, 1]
+          },
+          SharedManifest {
+            files: vec!["/base.css".into()],
+            entries: vec![0, 1, 2]
+          },
+        ]
+      }
+    );
+
+    // Files concatenated after rules that are not shared must stay in the entry.
+    let (entries, shared, manifest) = bundle_entries(
       fs! {
+        "/shared.css": ".shared { color: green }",
         "/a.css": r#"
-        @import "b.css" layer;
-        @import "b.css" layer;
+        @import "a-only.css";
+        @import "shared.css";
       "#,
+        "/a-only.css": ".a { color: red }",
         "/b.css": r#"
-        .b { color: red }
+        @import "shared.css";
+        .b { color: blue }
       "#
       },
-      "/a.css",
+      &["/a.css", "/b.css"],
+      true,
+    );
+    assert_eq!(
+      entries,
+      vec![
+        ".a{color:red}.shared{color:green}",
+        ".shared{color:green}.b{color:#00f}"
+      ]
     );
+    assert!(shared.is_empty());
+    assert!(manifest.shared.is_empty());
 
-    error_test(
+    // Files in a different order, or with different conditions in each entry, cannot be shared.
+    let (entries, shared, _) = bundle_entries(
       fs! {
+        "/x.css": ".x { color: green }",
+        "/y.css": ".y { color: red }",
         "/a.css": r#"
-        @import "b.css" layer;
-        .a { color: red }
+        @import "x.css";
+        @import "y.css";
       "#,
         "/b.css": r#"
-        @import "c.css" layer;
-        .b { color: green }
+        @import "y.css";
+        @import "x.css";
       "#,
         "/c.css": r#"
-        .c { color: green }
+        @import "x.css" print;
       "#
       },
-      "/a.css",
+      &["/a.css", "/b.css", "/c.css"],
+      true,
+    );
+    assert_eq!(
+      entries,
+      vec![
+        ".x{color:green}.y{color:red}",
+        ".y{color:red}.x{color:green}",
+        "@media print{.x{color:green}}"
+      ]
     );
+    assert!(shared.is_empty());
 
-    error_test(
-      fs! {
-        "/a.css": r#"
-        @import "b.css" layer;
+    let fs = fs! {
+      "/a.css": r#"
+        @import "b.css" layer(foo) print;
+        @import "c.css" supports(display: grid);
         .a { color: red }
       "#,
-        "/b.css": r#"
-        @import "c.css" layer(foo);
-        .b { color: green }
+      "/b.css": ".b { color: green }",
+      "/c.css": r#"
+        @import "d.css";
+        .c { color: blue }
       "#,
-        "/c.css": r#"
-        .c { color: green }
-      "#
-      },
-      "/a.css",
+      "/d.css": ".d { color: yellow }"
+    };
+    let mut bundler = Bundler::new(&fs, None, ParserOptions::default());
+    let graph = bundler.bundle(Path::new("/a.css")).unwrap().graph;
+    assert_eq!(
+      graph.files,
+      vec![
+        BundleGraphFile {
+          path: "/a.css".into(),
+          conditions: ImportConditions::default(),
+        },
+        BundleGraphFile {
+          path: "/b.css".into(),
+          conditions: ImportConditions {
+            layer: Some("foo".into()),
+            media: Some("print".into()),
+            supports: None,
+          },
+        },
+        BundleGraphFile {
+          path: "/c.css".into(),
+          conditions: ImportConditions {
+            layer: None,
+            media: None,
+            supports: Some("(display: grid)".into()),
+          },
+        },
+        BundleGraphFile {
+          path: "/d.css".into(),
+          conditions: ImportConditions {
+            layer: None,
+            media: None,
+            supports: Some("(display: grid)".into()),
+          },
+        },
+      ]
     );
+    assert_eq!(
+      graph.edges,
+      vec![
+        BundleGraphEdge {
+          from: 0,
+          to: 1,
+          kind: BundleGraphEdgeKind::Import,
+          specifier: "b.css".into(),
+          conditions: ImportConditions {
+            layer: Some("foo".into()),
+            media: Some("print".into()),
+            supports: None,
+          },
+          loc: ErrorLocation {
+            filename: "/a.css".into(),
+            line: 1,
+            column: 9,
+          },
+        },
+        BundleGraphEdge {
+          from: 0,
+          to: 2,
+          kind: BundleGraphEdgeKind::Import,
+          specifier: "c.css".into(),
+          conditions: ImportConditions {
+            layer: None,
+            media: None,
+            supports: Some("(display: grid)".into()),
+          },
+          loc: ErrorLocation {
+            filename: "/a.css".into(),
+            line: 2,
+            column: 9,
+          },
+        },
+        BundleGraphEdge {
+          from: 2,
+          to: 3,
+          kind: BundleGraphEdgeKind::Import,
+          specifier: "d.css".into(),
+          conditions: ImportConditions::default(),
+          loc: ErrorLocation {
+            filename: "/c.css".into(),
+            line: 1,
+            column: 9,
+          },
+        },
+      ]
+    );
+    assert_eq!(graph.order, vec![1, 3, 2, 0]);
 
-    let res = bundle(
+    let fs = ChangingProvider {
+      files: Mutex::new(HashMap::new()),
+      reads: Mutex::new(Vec::new()),
+    };
+    fs.set("/a.css", r#"@import "b.css"; @import "c.css"; .a { color: red }"#);
+    fs.set("/b.css", ".b { color: green }");
+    fs.set("/c.css", ".c { color: blue }");
+    let bundle_options = BundleOptions {
+      cache: true,
+      ..BundleOptions::default()
+    };
+    let mut bundler = Bundler::with_options(&fs, None, ParserOptions::default(), bundle_options);
+    let rebundle = |bundler: &mut Bundler<ChangingProvider>| {
+      let stylesheet = bundler.bundle(Path::new("/a.css")).unwrap().stylesheet;
+      let mut reads = fs.take_reads();
+      reads.sort();
+      let options = PrinterOptions {
+        minify: true,
+        ..PrinterOptions::default()
+      };
+      (stylesheet.to_css(options).unwrap().code, reads)
+    };
+    let paths = |paths: &[&str]| paths.iter().map(PathBuf::from).collect::<Vec<_>>();
+
+    let (code, reads) = rebundle(&mut bundler);
+    assert_eq!(code, ".b{color:green}.c{color:#00f}.a{color:red}");
+    assert_eq!(reads, paths(&["/a.css", "/b.css", "/c.css"]));
+
+    // Files are not read again unless they were invalidated.
+    let (code, reads) = rebundle(&mut bundler);
+    assert_eq!(code, ".b{color:green}.c{color:#00f}.a{color:red}");
+    assert!(reads.is_empty());
+
+    fs.set("/b.css", ".b { color: yellow }");
+    bundler.invalidate(Path::new("/b.css"));
+    let (code, reads) = rebundle(&mut bundler);
+    assert_eq!(code, ".b{color:#ff0}.c{color:#00f}.a{color:red}");
+    assert_eq!(reads, paths(&["/b.css"]));
+
+    // New imports of a changed file are loaded.
+    fs.set("/c.css", r#"@import "d.css"; .c { color: blue }"#);
+    fs.set("/d.css", ".d { color: green }");
+    bundler.invalidate_all();
+    let (code, reads) = rebundle(&mut bundler);
+    assert_eq!(code, ".b{color:#ff0}.d{color:green}.c{color:#00f}.a{color:red}");
+    assert_eq!(reads, paths(&["/a.css", "/b.css", "/c.css", "/d.css"]));
+
+    // Files that fail to parse are read again by the next bundle.
+    fs.set("/d.css", ".d..e { color: green }");
+    bundler.invalidate(Path::new("/d.css"));
+    assert!(bundler.bundle(Path::new("/a.css")).is_err());
+    let mut files = bundler.files();
+    files.sort();
+    assert_eq!(files, paths(&["/a.css", "/b.css", "/c.css", "/d.css"]));
+    fs.take_reads();
+    fs.set("/d.css", ".d { color: red }");
+    let (code, reads) = rebundle(&mut bundler);
+    assert_eq!(code, ".b{color:#ff0}.d{color:red}.c{color:#00f}.a{color:red}");
+    assert_eq!(reads, paths(&["/d.css"]));
+
+    fn bundle_with_options(fs: TestProvider, entry: &str, bundle_options: BundleOptions) -> ToCssResult {
+      let mut bundler = Bundler::with_options(&fs, None, ParserOptions::default(), bundle_options);
+      let stylesheet = bundler.bundle(Path::new(entry)).unwrap().stylesheet;
+      stylesheet.to_css(PrinterOptions::default()).unwrap()
+    }
+
+    let url_fs = || {
       fs! {
-        "/index.css": r#"
-        @import "a.css";
-        @import "b.css";
-      "#,
         "/a.css": r#"
-        @import "./c.css";
-        body { background: red; }
-      "#,
-        "/b.css": r#"
-        @import "./c.css";
-        body { color: red; }
+        @import "b/c.css";
+        @import "d/e.css";
+        .a { background: url(img.png) }
       "#,
-        "/c.css": r#"
-        body {
-          background: white;
-          color: black; 
+        "/b/c.css": r#"
+        .c { background: url(img.png?v=1#x) }
+        @font-face {
+          font-family: Foo;
+          src: url(fonts/foo.woff2) format("woff2"), url(/abs.woff);
         }
+        .d {
+          background-image: image-set(url(x.png) 1x, url("./x@2x.png") 2x);
+          mask: url(#mask);
+          --bg: url(bg.png);
+        }
+      "#,
+        "/d/e.css": r#"
+        .e { background: url(../b/img.png), url(https://example.com/img.png) }
       "#
+      }
+    };
+
+    let res = bundle_with_options(url_fs(), "/a.css", BundleOptions::default());
+    assert_eq!(
+      res.code,
+      indoc! { r#"
+      .c {
+        background: url(b/img.png?v=1#x);
+      }
+
+      @font-face {
+        font-family: Foo;
+        src: url(b/fonts/foo.woff2) format("woff2"), url(/abs.woff);
+      }
+
+      .d {
+        background-image: image-set("b/x.png", "b/x@2x.png" 2x);
+        mask: url(#mask);
+        --bg: url(b/bg.png);
+      }
+
+      .e {
+        background: url(b/img.png), url(https://example.com/img.png);
+      }
+
+      .a {
+        background: url(img.png);
+      }
+    "#}
+    );
+    assert_eq!(res.warnings.len(), 1);
+    assert_eq!(
+      res.warnings[0].kind,
+      WarningKind::AmbiguousUrlInCustomProperty { url: "bg.png".into() }
+    );
+    assert_eq!(res.warnings[0].loc.as_ref().unwrap().filename, "/b/c.css");
+
+    let res = bundle_with_options(
+      url_fs(),
+      "/a.css",
+      BundleOptions {
+        url_rebase: UrlRebase::Root(PathBuf::from("/")),
+        ..BundleOptions::default()
       },
-      "/index.css",
     );
     assert_eq!(
-      res,
+      res.code,
       indoc! { r#"
-      body {
-        background: red;
+      .c {
+        background: url(/b/img.png?v=1#x);
       }
 
-      body {
-        background: #fff;
-        color: #000;
+      @font-face {
+        font-family: Foo;
+        src: url(/b/fonts/foo.woff2) format("woff2"), url(/abs.woff);
       }
 
-      body {
-        color: red;
+      .d {
+        background-image: image-set("/b/x.png", "/b/x@2x.png" 2x);
+        mask: url(#mask);
+        --bg: url(/b/bg.png);
+      }
+
+      .e {
+        background: url(/b/img.png), url(https://example.com/img.png);
+      }
+
+      .a {
+        background: url(/img.png);
       }
     "#}
     );
 
-    let res = bundle(
+    let res = bundle_with_options(
+      url_fs(),
+      "/a.css",
+      BundleOptions {
+        url_rebase: UrlRebase::Preserve,
+        ..BundleOptions::default()
+      },
+    );
+    assert!(res.code.contains("background: url(img.png?v=1#x);"));
+    assert!(res
+      .code
+      .contains("background: url(../b/img.png), url(https://example.com/img.png);"));
+    assert!(res.warnings.is_empty());
+
+    // Files above the directory of a relative entry are rebased with `..` segments.
+    let res = bundle_with_options(
       fs! {
-        "/index.css": r#"
-        @import "a.css";
-        @import "b.css";
-        @import "a.css";
+        "index.css": r#"
+        @import "../lib/a.css";
+        .index { background: url(img.png) }
       "#,
+        "../lib/a.css": r#"
+        .a { background: url(img.png), url(../img.png) }
+      "#
+      },
+      "index.css",
+      BundleOptions::default(),
+    );
+    assert_eq!(
+      res.code,
+      indoc! { r#"
+      .a {
+        background: url(../lib/img.png), url(../img.png);
+      }
+
+      .index {
+        background: url(img.png);
+      }
+    "#}
+    );
+
+    let res = bundle_with_options(
+      fs! {
         "/a.css": r#"
-        body { background: green; }
+        @import "b/c.css";
+        .a { background: url(b/icon.png) }
       "#,
-        "/b.css": r#"
-        body { background: red; }
-      "#
+        "/b/c.css": r#"
+        .c { background: url(icon.png), url(icon.png#x), url(big.png), url(data.txt) }
+        .d { background: url("../logo.svg") }
+        @font-face {
+          font-family: Foo;
+          src: url(font.woff2) format("woff2");
+        }
+      "#,
+        "/b/icon.png": "abc",
+        "/b/big.png": "x".repeat(101),
+        "/b/data.txt": "abc",
+        "/b/font.woff2": "wOF2",
+        "/logo.svg": r#"<svg xmlns="http://www.w3.org/2000/svg"><path d="M0 0"/></svg>"#
+      },
+      "/a.css",
+      BundleOptions {
+        inline_asset_limit: Some(100),
+        ..BundleOptions::default()
       },
-      "/index.css",
     );
     assert_eq!(
-      res,
+      res.code,
       indoc! { r#"
-      body {
-        background: red;
+      .c {
+        background: url(data:image/png;base64,YWJj), url(b/icon.png#x), url(b/big.png), url(b/data.txt);
       }
 
-      body {
-        background: green;
+      .d {
+        background: url(data:image/svg+xml,%3Csvg%20xmlns=%22http://www.w3.org/2000/svg%22%3E%3Cpath%20d=%22M0%200%22/%3E%3C/svg%3E);
+      }
+
+      @font-face {
+        font-family: Foo;
+        src: url(data:font/woff2;base64,d09GMg==) format("woff2");
+      }
+
+      .a {
+        background: url(data:image/png;base64,YWJj);
       }
     "#}
     );
@@ -1252,4 +4433,71 @@ mod tests {
     //   "#
     // }, "/a.css");
   }
+
+  #[cfg(feature = "node-resolve")]
+  #[test]
+  fn test_resolve_node_style() {
+    let dir = std::env::temp_dir().join(format!("parcel-css-resolve-{}", std::process::id()));
+    let src = dir.join("src");
+    let pkg = dir.join("node_modules").join("pkg");
+    let scoped = dir.join("node_modules").join("@scope").join("theme");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&pkg).unwrap();
+    fs::create_dir_all(&scoped).unwrap();
+    fs::write(src.join("local.css"), "").unwrap();
+    fs::write(
+      pkg.join("package.json"),
+      r#"{"main": "index.js", "style": "dist/pkg.css"}"#,
+    )
+    .unwrap();
+    let exported = dir.join("node_modules").join("exported");
+    fs::create_dir_all(&exported).unwrap();
+    fs::write(
+      exported.join("package.json"),
+      r#"{
+        "style": "ignored.css",
+        "exports": {
+          ".": { "style": "./dist/index.css", "default": "./index.js" },
+          "./themes/*": { "default": "./dist/themes/*.css" },
+          "./package.json": "./package.json"
+        }
+      }"#,
+    )
+    .unwrap();
+    let entry = src.join("a.css");
+    assert_eq!(resolve_node_style("local.css", &entry).unwrap(), src.join("local.css"));
+    assert_eq!(
+      resolve_node_style("./missing.css", &entry).unwrap(),
+      src.join("./missing.css")
+    );
+    assert_eq!(resolve_node_style("pkg", &entry).unwrap(), pkg.join("dist/pkg.css"));
+    assert_eq!(resolve_node_style("~pkg", &entry).unwrap(), pkg.join("dist/pkg.css"));
+    assert_eq!(
+      resolve_node_style("pkg/other.css", &entry).unwrap(),
+      pkg.join("other.css")
+    );
+    assert_eq!(
+      resolve_node_style("@scope/theme", &entry).unwrap(),
+      scoped.join("index.css")
+    );
+    assert_eq!(
+      resolve_node_style("@scope/theme/dark.css", &entry).unwrap(),
+      scoped.join("dark.css")
+    );
+    assert!(resolve_node_style("~missing", &entry).is_err());
+    assert_eq!(
+      resolve_node_style("exported", &entry).unwrap(),
+      exported.join("./dist/index.css")
+    );
+    assert_eq!(
+      resolve_node_style("exported/themes/dark", &entry).unwrap(),
+      exported.join("./dist/themes/dark.css")
+    );
+    assert!(resolve_node_style("exported/dist/index.css", &entry).is_err());
+    assert_eq!(
+      resolve_node_style("~/styles/theme.css", &entry).unwrap_err().kind(),
+      std::io::ErrorKind::InvalidInput
+    );
+    fs::remove_dir_all(&dir).unwrap();
+  }
 }
@@ -30,6 +30,7 @@ pub enum Feature {
   CssPlaceholderShown,
   CssReadOnlyWrite,
   CssRrggbbaa,
+  CssScope,
   CssSel2,
   CssSel3,
   CssSelection,
@@ -1199,9 +1200,11 @@ impl Feature {
           return false;
         }
       }
-      Feature::CssNesting | Feature::CustomMediaQueries | Feature::MediaIntervalSyntax | Feature::OklabColors => {
-        return false
-      }
+      Feature::CssNesting
+      | Feature::CustomMediaQueries
+      | Feature::CssScope
+      | Feature::MediaIntervalSyntax
+      | Feature::OklabColors => return false,
       Feature::CssNotSelList => {
         if let Some(version) = browsers.edge {
           if version < 5767168 {
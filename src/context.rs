@@ -82,6 +82,7 @@ impl<'i> PropertyHandlerContext<'i> {
           declarations: DeclarationBlock {
             declarations: std::mem::take(&mut self.$decls),
             important_declarations: vec![],
+            at_rules: vec![],
           },
           rules: CssRuleList(vec![]),
           loc: style_rule.loc.clone(),
@@ -164,6 +165,7 @@ impl<'i> PropertyHandlerContext<'i> {
           declarations: DeclarationBlock {
             declarations: entry.declarations,
             important_declarations: entry.important_declarations,
+            at_rules: vec![],
           },
           rules: CssRuleList(vec![]),
           loc: style_rule.loc.clone(),
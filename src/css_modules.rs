@@ -7,6 +7,14 @@
 //! CSS modules can be enabled using the `css_modules` option when parsing a style sheet. When the
 //! style sheet is printed, hashes will be added to any declared names, and references to those names
 //! will be updated accordingly. A map of the original names to compiled (hashed) names will be returned.
+//!
+//! The format of the compiled names can be customized using a [Pattern](Pattern) in the CSS modules [Config](Config).
+//! Dashed identifiers such as CSS variables are global by default, but can be scoped as well by enabling
+//! the `dashed_idents` option.
+//!
+//! The [ICSS](https://github.com/css-modules/icss) `:import` and `:export` rules, as well as `@value` rules,
+//! are also supported. Values are substituted into property values and media queries, and are exported
+//! along with the compiled names.
 
 use crate::error::PrinterErrorKind;
 use crate::properties::css_modules::{Composes, ComposesFrom};
@@ -15,9 +23,191 @@ use data_encoding::{Encoding, Specification};
 use lazy_static::lazy_static;
 use parcel_selectors::SelectorList;
 use serde::Serialize;
+use smallvec::{smallvec, SmallVec};
 use std::collections::hash_map::DefaultHasher;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::path::Path;
+
+/// Configuration for CSS modules.
+#[derive(Default, Clone, Debug)]
+pub struct Config {
+  /// The pattern to use when renaming class names and other identifiers.
+  /// Default is `[hash]_[local]`.
+  pub pattern: Pattern,
+  /// Whether to scope dashed identifiers, e.g. custom property names, `var()` references,
+  /// `@property` rules, and `@custom-media` names. Default is `false`.
+  pub dashed_idents: bool,
+  /// Whether to require that every selector contains at least one local class name or id.
+  /// Selectors that only reference global names, e.g. `div > a` or `:global(.foo)`, result
+  /// in an error. Default is `false`.
+  pub pure: bool,
+}
+
+/// A CSS modules class name pattern, e.g. `[name]__[local]--[hash:5]`.
+///
+/// See [Segment](Segment) for the supported placeholders. The hash placeholders accept an
+/// optional length, which truncates the hash to at most that many characters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pattern {
+  /// The list of segments in the pattern.
+  pub segments: SmallVec<[Segment; 3]>,
+}
+
+impl Default for Pattern {
+  fn default() -> Self {
+    // Hash must come first so that CSS grid identifiers work.
+    // This is because grid lines may have an implicit -start or -end appended.
+    Pattern {
+      segments: smallvec![Segment::Hash(None), Segment::Literal("_".into()), Segment::Local],
+    }
+  }
+}
+
+/// A segment in a CSS modules class name [Pattern](Pattern).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Segment {
+  /// A literal string.
+  Literal(String),
+  /// The file name without the extension, i.e. `[name]`.
+  Name,
+  /// The original local name, i.e. `[local]`.
+  Local,
+  /// A hash of the file path, i.e. `[hash]` or `[hash:<length>]`.
+  Hash(Option<usize>),
+  /// A hash of the file contents, i.e. `[content-hash]` or `[content-hash:<length>]`.
+  ContentHash(Option<usize>),
+}
+
+/// An error that occurred while parsing a CSS modules [Pattern](Pattern).
+#[derive(Debug, PartialEq, Clone)]
+pub enum PatternParseError {
+  /// An unknown placeholder was encountered at the given index.
+  UnknownPlaceholder(String, usize),
+  /// An opening bracket with no matching closing bracket was encountered at the given index.
+  UnclosedBrackets(usize),
+  /// The length of a hash placeholder at the given index was not a positive integer.
+  InvalidHashLength(String, usize),
+  /// The pattern does not include the `[local]` placeholder.
+  MissingLocal,
+}
+
+impl fmt::Display for PatternParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    use PatternParseError::*;
+    match self {
+      UnknownPlaceholder(s, index) => write!(
+        f,
+        "Error parsing CSS modules pattern: unknown placeholder \"{}\" at index {}",
+        s, index
+      ),
+      UnclosedBrackets(index) => write!(
+        f,
+        "Error parsing CSS modules pattern: unclosed brackets at index {}",
+        index
+      ),
+      InvalidHashLength(s, index) => write!(
+        f,
+        "Error parsing CSS modules pattern: invalid hash length \"{}\" at index {}",
+        s, index
+      ),
+      MissingLocal => write!(
+        f,
+        "Error parsing CSS modules pattern: the [local] placeholder is required"
+      ),
+    }
+  }
+}
+
+impl std::error::Error for PatternParseError {}
+
+impl Pattern {
+  /// Parses a pattern from a string.
+  ///
+  /// Placeholders are wrapped in square brackets, and all other characters are copied
+  /// to the output as is. See [Segment](Segment) for the supported placeholders.
+  pub fn parse(input: &str) -> Result<Self, PatternParseError> {
+    let mut segments = SmallVec::new();
+    let mut start = 0;
+    for (index, _) in input.match_indices('[') {
+      if index < start {
+        continue;
+      }
+
+      if index > start {
+        segments.push(Segment::Literal(input[start..index].into()));
+      }
+
+      let end = match input[index..].find(']') {
+        Some(end) => index + end,
+        None => return Err(PatternParseError::UnclosedBrackets(index)),
+      };
+
+      let placeholder = &input[index + 1..end];
+      let (kind, len) = match placeholder.split_once(':') {
+        Some((kind, len)) => match len.parse::<usize>() {
+          Ok(len) if len > 0 => (kind, Some(len)),
+          _ => return Err(PatternParseError::InvalidHashLength(len.into(), index)),
+        },
+        None => (placeholder, None),
+      };
+
+      let segment = match (kind, len) {
+        ("name", None) => Segment::Name,
+        ("local", None) => Segment::Local,
+        ("hash", len) => Segment::Hash(len),
+        ("content-hash", len) => Segment::ContentHash(len),
+        _ => return Err(PatternParseError::UnknownPlaceholder(placeholder.into(), index)),
+      };
+
+      segments.push(segment);
+      start = end + 1;
+    }
+
+    if start < input.len() {
+      segments.push(Segment::Literal(input[start..].into()));
+    }
+
+    if !segments.contains(&Segment::Local) {
+      return Err(PatternParseError::MissingLocal);
+    }
+
+    Ok(Pattern { segments })
+  }
+
+  /// Returns whether the pattern includes a hash of the file contents.
+  pub(crate) fn has_content_hash(&self) -> bool {
+    self.segments.iter().any(|s| matches!(s, Segment::ContentHash(..)))
+  }
+
+  /// Returns whether the pattern ends with the local name. Otherwise, CSS grid line names
+  /// need special handling, because grid lines may have an implicit -start or -end appended.
+  pub(crate) fn ends_with_local(&self) -> bool {
+    matches!(self.segments.last(), Some(Segment::Local))
+  }
+
+  fn write(&self, hash: &str, name: &str, content_hash: &str, local: &str) -> String {
+    fn truncate(s: &str, len: Option<usize>) -> &str {
+      match len {
+        Some(len) if len < s.len() => &s[..len],
+        _ => s,
+      }
+    }
+
+    let mut res = String::new();
+    for segment in &self.segments {
+      match segment {
+        Segment::Literal(s) => res.push_str(s),
+        Segment::Name => res.push_str(name),
+        Segment::Local => res.push_str(local),
+        Segment::Hash(len) => res.push_str(truncate(hash, *len)),
+        Segment::ContentHash(len) => res.push_str(truncate(content_hash, *len)),
+      }
+    }
+    res
+  }
+}
 
 /// A referenced name within a CSS module, e.g. via the `composes` property.
 ///
@@ -48,7 +238,8 @@ pub enum CssModuleReference {
 #[derive(PartialEq, Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CssModuleExport {
-  /// The local (compiled) name for this export.
+  /// The local (compiled) name for this export. For values declared via `@value` or
+  /// an ICSS `:export` rule, this is the value itself.
   pub name: String,
   /// Other names that are composed by this export.
   pub composes: Vec<CssModuleReference>,
@@ -59,6 +250,116 @@ pub struct CssModuleExport {
 /// A map of exported names to values.
 pub type CssModuleExports = HashMap<String, CssModuleExport>;
 
+/// An error returned by [add_camel_case_aliases](add_camel_case_aliases) when the camelCased
+/// alias of an exported name conflicts with a different export.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CamelCaseCollision {
+  /// The exported name, e.g. `foo-bar`.
+  pub name: String,
+  /// The camelCased alias of the name, e.g. `fooBar`.
+  pub alias: String,
+}
+
+impl fmt::Display for CamelCaseCollision {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "The camelCase alias `{}` of the CSS module export `{}` conflicts with a different export of the same name",
+      self.alias, self.name
+    )
+  }
+}
+
+impl std::error::Error for CamelCaseCollision {}
+
+/// Adds a camelCased alias for each exported name that contains a dash, e.g. `fooBar` for `foo-bar`,
+/// as with the `camelCase` option of css-loader. The original names are kept, so exports can be
+/// referenced either way. Dashed identifiers such as `--foo` are left as is.
+///
+/// Returns an error if an alias conflicts with a different export.
+pub fn add_camel_case_aliases(exports: &mut CssModuleExports) -> Result<(), CamelCaseCollision> {
+  let mut aliases: Vec<(String, String)> = exports
+    .keys()
+    .filter(|name| !name.starts_with("--"))
+    .map(|name| (name.clone(), camel_case(name)))
+    .filter(|(name, alias)| name != alias)
+    .collect();
+  aliases.sort();
+
+  for (name, alias) in aliases {
+    let export = exports[&name].clone();
+    match exports.get(&alias) {
+      Some(existing) if *existing != export => return Err(CamelCaseCollision { name, alias }),
+      Some(_) => {}
+      None => {
+        exports.insert(alias, export);
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// Generates a TypeScript declaration file (i.e. `.d.ts`) for the given CSS module exports.
+///
+/// Each exported name is declared as a `string` property of the default export, sorted by name.
+/// Exports that compose other class names via the `composes` property are annotated with a
+/// doc comment listing the composed names. To declare camelCased names as well, add them to
+/// the exports first using [add_camel_case_aliases](add_camel_case_aliases).
+pub fn to_typescript_declarations(exports: &CssModuleExports) -> String {
+  let names: BTreeMap<_, _> = exports.iter().collect();
+
+  let mut res = String::from("declare const styles: {\n");
+  for (name, export) in names {
+    if !export.composes.is_empty() {
+      let composes = export
+        .composes
+        .iter()
+        .map(|reference| match reference {
+          CssModuleReference::Local { name } => format!("`{}`", name),
+          CssModuleReference::Global { name } => format!("`{}` (global)", name),
+          CssModuleReference::Dependency { name, specifier } => format!("`{}` from \"{}\"", name, specifier),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+      res.push_str(&format!("  /** Composes {}. */\n", composes));
+    }
+    res.push_str(&format!(
+      "  readonly \"{}\": string;\n",
+      name.replace('\\', "\\\\").replace('"', "\\\"")
+    ));
+  }
+  res.push_str("};\nexport default styles;\n");
+  res
+}
+
+fn camel_case(name: &str) -> String {
+  let mut res = String::with_capacity(name.len());
+  let mut upper = false;
+  for c in name.chars() {
+    if c == '-' && !res.is_empty() {
+      upper = true;
+    } else if upper {
+      res.extend(c.to_uppercase());
+      upper = false;
+    } else {
+      res.push(c);
+    }
+  }
+
+  if upper {
+    res.push('-');
+  }
+
+  res
+}
+
+/// A map of placeholders to references.
+///
+/// Names referenced from other files, e.g. `var(--foo from "file.css")`, are compiled to
+/// a placeholder, which should be replaced with the compiled name from the referenced file.
+pub type CssModuleReferences = HashMap<String, CssModuleReference>;
+
 lazy_static! {
   static ref ENCODER: Encoding = {
     let mut spec = Specification::new();
@@ -69,39 +370,191 @@ lazy_static! {
   };
 }
 
+/// The naming scope of a source file within a CSS module.
+struct Scope {
+  /// The source index of the file that defines the scope. Files included via `@import`
+  /// share the scope of the importing file, whereas files referenced via `composes` have their own.
+  source_index: u32,
+  hash: String,
+  name: String,
+  content_hash: String,
+}
+
 pub(crate) struct CssModule<'a> {
-  pub hash: &'a str,
+  pub config: &'a Config,
+  scopes: Vec<Scope>,
+  /// The source index of the scope whose exports are returned, i.e. the entry of a bundle.
+  entry: u32,
   pub exports: &'a mut CssModuleExports,
+  /// Exports of other CSS modules included in a bundle, e.g. via `composes`.
+  dependency_exports: HashMap<u32, CssModuleExports>,
+  pub references: &'a mut CssModuleReferences,
 }
 
 impl<'a> CssModule<'a> {
-  pub fn add_local(&mut self, exported: &str, local: &str) {
-    self.exports.entry(exported.into()).or_insert_with(|| CssModuleExport {
-      name: get_hashed_name(self.hash, local),
-      composes: vec![],
-      is_referenced: false,
-    });
+  pub fn new(
+    config: &'a Config,
+    sources: &[String],
+    source_scopes: Option<&Vec<u32>>,
+    content_hashes: Option<&Vec<String>>,
+    entry: u32,
+    exports: &'a mut CssModuleExports,
+    references: &'a mut CssModuleReferences,
+  ) -> Self {
+    let unknown = ["unknown.css".to_owned()];
+    let sources = if sources.is_empty() { &unknown[..] } else { sources };
+    let scopes = (0..sources.len())
+      .map(|index| {
+        let source_index = source_scopes.and_then(|scopes| scopes.get(index).copied()).unwrap_or(0);
+        let filename = &sources[source_index as usize];
+        let name = Path::new(filename)
+          .file_stem()
+          .and_then(|stem| stem.to_str())
+          .unwrap_or(filename);
+
+        Scope {
+          source_index,
+          hash: hash(filename),
+          name: name.into(),
+          content_hash: content_hashes
+            .and_then(|hashes| hashes.get(source_index as usize))
+            .cloned()
+            .unwrap_or_default(),
+        }
+      })
+      .collect();
+
+    CssModule {
+      config,
+      scopes,
+      entry,
+      exports,
+      dependency_exports: HashMap::new(),
+      references,
+    }
+  }
+
+  fn scope(&self, source_index: u32) -> &Scope {
+    self.scopes.get(source_index as usize).unwrap_or(&self.scopes[0])
+  }
+
+  fn exports_mut(&mut self, source_index: u32) -> &mut CssModuleExports {
+    match self.scope(source_index).source_index {
+      index if index == self.entry => &mut *self.exports,
+      index => self.dependency_exports.entry(index).or_default(),
+    }
   }
 
-  pub fn reference(&mut self, name: &str) {
-    match self.exports.entry(name.into()) {
+  /// Returns the compiled name for the given local name in the given source file,
+  /// according to the configured pattern.
+  pub fn get_name(&self, local: &str, source_index: u32) -> String {
+    let scope = self.scope(source_index);
+    self.config.pattern.write(&scope.hash, &scope.name, &scope.content_hash, local)
+  }
+
+  pub fn add_local(&mut self, exported: &str, local: &str, source_index: u32) {
+    let name = self.get_name(local, source_index);
+    self
+      .exports_mut(source_index)
+      .entry(exported.into())
+      .or_insert_with(|| CssModuleExport {
+        name,
+        composes: vec![],
+        is_referenced: false,
+      });
+  }
+
+  pub fn reference(&mut self, name: &str, source_index: u32) {
+    let compiled = self.get_name(name, source_index);
+    match self.exports_mut(source_index).entry(name.into()) {
       std::collections::hash_map::Entry::Occupied(mut entry) => {
         entry.get_mut().is_referenced = true;
       }
       std::collections::hash_map::Entry::Vacant(entry) => {
         entry.insert(CssModuleExport {
-          name: get_hashed_name(self.hash, name),
+          name: compiled,
+          composes: vec![],
+          is_referenced: true,
+        });
+      }
+    }
+  }
+
+  /// Adds a declared dashed ident (e.g. `--foo`) to the exports, and returns its compiled name.
+  pub fn add_dashed(&mut self, local: &str, source_index: u32) -> String {
+    let name = format!("--{}", self.get_name(&local[2..], source_index));
+    self
+      .exports_mut(source_index)
+      .entry(local.into())
+      .or_insert_with(|| CssModuleExport {
+        name,
+        composes: vec![],
+        is_referenced: false,
+      })
+      .name
+      .clone()
+  }
+
+  /// Handles a reference to a dashed ident, and returns its compiled name.
+  pub fn reference_dashed(&mut self, name: &str, from: &Option<ComposesFrom>, source_index: u32) -> String {
+    match from {
+      Some(ComposesFrom::Global) => name.into(),
+      Some(ComposesFrom::File(file)) => {
+        let placeholder = import_placeholder(file, name);
+        self.references.insert(
+          placeholder.clone(),
+          CssModuleReference::Dependency {
+            name: name.into(),
+            specifier: file.to_string(),
+          },
+        );
+        placeholder
+      }
+      Some(ComposesFrom::SourceIndex(index)) => format!("--{}", self.get_name(&name[2..], *index)),
+      None => {
+        let compiled = format!("--{}", self.get_name(&name[2..], source_index));
+        let export = self.exports_mut(source_index).entry(name.into()).or_insert(CssModuleExport {
+          name: compiled,
           composes: vec![],
           is_referenced: true,
         });
+        export.is_referenced = true;
+        export.name.clone()
       }
     }
   }
 
+  /// Adds a value declared via `@value` or an ICSS `:export` rule to the exports.
+  pub fn add_value(&mut self, name: &str, value: &str, source_index: u32) {
+    self.exports_mut(source_index).insert(
+      name.into(),
+      CssModuleExport {
+        name: value.into(),
+        composes: vec![],
+        is_referenced: false,
+      },
+    );
+  }
+
+  /// Adds a value imported from another file via `@value` or an ICSS `:import` rule to the
+  /// exports and references. The local name is exported as the placeholder for the imported value.
+  pub fn add_import(&mut self, local: &str, imported: &str, specifier: &str, source_index: u32) {
+    let placeholder = import_placeholder(specifier, imported);
+    self.references.insert(
+      placeholder.clone(),
+      CssModuleReference::Dependency {
+        name: imported.into(),
+        specifier: specifier.into(),
+      },
+    );
+    self.add_value(local, &placeholder, source_index);
+  }
+
   pub fn handle_composes(
     &mut self,
     selectors: &SelectorList<Selectors>,
     composes: &Composes,
+    source_index: u32,
   ) -> Result<(), PrinterErrorKind> {
     for sel in &selectors.0 {
       if sel.len() == 1 {
@@ -110,7 +563,10 @@ impl<'a> CssModule<'a> {
             for name in &composes.names {
               let reference = match &composes.from {
                 None => CssModuleReference::Local {
-                  name: get_hashed_name(self.hash, name.0.as_ref()),
+                  name: self.get_name(name.0.as_ref(), source_index),
+                },
+                Some(ComposesFrom::SourceIndex(index)) => CssModuleReference::Local {
+                  name: self.get_name(name.0.as_ref(), *index),
                 },
                 Some(ComposesFrom::Global) => CssModuleReference::Global {
                   name: name.0.as_ref().into(),
@@ -121,7 +577,7 @@ impl<'a> CssModule<'a> {
                 },
               };
 
-              let export = self.exports.get_mut(&id.0.as_ref().to_owned()).unwrap();
+              let export = self.exports_mut(source_index).get_mut(&id.0.as_ref().to_owned()).unwrap();
               if !export.composes.contains(&reference) {
                 export.composes.push(reference);
               }
@@ -140,10 +596,10 @@ impl<'a> CssModule<'a> {
   }
 }
 
-fn get_hashed_name(hash: &str, name: &str) -> String {
-  // Hash must come first so that CSS grid identifiers work.
-  // This is because grid lines may have an implicit -start or -end appended.
-  format!("{}_{}", hash, name)
+/// Returns the placeholder for a name imported from another file, which should be replaced
+/// with the compiled name or value from the referenced file.
+pub(crate) fn import_placeholder(specifier: &str, name: &str) -> String {
+  format!("--{}", hash(&format!("{}_{}", specifier, name)))
 }
 
 pub(crate) fn hash(s: &str) -> String {
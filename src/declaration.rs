@@ -2,9 +2,11 @@
 
 use crate::context::PropertyHandlerContext;
 use crate::error::{ParserError, PrinterError};
-use crate::parser::ParserOptions;
+use crate::macros::{impl_into_owned, impl_visit};
+use crate::parser::{parse_unknown_block, warn, ParserOptions};
 use crate::printer::Printer;
 use crate::properties::box_shadow::BoxShadowHandler;
+use crate::properties::custom::TokenList;
 use crate::properties::masking::MaskHandler;
 use crate::properties::Property;
 use crate::properties::{
@@ -27,8 +29,11 @@ use crate::properties::{
   transform::TransformHandler,
   transition::TransitionHandler,
 };
+use crate::rules::unknown::UnknownAtRule;
+use crate::rules::Location;
 use crate::targets::Browsers;
-use crate::traits::{PropertyHandler, ToCss};
+use crate::traits::{PropertyHandler, ToCss, Visit, Visitor};
+use crate::values::string::CowArcStr;
 use cssparser::*;
 
 /// A CSS declaration block.
@@ -37,13 +42,59 @@ use cssparser::*;
 /// and a list of normal declarations. This reduces memory usage compared
 /// with storing a boolean along with each property.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct DeclarationBlock<'i> {
   /// A list of `!important` declarations in the block.
   pub important_declarations: Vec<Property<'i>>,
   /// A list of normal declarations in the block.
   pub declarations: Vec<Property<'i>>,
+  /// A list of unknown at-rules in the block, e.g. `@apply`.
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub at_rules: Vec<DeclarationAtRule<'i>>,
 }
 
+/// An unknown at-rule within a declaration block, e.g. `@apply`.
+///
+/// These are stored separately from the properties, along with their position
+/// relative to the normal declarations so that they can be printed back in place.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct DeclarationAtRule<'i> {
+  /// The number of normal declarations that appear before the at-rule.
+  pub index: usize,
+  /// The at-rule.
+  pub rule: UnknownAtRule<'i>,
+}
+
+impl_visit!(DeclarationAtRule<'i> { index: _, rule });
+impl_into_owned!(DeclarationAtRule<'i> { index, rule });
+
+impl<'i> Visit<'i> for DeclarationBlock<'i> {
+  fn visit<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    visitor.visit_declaration_block(self)
+  }
+
+  fn visit_children<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    self.important_declarations.visit(visitor);
+    self.declarations.visit(visitor);
+    self.at_rules.visit(visitor);
+  }
+}
+
+impl_into_owned!(DeclarationBlock<'i> {
+  important_declarations,
+  declarations,
+  at_rules
+});
+
 impl<'i> DeclarationBlock<'i> {
   /// Parses a declaration block from CSS syntax.
   pub fn parse<'t>(
@@ -52,16 +103,23 @@ impl<'i> DeclarationBlock<'i> {
   ) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let mut important_declarations = DeclarationList::new();
     let mut declarations = DeclarationList::new();
+    let mut at_rules = Vec::new();
     let mut parser = DeclarationListParser::new(
       input,
       PropertyDeclarationParser {
         important_declarations: &mut important_declarations,
         declarations: &mut declarations,
+        at_rules: &mut at_rules,
         options,
       },
     );
     while let Some(res) = parser.next() {
       if let Err((err, _)) = res {
+        if options.error_recovery {
+          warn(err);
+          continue;
+        }
+
         return Err(err);
       }
     }
@@ -69,6 +127,7 @@ impl<'i> DeclarationBlock<'i> {
     Ok(DeclarationBlock {
       important_declarations,
       declarations,
+      at_rules,
     })
   }
 }
@@ -83,11 +142,23 @@ impl<'i> ToCss for DeclarationBlock<'i> {
     dest.indent();
 
     let mut i = 0;
-    let len = self.declarations.len() + self.important_declarations.len();
+    let len = self.len();
+    let mut at_rules = self.at_rules.iter().peekable();
+
+    macro_rules! write_at_rules {
+      ($index: expr) => {
+        while let Some(at_rule) = at_rules.next_if(|at_rule| at_rule.index <= $index) {
+          dest.newline()?;
+          at_rule.rule.to_css(dest)?;
+          i += 1;
+        }
+      };
+    }
 
     macro_rules! write {
       ($decls: expr, $important: literal) => {
-        for decl in &$decls {
+        for (index, decl) in $decls.iter().enumerate() {
+          write_at_rules!(index);
           dest.newline()?;
           decl.to_css(dest, $important)?;
           if i != len - 1 || !dest.minify {
@@ -99,6 +170,7 @@ impl<'i> ToCss for DeclarationBlock<'i> {
     }
 
     write!(self.declarations, false);
+    write_at_rules!(usize::MAX);
     write!(self.important_declarations, true);
 
     dest.dedent();
@@ -128,7 +200,17 @@ impl<'i> DeclarationBlock<'i> {
     }
 
     handle!(self.important_declarations, important_handler, true);
-    handle!(self.declarations, handler, false);
+
+    // Declarations are not combined across unknown at-rules, since those may
+    // depend on the order of the surrounding declarations (e.g. `@apply`).
+    let mut start = 0;
+    for at_rule in &mut self.at_rules {
+      handle!(self.declarations[start..at_rule.index], handler, false);
+      handler.finalize(context);
+      start = at_rule.index;
+      at_rule.index = handler.decls.len();
+    }
+    handle!(self.declarations[start..], handler, false);
 
     handler.finalize(context);
     important_handler.finalize(context);
@@ -138,13 +220,19 @@ impl<'i> DeclarationBlock<'i> {
 
   /// Returns whether the declaration block is empty.
   pub fn is_empty(&self) -> bool {
-    return self.declarations.is_empty() && self.important_declarations.is_empty();
+    return self.declarations.is_empty() && self.important_declarations.is_empty() && self.at_rules.is_empty();
+  }
+
+  /// Returns the number of declarations and at-rules in the block.
+  pub(crate) fn len(&self) -> usize {
+    self.declarations.len() + self.important_declarations.len() + self.at_rules.len()
   }
 }
 
 struct PropertyDeclarationParser<'a, 'i> {
   important_declarations: &'a mut Vec<Property<'i>>,
   declarations: &'a mut Vec<Property<'i>>,
+  at_rules: &'a mut Vec<DeclarationAtRule<'i>>,
   options: &'a ParserOptions,
 }
 
@@ -168,11 +256,66 @@ impl<'a, 'i> cssparser::DeclarationParser<'i> for PropertyDeclarationParser<'a,
   }
 }
 
-/// Default methods reject all at rules.
+/// Unknown at rules (e.g. `@apply`) are stored along with the declarations.
 impl<'a, 'i> AtRuleParser<'i> for PropertyDeclarationParser<'a, 'i> {
-  type Prelude = ();
+  type Prelude = (CowArcStr<'i>, TokenList<'i>);
   type AtRule = ();
   type Error = ParserError<'i>;
+
+  fn parse_prelude<'t>(
+    &mut self,
+    name: CowRcStr<'i>,
+    input: &mut cssparser::Parser<'i, 't>,
+  ) -> Result<Self::Prelude, cssparser::ParseError<'i, Self::Error>> {
+    input.skip_whitespace();
+    let prelude = TokenList::parse_raw(input, self.options, true)?;
+    Ok((name.into(), prelude))
+  }
+
+  fn parse_block<'t>(
+    &mut self,
+    (name, prelude): Self::Prelude,
+    start: &ParserState,
+    input: &mut cssparser::Parser<'i, 't>,
+  ) -> Result<Self::AtRule, cssparser::ParseError<'i, Self::Error>> {
+    let block = parse_unknown_block(input, self.options)?;
+    self.push_at_rule(name, prelude, Some(block), start);
+    Ok(())
+  }
+
+  fn rule_without_block(
+    &mut self,
+    (name, prelude): Self::Prelude,
+    start: &ParserState,
+  ) -> Result<Self::AtRule, ()> {
+    self.push_at_rule(name, prelude, None, start);
+    Ok(())
+  }
+}
+
+impl<'a, 'i> PropertyDeclarationParser<'a, 'i> {
+  fn push_at_rule(
+    &mut self,
+    name: CowArcStr<'i>,
+    prelude: TokenList<'i>,
+    block: Option<TokenList<'i>>,
+    start: &ParserState,
+  ) {
+    let loc = start.source_location();
+    self.at_rules.push(DeclarationAtRule {
+      index: self.declarations.len(),
+      rule: UnknownAtRule {
+        name,
+        prelude,
+        block,
+        loc: Location {
+          source_index: self.options.source_index,
+          line: loc.line,
+          column: loc.column,
+        },
+      },
+    });
+  }
 }
 
 pub(crate) fn parse_declaration<'i, 't>(
@@ -189,6 +332,9 @@ pub(crate) fn parse_declaration<'i, 't>(
       input.expect_ident_matching("important")
     })
     .is_ok();
+  // Ensure the whole declaration is valid before adding it, so that it can be dropped
+  // entirely during error recovery.
+  input.expect_exhausted()?;
   if important {
     important_declarations.push(property);
   } else {
@@ -1,7 +1,9 @@
 //! Error types.
 
+use crate::macros::impl_into_owned;
 use crate::properties::custom::Token;
 use crate::rules::Location;
+use crate::traits::IntoOwned;
 use crate::values::string::CowArcStr;
 use cssparser::{BasicParseErrorKind, ParseError, ParseErrorKind};
 use parcel_selectors::parser::SelectorParseErrorKind;
@@ -29,8 +31,19 @@ impl<T: fmt::Display> fmt::Display for Error<T> {
 
 impl<T: fmt::Display + fmt::Debug> std::error::Error for Error<T> {}
 
+impl<'any, T: IntoOwned<'any>> IntoOwned<'any> for Error<T> {
+  type Owned = Error<T::Owned>;
+
+  fn into_owned(self) -> Self::Owned {
+    Error {
+      kind: self.kind.into_owned(),
+      loc: self.loc,
+    }
+  }
+}
+
 /// A line and column location within a source file.
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub struct ErrorLocation {
   /// The filename in which the error occurred.
   pub filename: String,
@@ -67,6 +80,8 @@ pub enum ParserError<'i> {
   AtRuleInvalid(CowArcStr<'i>),
   /// Unexpectedly encountered the end of input data.
   EndOfInput,
+  /// A selector in a CSS module does not contain a local class or id, and the `pure` option is enabled.
+  ImpureCssModulesSelector(String),
   /// A declaration was invalid.
   InvalidDeclaration,
   /// A media query was invalid.
@@ -89,6 +104,23 @@ pub enum ParserError<'i> {
   UnexpectedToken(#[serde(skip)] Token<'i>),
 }
 
+impl_into_owned!(ParserError<'i> match {
+  AtRuleBodyInvalid,
+  AtRuleInvalid(name),
+  EndOfInput,
+  ImpureCssModulesSelector(selector),
+  InvalidDeclaration,
+  InvalidMediaQuery,
+  InvalidNesting,
+  InvalidPageSelector,
+  InvalidValue,
+  QualifiedRuleInvalid,
+  SelectorError(err),
+  UnexpectedImportRule,
+  UnexpectedNamespaceRule,
+  UnexpectedToken(token),
+});
+
 impl<'i> fmt::Display for ParserError<'i> {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     use ParserError::*;
@@ -96,6 +128,11 @@ impl<'i> fmt::Display for ParserError<'i> {
       AtRuleBodyInvalid => write!(f, "Invalid @ rule body"),
       AtRuleInvalid(name) => write!(f, "Unknown at rule: @{}", name),
       EndOfInput => write!(f, "Unexpected end of input"),
+      ImpureCssModulesSelector(selector) => write!(
+        f,
+        "Selector \"{}\" is not pure. Pure selectors must contain at least one local class or id",
+        selector
+      ),
       InvalidDeclaration => write!(f, "Invalid declaration"),
       InvalidMediaQuery => write!(f, "Invalid media query"),
       InvalidNesting => write!(f, "Invalid nesting"),
@@ -199,6 +236,28 @@ pub enum SelectorError<'i> {
   UnsupportedPseudoClassOrElement(CowArcStr<'i>),
 }
 
+impl_into_owned!(SelectorError<'i> match {
+  BadValueInAttr(token),
+  ClassNeedsIdent(token),
+  DanglingCombinator,
+  EmptySelector,
+  ExpectedBarInAttr(token),
+  ExpectedNamespace(name),
+  ExplicitNamespaceUnexpectedToken(token),
+  InvalidPseudoClassAfterPseudoElement,
+  InvalidPseudoClassAfterWebKitScrollbar,
+  InvalidPseudoClassBeforeWebKitScrollbar,
+  InvalidQualNameInAttr(token),
+  InvalidState,
+  MissingNestingPrefix,
+  MissingNestingSelector,
+  NoQualifiedNameInAttributeSelector(token),
+  PseudoElementExpectedIdent(token),
+  UnexpectedIdent(name),
+  UnexpectedTokenInAttributeSelector(token),
+  UnsupportedPseudoClassOrElement(name),
+});
+
 impl<'i> fmt::Display for SelectorError<'i> {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     use SelectorError::*;
@@ -296,11 +355,6 @@ pub enum MinifyErrorKind {
     /// The name of the `@custom-media` rule that was not defined.
     name: String,
   },
-  /// Boolean logic with media types in @custom-media rules is not supported.
-  UnsupportedCustomMediaBooleanLogic {
-    /// The source location of the `@custom-media` rule with unsupported boolean logic.
-    custom_media_loc: Location,
-  },
 }
 
 impl fmt::Display for MinifyErrorKind {
@@ -309,10 +363,6 @@ impl fmt::Display for MinifyErrorKind {
     match self {
       CircularCustomMedia { name } => write!(f, "Circular custom media query {} detected", name),
       CustomMediaNotDefined { name } => write!(f, "Custom media query {} is not defined", name),
-      UnsupportedCustomMediaBooleanLogic { .. } => write!(
-        f,
-        "Boolean logic with media types in @custom-media rules is not supported by Parcel CSS"
-      ),
     }
   }
 }
@@ -373,3 +423,121 @@ impl PrinterErrorKind {
     self.to_string()
   }
 }
+
+pub(crate) type MinifyWarning = ErrorWithLocation<WarningKind>;
+
+/// A warning emitted while bundling, minifying or printing a style sheet.
+pub type Warning = Error<WarningKind>;
+
+impl_into_owned!(WarningKind);
+
+/// A warning type.
+///
+/// Warnings describe decisions made while transforming a style sheet that may
+/// not match the author's intent, but do not prevent output from being generated.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum WarningKind {
+  /// A relative url within a custom property was rebased while bundling. Browsers may resolve it
+  /// relative to the style sheet where the property is used rather than declared.
+  AmbiguousUrlInCustomProperty {
+    /// The original url.
+    url: String,
+  },
+  /// A circular `@import` was ignored while bundling.
+  CircularImport {
+    /// The locations of the @import rules that form the cycle, in order.
+    chain: Vec<ErrorLocation>,
+  },
+  /// A feature is not supported by the browser targets, and could not be lowered.
+  UnsupportedFeature {
+    /// A description of the unsupported feature.
+    feature: String,
+  },
+  /// A feature could not be lowered for the browser targets, since parsing it requires a draft
+  /// syntax that is not enabled in the parser options.
+  DraftNotEnabled {
+    /// A description of the feature.
+    feature: String,
+    /// The name of the parser option that enables the draft syntax.
+    draft: String,
+  },
+  /// A `@media` rule referencing `@custom-media` rules was left unchanged, since boolean logic
+  /// with media types cannot be emulated for the browser targets.
+  UnsupportedCustomMediaBooleanLogic {
+    /// The source location of the `@custom-media` rule with unsupported boolean logic.
+    custom_media_loc: Location,
+  },
+  /// A vendor prefixed rule was removed, since the prefix is not needed by the browser targets.
+  VendorPrefixRemoved {
+    /// The prefixed name of the rule, e.g. `@-webkit-keyframes`.
+    name: String,
+  },
+  /// An `!important` declaration within `@keyframes` was removed, since it is ignored by browsers.
+  ImportantInKeyframes {
+    /// The name of the property that was removed.
+    property: String,
+  },
+  /// A color outside the sRGB gamut was converted to an RGB fallback for the browser targets.
+  LossyColorConversion {
+    /// The original color.
+    color: String,
+  },
+}
+
+impl fmt::Display for WarningKind {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    use WarningKind::*;
+    match self {
+      AmbiguousUrlInCustomProperty { url } => write!(
+        f,
+        "The relative url `{}` in a custom property was rebased relative to the file it was declared in, but may be resolved differently where the property is used",
+        url
+      ),
+      CircularImport { chain } => write!(
+        f,
+        "Circular @import of {}, which was ignored",
+        format_import_chain(chain)
+      ),
+      UnsupportedFeature { feature } => write!(
+        f,
+        "{} is not supported by the configured browser targets, and was left unchanged",
+        feature
+      ),
+      DraftNotEnabled { feature, draft } => write!(
+        f,
+        "{} could not be lowered for the configured browser targets, since the `{}` draft is not enabled",
+        feature, draft
+      ),
+      UnsupportedCustomMediaBooleanLogic { .. } => write!(
+        f,
+        "Boolean logic with media types in @custom-media rules cannot be lowered, so the @media rule was left unchanged"
+      ),
+      VendorPrefixRemoved { name } => write!(
+        f,
+        "{} is not needed by the configured browser targets, and was removed",
+        name
+      ),
+      ImportantInKeyframes { property } => write!(
+        f,
+        "The `!important` declaration of `{}` is ignored within @keyframes, and was removed",
+        property
+      ),
+      LossyColorConversion { color } => write!(
+        f,
+        "The color {} is outside the sRGB gamut, and its RGB fallback will not match exactly",
+        color
+      ),
+    }
+  }
+}
+
+/// Formats a chain of circular `@import` rules as a list of files, e.g. `a.css -> b.css -> a.css`.
+pub(crate) fn format_import_chain(chain: &[ErrorLocation]) -> String {
+  chain
+    .iter()
+    .chain(chain.first())
+    .map(|loc| loc.filename.as_str())
+    .collect::<Vec<_>>()
+    .join(" -> ")
+}
@@ -32,6 +32,8 @@ pub mod printer;
 pub mod properties;
 pub mod rules;
 mod selector;
+#[cfg(feature = "serde")]
+mod serialization;
 pub mod stylesheet;
 pub mod targets;
 pub mod traits;
@@ -40,7 +42,7 @@ pub mod vendor_prefix;
 
 #[cfg(test)]
 mod tests {
-  use crate::css_modules::{CssModuleExport, CssModuleExports, CssModuleReference};
+  use crate::css_modules::{self, CssModuleExport, CssModuleExports, CssModuleReference};
   use crate::dependencies::Dependency;
   use crate::error::{Error, ErrorLocation, MinifyErrorKind, ParserError, PrinterErrorKind, SelectorError};
   use crate::properties::custom::Token;
@@ -49,7 +51,7 @@ mod tests {
   use crate::rules::Location;
   use crate::stylesheet::*;
   use crate::targets::Browsers;
-  use crate::traits::{Parse, ToCss};
+  use crate::traits::{IntoOwned, Parse, ToCss};
   use crate::values::color::CssColor;
   use indoc::indoc;
   use std::collections::HashMap;
@@ -151,11 +153,20 @@ mod tests {
   }
 
   fn css_modules_test(source: &str, expected: &str, expected_exports: CssModuleExports) {
+    css_modules_test_with_config(source, expected, expected_exports, css_modules::Config::default())
+  }
+
+  fn css_modules_test_with_config(
+    source: &str,
+    expected: &str,
+    expected_exports: CssModuleExports,
+    config: css_modules::Config,
+  ) {
     let mut stylesheet = StyleSheet::parse(
       "test.css",
       &source,
       ParserOptions {
-        css_modules: true,
+        css_modules: Some(config),
         ..ParserOptions::default()
       },
     )
@@ -15907,6 +15918,460 @@ mod tests {
         "foo" => "EgL3uq_foo"
       },
     );
+
+    let pattern = |pattern: &str| css_modules::Config {
+      pattern: css_modules::Pattern::parse(pattern).unwrap(),
+      ..Default::default()
+    };
+
+    css_modules_test_with_config(
+      r#"
+      .foo {
+        color: red;
+      }
+
+      .bar {
+        composes: foo;
+        animation: fade 1s;
+      }
+    "#,
+      indoc! {r#"
+      .test__foo--EgL3u {
+        color: red;
+      }
+
+      .test__bar--EgL3u {
+        animation: test__fade--EgL3u 1s;
+      }
+    "#},
+      map! {
+        "foo" => "test__foo--EgL3u",
+        "bar" => "test__bar--EgL3u" "test__foo--EgL3u",
+        "fade" => "test__fade--EgL3u" referenced: true
+      },
+      pattern("[name]__[local]--[hash:5]"),
+    );
+
+    css_modules_test_with_config(
+      r#"
+      .foo {
+        grid-column: a-start / b-end;
+        grid-template-areas: "a b";
+      }
+    "#,
+      indoc! {r#"
+      .module-EgL3uq-foo {
+        grid-column: module-EgL3uq-a-start / module-EgL3uq-b-end;
+        grid-template-areas: "module-EgL3uq-a module-EgL3uq-b";
+      }
+    "#},
+      map! {
+        "foo" => "module-EgL3uq-foo",
+        "a" => "module-EgL3uq-a",
+        "b" => "module-EgL3uq-b",
+        "a-start" => "module-EgL3uq-a-start",
+        "b-end" => "module-EgL3uq-b-end"
+      },
+      pattern("module-[hash]-[local]"),
+    );
+
+    // The implicit -start and -end suffixes of grid lines are written after the compiled area name.
+    css_modules_test_with_config(
+      r#"
+      .foo {
+        grid-template-areas: "a b";
+      }
+
+      .bar {
+        grid-column: a-start / b-end;
+      }
+    "#,
+      indoc! {r#"
+      .foo-EgL3uq {
+        grid-template-areas: "a-EgL3uq b-EgL3uq";
+      }
+
+      .bar-EgL3uq {
+        grid-column: a-EgL3uq-start / b-EgL3uq-end;
+      }
+    "#},
+      map! {
+        "foo" => "foo-EgL3uq",
+        "bar" => "bar-EgL3uq",
+        "a" => "a-EgL3uq",
+        "b" => "b-EgL3uq"
+      },
+      pattern("[local]-[hash]"),
+    );
+
+    let stylesheet = StyleSheet::parse(
+      "test.css",
+      ".foo { color: red }",
+      ParserOptions {
+        css_modules: Some(pattern("[local]_[content-hash:6]")),
+        ..ParserOptions::default()
+      },
+    )
+    .unwrap();
+    let res = stylesheet.to_css(PrinterOptions::default()).unwrap();
+    let name = res.exports.unwrap()["foo"].name.clone();
+    assert!(name.starts_with("foo_") && name.len() == 10);
+    assert_eq!(res.code, format!(".{} {{\n  color: red;\n}}\n", name));
+
+    let dashed_idents = css_modules::Config {
+      dashed_idents: true,
+      ..Default::default()
+    };
+
+    css_modules_test_with_config(
+      r#"
+      :root {
+        --foo: red;
+        --bar: 2px;
+      }
+
+      .foo {
+        color: var(--foo);
+        margin: var(--baz, var(--bar));
+      }
+
+      @property --qux {
+        syntax: "<color>";
+        inherits: false;
+        initial-value: red;
+      }
+
+      @custom-media --narrow (max-width: 30em);
+
+      @media (--narrow) {
+        .foo {
+          color: var(--qux);
+        }
+      }
+
+      @font-palette-values --pink {
+        font-family: Bixa;
+      }
+
+      .bar {
+        font-palette: --pink;
+      }
+    "#,
+      indoc! {r#"
+      :root {
+        --EgL3uq_foo: red;
+        --EgL3uq_bar: 2px;
+      }
+
+      .EgL3uq_foo {
+        color: var(--EgL3uq_foo);
+        margin: var(--EgL3uq_baz, var(--EgL3uq_bar));
+      }
+
+      @property --EgL3uq_qux {
+        syntax: "<color>";
+        inherits: false;
+        initial-value: red;
+      }
+
+      @custom-media --EgL3uq_narrow (max-width: 30em);
+
+      @media (--EgL3uq_narrow) {
+        .EgL3uq_foo {
+          color: var(--EgL3uq_qux);
+        }
+      }
+
+      @font-palette-values --EgL3uq_pink {
+        font-family: Bixa;
+      }
+
+      .EgL3uq_bar {
+        font-palette: --EgL3uq_pink;
+      }
+    "#},
+      map! {
+        "--foo" => "--EgL3uq_foo" referenced: true,
+        "--bar" => "--EgL3uq_bar" referenced: true,
+        "--baz" => "--EgL3uq_baz" referenced: true,
+        "--qux" => "--EgL3uq_qux" referenced: true,
+        "--narrow" => "--EgL3uq_narrow" referenced: true,
+        "--pink" => "--EgL3uq_pink" referenced: true,
+        "foo" => "EgL3uq_foo",
+        "bar" => "EgL3uq_bar"
+      },
+      dashed_idents.clone(),
+    );
+
+    css_modules_test(
+      r#"
+      .foo {
+        --foo: red;
+        color: var(--foo);
+      }
+    "#,
+      indoc! {r#"
+      .EgL3uq_foo {
+        --foo: red;
+        color: var(--foo);
+      }
+    "#},
+      map! {
+        "foo" => "EgL3uq_foo"
+      },
+    );
+
+    let stylesheet = StyleSheet::parse(
+      "test.css",
+      r#"
+      .foo {
+        color: var(--color from "./colors.css");
+        background: var(--bg from global);
+      }
+    "#,
+      ParserOptions {
+        css_modules: Some(dashed_idents),
+        ..ParserOptions::default()
+      },
+    )
+    .unwrap();
+    let res = stylesheet.to_css(PrinterOptions::default()).unwrap();
+    let references = res.references.unwrap();
+    assert_eq!(references.len(), 1);
+    let (placeholder, reference) = references.iter().next().unwrap();
+    assert_eq!(
+      reference,
+      &CssModuleReference::Dependency {
+        name: "--color".into(),
+        specifier: "./colors.css".into()
+      }
+    );
+    assert_eq!(
+      res.code,
+      format!(
+        ".EgL3uq_foo {{\n  color: var({});\n  background: var(--bg);\n}}\n",
+        placeholder
+      )
+    );
+    assert_eq!(
+      res.exports.unwrap(),
+      map! {
+        "foo" => "EgL3uq_foo"
+      }
+    );
+
+    let exports = map! {
+      "foo-bar" => "EgL3uq_foo-bar",
+      "baz" => "EgL3uq_baz" "EgL3uq_foo-bar" "qux" global: true "a" from "./a.css",
+      "--color" => "--EgL3uq_color"
+    };
+    assert_eq!(
+      css_modules::to_typescript_declarations(&exports),
+      indoc! {r#"
+      declare const styles: {
+        readonly "--color": string;
+        /** Composes `EgL3uq_foo-bar`, `qux` (global), `a` from "./a.css". */
+        readonly "baz": string;
+        readonly "foo-bar": string;
+      };
+      export default styles;
+    "#}
+    );
+
+    // Both the original and camelCased names are declared.
+    let mut camel_case_exports = exports.clone();
+    css_modules::add_camel_case_aliases(&mut camel_case_exports).unwrap();
+    assert_eq!(camel_case_exports["fooBar"], exports["foo-bar"]);
+    assert_eq!(
+      css_modules::to_typescript_declarations(&camel_case_exports),
+      indoc! {r#"
+      declare const styles: {
+        readonly "--color": string;
+        /** Composes `EgL3uq_foo-bar`, `qux` (global), `a` from "./a.css". */
+        readonly "baz": string;
+        readonly "foo-bar": string;
+        readonly "fooBar": string;
+      };
+      export default styles;
+    "#}
+    );
+
+    let mut exports = map! {
+      "foo-bar" => "EgL3uq_foo-bar",
+      "fooBar" => "EgL3uq_fooBar"
+    };
+    assert_eq!(
+      css_modules::add_camel_case_aliases(&mut exports),
+      Err(css_modules::CamelCaseCollision {
+        name: "foo-bar".into(),
+        alias: "fooBar".into(),
+      })
+    );
+
+    css_modules_test(
+      r#"
+      @value primary: #BF4040;
+      @value small: (max-width: 599px);
+      @value thin-border 1px solid primary;
+
+      .foo {
+        color: primary;
+        border: thin-border;
+      }
+
+      @media small {
+        .foo {
+          color: red;
+        }
+      }
+
+      :export {
+        accent: primary;
+      }
+    "#,
+      indoc! {r#"
+      .EgL3uq_foo {
+        color: #bf4040;
+        border: 1px solid #bf4040;
+      }
+
+      @media (max-width: 599px) {
+        .EgL3uq_foo {
+          color: red;
+        }
+      }
+    "#},
+      map! {
+        "primary" => "#BF4040",
+        "small" => "(max-width: 599px)",
+        "thin-border" => "1px solid #BF4040",
+        "accent" => "#BF4040",
+        "foo" => "EgL3uq_foo"
+      },
+    );
+
+    let stylesheet = StyleSheet::parse(
+      "test.css",
+      r#"
+      @value primary, secondary as accent from "./colors.css";
+
+      :import("./theme.css") {
+        bg: background;
+      }
+
+      .foo {
+        color: accent;
+        background: bg;
+      }
+    "#,
+      ParserOptions {
+        css_modules: Some(css_modules::Config::default()),
+        ..ParserOptions::default()
+      },
+    )
+    .unwrap();
+    let res = stylesheet.to_css(PrinterOptions::default()).unwrap();
+    let references = res.references.unwrap();
+    assert_eq!(references.len(), 3);
+    let placeholder = |name: &str, specifier: &str| {
+      let reference = CssModuleReference::Dependency {
+        name: name.into(),
+        specifier: specifier.into(),
+      };
+      references.iter().find(|(_, r)| **r == reference).unwrap().0.clone()
+    };
+    let accent = placeholder("secondary", "./colors.css");
+    let bg = placeholder("background", "./theme.css");
+    assert_eq!(
+      res.code,
+      format!(".EgL3uq_foo {{\n  color: {};\n  background: {};\n}}\n", accent, bg)
+    );
+    let exports = res.exports.unwrap();
+    assert_eq!(exports["primary"].name, placeholder("primary", "./colors.css"));
+    assert_eq!(exports["accent"].name, accent);
+    assert_eq!(exports["bg"].name, bg);
+
+    let stylesheet = StyleSheet::parse(
+      "test.css",
+      r#"
+      @value primary: red;
+      @value accent from "./colors.css";
+      @import "foo.css";
+      .foo { color: primary }
+    "#,
+      ParserOptions {
+        css_modules: Some(css_modules::Config::default()),
+        ..ParserOptions::default()
+      },
+    )
+    .unwrap();
+    assert!(matches!(stylesheet.rules.0[2], CssRule::Import(_)));
+    let res = stylesheet.to_css(PrinterOptions::default()).unwrap();
+    assert_eq!(res.code, "@import \"foo.css\";\n\n.EgL3uq_foo {\n  color: red;\n}\n");
+
+    let pure = |source: &str| {
+      StyleSheet::parse(
+        "test.css",
+        source,
+        ParserOptions {
+          css_modules: Some(css_modules::Config {
+            pure: true,
+            ..Default::default()
+          }),
+          nesting: true,
+          ..ParserOptions::default()
+        },
+      )
+      .map(|_| ())
+    };
+
+    assert!(pure(".foo, div#bar, :global(.baz) .qux, a:not(.foo), :local(.foo) div {}").is_ok());
+    assert!(pure(".foo { & > div {} @media print { & span {} } }").is_ok());
+    let err = pure("\n.foo {}\n\n  div > a { color: red }").unwrap_err();
+    assert_eq!(err.kind, ParserError::ImpureCssModulesSelector("div > a".into()));
+    assert_eq!(err.loc.map(|loc| (loc.line, loc.column)), Some((3, 3)));
+    let err = pure(".foo,\n  :global(.bar) {}").unwrap_err();
+    assert_eq!(err.kind, ParserError::ImpureCssModulesSelector(".bar".into()));
+    assert_eq!(err.loc.map(|loc| (loc.line, loc.column)), Some((1, 3)));
+    assert_eq!(
+      pure(".foo { @nest div & {} } :is(.foo, div) {}").unwrap_err().kind,
+      ParserError::ImpureCssModulesSelector(":is(.foo, div)".into())
+    );
+    // Rules that would be removed by minification are still validated.
+    assert_eq!(
+      pure("div {}").unwrap_err().kind,
+      ParserError::ImpureCssModulesSelector("div".into())
+    );
+
+    use css_modules::{Pattern, PatternParseError, Segment};
+    assert_eq!(
+      Pattern::parse("[name]_[local]--[content-hash:8]").unwrap().segments.as_slice(),
+      &[
+        Segment::Name,
+        Segment::Literal("_".into()),
+        Segment::Local,
+        Segment::Literal("--".into()),
+        Segment::ContentHash(Some(8))
+      ]
+    );
+    assert_eq!(Pattern::parse("[hash]_[local]").unwrap(), Pattern::default());
+    assert_eq!(
+      Pattern::parse("[local]-[foo]"),
+      Err(PatternParseError::UnknownPlaceholder("foo".into(), 8))
+    );
+    assert_eq!(
+      Pattern::parse("[local]-[hash"),
+      Err(PatternParseError::UnclosedBrackets(8))
+    );
+    assert_eq!(
+      Pattern::parse("[local]-[hash:0]"),
+      Err(PatternParseError::InvalidHashLength("0".into(), 8))
+    );
+    assert_eq!(
+      Pattern::parse("[local]-[name:5]"),
+      Err(PatternParseError::UnknownPlaceholder("name:5".into(), 8))
+    );
+    assert_eq!(Pattern::parse("[name]-[hash]"), Err(PatternParseError::MissingLocal));
   }
 
   #[test]
@@ -15969,7 +16434,7 @@ mod tests {
       "test.css",
       &source,
       ParserOptions {
-        css_modules: true,
+        css_modules: Some(Default::default()),
         ..ParserOptions::default()
       },
     )
@@ -17060,6 +17525,29 @@ mod tests {
 
   #[test]
   fn test_custom_media() {
+    use crate::error::{Warning, WarningKind};
+
+    // @custom-media rules are kept when printing without minifying, and must end with a semicolon.
+    let stylesheet = StyleSheet::parse(
+      "test.css",
+      "@custom-media --narrow (max-width: 30em); @media (--narrow) { .a { color: red } }",
+      ParserOptions {
+        custom_media: true,
+        ..ParserOptions::default()
+      },
+    )
+    .unwrap();
+    let res = stylesheet
+      .to_css(PrinterOptions {
+        minify: true,
+        ..PrinterOptions::default()
+      })
+      .unwrap();
+    assert_eq!(
+      res.code,
+      "@custom-media --narrow (max-width:30em);@media (--narrow){.a{color:red}}"
+    );
+
     custom_media_test(
       r#"
       @custom-media --modern (color), (hover);
@@ -17416,18 +17904,41 @@ mod tests {
       assert_eq!(res, Err(err))
     }
 
-    custom_media_error_test(
-      r#"
-      @custom-media --color-print print and (color);
-
-      @media screen and (--color-print) {
-        .a {
-          color: green;
-        }
+    fn custom_media_warning_test(source: &str, warning: Warning) {
+      let mut stylesheet = StyleSheet::parse(
+        "test.css",
+        &source,
+        ParserOptions {
+          custom_media: true,
+          ..ParserOptions::default()
+        },
+      )
+      .unwrap();
+      stylesheet
+        .minify(MinifyOptions {
+          targets: Some(Browsers {
+            chrome: Some(95 << 16),
+            ..Browsers::default()
+          }),
+          ..MinifyOptions::default()
+        })
+        .unwrap();
+      let res = stylesheet.to_css(PrinterOptions::default()).unwrap();
+      assert_eq!(res.warnings, vec![warning])
+    }
+
+    custom_media_warning_test(
+      r#"
+      @custom-media --color-print print and (color);
+
+      @media screen and (--color-print) {
+        .a {
+          color: green;
+        }
       }
       "#,
-      Error {
-        kind: MinifyErrorKind::UnsupportedCustomMediaBooleanLogic {
+      Warning {
+        kind: WarningKind::UnsupportedCustomMediaBooleanLogic {
           custom_media_loc: Location {
             source_index: 0,
             line: 1,
@@ -17442,7 +17953,7 @@ mod tests {
       },
     );
 
-    custom_media_error_test(
+    custom_media_warning_test(
       r#"
       @custom-media --color-print print and (color);
 
@@ -17452,8 +17963,8 @@ mod tests {
         }
       }
       "#,
-      Error {
-        kind: MinifyErrorKind::UnsupportedCustomMediaBooleanLogic {
+      Warning {
+        kind: WarningKind::UnsupportedCustomMediaBooleanLogic {
           custom_media_loc: Location {
             source_index: 0,
             line: 1,
@@ -17468,15 +17979,15 @@ mod tests {
       },
     );
 
-    custom_media_error_test(
+    custom_media_warning_test(
       r#"
       @custom-media --color-print print and (color);
       @custom-media --color-screen screen and (color);
 
       @media (--color-print) or (--color-screen) {}
       "#,
-      Error {
-        kind: MinifyErrorKind::UnsupportedCustomMediaBooleanLogic {
+      Warning {
+        kind: WarningKind::UnsupportedCustomMediaBooleanLogic {
           custom_media_loc: Location {
             source_index: 0,
             line: 2,
@@ -17491,15 +18002,15 @@ mod tests {
       },
     );
 
-    custom_media_error_test(
+    custom_media_warning_test(
       r#"
       @custom-media --color-print print and (color);
       @custom-media --color-screen screen and (color);
 
       @media (--color-print) and (--color-screen) {}
       "#,
-      Error {
-        kind: MinifyErrorKind::UnsupportedCustomMediaBooleanLogic {
+      Warning {
+        kind: WarningKind::UnsupportedCustomMediaBooleanLogic {
           custom_media_loc: Location {
             source_index: 0,
             line: 2,
@@ -17514,15 +18025,15 @@ mod tests {
       },
     );
 
-    custom_media_error_test(
+    custom_media_warning_test(
       r#"
       @custom-media --screen screen;
       @custom-media --print print;
 
       @media (--print) and (--screen) {}
       "#,
-      Error {
-        kind: MinifyErrorKind::UnsupportedCustomMediaBooleanLogic {
+      Warning {
+        kind: WarningKind::UnsupportedCustomMediaBooleanLogic {
           custom_media_loc: Location {
             source_index: 0,
             line: 1,
@@ -17537,7 +18048,7 @@ mod tests {
       },
     );
 
-    custom_media_error_test(
+    custom_media_warning_test(
       r#"
       @custom-media --not-print not print and (color);
       @custom-media --not-screen not screen and (color);
@@ -17548,8 +18059,8 @@ mod tests {
         }
       }
       "#,
-      Error {
-        kind: MinifyErrorKind::UnsupportedCustomMediaBooleanLogic {
+      Warning {
+        kind: WarningKind::UnsupportedCustomMediaBooleanLogic {
           custom_media_loc: Location {
             source_index: 0,
             line: 2,
@@ -17564,7 +18075,7 @@ mod tests {
       },
     );
 
-    custom_media_error_test(
+    custom_media_warning_test(
       r#"
       @custom-media --color screen and (color), print and (color);
 
@@ -17574,8 +18085,8 @@ mod tests {
         }
       }
       "#,
-      Error {
-        kind: MinifyErrorKind::UnsupportedCustomMediaBooleanLogic {
+      Warning {
+        kind: WarningKind::UnsupportedCustomMediaBooleanLogic {
           custom_media_loc: Location {
             source_index: 0,
             line: 1,
@@ -18037,4 +18548,879 @@ mod tests {
       "@property --property-name{syntax:\"<color>+\";inherits:false;initial-value:#ff0 #00f}",
     );
   }
+
+  #[test]
+  fn test_container() {
+    minify_test(
+      "@container (min-width: 240px) { .foo { color: chartreuse }}",
+      "@container (min-width:240px){.foo{color:#7fff00}}",
+    );
+    minify_test(
+      "@container my-layout (inline-size > 45em) { .foo { color: chartreuse }}",
+      "@container my-layout (inline-size>45em){.foo{color:#7fff00}}",
+    );
+    minify_test(
+      "@container (100px < width < 200px) { .foo { color: chartreuse }}",
+      "@container (100px<width<200px){.foo{color:#7fff00}}",
+    );
+    minify_test(
+      "@container (width > 400px) and (height > 400px) { .foo { color: chartreuse }}",
+      "@container (width>400px) and (height>400px){.foo{color:#7fff00}}",
+    );
+    minify_test(
+      "@container (width > 400px) or (orientation: portrait) { .foo { color: chartreuse }}",
+      "@container (width>400px) or (orientation:portrait){.foo{color:#7fff00}}",
+    );
+    minify_test(
+      "@container not (width > 400px) { .foo { color: chartreuse }}",
+      "@container not (width>400px){.foo{color:#7fff00}}",
+    );
+    minify_test(
+      "@container ((width > 400px) and (height > 400px)) or (aspect-ratio: 2/1) { .foo { color: chartreuse }}",
+      "@container ((width>400px) and (height>400px)) or (aspect-ratio:2){.foo{color:#7fff00}}",
+    );
+    minify_test(
+      "@container style(--responsive: true) { .foo { color: chartreuse }}",
+      "@container style(--responsive:true){.foo{color:#7fff00}}",
+    );
+    minify_test(
+      "@container card style((color: red) and (not (--theme: dark))) { .foo { color: chartreuse }}",
+      "@container card style((color:red) and (not (--theme:dark))){.foo{color:#7fff00}}",
+    );
+    minify_test(
+      "@container (width > 400px) and style(--responsive: true) { .foo { color: chartreuse }}",
+      "@container (width>400px) and style(--responsive:true){.foo{color:#7fff00}}",
+    );
+    minify_test("@container (min-width: 240px) { .foo { }}", "");
+    nesting_test(
+      r#"
+        .foo {
+          display: grid;
+
+          @container (width > 1024px) {
+            grid-auto-flow: column;
+          }
+        }
+      "#,
+      indoc! {r#"
+        .foo {
+          display: grid;
+        }
+
+        @container (width > 1024px) {
+          .foo {
+            grid-auto-flow: column;
+          }
+        }
+      "#},
+    );
+    error_test(
+      "@container none (min-width: 240px) { .foo { color: chartreuse }}",
+      ParserError::UnexpectedToken(Token::Ident("none".into())),
+    );
+    error_test(
+      "@container screen and (min-width: 240px) { .foo { color: chartreuse }}",
+      ParserError::UnexpectedToken(Token::Ident("and".into())),
+    );
+
+    // Range syntax is supported by all browsers that support container queries, so it is not lowered.
+    prefix_test(
+      r#"
+        @container (width >= 240px) {
+          .foo {
+            color: chartreuse;
+          }
+        }
+      "#,
+      indoc! { r#"
+        @container (width >= 240px) {
+          .foo {
+            color: #7fff00;
+          }
+        }
+      "#},
+      Browsers {
+        firefox: Some(60 << 16),
+        ..Browsers::default()
+      },
+    );
+
+    minify_test(
+      ".foo { container-type: inline-size }",
+      ".foo{container-type:inline-size}",
+    );
+    minify_test(".foo { container-type: normal }", ".foo{container-type:normal}");
+    minify_test(".foo { container-name: none }", ".foo{container-name:none}");
+    minify_test(".foo { container-name: foo bar }", ".foo{container-name:foo bar}");
+    minify_test(".foo { container: my-layout }", ".foo{container:my-layout}");
+    minify_test(".foo { container: my-layout / normal }", ".foo{container:my-layout}");
+    minify_test(".foo { container: my-layout / size }", ".foo{container:my-layout/size}");
+    minify_test(
+      ".foo { container: none / inline-size }",
+      ".foo{container:none/inline-size}",
+    );
+    minify_test(".foo { container-name: and }", ".foo{container-name:and}");
+  }
+
+  #[test]
+  fn test_scope() {
+    minify_test(
+      "@scope (.card) { .title { color: red } }",
+      "@scope(.card){.title{color:red}}",
+    );
+    minify_test(
+      "@scope (.card) to (.content) { img { border: 1px solid black } }",
+      "@scope(.card) to (.content){img{border:1px solid #000}}",
+    );
+    minify_test(
+      "@scope (.a, .b) to (.c, .d) { :scope { color: red } }",
+      "@scope(.a,.b) to (.c,.d){:scope{color:red}}",
+    );
+    minify_test("@scope { .foo { color: red } }", "@scope{.foo{color:red}}");
+    minify_test(
+      "@scope (.card) { & > .title { color: red } }",
+      "@scope(.card){&>.title{color:red}}",
+    );
+    minify_test("@scope (.card) { .title { } }", "");
+    test(
+      r#"
+        @scope (.card) to (.content) {
+          img {
+            border: 1px solid black;
+          }
+        }
+      "#,
+      indoc! {r#"
+        @scope (.card) to (.content) {
+          img {
+            border: 1px solid #000;
+          }
+        }
+      "#},
+    );
+    error_test(
+      "@scope (.card) to .content { .title { color: red } }",
+      ParserError::UnexpectedToken(Token::Delim('.')),
+    );
+
+    let targets = Browsers {
+      chrome: Some(95 << 16),
+      ..Browsers::default()
+    };
+    prefix_test(
+      r#"
+        @scope (.card) {
+          .title {
+            color: red;
+          }
+        }
+      "#,
+      indoc! {r#"
+        :where(.card) .title {
+          color: red;
+        }
+      "#},
+      targets,
+    );
+    prefix_test(
+      r#"
+        @scope (.card) to (.content) {
+          img {
+            border: 1px solid black;
+          }
+        }
+      "#,
+      indoc! {r#"
+        :where(.card) img:not(:where(.card) .content, :where(.card) .content *) {
+          border: 1px solid #000;
+        }
+      "#},
+      targets,
+    );
+    prefix_test(
+      r#"
+        @scope (.card) to (.content) {
+          :scope {
+            color: red;
+          }
+
+          & > .title::before {
+            color: green;
+          }
+
+          div& {
+            color: blue;
+          }
+        }
+      "#,
+      indoc! {r#"
+        :where(.card):not(:where(.card) .content, :where(.card) .content *) {
+          color: red;
+        }
+
+        :where(.card) > .title:not(:where(.card) .content, :where(.card) .content *):before {
+          color: green;
+        }
+
+        div:where(.card):not(:where(.card) .content, :where(.card) .content *) {
+          color: #00f;
+        }
+      "#},
+      targets,
+    );
+    prefix_test(
+      r#"
+        @scope (.card) to (:scope > .content) {
+          .title {
+            color: red;
+          }
+        }
+      "#,
+      indoc! {r#"
+        :where(.card) .title:not(:where(.card) > .content, :where(.card) > .content *) {
+          color: red;
+        }
+      "#},
+      targets,
+    );
+    prefix_test(
+      r#"
+        @scope (.a, .b) {
+          @media (min-width: 240px) {
+            .title {
+              color: red;
+            }
+          }
+        }
+      "#,
+      indoc! {r#"
+        @media (min-width: 240px) {
+          :where(.a, .b) .title {
+            color: red;
+          }
+        }
+      "#},
+      targets,
+    );
+    prefix_test(
+      r#"
+        @scope (.card) to (.content) {
+          .title {
+            color: red;
+          }
+        }
+      "#,
+      indoc! {r#"
+        :where(.card) .title:not(:where(.card) .content):not(:where(.card) .content *) {
+          color: red;
+        }
+      "#},
+      Browsers {
+        safari: Some(8 << 16),
+        ..Browsers::default()
+      },
+    );
+    prefix_test(
+      r#"
+        @scope {
+          .title {
+            color: red;
+          }
+        }
+      "#,
+      indoc! {r#"
+        @scope {
+          .title {
+            color: red;
+          }
+        }
+      "#},
+      targets,
+    );
+    nesting_test(
+      r#"
+        @scope (.card) to (.content) {
+          .title {
+            color: red;
+
+            & .icon {
+              color: green;
+            }
+          }
+        }
+      "#,
+      indoc! {r#"
+        :where(.card) .title:not(:where(.card) .content, :where(.card) .content *) {
+          color: red;
+        }
+
+        :where(.card) .title:not(:where(.card) .content, :where(.card) .content *) .icon {
+          color: green;
+        }
+      "#},
+    );
+  }
+
+  #[test]
+  fn test_unknown_at_rules() {
+    minify_test("@tailwind base;", "@tailwind base;");
+    minify_test("@tailwind   utilities ;", "@tailwind utilities;");
+    minify_test("@foo;", "@foo;");
+    minify_test("@foo {}", "@foo{}");
+    minify_test(
+      "@starting-style { .foo { opacity: 0 } }",
+      "@starting-style{.foo { opacity: 0 }}",
+    );
+    minify_test(
+      "@screen md { &.foo > .bar { color: red } }",
+      "@screen md{&.foo > .bar { color: red }}",
+    );
+    // Whitespace around delimeters is only preserved within unknown at-rules.
+    minify_test(".foo { --foo: a . b & c }", ".foo{--foo:a.b&c}");
+    test(
+      ".foo { --foo: a . b & c }",
+      indoc! {r#"
+        .foo {
+          --foo: a. b& c;
+        }
+      "#},
+    );
+    minify_test(
+      "@view-transition { navigation: auto; }",
+      "@view-transition{navigation: auto;}",
+    );
+    minify_test(
+      "@tailwind base; .foo { color: red } @-x-custom (min-width: 100px) and (color) { .bar { color: blue } }",
+      "@tailwind base;.foo{color:red}@-x-custom (min-width: 100px)and (color){.bar { color: blue }}",
+    );
+    test(
+      r#"
+        @tailwind base;
+        @tailwind components;
+
+        .foo {
+          color: red;
+        }
+
+        @media (min-width: 100px) {
+          @screen md {
+            .bar {
+              color: blue;
+            }
+          }
+        }
+      "#,
+      indoc! {r#"
+        @tailwind base;
+
+        @tailwind components;
+
+        .foo {
+          color: red;
+        }
+
+        @media (min-width: 100px) {
+          @screen md {
+            .bar { color: blue; }
+          }
+        }
+      "#},
+    );
+    nesting_test_no_targets(
+      r#"
+        .foo {
+          color: red;
+          @apply bg-blue font-bold;
+        }
+      "#,
+      indoc! {r#"
+        .foo {
+          color: red;
+          @apply bg-blue font-bold;
+        }
+      "#},
+    );
+    nesting_test_no_targets(
+      r#"
+        .foo {
+          @apply font-bold py-2;
+          color: red;
+        }
+      "#,
+      indoc! {r#"
+        .foo {
+          @apply font-bold py-2;
+          color: red;
+        }
+      "#},
+    );
+    nesting_test_no_targets(
+      r#"
+        .foo {
+          color: red;
+          @apply font-bold;
+          width: 10px;
+
+          & .bar {
+            color: red;
+          }
+        }
+      "#,
+      indoc! {r#"
+        .foo {
+          color: red;
+          @apply font-bold;
+          width: 10px;
+
+          & .bar {
+            color: red;
+          }
+        }
+      "#},
+    );
+    nesting_test(
+      r#"
+        .foo {
+          @apply font-bold;
+          color: red;
+
+          & .bar {
+            color: red;
+          }
+        }
+      "#,
+      indoc! {r#"
+        .foo {
+          @apply font-bold;
+          color: red;
+        }
+
+        .foo .bar {
+          color: red;
+        }
+      "#},
+    );
+    minify_test(
+      ".foo { @apply font-bold py-2; color: red }",
+      ".foo{@apply font-bold py-2;color:red}",
+    );
+    minify_test(
+      ".foo { color: red; @apply font-bold py-2 }",
+      ".foo{color:red;@apply font-bold py-2;}",
+    );
+    minify_test(
+      ".foo { color: red; @starting-style { opacity: 0 } }",
+      ".foo{color:red;@starting-style{opacity: 0}}",
+    );
+    // Declarations are not merged across unknown at-rules.
+    minify_test(
+      ".foo { color: red; @apply text-lg; color: blue }",
+      ".foo{color:red;@apply text-lg;color:#00f}",
+    );
+    minify_test(
+      ".foo { color: red } .foo { @apply text-lg }",
+      ".foo{color:red}.foo{@apply text-lg;}",
+    );
+    test(
+      ".foo { color: red; @apply font-bold; width: 10px }",
+      indoc! {r#"
+        .foo {
+          color: red;
+          @apply font-bold;
+          width: 10px;
+        }
+      "#},
+    );
+    attr_test(
+      "color: red; @apply font-bold; width: 10px",
+      "color:red;@apply font-bold;width:10px",
+      true,
+      None,
+    );
+  }
+
+  #[test]
+  fn test_error_recovery() {
+    use crate::error::{Error, ErrorLocation, SelectorError};
+
+    let mut stylesheet = StyleSheet::parse(
+      "test.css",
+      r#"
+        .foo { color: red; }
+        ..bar { color: green; }
+        .baz { color: yellow !imp; width: 20px; }
+        @media (min-width: 100px) {
+          .qux:nth-child(foo) { color: red }
+          .qux { color: blue }
+        }
+      "#,
+      ParserOptions {
+        error_recovery: true,
+        ..ParserOptions::default()
+      },
+    )
+    .unwrap();
+    stylesheet.minify(MinifyOptions::default()).unwrap();
+    let res = stylesheet
+      .to_css(PrinterOptions {
+        minify: true,
+        ..PrinterOptions::default()
+      })
+      .unwrap();
+    assert_eq!(
+      res.code,
+      ".foo{color:red}.baz{width:20px}@media (min-width:100px){.qux{color:#00f}}"
+    );
+
+    let loc = |line, column| {
+      Some(ErrorLocation {
+        filename: "test.css".into(),
+        line,
+        column,
+      })
+    };
+    assert_eq!(
+      stylesheet.parser_warnings,
+      vec![
+        Error {
+          kind: ParserError::SelectorError(SelectorError::ClassNeedsIdent(Token::Delim('.'))),
+          loc: loc(2, 10),
+        },
+        Error {
+          kind: ParserError::UnexpectedToken(Token::Delim('!')),
+          loc: loc(3, 30),
+        },
+        Error {
+          kind: ParserError::UnexpectedToken(Token::Ident("foo".into())),
+          loc: loc(5, 29),
+        },
+      ]
+    );
+
+    // Without error recovery, the first error is returned.
+    error_test(
+      ".baz { color: yellow !imp; width: 20px; }",
+      ParserError::UnexpectedToken(Token::Delim('!')),
+    );
+
+    // Invalid nested rules are dropped, and only reported with error recovery.
+    minify_test(
+      "@media (min-width: 100px) { .qux:nth-child(foo) { color: red } .qux { color: blue } }",
+      "@media (min-width:100px){.qux{color:#00f}}",
+    );
+  }
+
+  #[test]
+  fn test_warnings() {
+    use crate::error::{ErrorLocation, Warning, WarningKind};
+
+    let mut stylesheet = StyleSheet::parse(
+      "test.css",
+      r#"
+        .foo { color: lab(50% 100 0); background: lab(50% 10 10); }
+        @keyframes fade {
+          from { opacity: 0 !important; color: red; }
+        }
+        @custom-media --small (max-width: 30em);
+        @scope {
+          .bar { color: red; }
+        }
+      "#,
+      ParserOptions::default(),
+    )
+    .unwrap();
+    let targets = Some(Browsers {
+      chrome: Some(90 << 16),
+      ..Browsers::default()
+    });
+    stylesheet
+      .minify(MinifyOptions {
+        targets,
+        ..MinifyOptions::default()
+      })
+      .unwrap();
+    let res = stylesheet
+      .to_css(PrinterOptions {
+        targets,
+        ..PrinterOptions::default()
+      })
+      .unwrap();
+
+    let loc = |line, column| {
+      Some(ErrorLocation {
+        filename: "test.css".into(),
+        line,
+        column,
+      })
+    };
+    assert_eq!(
+      res.warnings,
+      vec![
+        Warning {
+          kind: WarningKind::LossyColorConversion {
+            color: "lab(50% 100 0)".into(),
+          },
+          loc: loc(1, 9),
+        },
+        Warning {
+          kind: WarningKind::ImportantInKeyframes {
+            property: "opacity".into(),
+          },
+          loc: loc(3, 11),
+        },
+        Warning {
+          kind: WarningKind::DraftNotEnabled {
+            feature: "@custom-media".into(),
+            draft: "custom_media".into(),
+          },
+          loc: loc(5, 9),
+        },
+        Warning {
+          kind: WarningKind::UnsupportedFeature {
+            feature: "@scope without a scope start".into(),
+          },
+          loc: loc(6, 9),
+        },
+      ]
+    );
+
+    // Prefixed rules that are not needed by the targets are removed.
+    let mut stylesheet = StyleSheet::parse(
+      "test.css",
+      "@-webkit-keyframes fade { to { opacity: 0 } } @keyframes fade { to { opacity: 0 } }",
+      ParserOptions::default(),
+    )
+    .unwrap();
+    stylesheet
+      .minify(MinifyOptions {
+        targets,
+        ..MinifyOptions::default()
+      })
+      .unwrap();
+    let res = stylesheet
+      .to_css(PrinterOptions {
+        minify: true,
+        targets,
+        ..PrinterOptions::default()
+      })
+      .unwrap();
+    assert_eq!(res.code, "@keyframes fade{to{opacity:0}}");
+    assert_eq!(
+      res.warnings,
+      vec![Warning {
+        kind: WarningKind::VendorPrefixRemoved {
+          name: "@-webkit-keyframes".into(),
+        },
+        loc: loc(0, 1),
+      }]
+    );
+
+    // @media rules that use boolean logic with the media types of @custom-media rules are left unchanged.
+    let mut stylesheet = StyleSheet::parse(
+      "test.css",
+      "@custom-media --print print;\n@media screen and (--print) { .a { color: red } }",
+      ParserOptions {
+        custom_media: true,
+        ..ParserOptions::default()
+      },
+    )
+    .unwrap();
+    stylesheet
+      .minify(MinifyOptions {
+        targets,
+        ..MinifyOptions::default()
+      })
+      .unwrap();
+    let res = stylesheet
+      .to_css(PrinterOptions {
+        minify: true,
+        targets,
+        ..PrinterOptions::default()
+      })
+      .unwrap();
+    assert_eq!(res.code, "@media screen and (--print){.a{color:red}}");
+    assert_eq!(
+      res.warnings,
+      vec![Warning {
+        kind: WarningKind::UnsupportedCustomMediaBooleanLogic {
+          custom_media_loc: Location {
+            source_index: 0,
+            line: 0,
+            column: 1,
+          },
+        },
+        loc: loc(1, 1),
+      }]
+    );
+
+    // No warnings are emitted without browser targets, and `!important` declarations in @keyframes are kept.
+    let mut stylesheet = StyleSheet::parse(
+      "test.css",
+      ".foo { color: lab(50% 100 0) } @scope { .bar { color: red } } @keyframes fade { to { opacity: 0 !important } }",
+      ParserOptions::default(),
+    )
+    .unwrap();
+    stylesheet.minify(MinifyOptions::default()).unwrap();
+    let res = stylesheet
+      .to_css(PrinterOptions {
+        minify: true,
+        ..PrinterOptions::default()
+      })
+      .unwrap();
+    assert_eq!(res.warnings, vec![]);
+    assert!(res.code.ends_with("@keyframes fade{to{opacity:0!important}}"));
+  }
+
+  #[test]
+  fn test_visitor() {
+    use crate::selector::SelectorIdent;
+    use crate::traits::{Visit, Visitor};
+    use crate::values::length::LengthValue;
+    use crate::values::url::Url;
+
+    struct TestVisitor {
+      rules: usize,
+    }
+
+    impl<'i> Visitor<'i> for TestVisitor {
+      fn visit_rule(&mut self, rule: &mut CssRule<'i>) {
+        self.rules += 1;
+        rule.visit_children(self)
+      }
+
+      fn visit_length(&mut self, length: &mut LengthValue) {
+        if let LengthValue::Px(px) = length {
+          *length = LengthValue::Rem(*px / 16.0);
+        }
+      }
+
+      fn visit_url(&mut self, url: &mut Url<'i>) {
+        url.url = format!("https://example.com/{}", url.url).into();
+      }
+
+      fn visit_selector_ident(&mut self, ident: &mut SelectorIdent<'i>) {
+        ident.0 = format!("x-{}", ident.0).into();
+      }
+    }
+
+    let mut stylesheet = StyleSheet::parse(
+      "test.css",
+      r#"
+      .foo {
+        width: 32px;
+        background: url(foo.png) 8px 16px;
+      }
+
+      @media (min-width: 160px) {
+        .bar:not(#baz) {
+          margin: calc(100% - 48px);
+          mask-image: url(bar.svg);
+        }
+      }
+    "#,
+      ParserOptions::default(),
+    )
+    .unwrap();
+
+    let mut visitor = TestVisitor { rules: 0 };
+    stylesheet.visit(&mut visitor);
+    assert_eq!(visitor.rules, 3);
+
+    let res = stylesheet
+      .to_css(PrinterOptions {
+        minify: true,
+        ..PrinterOptions::default()
+      })
+      .unwrap();
+    assert_eq!(
+      res.code,
+      ".x-foo{width:2rem;background:url(https://example.com/foo.png) .5rem 1rem}@media (min-width:10rem){.x-bar:not(#x-baz){margin:calc(100% - 3rem);mask-image:url(https://example.com/bar.svg)}}"
+    );
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  fn test_serde() {
+    fn round_trip(source: &str) {
+      let stylesheet = StyleSheet::parse("test.css", source, ParserOptions::default()).unwrap();
+      let expected = stylesheet.to_css(PrinterOptions::default()).unwrap().code;
+
+      let json = serde_json::to_string(&stylesheet).unwrap();
+      let deserialized: StyleSheet = serde_json::from_str(&json).unwrap();
+      let res = deserialized.to_css(PrinterOptions::default()).unwrap();
+      assert_eq!(res.code, expected);
+    }
+
+    round_trip(".foo > .bar:hover, #baz::before, a[href^=\"https\" i] { color: red; margin: 10px auto }");
+    round_trip("@namespace ns url(http://example.com); ns|a:not(.foo):nth-child(2n + 1)::-webkit-scrollbar-thumb:horizontal { -webkit-transition: opacity 200ms ease-in }");
+    round_trip(".foo { --custom: 1px solid var(--x, blue); width: calc(100% - var(--y)) }");
+    round_trip(".foo { grid-auto-flow: column dense; text-decoration: underline overline dotted red }");
+    round_trip(
+      ".foo { background: url(img.png) no-repeat center / cover, linear-gradient(to right, red 10%, blue) }",
+    );
+    round_trip("@media screen and (min-width: 100px) and (400px < height <= 800px) { .foo { display: flex } }");
+    round_trip("@supports (display: grid) and (not (display: inline-grid)) { .foo { display: grid } }");
+    round_trip("@keyframes fade { from { opacity: 0 } 50% { opacity: .5 } to { opacity: 1 } }");
+    round_trip(
+      "@font-face { font-family: Foo; src: url(foo.woff2) format(\"woff2\"); unicode-range: U+0000-00FF, U+0131 }",
+    );
+    round_trip("@import url(foo.css) layer(base) supports(display: grid) print; @layer a.b, c;");
+    round_trip("@namespace svg url(http://www.w3.org/2000/svg); @page :first { margin: 1in }");
+
+    let stylesheet = StyleSheet::parse("test.css", ".foo { color: red }", ParserOptions::default()).unwrap();
+    let json = serde_json::to_value(&stylesheet).unwrap();
+    assert_eq!(
+      json["rules"][0],
+      serde_json::json!({
+        "type": "style",
+        "value": {
+          "selectors": [[{ "type": "class", "value": "foo" }]],
+          "vendorPrefix": [],
+          "declarations": {
+            "importantDeclarations": [],
+            "declarations": [{
+              "property": "color",
+              "value": { "type": "rgba", "value": [255, 0, 0, 255] }
+            }]
+          },
+          "rules": [],
+          "loc": { "source_index": 0, "line": 0, "column": 1 }
+        }
+      })
+    );
+  }
+  #[test]
+  fn test_into_owned() {
+    fn owned(source: &str) -> (StyleSheet<'static>, String) {
+      let source = source.to_owned();
+      let stylesheet = StyleSheet::parse("test.css", &source, ParserOptions::default()).unwrap();
+      let expected = stylesheet.to_css(PrinterOptions::default()).unwrap().code;
+      (stylesheet.into_owned(), expected)
+    }
+
+    fn test(source: &str) {
+      let (stylesheet, expected) = owned(source);
+      let res = std::thread::spawn(move || stylesheet.to_css(PrinterOptions::default()).unwrap().code)
+        .join()
+        .unwrap();
+      assert_eq!(res, expected);
+    }
+
+    test(".foo > .bar:hover, #baz::before, a[href^=\"https\" i] { color: red; margin: 10px auto }");
+    test("@namespace ns url(http://example.com); ns|a:not(.foo):lang(en)::-webkit-scrollbar-thumb { -webkit-transition: opacity 200ms ease-in }");
+    test(".foo { --custom: 1px solid var(--x, blue); width: calc(100% - var(--y)); unknown: foo }");
+    test(".foo { background: url(img.png) no-repeat center / cover; font-family: \"Helvetica Neue\", serif }");
+    test("@media screen and (min-width: 100px) { .foo { grid-template-areas: \"a b\" \"c d\" } }");
+    test("@supports (display: grid) and selector(a > b) { .foo { display: grid } }");
+    test("@keyframes fade { from { opacity: 0 } to { opacity: 1 } }");
+    test("@font-face { font-family: Foo; src: url(foo.woff2) format(\"woff2\") }");
+    test("@import url(foo.css) layer(base) print; @layer a.b, c; @tailwind base;");
+    test("@container sidebar (min-width: 400px) { .foo { color: red } }");
+
+    let source = String::from("color: red; --foo: bar");
+    let attr = StyleAttribute::parse(&source).unwrap().into_owned();
+    drop(source);
+    let res = attr.to_css(PrinterOptions::default()).unwrap();
+    assert_eq!(res.code, "color: red; --foo: bar");
+  }
 }
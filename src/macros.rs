@@ -10,6 +10,7 @@ macro_rules! enum_property {
   ) => {
     $(#[$outer])*
     #[derive(Debug, Clone, Copy, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
     $vis enum $name {
       $(
         $(#[$meta])*
@@ -54,6 +55,9 @@ macro_rules! enum_property {
         }
       }
     }
+
+    $crate::macros::impl_visit!($name);
+    $crate::macros::impl_into_owned!($name);
   };
   (
     $(#[$outer:meta])*
@@ -66,9 +70,11 @@ macro_rules! enum_property {
   ) => {
     $(#[$outer])*
     #[derive(Debug, Clone, Copy, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     $vis enum $name {
       $(
         $(#[$meta])*
+        #[cfg_attr(feature = "serde", serde(rename = $str))]
         $id,
       )+
     }
@@ -110,11 +116,195 @@ macro_rules! enum_property {
         }
       }
     }
+
+    $crate::macros::impl_visit!($name);
+    $crate::macros::impl_into_owned!($name);
   };
 }
 
 pub(crate) use enum_property;
 
+macro_rules! impl_visit {
+  // Structs visit each of their fields in order. All fields must be listed, so that new fields are not
+  // missed. Fields without children are marked with `: _`, e.g. `loc: _`.
+  (
+    $name: ident$(<$l: lifetime>)? {
+      $( $field: tt $(: $skip: tt)? ),* $(,)?
+    }
+  ) => {
+    impl<'i> $crate::traits::Visit<'i> for $name$(<$l>)? {
+      fn visit_children<V: $crate::traits::Visitor<'i>>(&mut self, visitor: &mut V) {
+        #[allow(unused_imports)]
+        use $crate::traits::Visit;
+        let $name { $( $field: _ ),* } = self;
+        $(
+          $crate::macros::impl_visit!(@field self visitor $field $(: $skip)?);
+        )*
+      }
+    }
+  };
+  // Enums visit the fields of each variant. All variants and fields must be listed, so that new ones are
+  // not missed. Unit variants are listed by name only, and fields without children are `_` in tuple
+  // variants or marked with `: _` in struct variants.
+  (
+    $name: ident$(<$l: lifetime>)? match {
+      $( $variant: ident $( ( $( $t: tt ),* ) )? $( { $( $s: ident $(: $sskip: tt)? ),* } )? ),* $(,)?
+    }
+  ) => {
+    impl<'i> $crate::traits::Visit<'i> for $name$(<$l>)? {
+      fn visit_children<V: $crate::traits::Visitor<'i>>(&mut self, visitor: &mut V) {
+        #[allow(unused_imports)]
+        use $crate::traits::Visit;
+        match self {
+          $(
+            $name::$variant $( ( $( $t ),* ) )? $( { $( $s $(: $sskip)? ),* } )? => {
+              $( $( $crate::macros::impl_visit!(@binding visitor $t); )* )?
+              $( $( $crate::macros::impl_visit!(@binding visitor $s $(: $sskip)?); )* )?
+            }
+          )*
+        }
+      }
+    }
+  };
+  (@field $self: ident $visitor: ident $field: tt : _) => {};
+  (@field $self: ident $visitor: ident $field: tt) => {
+    $self.$field.visit($visitor);
+  };
+  (@binding $visitor: ident _) => {};
+  (@binding $visitor: ident $binding: ident : _) => {};
+  (@binding $visitor: ident $binding: ident) => {
+    $binding.visit($visitor);
+  };
+  // Types without any children, e.g. keywords.
+  (
+    $( $name: ident$(<$l: lifetime>)? ),+ $(,)?
+  ) => {
+    $(
+      impl<'i> $crate::traits::Visit<'i> for $name$(<$l>)? {
+        #[inline]
+        fn visit_children<V: $crate::traits::Visitor<'i>>(&mut self, _: &mut V) {}
+      }
+    )+
+  };
+}
+
+pub(crate) use impl_visit;
+
+macro_rules! impl_into_owned {
+  // Structs convert each of their fields. All fields must be listed.
+  (
+    $name: ident<'i> {
+      $( $field: tt ),* $(,)?
+    }
+  ) => {
+    impl<'i, 'any> $crate::traits::IntoOwned<'any> for $name<'i> {
+      type Owned = $name<'any>;
+
+      fn into_owned(self) -> Self::Owned {
+        #[allow(unused_imports)]
+        use $crate::traits::IntoOwned;
+        $name {
+          $( $field: self.$field.into_owned(), )*
+        }
+      }
+    }
+  };
+  // Enums convert the fields of each variant. All variants must be listed.
+  (
+    $name: ident<'i> match {
+      $( $variant: ident $( ( $( $t: ident ),* ) )? $( { $( $s: ident ),* } )? ),* $(,)?
+    }
+  ) => {
+    impl<'i, 'any> $crate::traits::IntoOwned<'any> for $name<'i> {
+      type Owned = $name<'any>;
+
+      fn into_owned(self) -> Self::Owned {
+        #[allow(unused_imports)]
+        use $crate::traits::IntoOwned;
+        match self {
+          $(
+            $name::$variant $( ( $( $t ),* ) )? $( { $( $s ),* } )? => {
+              $name::$variant $( ( $( $t.into_owned() ),* ) )? $( { $( $s: $s.into_owned() ),* } )?
+            }
+          )*
+        }
+      }
+    }
+  };
+  // Structs without a lifetime do not borrow from the source.
+  (
+    $name: ident {
+      $( $field: tt ),* $(,)?
+    }
+  ) => {
+    $crate::macros::impl_into_owned!($name);
+  };
+  // Types that do not borrow from the source are returned as is.
+  (
+    $( $name: ident $( < $( $g: ident ),+ > )? ),+ $(,)?
+  ) => {
+    $(
+      impl<'any $( $( , $g: 'static )+ )?> $crate::traits::IntoOwned<'any> for $name $( < $( $g ),+ > )? {
+        type Owned = Self;
+
+        #[inline]
+        fn into_owned(self) -> Self {
+          self
+        }
+      }
+    )+
+  };
+}
+
+pub(crate) use impl_into_owned;
+
+// Bit flags are serialized as a list of the names of the flags that are set.
+macro_rules! impl_bitflags_serde {
+  (
+    $name: ident {
+      $( $str: literal: $flag: ident, )+
+    }
+  ) => {
+    #[cfg(feature = "serde")]
+    impl serde::Serialize for $name {
+      fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+      where
+        S: serde::Serializer,
+      {
+        let mut names = Vec::new();
+        $(
+          if !$name::$flag.is_empty() && self.contains($name::$flag) {
+            names.push($str);
+          }
+        )+
+        names.serialize(serializer)
+      }
+    }
+
+    #[cfg(feature = "serde")]
+    impl<'de> serde::Deserialize<'de> for $name {
+      fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+      where
+        D: serde::Deserializer<'de>,
+      {
+        let names = Vec::<String>::deserialize(deserializer)?;
+        let mut flags = $name::empty();
+        for name in names {
+          match name.as_str() {
+            $(
+              $str => flags |= $name::$flag,
+            )+
+            _ => return Err(serde::de::Error::unknown_variant(&name, &[$($str),+])),
+          }
+        }
+        Ok(flags)
+      }
+    }
+  };
+}
+
+pub(crate) use impl_bitflags_serde;
+
 macro_rules! shorthand_property {
   (
     $(#[$outer:meta])*
@@ -129,6 +319,7 @@ macro_rules! shorthand_property {
   ) => {
     $(#[$outer])*
     #[derive(Debug, Clone, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "camelCase"))]
     pub struct $name$(<$l>)? {
       $(#[$first_meta])*
       pub $first_key: $first_type,
@@ -198,6 +389,9 @@ macro_rules! shorthand_property {
         Ok(())
       }
     }
+
+    $crate::macros::impl_visit!($name$(<$l>)? { $first_key, $($key),* });
+    $crate::macros::impl_into_owned!($name$(<$l>)? { $first_key, $($key),* });
   };
 }
 
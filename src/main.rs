@@ -1,10 +1,13 @@
 use clap::Parser;
-use parcel_css::bundler::{Bundler, FileProvider};
+use parcel_css::bundler::{Bundle, BundleOptions, Bundler, FileProvider};
+use parcel_css::css_modules::{self, Pattern};
 use parcel_css::stylesheet::{MinifyOptions, ParserOptions, PrinterOptions, StyleSheet};
 use parcel_css::targets::Browsers;
 use parcel_sourcemap::SourceMap;
 use serde::Serialize;
-use std::{ffi, fs, io, path, path::Path};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use std::{ffi, fs, io, path};
 
 #[cfg(target_os = "macos")]
 #[global_allocator]
@@ -31,11 +34,33 @@ struct CliArgs {
   /// If no filename is provided, <output_file>.json will be used.
   #[clap(long, group = "css_modules", requires = "output_file")]
   css_modules: Option<Option<String>>,
+  /// The pattern to use for compiled CSS module class names, e.g. `[name]__[local]--[hash:5]`.
+  #[clap(long, requires = "css_modules")]
+  css_modules_pattern: Option<String>,
+  /// Enable CSS modules scoping of dashed identifiers, e.g. CSS variables.
+  #[clap(long, requires = "css_modules")]
+  css_modules_dashed_idents: bool,
+  /// Require every selector in a CSS module to contain at least one local class or id.
+  #[clap(long, requires = "css_modules")]
+  css_modules_pure: bool,
+  /// Generate a TypeScript declaration file for the CSS module exports.
+  /// If no filename is provided, <output_file>.d.ts will be used.
+  #[clap(long, requires = "css_modules")]
+  css_modules_dts: Option<Option<String>>,
+  /// Also export a camelCased alias of each CSS module name, e.g. `fooBar` for `foo-bar`.
+  #[clap(long, requires = "css_modules")]
+  css_modules_camel_case: bool,
   /// Enable sourcemap, at <output_file>.map
   #[clap(long, requires = "output_file")]
   sourcemap: bool,
   #[clap(long)]
   bundle: bool,
+  /// Rebuild whenever the input file, or any file in its bundle, changes
+  #[clap(short, long)]
+  watch: bool,
+  /// Write the import graph of the bundle as JSON to the given file
+  #[clap(long, requires = "bundle")]
+  bundle_graph: Option<String>,
   #[clap(short, long)]
   targets: Vec<String>,
 }
@@ -52,52 +77,211 @@ struct SourceMapJson<'a> {
 
 pub fn main() -> Result<(), std::io::Error> {
   let cli_args = CliArgs::parse();
-  let source = fs::read_to_string(&cli_args.input_file)?;
+  let css_modules = if cli_args.css_modules.is_some() {
+    let pattern = if let Some(pattern) = cli_args.css_modules_pattern.as_ref() {
+      Pattern::parse(pattern).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?
+    } else {
+      Pattern::default()
+    };
+    Some(css_modules::Config {
+      pattern,
+      dashed_idents: cli_args.css_modules_dashed_idents,
+      pure: cli_args.css_modules_pure,
+    })
+  } else {
+    None
+  };
+
+  let targets = browserslist_to_targets(cli_args.targets.clone()).unwrap();
+
+  // In watch mode, parsed files are cached so that only changed files are parsed again.
+  let fs = FileProvider::new();
+  let bundle_options = BundleOptions {
+    cache: cli_args.watch,
+    ..BundleOptions::default()
+  };
+  let mut bundler = Bundler::with_options(&fs, None, parser_options(&cli_args, &css_modules), bundle_options);
+
+  let files = match build(&cli_args, &css_modules, targets, &mut bundler) {
+    Ok(files) => files,
+    Err(err) => {
+      eprintln!("{}", err);
+      if !cli_args.watch {
+        std::process::exit(1);
+      }
+      failed_files(&cli_args, &bundler)
+    }
+  };
+
+  if !cli_args.watch {
+    return Ok(());
+  }
+
+  watch(&cli_args, &css_modules, targets, &mut bundler, files)
+}
+
+/// Polls the given files for changes, and rebuilds whenever any of them changes. Files are polled
+/// rather than relying on file system events, so that this works on any file system.
+fn watch(
+  cli_args: &CliArgs,
+  css_modules: &Option<css_modules::Config>,
+  targets: Option<Browsers>,
+  bundler: &mut Bundler<FileProvider>,
+  files: Vec<PathBuf>,
+) -> ! {
+  let mut watched = watch_files(files);
+  loop {
+    std::thread::sleep(Duration::from_millis(WATCH_INTERVAL));
+
+    let changed: Vec<&PathBuf> = watched
+      .iter()
+      .filter(|(path, version)| file_version(path) != *version)
+      .map(|(path, _)| path)
+      .collect();
+    if changed.is_empty() {
+      continue;
+    }
+
+    // Only changed files are read and parsed again. The provider does not keep the contents of
+    // files, so memory use does not grow with each rebuild.
+    for path in &changed {
+      bundler.invalidate(path);
+    }
+
+    // If the build fails, watch every file the bundler tried to load, including the one that failed.
+    let files = match build(cli_args, css_modules, targets, bundler) {
+      Ok(files) => {
+        eprintln!("Rebuilt {}", cli_args.input_file);
+        files
+      }
+      Err(err) => {
+        eprintln!("{}", err);
+        failed_files(cli_args, bundler)
+      }
+    };
+    watched = watch_files(files);
+  }
+}
+
+/// Returns the files to watch after a failed build: the input file and, when bundling, every file
+/// the bundler tried to load, so that fixing or adding any of them triggers a rebuild.
+fn failed_files(cli_args: &CliArgs, bundler: &Bundler<FileProvider>) -> Vec<PathBuf> {
+  let mut files = vec![PathBuf::from(&cli_args.input_file)];
+  if cli_args.bundle {
+    files.extend(bundler.files());
+  }
+  files
+}
+
+/// How often to check for changes in watch mode, in milliseconds.
+const WATCH_INTERVAL: u64 = 100;
+
+/// The modification time and size of a file, used to detect changes in watch mode.
+type FileVersion = Option<(SystemTime, u64)>;
 
-  let absolute_path = fs::canonicalize(&cli_args.input_file)?;
-  let filename = pathdiff::diff_paths(absolute_path, std::env::current_dir()?).unwrap();
-  let filename = filename.to_str().unwrap();
-  let options = ParserOptions {
+fn file_version(path: &Path) -> FileVersion {
+  let metadata = fs::metadata(path).ok()?;
+  Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn watch_files(files: Vec<PathBuf>) -> Vec<(PathBuf, FileVersion)> {
+  files
+    .into_iter()
+    .map(|path| {
+      let version = file_version(&path);
+      (path, version)
+    })
+    .collect()
+}
+
+fn parser_options(cli_args: &CliArgs, css_modules: &Option<css_modules::Config>) -> ParserOptions {
+  ParserOptions {
     nesting: cli_args.nesting,
-    css_modules: cli_args.css_modules.is_some(),
+    css_modules: css_modules.clone(),
     custom_media: cli_args.custom_media,
     ..ParserOptions::default()
-  };
+  }
+}
 
-  let fs = FileProvider::new();
+/// Compiles the input file and writes the outputs. Returns the files that were compiled,
+/// i.e. the input file and, when bundling, all files in its bundle graph.
+fn build<'a>(
+  cli_args: &CliArgs,
+  css_modules: &Option<css_modules::Config>,
+  targets: Option<Browsers>,
+  bundler: &mut Bundler<'a, '_, FileProvider>,
+) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
   let mut source_map = if cli_args.sourcemap {
     Some(SourceMap::new("/"))
   } else {
     None
   };
 
-  let mut stylesheet = if cli_args.bundle {
-    let mut bundler = Bundler::new(&fs, source_map.as_mut(), options);
-    bundler.bundle(Path::new(&cli_args.input_file)).unwrap()
+  if cli_args.bundle {
+    let Bundle { stylesheet, graph } =
+      bundler.bundle(Path::new(&cli_args.input_file)).map_err(|e| e.to_string())?;
+    if let Some(bundle_graph) = &cli_args.bundle_graph {
+      fs::write(bundle_graph, serde_json::to_string(&graph)?)?;
+    }
+
+    // Sources are added in order, so that their indexes in the source map match the style sheet.
+    if let Some(sm) = &mut source_map {
+      for (source_index, filename) in stylesheet.sources.iter().enumerate() {
+        sm.add_source(filename);
+        if let Ok(code) = fs::read_to_string(filename) {
+          let _ = sm.set_source_content(source_index, &code);
+        }
+      }
+    }
+
+    write_output(cli_args, stylesheet, source_map, targets)?;
+    Ok(graph.files.iter().map(|file| file.path.clone()).collect())
   } else {
+    let source = fs::read_to_string(&cli_args.input_file)?;
+    let absolute_path = fs::canonicalize(&cli_args.input_file)?;
+    let filename = pathdiff::diff_paths(absolute_path, std::env::current_dir()?).unwrap();
+    let filename = filename.to_str().unwrap();
     if let Some(sm) = &mut source_map {
       sm.add_source(&filename);
       let _ = sm.set_source_content(0, &source);
     }
-    StyleSheet::parse(filename, &source, options).unwrap()
-  };
 
-  let targets = browserslist_to_targets(cli_args.targets).unwrap();
+    let options = parser_options(cli_args, css_modules);
+    let stylesheet = StyleSheet::parse(filename, &source, options).map_err(|e| e.to_string())?;
+    write_output(cli_args, stylesheet, source_map, targets)?;
+    Ok(vec![PathBuf::from(&cli_args.input_file)])
+  }
+}
+
+/// Minifies and prints the style sheet, and writes the output file, source map and CSS modules files.
+fn write_output(
+  cli_args: &CliArgs,
+  mut stylesheet: StyleSheet,
+  mut source_map: Option<SourceMap>,
+  targets: Option<Browsers>,
+) -> Result<(), Box<dyn std::error::Error>> {
   stylesheet
     .minify(MinifyOptions {
       targets,
       ..MinifyOptions::default()
     })
-    .unwrap();
+    .map_err(|e| e.to_string())?;
 
-  let res = stylesheet
+  let mut res = stylesheet
     .to_css(PrinterOptions {
       minify: cli_args.minify,
       source_map: source_map.as_mut(),
       targets,
       ..PrinterOptions::default()
     })
-    .unwrap();
+    .map_err(|e| e.to_string())?;
+
+  if cli_args.css_modules_camel_case {
+    if let Some(exports) = &mut res.exports {
+      css_modules::add_camel_case_aliases(exports)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+    }
+  }
 
   let map = if let Some(ref mut source_map) = source_map {
     let mut vlq_output: Vec<u8> = Vec::new();
@@ -130,17 +314,24 @@ pub fn main() -> Result<(), std::io::Error> {
 
     fs::write(output_file, code.as_bytes())?;
 
-    if let Some(css_modules) = cli_args.css_modules {
+    if let Some(css_modules) = &cli_args.css_modules {
       let css_modules_filename = if let Some(name) = css_modules {
-        name
+        name.clone()
       } else {
         infer_css_modules_filename(&output_file)?
       };
-      if let Some(exports) = res.exports {
-        let css_modules_json = serde_json::to_string(&exports)?;
+      if let Some(exports) = &res.exports {
+        let css_modules_json = serde_json::to_string(exports)?;
         fs::write(css_modules_filename, css_modules_json)?;
       }
     }
+
+    if let Some(css_modules_dts) = &cli_args.css_modules_dts {
+      let dts_filename = css_modules_dts.clone().unwrap_or_else(|| format!("{}.d.ts", output_file));
+      if let Some(exports) = &res.exports {
+        fs::write(dts_filename, css_modules::to_typescript_declarations(exports))?;
+      }
+    }
   } else {
     println!("{}", res.code);
   }
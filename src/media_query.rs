@@ -2,11 +2,11 @@
 
 use crate::compat::Feature;
 use crate::error::{ErrorWithLocation, MinifyError, MinifyErrorKind, ParserError, PrinterError};
-use crate::macros::enum_property;
+use crate::macros::{enum_property, impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::rules::custom_media::CustomMediaRule;
 use crate::rules::Location;
-use crate::traits::{Parse, ToCss};
+use crate::traits::{Parse, ToCss, Visit, Visitor};
 use crate::values::number::CSSNumber;
 use crate::values::string::CowArcStr;
 use crate::values::{length::Length, ratio::Ratio, resolution::Resolution};
@@ -16,11 +16,19 @@ use std::collections::{HashMap, HashSet};
 
 /// A [media query list](https://drafts.csswg.org/mediaqueries/#mq-list).
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct MediaList<'i> {
   /// The list of media queries.
   pub media_queries: Vec<MediaQuery<'i>>,
 }
 
+impl_visit!(MediaList<'i> { media_queries });
+impl_into_owned!(MediaList<'i> { media_queries });
+
 impl<'i> MediaList<'i> {
   /// Creates an empty media query list.
   pub fn new() -> Self {
@@ -55,7 +63,7 @@ impl<'i> MediaList<'i> {
     &mut self,
     loc: Location,
     custom_media: &HashMap<CowArcStr<'i>, CustomMediaRule<'i>>,
-  ) -> Result<(), MinifyError> {
+  ) -> Result<(), CustomMediaError> {
     for query in self.media_queries.iter_mut() {
       query.transform_custom_media(loc, custom_media)?;
     }
@@ -141,6 +149,11 @@ enum_property! {
 
 /// A [media type](https://drafts.csswg.org/mediaqueries/#media-types) within a media query.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum MediaType<'i> {
   /// Matches all devices.
   All,
@@ -153,6 +166,8 @@ pub enum MediaType<'i> {
   Custom(CowArcStr<'i>),
 }
 
+impl_into_owned!(MediaType<'i> match { All, Print, Screen, Custom(c) });
+
 impl<'i> Parse<'i> for MediaType<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let name = input.expect_ident()?;
@@ -167,6 +182,11 @@ impl<'i> Parse<'i> for MediaType<'i> {
 
 /// A [media query](https://drafts.csswg.org/mediaqueries/#media).
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct MediaQuery<'i> {
   /// The qualifier for this query.
   pub qualifier: Option<Qualifier>,
@@ -177,6 +197,18 @@ pub struct MediaQuery<'i> {
   pub condition: Option<MediaCondition<'i>>,
 }
 
+impl<'i> Visit<'i> for MediaQuery<'i> {
+  fn visit<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    visitor.visit_media_query(self)
+  }
+
+  fn visit_children<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    self.condition.visit(visitor)
+  }
+}
+
+impl_into_owned!(MediaQuery<'i> { qualifier, media_type, condition });
+
 impl<'i> MediaQuery<'i> {
   /// Parse a media query given css input.
   ///
@@ -210,7 +242,7 @@ impl<'i> MediaQuery<'i> {
     &mut self,
     loc: Location,
     custom_media: &HashMap<CowArcStr<'i>, CustomMediaRule<'i>>,
-  ) -> Result<(), MinifyError> {
+  ) -> Result<(), CustomMediaError> {
     if let Some(condition) = &mut self.condition {
       let used = process_condition(
         loc,
@@ -354,6 +386,11 @@ enum_property! {
 
 /// Represents a media condition.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum MediaCondition<'i> {
   /// A media feature, implicitly parenthesized.
   Feature(MediaFeature<'i>),
@@ -365,6 +402,9 @@ pub enum MediaCondition<'i> {
   InParens(Box<MediaCondition<'i>>),
 }
 
+impl_visit!(MediaCondition<'i> match { Feature(f), Not(c), Operation(c, o), InParens(c) });
+impl_into_owned!(MediaCondition<'i> match { Feature(m), Not(b), Operation(v, o), InParens(b) });
+
 impl<'i> MediaCondition<'i> {
   /// Parse a single media condition.
   pub fn parse<'t>(input: &mut Parser<'i, 't>, allow_or: bool) -> Result<Self, ParseError<'i, ParserError<'i>>> {
@@ -460,6 +500,11 @@ impl<'i> ToCss for MediaCondition<'i> {
 
 /// A [comparator](https://drafts.csswg.org/mediaqueries/#typedef-mf-comparison) within a media query.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "kebab-case")
+)]
 pub enum MediaFeatureComparison {
   /// `=`
   Equal,
@@ -473,6 +518,8 @@ pub enum MediaFeatureComparison {
   LessThanEqual,
 }
 
+impl_into_owned!(MediaFeatureComparison);
+
 impl ToCss for MediaFeatureComparison {
   fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
   where
@@ -511,6 +558,11 @@ impl MediaFeatureComparison {
 
 /// A [media feature](https://drafts.csswg.org/mediaqueries/#typedef-media-feature)
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum MediaFeature<'i> {
   /// A plain media feature, e.g. `(min-width: 240px)`.
   Plain {
@@ -531,6 +583,7 @@ pub enum MediaFeature<'i> {
     value: MediaFeatureValue<'i>,
   },
   /// An interval, e.g. `(120px < width < 240px)`.
+  #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
   Interval {
     /// The name of the feature.
     name: CowArcStr<'i>,
@@ -545,6 +598,19 @@ pub enum MediaFeature<'i> {
   },
 }
 
+impl_visit!(MediaFeature<'i> match {
+  Plain { name: _, value },
+  Boolean(_),
+  Range { name: _, operator: _, value },
+  Interval { name: _, start, start_operator: _, end, end_operator: _ },
+});
+impl_into_owned!(MediaFeature<'i> match {
+  Plain { name, value },
+  Boolean(c),
+  Range { name, operator, value },
+  Interval { name, start, start_operator, end, end_operator },
+});
+
 impl<'i> Parse<'i> for MediaFeature<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if let Ok(res) = input.try_parse(Self::parse_name_first) {
@@ -611,6 +677,22 @@ impl<'i> MediaFeature<'i> {
 
 impl<'i> ToCss for MediaFeature<'i> {
   fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    self.to_css_with_lowering(dest, true)
+  }
+}
+
+impl<'i> MediaFeature<'i> {
+  /// Serializes the feature. If `lower_ranges` is true, range and interval syntax is
+  /// converted to `min-` and `max-` prefixed features when unsupported by the targets.
+  /// Container queries always support range syntax, so they are never lowered.
+  pub(crate) fn to_css_with_lowering<W>(
+    &self,
+    dest: &mut Printer<W>,
+    lower_ranges: bool,
+  ) -> Result<(), PrinterError>
   where
     W: std::fmt::Write,
   {
@@ -618,7 +700,12 @@ impl<'i> ToCss for MediaFeature<'i> {
 
     match self {
       MediaFeature::Boolean(name) => {
-        serialize_identifier(name, dest)?;
+        if name.starts_with("--") {
+          // A reference to a custom media query, which may be scoped in CSS modules.
+          dest.write_dashed_ident_reference(name, &None)?;
+        } else {
+          serialize_identifier(name, dest)?;
+        }
       }
       MediaFeature::Plain { name, value } => {
         serialize_identifier(name, dest)?;
@@ -627,7 +714,7 @@ impl<'i> ToCss for MediaFeature<'i> {
       }
       MediaFeature::Range { name, operator, value } => {
         // If range syntax is unsupported, use min/max prefix if possible.
-        if let Some(targets) = dest.targets {
+        if let (true, Some(targets)) = (lower_ranges, dest.targets) {
           if !Feature::MediaRangeSyntax.is_compatible(targets) {
             return write_min_max(operator, name, value, dest);
           }
@@ -644,7 +731,7 @@ impl<'i> ToCss for MediaFeature<'i> {
         end,
         end_operator,
       } => {
-        if let Some(targets) = dest.targets {
+        if let (true, Some(targets)) = (lower_ranges, dest.targets) {
           if !Feature::MediaIntervalSyntax.is_compatible(targets) {
             write_min_max(&start_operator.opposite(), name, start, dest)?;
             dest.write_str(" and (")?;
@@ -707,6 +794,11 @@ where
 ///
 /// See [MediaFeature](MediaFeature).
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum MediaFeatureValue<'i> {
   /// A length value.
   Length(Length),
@@ -720,6 +812,9 @@ pub enum MediaFeatureValue<'i> {
   Ident(CowArcStr<'i>),
 }
 
+impl_visit!(MediaFeatureValue<'i> match { Length(l), Number(_), Resolution(_), Ratio(_), Ident(_) });
+impl_into_owned!(MediaFeatureValue<'i> match { Length(l), Number(c), Resolution(r), Ratio(r), Ident(c) });
+
 impl<'i> Parse<'i> for MediaFeatureValue<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     // Ratios are ambigous with numbers because the second param is optional (e.g. 2/1 == 2).
@@ -814,6 +909,14 @@ fn consume_operation_or_colon<'i, 't>(
   }))
 }
 
+/// An error that occurred while replacing references to `@custom-media` rules.
+pub(crate) enum CustomMediaError {
+  /// An error that prevents the style sheet from being minified.
+  Error(MinifyError),
+  /// Boolean logic with media types that cannot be emulated, in the `@custom-media` rule at the given location.
+  UnsupportedBooleanLogic(Location),
+}
+
 fn process_condition<'i>(
   loc: Location,
   custom_media: &HashMap<CowArcStr<'i>, CustomMediaRule<'i>>,
@@ -821,7 +924,7 @@ fn process_condition<'i>(
   qualifier: &mut Option<Qualifier>,
   condition: &mut MediaCondition<'i>,
   seen: &mut HashSet<CowArcStr<'i>>,
-) -> Result<bool, MinifyError> {
+) -> Result<bool, CustomMediaError> {
   match condition {
     MediaCondition::Not(cond) => {
       let used = process_condition(loc, custom_media, media_type, qualifier, &mut *cond, seen)?;
@@ -867,15 +970,17 @@ fn process_condition<'i>(
       }
 
       if seen.contains(name) {
-        return Err(ErrorWithLocation {
+        return Err(CustomMediaError::Error(ErrorWithLocation {
           kind: MinifyErrorKind::CircularCustomMedia { name: name.to_string() },
           loc,
-        });
+        }));
       }
 
-      let rule = custom_media.get(name).ok_or_else(|| ErrorWithLocation {
-        kind: MinifyErrorKind::CustomMediaNotDefined { name: name.to_string() },
-        loc,
+      let rule = custom_media.get(name).ok_or_else(|| {
+        CustomMediaError::Error(ErrorWithLocation {
+          kind: MinifyErrorKind::CustomMediaNotDefined { name: name.to_string() },
+          loc,
+        })
       })?;
 
       seen.insert(name.clone());
@@ -898,13 +1003,8 @@ fn process_condition<'i>(
               *media_type = query.media_type.clone();
               *qualifier = query.qualifier.clone();
             } else if query.media_type != *media_type || query.qualifier != *qualifier {
-              // Boolean logic with media types is hard to emulate, so we error for now.
-              res = Err(ErrorWithLocation {
-                kind: MinifyErrorKind::UnsupportedCustomMediaBooleanLogic {
-                  custom_media_loc: rule.loc,
-                },
-                loc,
-              });
+              // Boolean logic with media types is hard to emulate, so the query is left unchanged for now.
+              res = Err(CustomMediaError::UnsupportedBooleanLogic(rule.loc));
               return None;
             }
           }
@@ -1,15 +1,21 @@
-use crate::declaration::{parse_declaration, DeclarationBlock, DeclarationList};
-use crate::error::ParserError;
+use crate::css_modules;
+use crate::declaration::{parse_declaration, DeclarationAtRule, DeclarationBlock, DeclarationList};
+use crate::error::{Error, ParserError};
+use crate::macros::impl_into_owned;
 use crate::media_query::*;
+use crate::properties::contain::parse_container_name;
+use crate::properties::custom::TokenList;
 use crate::rules::font_palette_values::FontPaletteValuesRule;
 use crate::rules::layer::{LayerBlockRule, LayerStatementRule};
 use crate::rules::property::PropertyRule;
 use crate::rules::viewport::ViewportRule;
 use crate::rules::{
+  container::{ContainerCondition, ContainerRule},
   counter_style::CounterStyleRule,
   custom_media::CustomMediaRule,
   document::MozDocumentRule,
   font_face::{FontFaceDeclarationParser, FontFaceRule},
+  icss::{parse_raw, IcssExportRule, IcssImport, IcssImportRule, ValueRule},
   import::ImportRule,
   keyframes::{KeyframeListParser, KeyframesRule},
   layer::LayerName,
@@ -17,17 +23,20 @@ use crate::rules::{
   namespace::NamespaceRule,
   nesting::NestingRule,
   page::{PageRule, PageSelector},
+  scope::ScopeRule,
   style::StyleRule,
   supports::{SupportsCondition, SupportsRule},
+  unknown::UnknownAtRule,
   CssRule, CssRuleList, Location,
 };
-use crate::selector::{SelectorParser, Selectors};
-use crate::traits::Parse;
+use crate::selector::{find_impure_css_modules_selector, SelectorParser, Selectors};
+use crate::traits::{IntoOwned, Parse};
 use crate::values::ident::{CustomIdent, DashedIdent};
 use crate::values::string::CowArcStr;
 use crate::vendor_prefix::VendorPrefix;
 use cssparser::*;
 use parcel_selectors::{parser::NestingRequirement, SelectorList};
+use std::cell::RefCell;
 use std::collections::HashMap;
 
 /// CSS parsing options.
@@ -37,11 +46,46 @@ pub struct ParserOptions {
   pub nesting: bool,
   /// Whether to enable the [custom media](https://drafts.csswg.org/mediaqueries-5/#custom-mq) draft syntax.
   pub custom_media: bool,
-  /// Whether the enable [CSS modules](https://github.com/css-modules/css-modules).
-  pub css_modules: bool,
+  /// Whether the enable [CSS modules](https://github.com/css-modules/css-modules), and the
+  /// configuration to use, e.g. the pattern for compiled class names.
+  pub css_modules: Option<css_modules::Config>,
   /// The source index to assign to all parsed rules. Impacts the source map when
   /// the style sheet is serialized.
   pub source_index: u32,
+  /// Whether to ignore invalid rules and declarations rather than erroring.
+  /// When enabled, the errors are returned as the
+  /// [parser_warnings](crate::stylesheet::StyleSheet::parser_warnings) of the style sheet instead.
+  pub error_recovery: bool,
+}
+
+impl_into_owned!(ParserOptions);
+
+thread_local! {
+  /// The filename and warnings of the style sheet being parsed on the current thread, if any.
+  static WARNINGS: RefCell<Option<(String, Vec<Error<ParserError<'static>>>)>> = RefCell::new(None);
+}
+
+/// Runs the given parse function, and returns the warnings it emitted along with its result.
+/// Parsing is synchronous, so warnings are collected for the current thread, which avoids passing
+/// a mutable list through every parser.
+pub(crate) fn collect_warnings<T>(
+  filename: &str,
+  parse: impl FnOnce() -> T,
+) -> (T, Vec<Error<ParserError<'static>>>) {
+  let previous = WARNINGS.with(|warnings| warnings.replace(Some((filename.to_owned(), Vec::new()))));
+  let res = parse();
+  let collected = WARNINGS.with(|warnings| warnings.replace(previous));
+  (res, collected.map_or_else(Vec::new, |(_, warnings)| warnings))
+}
+
+/// Adds a warning for an invalid rule or declaration that was skipped due to the `error_recovery` option.
+/// Warnings are converted to owned values, so that they do not borrow from the source.
+pub(crate) fn warn<'i>(warning: ParseError<'i, ParserError<'i>>) {
+  WARNINGS.with(|warnings| {
+    if let Some((filename, warnings)) = &mut *warnings.borrow_mut() {
+      warnings.push(Error::from(warning, filename.clone()).into_owned());
+    }
+  })
 }
 
 #[derive(PartialEq, PartialOrd)]
@@ -76,6 +120,7 @@ impl<'a, 'b, 'i> TopLevelRuleParser<'a, 'i> {
       default_namespace: &mut self.default_namespace,
       namespace_prefixes: &mut self.namespace_prefixes,
       options: &self.options,
+      is_in_scope: false,
     }
   }
 }
@@ -123,6 +168,26 @@ pub enum AtRulePrelude<'i> {
   Layer(Vec<LayerName<'i>>),
   /// An @property prelude.
   Property(DashedIdent<'i>),
+  /// A @container prelude.
+  Container(Option<CustomIdent<'i>>, ContainerCondition<'i>),
+  /// A @scope prelude, with the scope start and end selectors.
+  Scope(Option<SelectorList<'i, Selectors>>, Option<SelectorList<'i, Selectors>>),
+  /// An unknown prelude, with the name of the at-rule.
+  Unknown(CowArcStr<'i>, TokenList<'i>),
+  /// A CSS modules @value prelude, with the name and raw value.
+  Value(CowArcStr<'i>, CowArcStr<'i>),
+  /// A CSS modules @value prelude that imports values from another file.
+  ValueImport(Vec<IcssImport<'i>>, CowArcStr<'i>),
+}
+
+/// A qualified rule prelude at the top level of a style sheet.
+pub enum QualifiedRulePrelude<'i> {
+  /// A style rule prelude, with its selectors.
+  Selectors(SelectorList<'i, Selectors>),
+  /// An ICSS :import prelude, with the specifier of the file to import from.
+  IcssImport(CowArcStr<'i>),
+  /// An ICSS :export prelude.
+  IcssExport,
 }
 
 impl<'a, 'i> AtRuleParser<'i> for TopLevelRuleParser<'a, 'i> {
@@ -179,7 +244,7 @@ impl<'a, 'i> AtRuleParser<'i> for TopLevelRuleParser<'a, 'i> {
         input.expect_string()?;
         return Ok(AtRulePrelude::Charset)
       },
-      "custom-media" if self.options.custom_media => {
+      "custom-media" if self.options.custom_media || matches!(&self.options.css_modules, Some(config) if config.dashed_idents) => {
         let name = DashedIdent::parse(input)?;
         let media = MediaList::parse(input)?;
         return Ok(AtRulePrelude::CustomMedia(name, media))
@@ -188,6 +253,16 @@ impl<'a, 'i> AtRuleParser<'i> for TopLevelRuleParser<'a, 'i> {
         let name = DashedIdent::parse(input)?;
         return Ok(AtRulePrelude::Property(name))
       },
+      "value" if self.options.css_modules.is_some() => {
+        if let Ok((imports, specifier)) = input.try_parse(parse_value_import) {
+          return Ok(AtRulePrelude::ValueImport(imports, specifier))
+        }
+
+        let name = input.expect_ident()?.into();
+        let _ = input.try_parse(|input| input.expect_colon());
+        let value = parse_raw(input)?.into();
+        return Ok(AtRulePrelude::Value(name, value))
+      },
       _ => {}
     }
 
@@ -245,6 +320,13 @@ impl<'a, 'i> AtRuleParser<'i> for TopLevelRuleParser<'a, 'i> {
         self.state = State::Body;
         CssRule::CustomMedia(CustomMediaRule { name, query, loc })
       }
+      // `@value` rules may appear before @import rules, so they do not change the state.
+      AtRulePrelude::Value(name, value) => CssRule::Value(ValueRule { name, value, loc }),
+      AtRulePrelude::ValueImport(imports, specifier) => CssRule::IcssImport(IcssImportRule {
+        specifier,
+        imports,
+        loc,
+      }),
       AtRulePrelude::Layer(_) => {
         // @layer statements are allowed before @import rules, but cannot be interleaved.
         if self.state <= State::Layers {
@@ -255,6 +337,7 @@ impl<'a, 'i> AtRuleParser<'i> for TopLevelRuleParser<'a, 'i> {
         AtRuleParser::rule_without_block(&mut self.nested(), prelude, start)?
       }
       AtRulePrelude::Charset => CssRule::Ignored,
+      AtRulePrelude::Unknown(..) => AtRuleParser::rule_without_block(&mut self.nested(), prelude, start)?,
       _ => return Err(()),
     };
 
@@ -263,7 +346,7 @@ impl<'a, 'i> AtRuleParser<'i> for TopLevelRuleParser<'a, 'i> {
 }
 
 impl<'a, 'i> QualifiedRuleParser<'i> for TopLevelRuleParser<'a, 'i> {
-  type Prelude = SelectorList<'i, Selectors>;
+  type Prelude = QualifiedRulePrelude<'i>;
   type QualifiedRule = (SourcePosition, CssRule<'i>);
   type Error = ParserError<'i>;
 
@@ -273,7 +356,14 @@ impl<'a, 'i> QualifiedRuleParser<'i> for TopLevelRuleParser<'a, 'i> {
     input: &mut Parser<'i, 't>,
   ) -> Result<Self::Prelude, ParseError<'i, Self::Error>> {
     self.state = State::Body;
-    QualifiedRuleParser::parse_prelude(&mut self.nested(), input)
+    if self.options.css_modules.is_some() {
+      if let Ok(prelude) = input.try_parse(parse_icss_prelude) {
+        return Ok(prelude);
+      }
+    }
+
+    let selectors = QualifiedRuleParser::parse_prelude(&mut self.nested(), input)?;
+    Ok(QualifiedRulePrelude::Selectors(selectors))
   }
 
   #[inline]
@@ -283,16 +373,75 @@ impl<'a, 'i> QualifiedRuleParser<'i> for TopLevelRuleParser<'a, 'i> {
     start: &ParserState,
     input: &mut Parser<'i, 't>,
   ) -> Result<Self::QualifiedRule, ParseError<'i, Self::Error>> {
-    let rule = QualifiedRuleParser::parse_block(&mut self.nested(), prelude, start, input)?;
+    let rule = match prelude {
+      QualifiedRulePrelude::Selectors(selectors) => {
+        QualifiedRuleParser::parse_block(&mut self.nested(), selectors, start, input)?
+      }
+      QualifiedRulePrelude::IcssImport(specifier) => {
+        CssRule::IcssImport(IcssImportRule::parse(specifier, input, self.nested().loc(start))?)
+      }
+      QualifiedRulePrelude::IcssExport => {
+        CssRule::IcssExport(IcssExportRule::parse(input, self.nested().loc(start))?)
+      }
+    };
     Ok((start.position(), rule))
   }
 }
 
+/// Parses the prelude of an ICSS `:import("./file.css")` or `:export` rule.
+fn parse_icss_prelude<'i, 't>(
+  input: &mut Parser<'i, 't>,
+) -> Result<QualifiedRulePrelude<'i>, ParseError<'i, ParserError<'i>>> {
+  input.expect_colon()?;
+  let location = input.current_source_location();
+  let prelude = match input.next_including_whitespace()?.clone() {
+    Token::Ident(ref name) if name.eq_ignore_ascii_case("export") => QualifiedRulePrelude::IcssExport,
+    Token::Function(ref name) if name.eq_ignore_ascii_case("import") => {
+      let specifier = input.parse_nested_block(|input| {
+        if let Ok(specifier) = input.try_parse(|input| input.expect_string_cloned()) {
+          input.expect_exhausted()?;
+          return Ok(specifier.into());
+        }
+
+        Ok(parse_raw(input)?.into())
+      })?;
+      QualifiedRulePrelude::IcssImport(specifier)
+    }
+    t => return Err(location.new_unexpected_token_error(t)),
+  };
+
+  input.expect_exhausted()?;
+  Ok(prelude)
+}
+
+/// Parses the prelude of an `@value` rule that imports values from another file,
+/// e.g. `@value primary, secondary as accent from "./colors.css"`.
+fn parse_value_import<'i, 't>(
+  input: &mut Parser<'i, 't>,
+) -> Result<(Vec<IcssImport<'i>>, CowArcStr<'i>), ParseError<'i, ParserError<'i>>> {
+  let imports = if input.try_parse(|input| input.expect_parenthesis_block()).is_ok() {
+    input.parse_nested_block(|input| input.parse_comma_separated(IcssImport::parse))?
+  } else {
+    let mut imports = vec![IcssImport::parse(input)?];
+    while input.try_parse(|input| input.expect_comma()).is_ok() {
+      imports.push(IcssImport::parse(input)?);
+    }
+    imports
+  };
+
+  input.expect_ident_matching("from")?;
+  let specifier = input.expect_string_cloned()?;
+  input.expect_exhausted()?;
+  Ok((imports, specifier.into()))
+}
+
 #[derive(Clone)]
 struct NestedRuleParser<'a, 'i> {
   default_namespace: &'a Option<CowArcStr<'i>>,
   namespace_prefixes: &'a HashMap<CowArcStr<'i>, CowArcStr<'i>>,
   options: &'a ParserOptions,
+  /// Whether the rules are within a `@scope` rule, where `&` refers to the scoping root.
+  is_in_scope: bool,
 }
 
 impl<'a, 'b, 'i> NestedRuleParser<'a, 'i> {
@@ -301,6 +450,7 @@ impl<'a, 'b, 'i> NestedRuleParser<'a, 'i> {
       default_namespace: self.default_namespace,
       namespace_prefixes: self.namespace_prefixes,
       options: self.options,
+      is_in_scope: self.is_in_scope,
     };
 
     let mut iter = RuleListParser::new_for_nested_rule(input, nested_parser);
@@ -309,8 +459,11 @@ impl<'a, 'b, 'i> NestedRuleParser<'a, 'i> {
       match result {
         Ok(CssRule::Ignored) => {}
         Ok(rule) => rules.push(rule),
-        Err(_) => {
-          // TODO
+        Err((e, _)) => {
+          // Invalid nested rules are skipped. They are only reported when error recovery is enabled.
+          if self.options.error_recovery {
+            warn(e);
+          }
         }
       }
     }
@@ -347,6 +500,34 @@ impl<'a, 'b, 'i> AtRuleParser<'i> for NestedRuleParser<'a, 'i> {
         let cond = SupportsCondition::parse(input)?;
         Ok(AtRulePrelude::Supports(cond))
       },
+      "container" => {
+        let name = input.try_parse(parse_container_name).ok();
+        let condition = ContainerCondition::parse(input)?;
+        Ok(AtRulePrelude::Container(name, condition))
+      },
+      "scope" => {
+        let selector_parser = SelectorParser {
+          default_namespace: self.default_namespace,
+          namespace_prefixes: self.namespace_prefixes,
+          is_nesting_allowed: self.is_in_scope,
+          css_modules: self.options.css_modules.is_some()
+        };
+
+        let scope_start = if input.try_parse(|input| input.expect_parenthesis_block()).is_ok() {
+          Some(input.parse_nested_block(|input| SelectorList::parse(&selector_parser, input, NestingRequirement::None))?)
+        } else {
+          None
+        };
+
+        let scope_end = if input.try_parse(|input| input.expect_ident_matching("to")).is_ok() {
+          input.expect_parenthesis_block()?;
+          Some(input.parse_nested_block(|input| SelectorList::parse(&selector_parser, input, NestingRequirement::None))?)
+        } else {
+          None
+        };
+
+        Ok(AtRulePrelude::Scope(scope_start, scope_end))
+      },
       "font-face" => {
         Ok(AtRulePrelude::FontFace)
       },
@@ -428,7 +609,11 @@ impl<'a, 'b, 'i> AtRuleParser<'i> for NestedRuleParser<'a, 'i> {
         };
         Ok(AtRulePrelude::Layer(names))
       },
-      _ => Err(input.new_error(BasicParseErrorKind::AtRuleInvalid(name)))
+      _ => {
+        input.skip_whitespace();
+        let prelude = TokenList::parse_raw(input, self.options, true)?;
+        Ok(AtRulePrelude::Unknown(name.into(), prelude))
+      }
     }
   }
 
@@ -485,6 +670,24 @@ impl<'a, 'b, 'i> AtRuleParser<'i> for NestedRuleParser<'a, 'i> {
         rules: self.parse_nested_rules(input),
         loc,
       })),
+      AtRulePrelude::Container(name, condition) => Ok(CssRule::Container(ContainerRule {
+        name,
+        condition,
+        rules: self.parse_nested_rules(input),
+        loc,
+      })),
+      AtRulePrelude::Scope(scope_start, scope_end) => {
+        let mut parser = NestedRuleParser {
+          is_in_scope: true,
+          ..self.clone()
+        };
+        Ok(CssRule::Scope(ScopeRule {
+          scope_start,
+          scope_end,
+          rules: parser.parse_nested_rules(input),
+          loc,
+        }))
+      }
       AtRulePrelude::Viewport(vendor_prefix) => {
         Ok(CssRule::Viewport(ViewportRule {
           vendor_prefix,
@@ -495,7 +698,12 @@ impl<'a, 'b, 'i> AtRuleParser<'i> for NestedRuleParser<'a, 'i> {
         }))
       }
       AtRulePrelude::Keyframes(name, vendor_prefix) => {
-        let iter = RuleListParser::new_for_nested_rule(input, KeyframeListParser);
+        let iter = RuleListParser::new_for_nested_rule(
+          input,
+          KeyframeListParser {
+            source_index: self.options.source_index,
+          },
+        );
         Ok(CssRule::Keyframes(KeyframesRule {
           name,
           keyframes: iter.filter_map(Result::ok).collect(),
@@ -528,9 +736,17 @@ impl<'a, 'b, 'i> AtRuleParser<'i> for NestedRuleParser<'a, 'i> {
         }))
       }
       AtRulePrelude::Property(name) => Ok(CssRule::Property(PropertyRule::parse(name, input, loc)?)),
+      AtRulePrelude::Unknown(name, prelude) => Ok(CssRule::Unknown(UnknownAtRule {
+        name,
+        prelude,
+        block: Some(parse_unknown_block(input, self.options)?),
+        loc,
+      })),
       AtRulePrelude::Import(..)
       | AtRulePrelude::Namespace(..)
       | AtRulePrelude::CustomMedia(..)
+      | AtRulePrelude::Value(..)
+      | AtRulePrelude::ValueImport(..)
       | AtRulePrelude::Charset => {
         // These rules don't have blocks.
         Err(input.new_unexpected_token_error(Token::CurlyBracketBlock))
@@ -550,6 +766,12 @@ impl<'a, 'b, 'i> AtRuleParser<'i> for NestedRuleParser<'a, 'i> {
 
         Ok(CssRule::LayerStatement(LayerStatementRule { names, loc }))
       }
+      AtRulePrelude::Unknown(name, prelude) => Ok(CssRule::Unknown(UnknownAtRule {
+        name,
+        prelude,
+        block: None,
+        loc,
+      })),
       _ => Err(()),
     }
   }
@@ -567,10 +789,10 @@ impl<'a, 'b, 'i> QualifiedRuleParser<'i> for NestedRuleParser<'a, 'i> {
     let selector_parser = SelectorParser {
       default_namespace: self.default_namespace,
       namespace_prefixes: self.namespace_prefixes,
-      is_nesting_allowed: false,
-      css_modules: self.options.css_modules,
+      is_nesting_allowed: self.is_in_scope,
+      css_modules: self.options.css_modules.is_some(),
     };
-    SelectorList::parse(&selector_parser, input, NestingRequirement::None)
+    parse_style_rule_selectors(&selector_parser, input, NestingRequirement::None, self.options)
   }
 
   fn parse_block<'t>(
@@ -595,6 +817,32 @@ impl<'a, 'b, 'i> QualifiedRuleParser<'i> for NestedRuleParser<'a, 'i> {
   }
 }
 
+/// Parses the selectors of a style rule. When the CSS modules `pure` option is enabled, an error is
+/// returned at the location of the first selector that does not contain a local class or id.
+fn parse_style_rule_selectors<'a, 'i, 't>(
+  selector_parser: &SelectorParser<'a, 'i>,
+  input: &mut Parser<'i, 't>,
+  nesting_requirement: NestingRequirement,
+  options: &ParserOptions,
+) -> Result<SelectorList<'i, Selectors>, ParseError<'i, ParserError<'i>>> {
+  if !matches!(&options.css_modules, Some(config) if config.pure) {
+    return SelectorList::parse(selector_parser, input, nesting_requirement);
+  }
+
+  // Each selector is parsed separately so that its location is known.
+  let selectors = input.parse_comma_separated(|input| {
+    input.skip_whitespace();
+    let location = input.current_source_location();
+    let selectors = SelectorList::parse(selector_parser, input, nesting_requirement)?;
+    match find_impure_css_modules_selector(&selectors) {
+      Some(selector) => Err(location.new_custom_error(ParserError::ImpureCssModulesSelector(selector))),
+      None => Ok(selectors.0),
+    }
+  })?;
+
+  Ok(SelectorList(selectors.into_iter().flatten().collect()))
+}
+
 fn parse_declarations_and_nested_rules<'a, 'i, 't>(
   input: &mut Parser<'i, 't>,
   default_namespace: &'a Option<CowArcStr<'i>>,
@@ -603,6 +851,7 @@ fn parse_declarations_and_nested_rules<'a, 'i, 't>(
 ) -> Result<(DeclarationBlock<'i>, CssRuleList<'i>), ParseError<'i, ParserError<'i>>> {
   let mut important_declarations = DeclarationList::new();
   let mut declarations = DeclarationList::new();
+  let mut at_rules = Vec::new();
   let mut rules = CssRuleList(vec![]);
   let parser = StyleRuleParser {
     default_namespace,
@@ -610,6 +859,7 @@ fn parse_declarations_and_nested_rules<'a, 'i, 't>(
     options,
     declarations: &mut declarations,
     important_declarations: &mut important_declarations,
+    at_rules: &mut at_rules,
     rules: &mut rules,
   };
 
@@ -630,6 +880,11 @@ fn parse_declarations_and_nested_rules<'a, 'i, 't>(
   let mut iter = RuleListParser::new_for_nested_rule(declaration_parser.input, declaration_parser.parser);
   while let Some(result) = iter.next() {
     if let Err((err, _)) = result {
+      if options.error_recovery {
+        warn(err);
+        continue;
+      }
+
       return Err(err);
     }
   }
@@ -638,6 +893,7 @@ fn parse_declarations_and_nested_rules<'a, 'i, 't>(
     DeclarationBlock {
       declarations,
       important_declarations,
+      at_rules,
     },
     rules,
   ))
@@ -649,9 +905,25 @@ pub struct StyleRuleParser<'a, 'i> {
   options: &'a ParserOptions,
   declarations: &'a mut DeclarationList<'i>,
   important_declarations: &'a mut DeclarationList<'i>,
+  at_rules: &'a mut Vec<DeclarationAtRule<'i>>,
   rules: &'a mut CssRuleList<'i>,
 }
 
+impl<'a, 'i> StyleRuleParser<'a, 'i> {
+  fn push_unknown(&mut self, rule: UnknownAtRule<'i>) {
+    // Unknown at-rules before any nested rules are kept with the declarations,
+    // so that their position relative to them is preserved.
+    if self.rules.0.is_empty() {
+      self.at_rules.push(DeclarationAtRule {
+        index: self.declarations.len(),
+        rule,
+      });
+    } else {
+      self.rules.0.push(CssRule::Unknown(rule));
+    }
+  }
+}
+
 /// Parse a declaration within {} block: `color: blue`
 impl<'a, 'i> cssparser::DeclarationParser<'i> for StyleRuleParser<'a, 'i> {
   type Declaration = ();
@@ -695,17 +967,27 @@ impl<'a, 'i> AtRuleParser<'i> for StyleRuleParser<'a, 'i> {
         let cond = SupportsCondition::parse(input)?;
         Ok(AtRulePrelude::Supports(cond))
       },
+      "container" => {
+        let name = input.try_parse(parse_container_name).ok();
+        let condition = ContainerCondition::parse(input)?;
+        Ok(AtRulePrelude::Container(name, condition))
+      },
       "nest" => {
         let selector_parser = SelectorParser {
           default_namespace: self.default_namespace,
           namespace_prefixes: self.namespace_prefixes,
           is_nesting_allowed: true,
-          css_modules: self.options.css_modules
+          css_modules: self.options.css_modules.is_some()
         };
-        let selectors = SelectorList::parse(&selector_parser, input, NestingRequirement::Contained)?;
+        let selectors =
+          parse_style_rule_selectors(&selector_parser, input, NestingRequirement::Contained, self.options)?;
         Ok(AtRulePrelude::Nest(selectors))
       },
-      _ => Err(input.new_error(BasicParseErrorKind::AtRuleInvalid(name)))
+      _ => {
+        input.skip_whitespace();
+        let prelude = TokenList::parse_raw(input, self.options, true)?;
+        Ok(AtRulePrelude::Unknown(name.into(), prelude))
+      }
     }
   }
 
@@ -750,6 +1032,30 @@ impl<'a, 'i> AtRuleParser<'i> for StyleRuleParser<'a, 'i> {
         }));
         Ok(())
       }
+      AtRulePrelude::Container(name, condition) => {
+        self.rules.0.push(CssRule::Container(ContainerRule {
+          name,
+          condition,
+          rules: parse_nested_at_rule(
+            input,
+            self.options.source_index,
+            self.default_namespace,
+            self.namespace_prefixes,
+            self.options,
+          )?,
+          loc,
+        }));
+        Ok(())
+      }
+      AtRulePrelude::Unknown(name, prelude) => {
+        self.push_unknown(UnknownAtRule {
+          name,
+          prelude,
+          block: Some(parse_unknown_block(input, self.options)?),
+          loc,
+        });
+        Ok(())
+      }
       AtRulePrelude::Nest(selectors) => {
         let (declarations, rules) = parse_declarations_and_nested_rules(
           input,
@@ -774,6 +1080,37 @@ impl<'a, 'i> AtRuleParser<'i> for StyleRuleParser<'a, 'i> {
       }
     }
   }
+
+  #[inline]
+  fn rule_without_block(&mut self, prelude: AtRulePrelude<'i>, start: &ParserState) -> Result<Self::AtRule, ()> {
+    let loc = start.source_location();
+    let loc = Location {
+      source_index: self.options.source_index,
+      line: loc.line,
+      column: loc.column,
+    };
+    match prelude {
+      AtRulePrelude::Unknown(name, prelude) => {
+        self.push_unknown(UnknownAtRule {
+          name,
+          prelude,
+          block: None,
+          loc,
+        });
+        Ok(())
+      }
+      _ => Err(()),
+    }
+  }
+}
+
+#[inline]
+pub(crate) fn parse_unknown_block<'i, 't>(
+  input: &mut Parser<'i, 't>,
+  options: &ParserOptions,
+) -> Result<TokenList<'i>, ParseError<'i, ParserError<'i>>> {
+  input.skip_whitespace();
+  TokenList::parse_raw(input, options, true)
 }
 
 #[inline]
@@ -791,9 +1128,9 @@ fn parse_nested_at_rule<'a, 'i, 't>(
     column: loc.column,
   };
 
-  // Declarations can be immediately within @media and @supports blocks that are nested within a parent style rule.
+  // Declarations can be immediately within @media, @supports, and @container blocks that are nested within a parent style rule.
   // These act the same way as if they were nested within a `& { ... }` block.
-  let (declarations, mut rules) =
+  let (mut declarations, mut rules) =
     parse_declarations_and_nested_rules(input, default_namespace, namespace_prefixes, options)?;
 
   if declarations.declarations.len() > 0 {
@@ -809,6 +1146,15 @@ fn parse_nested_at_rule<'a, 'i, 't>(
         loc,
       }),
     )
+  } else {
+    // Unknown at-rules without any declarations around them stay as nested rules.
+    let mut at_rules: Vec<CssRule> = declarations
+      .at_rules
+      .drain(..)
+      .map(|at_rule| CssRule::Unknown(at_rule.rule))
+      .collect();
+    at_rules.append(&mut rules.0);
+    rules.0 = at_rules;
   }
 
   Ok(rules)
@@ -827,9 +1173,9 @@ impl<'a, 'b, 'i> QualifiedRuleParser<'i> for StyleRuleParser<'a, 'i> {
       default_namespace: self.default_namespace,
       namespace_prefixes: self.namespace_prefixes,
       is_nesting_allowed: true,
-      css_modules: self.options.css_modules,
+      css_modules: self.options.css_modules.is_some(),
     };
-    SelectorList::parse(&selector_parser, input, NestingRequirement::Prefixed)
+    parse_style_rule_selectors(&selector_parser, input, NestingRequirement::Prefixed, self.options)
   }
 
   fn parse_block<'t>(
@@ -2,7 +2,8 @@
 
 use crate::css_modules::CssModule;
 use crate::dependencies::Dependency;
-use crate::error::{Error, ErrorLocation, PrinterError, PrinterErrorKind};
+use crate::error::{Error, ErrorLocation, PrinterError, PrinterErrorKind, Warning, WarningKind};
+use crate::properties::css_modules::ComposesFrom;
 use crate::rules::Location;
 use crate::targets::Browsers;
 use crate::vendor_prefix::VendorPrefix;
@@ -62,6 +63,7 @@ pub struct Printer<'a, W> {
   dest: &'a mut W,
   source_map: Option<&'a mut SourceMap>,
   pub(crate) source_index: u32,
+  pub(crate) loc: Location,
   indent: u8,
   line: u32,
   col: u32,
@@ -74,6 +76,7 @@ pub struct Printer<'a, W> {
   pub(crate) css_module: Option<CssModule<'a>>,
   pub(crate) dependencies: Option<Vec<Dependency>>,
   pub(crate) pseudo_classes: Option<PseudoClasses<'a>>,
+  pub(crate) warnings: Vec<Warning>,
 }
 
 impl<'a, W: std::fmt::Write + Sized> Printer<'a, W> {
@@ -84,6 +87,11 @@ impl<'a, W: std::fmt::Write + Sized> Printer<'a, W> {
       dest,
       source_map: options.source_map,
       source_index: 0,
+      loc: Location {
+        source_index: 0,
+        line: 0,
+        column: 1,
+      },
       indent: 0,
       line: 0,
       col: 0,
@@ -98,6 +106,7 @@ impl<'a, W: std::fmt::Write + Sized> Printer<'a, W> {
         None
       },
       pseudo_classes: options.pseudo_classes,
+      warnings: Vec::new(),
     }
   }
 
@@ -201,6 +210,7 @@ impl<'a, W: std::fmt::Write + Sized> Printer<'a, W> {
   /// Adds a mapping to the source map, if any.
   pub fn add_mapping(&mut self, loc: Location) {
     self.source_index = loc.source_index;
+    self.loc = loc;
     if let Some(map) = &mut self.source_map {
       map.add_mapping(
         self.line,
@@ -216,26 +226,52 @@ impl<'a, W: std::fmt::Write + Sized> Printer<'a, W> {
   }
 
   /// Writes a CSS identifier to the underlying destination, escaping it
-  /// as appropriate. If the `css_modules` option was enabled, then a hash
-  /// is added, and the mapping is added to the CSS module.
+  /// as appropriate. If the `css_modules` option was enabled, then the name
+  /// is compiled according to the configured pattern, and the mapping is added
+  /// to the CSS module.
   pub fn write_ident(&mut self, ident: &str) -> Result<(), PrinterError> {
-    let hash = if let Some(css_module) = &self.css_module {
-      Some(css_module.hash)
+    let name = if let Some(css_module) = &mut self.css_module {
+      css_module.add_local(ident, ident, self.loc.source_index);
+      Some(css_module.get_name(ident, self.loc.source_index))
     } else {
       None
     };
 
-    if let Some(hash) = hash {
-      serialize_identifier(hash, self)?;
-      self.write_char('_')?;
-    }
+    serialize_identifier(name.as_deref().unwrap_or(ident), self)?;
+    Ok(())
+  }
 
-    serialize_identifier(ident, self)?;
+  /// Writes a declared dashed identifier (e.g. `--foo`). If the `dashed_idents` CSS modules option
+  /// was enabled, then the name is compiled according to the configured pattern, and the mapping is
+  /// added to the CSS module.
+  pub fn write_dashed_ident(&mut self, ident: &str) -> Result<(), PrinterError> {
+    let name = match &mut self.css_module {
+      Some(css_module) if css_module.config.dashed_idents => {
+        Some(css_module.add_dashed(ident, self.loc.source_index))
+      }
+      _ => None,
+    };
 
-    if let Some(css_module) = &mut self.css_module {
-      css_module.add_local(&ident, &ident);
-    }
+    serialize_identifier(name.as_deref().unwrap_or(ident), self)?;
+    Ok(())
+  }
 
+  /// Writes a reference to a dashed identifier, e.g. within `var()`. If the `dashed_idents`
+  /// CSS modules option was enabled, then the name is compiled according to where it is
+  /// referenced from.
+  pub(crate) fn write_dashed_ident_reference(
+    &mut self,
+    ident: &str,
+    from: &Option<ComposesFrom>,
+  ) -> Result<(), PrinterError> {
+    let name = match &mut self.css_module {
+      Some(css_module) if css_module.config.dashed_idents => {
+        Some(css_module.reference_dashed(ident, from, self.loc.source_index))
+      }
+      _ => None,
+    };
+
+    serialize_identifier(name.as_deref().unwrap_or(ident), self)?;
     Ok(())
   }
 
@@ -250,6 +286,23 @@ impl<'a, W: std::fmt::Write + Sized> Printer<'a, W> {
       }),
     }
   }
+
+  /// Adds a warning at the given location, which will be returned as part of the
+  /// [ToCssResult](super::stylesheet::ToCssResult).
+  pub(crate) fn warn(&mut self, kind: WarningKind, loc: Location) {
+    let filename = self.source_filename(loc);
+    self.warnings.push(Warning {
+      kind,
+      loc: Some(ErrorLocation::new(loc, filename)),
+    })
+  }
+
+  fn source_filename(&self, loc: Location) -> String {
+    match self.sources.and_then(|sources| sources.get(loc.source_index as usize)) {
+      Some(filename) => filename.clone(),
+      None => self.filename().into(),
+    }
+  }
 }
 
 impl<'a, W: std::fmt::Write + Sized> std::fmt::Write for Printer<'a, W> {
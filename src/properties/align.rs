@@ -18,6 +18,11 @@ use cssparser::*;
 /// A [`<baseline-position>`](https://www.w3.org/TR/css-align-3/#typedef-baseline-position) value,
 /// as used in the alignment properties.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "kebab-case")
+)]
 pub enum BaselinePosition {
   /// The first baseline.
   First,
@@ -25,6 +30,9 @@ pub enum BaselinePosition {
   Last,
 }
 
+impl_visit!(BaselinePosition);
+impl_into_owned!(BaselinePosition);
+
 impl<'i> Parse<'i> for BaselinePosition {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let location = input.current_source_location();
@@ -102,6 +110,11 @@ enum_property! {
 
 /// A value for the [align-content](https://www.w3.org/TR/css-align-3/#propdef-align-content) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum AlignContent {
   /// Default alignment.
   Normal,
@@ -113,6 +126,9 @@ pub enum AlignContent {
   ContentPosition(Option<OverflowPosition>, ContentPosition),
 }
 
+impl_visit!(AlignContent);
+impl_into_owned!(AlignContent);
+
 impl<'i> Parse<'i> for AlignContent {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|input| input.expect_ident_matching("normal")).is_ok() {
@@ -156,6 +172,11 @@ impl ToCss for AlignContent {
 
 /// A value for the [justify-content](https://www.w3.org/TR/css-align-3/#propdef-justify-content) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum JustifyContent {
   /// Default justification.
   Normal,
@@ -169,6 +190,9 @@ pub enum JustifyContent {
   Right(Option<OverflowPosition>),
 }
 
+impl_visit!(JustifyContent);
+impl_into_owned!(JustifyContent);
+
 impl<'i> Parse<'i> for JustifyContent {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|input| input.expect_ident_matching("normal")).is_ok() {
@@ -234,6 +258,11 @@ impl ToCss for JustifyContent {
 
 /// A value for the [place-content](https://www.w3.org/TR/css-align-3/#place-content) shorthand property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct PlaceContent {
   /// The content alignment.
   pub align: AlignContent,
@@ -241,6 +270,9 @@ pub struct PlaceContent {
   pub justify: JustifyContent,
 }
 
+impl_visit!(PlaceContent);
+impl_into_owned!(PlaceContent);
+
 impl<'i> Parse<'i> for PlaceContent {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let align = AlignContent::parse(input)?;
@@ -311,6 +343,11 @@ enum_property! {
 
 /// A value for the [align-self](https://www.w3.org/TR/css-align-3/#align-self-property) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum AlignSelf {
   /// Automatic alignment.
   Auto,
@@ -324,6 +361,9 @@ pub enum AlignSelf {
   SelfPosition(Option<OverflowPosition>, SelfPosition),
 }
 
+impl_visit!(AlignSelf);
+impl_into_owned!(AlignSelf);
+
 impl<'i> Parse<'i> for AlignSelf {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|input| input.expect_ident_matching("auto")).is_ok() {
@@ -372,6 +412,11 @@ impl ToCss for AlignSelf {
 
 /// A value for the [justify-self](https://www.w3.org/TR/css-align-3/#justify-self-property) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum JustifySelf {
   /// Automatic justification.
   Auto,
@@ -389,6 +434,9 @@ pub enum JustifySelf {
   Right(Option<OverflowPosition>),
 }
 
+impl_visit!(JustifySelf);
+impl_into_owned!(JustifySelf);
+
 impl<'i> Parse<'i> for JustifySelf {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|input| input.expect_ident_matching("auto")).is_ok() {
@@ -464,6 +512,11 @@ impl ToCss for JustifySelf {
 
 /// A value for the [place-self](https://www.w3.org/TR/css-align-3/#place-self-property) shorthand property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct PlaceSelf {
   /// The item alignment.
   pub align: AlignSelf,
@@ -471,6 +524,9 @@ pub struct PlaceSelf {
   pub justify: JustifySelf,
 }
 
+impl_visit!(PlaceSelf);
+impl_into_owned!(PlaceSelf);
+
 impl<'i> Parse<'i> for PlaceSelf {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let align = AlignSelf::parse(input)?;
@@ -522,6 +578,11 @@ impl ToCss for PlaceSelf {
 
 /// A value for the [align-items](https://www.w3.org/TR/css-align-3/#align-items-property) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum AlignItems {
   /// Default alignment.
   Normal,
@@ -533,6 +594,9 @@ pub enum AlignItems {
   SelfPosition(Option<OverflowPosition>, SelfPosition),
 }
 
+impl_visit!(AlignItems);
+impl_into_owned!(AlignItems);
+
 impl<'i> Parse<'i> for AlignItems {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|input| input.expect_ident_matching("normal")).is_ok() {
@@ -576,6 +640,11 @@ impl ToCss for AlignItems {
 
 /// A legacy justification keyword, as used in the `justify-items` property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "kebab-case")
+)]
 pub enum LegacyJustify {
   /// Left justify.
   Left,
@@ -585,6 +654,9 @@ pub enum LegacyJustify {
   Center,
 }
 
+impl_visit!(LegacyJustify);
+impl_into_owned!(LegacyJustify);
+
 impl<'i> Parse<'i> for LegacyJustify {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let location = input.current_source_location();
@@ -637,6 +709,11 @@ impl ToCss for LegacyJustify {
 
 /// A value for the [justify-items](https://www.w3.org/TR/css-align-3/#justify-items-property) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum JustifyItems {
   /// Default justification.
   Normal,
@@ -654,6 +731,9 @@ pub enum JustifyItems {
   Legacy(LegacyJustify),
 }
 
+impl_visit!(JustifyItems);
+impl_into_owned!(JustifyItems);
+
 impl<'i> Parse<'i> for JustifyItems {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|input| input.expect_ident_matching("normal")).is_ok() {
@@ -729,6 +809,11 @@ impl ToCss for JustifyItems {
 
 /// A value for the [place-items](https://www.w3.org/TR/css-align-3/#place-items-property) shorthand property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct PlaceItems {
   /// The item alignment.
   pub align: AlignItems,
@@ -736,6 +821,9 @@ pub struct PlaceItems {
   pub justify: JustifyItems,
 }
 
+impl_visit!(PlaceItems);
+impl_into_owned!(PlaceItems);
+
 impl<'i> Parse<'i> for PlaceItems {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let align = AlignItems::parse(input)?;
@@ -786,6 +874,11 @@ impl ToCss for PlaceItems {
 /// A [gap](https://www.w3.org/TR/css-align-3/#column-row-gap) value, as used in the
 /// `column-gap` and `row-gap` properties.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum GapValue {
   /// Equal to `1em` for multi-column containers, and zero otherwise.
   Normal,
@@ -793,6 +886,9 @@ pub enum GapValue {
   LengthPercentage(LengthPercentage),
 }
 
+impl_visit!(GapValue match { Normal, LengthPercentage(l) });
+impl_into_owned!(GapValue);
+
 impl<'i> Parse<'i> for GapValue {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|input| input.expect_ident_matching("normal")).is_ok() {
@@ -818,6 +914,11 @@ impl ToCss for GapValue {
 
 /// A value for the [gap](https://www.w3.org/TR/css-align-3/#gap-shorthand) shorthand property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Gap {
   /// The row gap.
   pub row: GapValue,
@@ -825,6 +926,9 @@ pub struct Gap {
   pub column: GapValue,
 }
 
+impl_visit!(Gap { row, column });
+impl_into_owned!(Gap);
+
 impl<'i> Parse<'i> for Gap {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let row = GapValue::parse(input)?;
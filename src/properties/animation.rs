@@ -17,6 +17,11 @@ use smallvec::SmallVec;
 
 /// A value for the [animation-name](https://drafts.csswg.org/css-animations/#animation-name) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum AnimationName<'i> {
   /// The `none` keyword.
   None,
@@ -24,6 +29,9 @@ pub enum AnimationName<'i> {
   Ident(CustomIdent<'i>),
 }
 
+impl_visit!(AnimationName<'i> match { None, Ident(i) });
+impl_into_owned!(AnimationName<'i> match { None, Ident(c) });
+
 impl<'i> Parse<'i> for AnimationName<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
@@ -49,7 +57,7 @@ impl<'i> ToCss for AnimationName<'i> {
       AnimationName::None => dest.write_str("none"),
       AnimationName::Ident(s) => {
         if let Some(css_module) = &mut dest.css_module {
-          css_module.reference(&s.0)
+          css_module.reference(&s.0, dest.loc.source_index)
         }
         s.to_css(dest)
       }
@@ -62,6 +70,11 @@ pub type AnimationNameList<'i> = SmallVec<[AnimationName<'i>; 1]>;
 
 /// A value for the [animation-iteration-count](https://drafts.csswg.org/css-animations/#animation-iteration-count) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum AnimationIterationCount {
   /// The animation will repeat the specified number of times.
   Number(CSSNumber),
@@ -69,6 +82,9 @@ pub enum AnimationIterationCount {
   Infinite,
 }
 
+impl_visit!(AnimationIterationCount);
+impl_into_owned!(AnimationIterationCount);
+
 impl<'i> Parse<'i> for AnimationIterationCount {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|input| input.expect_ident_matching("infinite")).is_ok() {
@@ -132,6 +148,11 @@ enum_property! {
 
 /// A value for the [animation](https://drafts.csswg.org/css-animations/#animation) shorthand property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Animation<'i> {
   /// The animation name.
   pub name: AnimationName<'i>,
@@ -151,6 +172,18 @@ pub struct Animation<'i> {
   pub fill_mode: AnimationFillMode,
 }
 
+impl_visit!(Animation<'i> {
+  name,
+  duration: _,
+  timing_function: _,
+  iteration_count: _,
+  direction: _,
+  play_state: _,
+  delay: _,
+  fill_mode: _
+});
+impl_into_owned!(Animation<'i> { name, duration, timing_function, iteration_count, direction, play_state, delay, fill_mode });
+
 impl<'i> Parse<'i> for Animation<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let mut name = None;
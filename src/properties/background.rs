@@ -18,6 +18,11 @@ use smallvec::SmallVec;
 
 /// A value for the [background-size](https://www.w3.org/TR/css-backgrounds-3/#background-size) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum BackgroundSize {
   /// An explicit background size.
   Explicit {
@@ -32,6 +37,9 @@ pub enum BackgroundSize {
   Contain,
 }
 
+impl_visit!(BackgroundSize match { Explicit { width, height }, Cover, Contain });
+impl_into_owned!(BackgroundSize);
+
 impl Default for BackgroundSize {
   fn default() -> BackgroundSize {
     BackgroundSize::Explicit {
@@ -104,6 +112,11 @@ enum_property! {
 
 /// A value for the [background-repeat](https://www.w3.org/TR/css-backgrounds-3/#background-repeat) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct BackgroundRepeat {
   /// A repeat style for the x direction.
   pub x: BackgroundRepeatKeyword,
@@ -111,6 +124,9 @@ pub struct BackgroundRepeat {
   pub y: BackgroundRepeatKeyword,
 }
 
+impl_visit!(BackgroundRepeat);
+impl_into_owned!(BackgroundRepeat);
+
 impl Default for BackgroundRepeat {
   fn default() -> BackgroundRepeat {
     BackgroundRepeat {
@@ -245,6 +261,11 @@ impl BackgroundClip {
 
 /// A value for the [background](https://www.w3.org/TR/css-backgrounds-3/#background) shorthand property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Background<'i> {
   /// The background image.
   pub image: Image<'i>,
@@ -264,6 +285,9 @@ pub struct Background<'i> {
   pub clip: BackgroundClip,
 }
 
+impl_visit!(Background<'i> { image, color, position, repeat: _, size, attachment: _, origin: _, clip: _ });
+impl_into_owned!(Background<'i> { image, color, position, repeat, size, attachment, origin, clip });
+
 impl<'i> Parse<'i> for Background<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let mut color: Option<CssColor> = None;
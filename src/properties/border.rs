@@ -12,7 +12,7 @@ use crate::printer::Printer;
 use crate::properties::custom::UnparsedProperty;
 use crate::properties::{Property, PropertyId};
 use crate::targets::Browsers;
-use crate::traits::{FallbackValues, Parse, PropertyHandler, ToCss};
+use crate::traits::{FallbackValues, Parse, PropertyHandler, ToCss, Visit, Visitor};
 use crate::values::color::{ColorFallbackKind, CssColor};
 use crate::values::length::*;
 use crate::values::rect::Rect;
@@ -20,6 +20,11 @@ use cssparser::*;
 
 /// A value for the [border-width](https://www.w3.org/TR/css-backgrounds-3/#border-width) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum BorderSideWidth {
   /// A UA defined `thin` value.
   Thin,
@@ -31,6 +36,9 @@ pub enum BorderSideWidth {
   Length(Length),
 }
 
+impl_visit!(BorderSideWidth match { Thin, Medium, Thick, Length(l) });
+impl_into_owned!(BorderSideWidth);
+
 impl Default for BorderSideWidth {
   fn default() -> BorderSideWidth {
     BorderSideWidth::Medium
@@ -102,6 +110,11 @@ impl Default for BorderStyle {
 
 /// A generic type that represents the `border` and `outline` shorthand properties.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct GenericBorder<S> {
   /// The width of the border.
   pub width: BorderSideWidth,
@@ -111,6 +124,15 @@ pub struct GenericBorder<S> {
   pub color: CssColor,
 }
 
+impl<'i, S> Visit<'i> for GenericBorder<S> {
+  fn visit_children<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    self.width.visit(visitor);
+    self.color.visit(visitor);
+  }
+}
+
+impl_into_owned!(GenericBorder<S>);
+
 impl<S: Default> Default for GenericBorder<S> {
   fn default() -> GenericBorder<S> {
     GenericBorder {
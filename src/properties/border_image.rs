@@ -37,6 +37,7 @@ enum_property! {
 
 /// A value for the [border-image-repeat](https://www.w3.org/TR/css-backgrounds-3/#border-image-repeat) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BorderImageRepeat(
   /// The top and bottom repeat value.
   pub BorderImageRepeatKeyword,
@@ -44,6 +45,9 @@ pub struct BorderImageRepeat(
   pub BorderImageRepeatKeyword,
 );
 
+impl_visit!(BorderImageRepeat);
+impl_into_owned!(BorderImageRepeat);
+
 impl Default for BorderImageRepeat {
   fn default() -> BorderImageRepeat {
     BorderImageRepeat(BorderImageRepeatKeyword::Stretch, BorderImageRepeatKeyword::Stretch)
@@ -74,6 +78,11 @@ impl ToCss for BorderImageRepeat {
 
 /// A value for the [border-image-width](https://www.w3.org/TR/css-backgrounds-3/#border-image-width) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum BorderImageSideWidth {
   /// A number representing a multiple of the border width.
   Number(CSSNumber),
@@ -83,6 +92,9 @@ pub enum BorderImageSideWidth {
   Auto,
 }
 
+impl_visit!(BorderImageSideWidth match { Number(_), LengthPercentage(l), Auto });
+impl_into_owned!(BorderImageSideWidth);
+
 impl Default for BorderImageSideWidth {
   fn default() -> BorderImageSideWidth {
     BorderImageSideWidth::Number(1.0)
@@ -123,6 +135,11 @@ impl ToCss for BorderImageSideWidth {
 
 /// A value for the [border-image-slice](https://www.w3.org/TR/css-backgrounds-3/#border-image-slice) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct BorderImageSlice {
   /// The offsets from the edges of the image.
   pub offsets: Rect<NumberOrPercentage>,
@@ -130,6 +147,9 @@ pub struct BorderImageSlice {
   pub fill: bool,
 }
 
+impl_visit!(BorderImageSlice);
+impl_into_owned!(BorderImageSlice);
+
 impl Default for BorderImageSlice {
   fn default() -> BorderImageSlice {
     BorderImageSlice {
@@ -165,6 +185,11 @@ impl ToCss for BorderImageSlice {
 
 /// A value for the [border-image](https://www.w3.org/TR/css-backgrounds-3/#border-image) shorthand property.
 #[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct BorderImage<'i> {
   /// The border image.
   pub source: Image<'i>,
@@ -178,6 +203,9 @@ pub struct BorderImage<'i> {
   pub repeat: BorderImageRepeat,
 }
 
+impl_visit!(BorderImage<'i> { source, slice: _, width, outset, repeat: _ });
+impl_into_owned!(BorderImage<'i> { source, slice, width, outset, repeat });
+
 impl<'i> Parse<'i> for BorderImage<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     BorderImage::parse_with_callback(input, |_| false)
@@ -5,6 +5,7 @@ use crate::context::PropertyHandlerContext;
 use crate::declaration::DeclarationList;
 use crate::error::{ParserError, PrinterError};
 use crate::logical::PropertyCategory;
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::prefixes::Feature;
 use crate::printer::Printer;
 use crate::properties::{Property, PropertyId, VendorPrefix};
@@ -17,6 +18,11 @@ use cssparser::*;
 
 /// A value for the [border-radius](https://www.w3.org/TR/css-backgrounds-3/#border-radius) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct BorderRadius {
   /// The x and y radius values for the top left corner.
   pub top_left: Size2D<LengthPercentage>,
@@ -28,6 +34,14 @@ pub struct BorderRadius {
   pub bottom_right: Size2D<LengthPercentage>,
 }
 
+impl_visit!(BorderRadius {
+  top_left,
+  top_right,
+  bottom_left,
+  bottom_right
+});
+impl_into_owned!(BorderRadius);
+
 impl Default for BorderRadius {
   fn default() -> BorderRadius {
     let zero = Size2D(LengthPercentage::zero(), LengthPercentage::zero());
@@ -4,6 +4,7 @@ use super::PropertyId;
 use crate::context::PropertyHandlerContext;
 use crate::declaration::DeclarationList;
 use crate::error::{ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::prefixes::Feature;
 use crate::printer::Printer;
 use crate::properties::Property;
@@ -17,6 +18,11 @@ use smallvec::SmallVec;
 
 /// A value for the [box-shadow](https://drafts.csswg.org/css-backgrounds/#box-shadow) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct BoxShadow {
   /// The color of the box shadow.
   pub color: CssColor,
@@ -32,6 +38,16 @@ pub struct BoxShadow {
   pub inset: bool,
 }
 
+impl_visit!(BoxShadow {
+  color,
+  x_offset,
+  y_offset,
+  blur,
+  spread,
+  inset: _
+});
+impl_into_owned!(BoxShadow);
+
 impl<'i> Parse<'i> for BoxShadow {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let mut color = None;
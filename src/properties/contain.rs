@@ -0,0 +1,150 @@
+//! CSS properties related to containment.
+
+use crate::error::{ParserError, PrinterError};
+use crate::macros::{enum_property, impl_into_owned, impl_visit};
+use crate::printer::Printer;
+use crate::traits::{Parse, ToCss};
+use crate::values::ident::{CustomIdent, CustomIdentList};
+use cssparser::*;
+use smallvec::SmallVec;
+
+enum_property! {
+  /// A value for the [container-type](https://drafts.csswg.org/css-contain-3/#container-type) property.
+  /// Establishes the element as a query container for the purpose of container queries.
+  pub enum ContainerType {
+    /// The element is not a query container for any container size queries,
+    /// but remains a query container for container style queries.
+    "normal": Normal,
+    /// Establishes a query container for container size queries on the container’s own inline axis.
+    "inline-size": InlineSize,
+    /// Establishes a query container for container size queries on both the inline and block axis.
+    "size": Size,
+  }
+}
+
+impl Default for ContainerType {
+  fn default() -> Self {
+    ContainerType::Normal
+  }
+}
+
+/// A value for the [container-name](https://drafts.csswg.org/css-contain-3/#container-name) property.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+pub enum ContainerNameList<'i> {
+  /// The `none` keyword.
+  None,
+  /// A list of container names.
+  Names(CustomIdentList<'i>),
+}
+
+impl_visit!(ContainerNameList<'i> match { None, Names(names) });
+impl_into_owned!(ContainerNameList<'i> match { None, Names(c) });
+
+impl<'i> Default for ContainerNameList<'i> {
+  fn default() -> Self {
+    ContainerNameList::None
+  }
+}
+
+impl<'i> Parse<'i> for ContainerNameList<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
+      return Ok(ContainerNameList::None);
+    }
+
+    let mut names = SmallVec::new();
+    while let Ok(name) = input.try_parse(parse_container_name) {
+      names.push(name);
+    }
+
+    if names.is_empty() {
+      return Err(input.new_error_for_next_token());
+    }
+
+    Ok(ContainerNameList::Names(names))
+  }
+}
+
+/// Parses a [`<container-name>`](https://drafts.csswg.org/css-contain-3/#typedef-container-name),
+/// which is a `<custom-ident>` excluding the keywords `none`, `and`, `not`, and `or`.
+pub(crate) fn parse_container_name<'i, 't>(
+  input: &mut Parser<'i, 't>,
+) -> Result<CustomIdent<'i>, ParseError<'i, ParserError<'i>>> {
+  let location = input.current_source_location();
+  let ident = CustomIdent::parse(input)?;
+  match_ignore_ascii_case! { &*ident.0,
+    "none" | "and" | "not" | "or" => Err(location.new_custom_error(ParserError::InvalidValue)),
+    _ => Ok(ident)
+  }
+}
+
+impl<'i> ToCss for ContainerNameList<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match self {
+      ContainerNameList::None => dest.write_str("none"),
+      ContainerNameList::Names(names) => {
+        let mut first = true;
+        for name in names {
+          if first {
+            first = false;
+          } else {
+            dest.write_char(' ')?;
+          }
+          name.to_css(dest)?;
+        }
+        Ok(())
+      }
+    }
+  }
+}
+
+/// A value for the [container](https://drafts.csswg.org/css-contain-3/#container-shorthand) shorthand property.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct Container<'i> {
+  /// The container name.
+  pub name: ContainerNameList<'i>,
+  /// The container type.
+  pub container_type: ContainerType,
+}
+
+impl_visit!(Container<'i> { name, container_type: _ });
+impl_into_owned!(Container<'i> { name, container_type });
+
+impl<'i> Parse<'i> for Container<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let name = ContainerNameList::parse(input)?;
+    let container_type = if input.try_parse(|input| input.expect_delim('/')).is_ok() {
+      ContainerType::parse(input)?
+    } else {
+      ContainerType::default()
+    };
+    Ok(Container { name, container_type })
+  }
+}
+
+impl<'i> ToCss for Container<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    self.name.to_css(dest)?;
+    if self.container_type != ContainerType::default() {
+      dest.delim('/', true)?;
+      self.container_type.to_css(dest)?;
+    }
+    Ok(())
+  }
+}
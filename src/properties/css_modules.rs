@@ -1,6 +1,7 @@
 //! Properties related to CSS modules.
 
 use crate::error::{ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::traits::{Parse, ToCss};
 use crate::values::ident::{CustomIdent, CustomIdentList};
@@ -10,24 +11,55 @@ use smallvec::SmallVec;
 
 /// A value for the [composes](https://github.com/css-modules/css-modules/#dependencies) property from CSS modules.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Composes<'i> {
   /// A list of class names to compose.
   pub names: CustomIdentList<'i>,
   /// Where the class names are composed from.
   pub from: Option<ComposesFrom<'i>>,
   /// The source location of the `composes` property.
+  #[cfg_attr(feature = "serde", serde(with = "crate::serialization::SourceLocationDef"))]
   pub loc: SourceLocation,
 }
 
+impl_visit!(Composes<'i> { names, from: _, loc: _ });
+impl_into_owned!(Composes<'i> { names, from, loc });
+
 /// Defines where the class names referenced in the `composes` property are located.
 ///
 /// See [Composes](Composes).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum ComposesFrom<'i> {
   /// The class name is global.
   Global,
   /// The class name comes from the specified file.
   File(CowArcStr<'i>),
+  /// The class name comes from the file with the given source index within a bundle.
+  /// The bundler resolves file references to this when bundling CSS modules.
+  SourceIndex(u32),
+}
+
+impl_visit!(ComposesFrom<'i>);
+impl_into_owned!(ComposesFrom<'i> match { Global, File(c), SourceIndex(i) });
+
+impl<'i> Parse<'i> for ComposesFrom<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if let Ok(file) = input.try_parse(|input| input.expect_string_cloned()) {
+      Ok(ComposesFrom::File(file.into()))
+    } else {
+      input.expect_ident_matching("global")?;
+      Ok(ComposesFrom::Global)
+    }
+  }
 }
 
 impl<'i> Parse<'i> for Composes<'i> {
@@ -43,12 +75,7 @@ impl<'i> Parse<'i> for Composes<'i> {
     }
 
     let from = if input.try_parse(|input| input.expect_ident_matching("from")).is_ok() {
-      if let Ok(file) = input.try_parse(|input| input.expect_string_cloned()) {
-        Some(ComposesFrom::File(file.into()))
-      } else {
-        input.expect_ident_matching("global")?;
-        Some(ComposesFrom::Global)
-      }
+      Some(ComposesFrom::parse(input)?)
     } else {
       None
     };
@@ -83,12 +110,14 @@ impl ToCss for Composes<'_> {
       name.to_css(dest)?;
     }
 
-    if let Some(from) = &self.from {
-      dest.write_str(" from ")?;
-      match from {
-        ComposesFrom::Global => dest.write_str("global")?,
-        ComposesFrom::File(file) => serialize_string(&file, dest)?,
+    match &self.from {
+      Some(ComposesFrom::Global) => dest.write_str(" from global")?,
+      Some(ComposesFrom::File(file)) => {
+        dest.write_str(" from ")?;
+        serialize_string(&file, dest)?;
       }
+      // Files resolved by the bundler are already included in the bundle.
+      Some(ComposesFrom::SourceIndex(_)) | None => {}
     }
 
     Ok(())
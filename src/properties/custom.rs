@@ -1,6 +1,8 @@
 //! CSS custom properties and unparsed token values.
 
 use crate::error::{ParserError, PrinterError, PrinterErrorKind};
+use crate::macros::{impl_into_owned, impl_visit};
+use crate::parser::ParserOptions;
 use crate::prefixes::Feature;
 use crate::printer::Printer;
 use crate::properties::PropertyId;
@@ -8,6 +10,7 @@ use crate::rules::supports::SupportsCondition;
 use crate::targets::Browsers;
 use crate::traits::{Parse, ToCss};
 use crate::values::color::{ColorFallbackKind, CssColor};
+use crate::values::ident::DashedIdentReference;
 use crate::values::length::serialize_dimension;
 use crate::values::string::CowArcStr;
 use crate::values::url::Url;
@@ -16,6 +19,11 @@ use cssparser::*;
 
 /// A CSS custom property, representing any unknown property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct CustomProperty<'i> {
   /// The name of the property.
   pub name: CowArcStr<'i>,
@@ -23,13 +31,17 @@ pub struct CustomProperty<'i> {
   pub value: TokenList<'i>,
 }
 
+impl_visit!(CustomProperty<'i> { name: _, value });
+impl_into_owned!(CustomProperty<'i> { name, value });
+
 impl<'i> CustomProperty<'i> {
   /// Parses a custom property with the given name.
   pub fn parse<'t>(
     name: CowRcStr<'i>,
     input: &mut Parser<'i, 't>,
+    options: &ParserOptions,
   ) -> Result<Self, ParseError<'i, ParserError<'i>>> {
-    let value = TokenList::parse(input)?;
+    let value = TokenList::parse(input, options)?;
     Ok(CustomProperty {
       name: name.into(),
       value,
@@ -43,6 +55,11 @@ impl<'i> CustomProperty<'i> {
 /// be parsed, e.g. in the case css `var()` references are encountered.
 /// In this case, the raw tokens are stored instead.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct UnparsedProperty<'i> {
   /// The id of the property.
   pub property_id: PropertyId<'i>,
@@ -50,13 +67,17 @@ pub struct UnparsedProperty<'i> {
   pub value: TokenList<'i>,
 }
 
+impl_visit!(UnparsedProperty<'i> { property_id: _, value });
+impl_into_owned!(UnparsedProperty<'i> { property_id, value });
+
 impl<'i> UnparsedProperty<'i> {
   /// Parses a property with the given id as a token list.
   pub fn parse<'t>(
     property_id: PropertyId<'i>,
     input: &mut Parser<'i, 't>,
+    options: &ParserOptions,
   ) -> Result<Self, ParseError<'i, ParserError<'i>>> {
-    let value = TokenList::parse(input)?;
+    let value = TokenList::parse(input, options)?;
     Ok(UnparsedProperty { property_id, value })
   }
 
@@ -81,10 +102,19 @@ impl<'i> UnparsedProperty<'i> {
 
 /// A raw list of CSS tokens, with embedded parsed values.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TokenList<'i>(pub Vec<TokenOrValue<'i>>);
 
+impl_visit!(TokenList<'i> { 0 });
+impl_into_owned!(TokenList<'i> { 0 });
+
 /// A raw CSS token, or a parsed value.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum TokenOrValue<'i> {
   /// A token.
   Token(Token<'i>),
@@ -92,8 +122,13 @@ pub enum TokenOrValue<'i> {
   Color(CssColor),
   /// A parsed CSS url.
   Url(Url<'i>),
+  /// A CSS variable reference.
+  Var(Variable<'i>),
 }
 
+impl_visit!(TokenOrValue<'i> match { Token(_), Color(c), Url(u), Var(v) });
+impl_into_owned!(TokenOrValue<'i> match { Token(t), Color(c), Url(u), Var(v) });
+
 impl<'i> From<Token<'i>> for TokenOrValue<'i> {
   fn from(token: Token<'i>) -> TokenOrValue<'i> {
     TokenOrValue::Token(token)
@@ -108,31 +143,46 @@ impl<'i> TokenOrValue<'i> {
 }
 
 impl<'i> TokenList<'i> {
-  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+  fn parse<'t>(
+    input: &mut Parser<'i, 't>,
+    options: &ParserOptions,
+  ) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     input.parse_until_before(Delimiter::Bang | Delimiter::Semicolon, |input| {
-      let mut tokens = vec![];
-      TokenList::parse_into(input, &mut tokens)?;
-
-      // Slice off leading and trailing whitespace if there are at least two tokens.
-      // If there is only one token, we must preserve it. e.g. `--foo: ;` is valid.
-      if tokens.len() >= 2 {
-        let mut slice = &tokens[..];
-        if matches!(tokens.first(), Some(token) if token.is_whitespace()) {
-          slice = &slice[1..];
-        }
-        if matches!(tokens.last(), Some(token) if token.is_whitespace()) {
-          slice = &slice[..slice.len() - 1];
-        }
-        return Ok(TokenList(slice.to_vec()));
+      TokenList::parse_raw(input, options, false)
+    })
+  }
+
+  /// Parses all remaining tokens in the input, e.g. a `var()` fallback, or the prelude or block of an
+  /// unknown at-rule. Within unknown at-rules, whitespace is only removed around math operators and commas.
+  pub(crate) fn parse_raw<'t>(
+    input: &mut Parser<'i, 't>,
+    options: &ParserOptions,
+    is_unknown_at_rule: bool,
+  ) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let mut tokens = vec![];
+    TokenList::parse_into(input, &mut tokens, options, is_unknown_at_rule)?;
+
+    // Slice off leading and trailing whitespace if there are at least two tokens.
+    // If there is only one token, we must preserve it. e.g. `--foo: ;` is valid.
+    if tokens.len() >= 2 {
+      let mut slice = &tokens[..];
+      if matches!(tokens.first(), Some(token) if token.is_whitespace()) {
+        slice = &slice[1..];
+      }
+      if matches!(tokens.last(), Some(token) if token.is_whitespace()) {
+        slice = &slice[..slice.len() - 1];
       }
+      return Ok(TokenList(slice.to_vec()));
+    }
 
-      return Ok(TokenList(tokens));
-    })
+    return Ok(TokenList(tokens));
   }
 
   fn parse_into<'t>(
     input: &mut Parser<'i, 't>,
     tokens: &mut Vec<TokenOrValue<'i>>,
+    options: &ParserOptions,
+    is_unknown_at_rule: bool,
   ) -> Result<(), ParseError<'i, ParserError<'i>>> {
     let mut last_is_delim = false;
     let mut last_is_whitespace = false;
@@ -143,7 +193,7 @@ impl<'i> TokenList<'i> {
           // Skip whitespace if the last token was a delimeter.
           // Otherwise, replace all whitespace and comments with a single space character.
           if !last_is_delim {
-            tokens.push(Token::WhiteSpace(" ").into());
+            tokens.push(Token::WhiteSpace(" ".into()).into());
             last_is_whitespace = true;
           }
         }
@@ -159,9 +209,13 @@ impl<'i> TokenList<'i> {
             tokens.push(TokenOrValue::Url(Url::parse(input)?));
             last_is_delim = false;
             last_is_whitespace = false;
+          } else if let Some(var) = try_parse_var(&f, input, options) {
+            tokens.push(TokenOrValue::Var(var));
+            last_is_delim = true; // Whitespace is not required after the closing parenthesis.
+            last_is_whitespace = false;
           } else {
             tokens.push(Token::Function(f).into());
-            input.parse_nested_block(|input| TokenList::parse_into(input, tokens))?;
+            input.parse_nested_block(|input| TokenList::parse_into(input, tokens, options, is_unknown_at_rule))?;
             tokens.push(Token::CloseParenthesis.into());
             last_is_delim = true; // Whitespace is not required after any of these chars.
             last_is_whitespace = false;
@@ -193,14 +247,20 @@ impl<'i> TokenList<'i> {
             _ => unreachable!(),
           };
 
-          input.parse_nested_block(|input| TokenList::parse_into(input, tokens))?;
+          input.parse_nested_block(|input| TokenList::parse_into(input, tokens, options, is_unknown_at_rule))?;
 
           tokens.push(closing_delimiter.into());
           last_is_delim = true; // Whitespace is not required after any of these chars.
           last_is_whitespace = false;
         }
         Ok(token) => {
-          last_is_delim = matches!(token, cssparser::Token::Delim(_) | cssparser::Token::Comma);
+          // In unknown at-rules, whitespace around other delimeters (e.g. `.` or `&`) may be significant,
+          // so it is preserved.
+          last_is_delim = match token {
+            cssparser::Token::Delim('+' | '-' | '/' | '*') | cssparser::Token::Comma => true,
+            cssparser::Token::Delim(_) => !is_unknown_at_rule,
+            _ => false,
+          };
 
           // If this is a delimeter, and the last token was whitespace,
           // replace the whitespace with the delimeter since both are not required.
@@ -242,8 +302,44 @@ fn try_parse_color_token<'i, 't>(
   None
 }
 
+#[inline]
+fn try_parse_var<'i, 't>(
+  f: &CowArcStr<'i>,
+  input: &mut Parser<'i, 't>,
+  options: &ParserOptions,
+) -> Option<Variable<'i>> {
+  if !f.eq_ignore_ascii_case("var") {
+    return None;
+  }
+
+  input
+    .try_parse(|input| input.parse_nested_block(|input| Variable::parse(input, options)))
+    .ok()
+}
+
 impl<'i> TokenList<'i> {
   pub(crate) fn to_css<W>(&self, dest: &mut Printer<W>, is_custom_property: bool) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    self.to_css_with(dest, is_custom_property, false)
+  }
+
+  /// Serializes the prelude or block of an unknown at-rule, in which the whitespace
+  /// around delimeters was preserved while parsing.
+  pub(crate) fn to_css_unknown_at_rule<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    self.to_css_with(dest, false, true)
+  }
+
+  fn to_css_with<W>(
+    &self,
+    dest: &mut Printer<W>,
+    is_custom_property: bool,
+    is_unknown_at_rule: bool,
+  ) -> Result<(), PrinterError>
   where
     W: std::fmt::Write,
   {
@@ -265,52 +361,116 @@ impl<'i> TokenList<'i> {
           }
           url.to_css(dest)?
         }
-        TokenOrValue::Token(token) => {
-          match token {
-            Token::Delim(d) => {
-              if *d == '+' || *d == '-' {
-                dest.write_char(' ')?;
-                dest.write_char(*d)?;
-                dest.write_char(' ')?;
-              } else {
-                let ws_before = *d == '/' || *d == '*';
-                dest.delim(*d, ws_before)?;
-              }
-            }
-            Token::Comma => {
-              dest.delim(',', false)?;
-            }
-            Token::CloseParenthesis | Token::CloseSquareBracket | Token::CloseCurlyBracket => {
-              token.to_css(dest)?;
-              if !dest.minify
-                && i != self.0.len() - 1
-                && !matches!(self.0[i + 1], TokenOrValue::Token(Token::Comma))
-              {
-                // Whitespace is removed during parsing, so add it back if we aren't minifying.
-                dest.write_char(' ')?;
-              }
-            }
-            Token::Dimension { value, unit, .. } => {
-              serialize_dimension(*value, unit, dest)?;
-            }
-            Token::Number { value, .. } => {
-              value.to_css(dest)?;
-            }
-            _ => {
-              token.to_css(dest)?;
+        TokenOrValue::Var(var) => {
+          var.to_css(dest, is_custom_property)?;
+          self.write_whitespace_if_needed(i, dest)?;
+        }
+        TokenOrValue::Token(token) => match token {
+          Token::Delim(d) => {
+            if *d == '+' || *d == '-' {
+              dest.write_char(' ')?;
+              dest.write_char(*d)?;
+              dest.write_char(' ')?;
+            } else if is_unknown_at_rule && *d != '/' && *d != '*' {
+              dest.write_char(*d)?;
+            } else {
+              let ws_before = *d == '/' || *d == '*';
+              dest.delim(*d, ws_before)?;
             }
           }
-        }
+          Token::Comma => {
+            dest.delim(',', false)?;
+          }
+          Token::CloseParenthesis | Token::CloseSquareBracket | Token::CloseCurlyBracket => {
+            token.to_css(dest)?;
+            self.write_whitespace_if_needed(i, dest)?;
+          }
+          Token::Dimension { value, unit, .. } => {
+            serialize_dimension(*value, unit, dest)?;
+          }
+          Token::Number { value, .. } => {
+            value.to_css(dest)?;
+          }
+          _ => {
+            token.to_css(dest)?;
+          }
+        },
       }
     }
 
     Ok(())
   }
+
+  #[inline]
+  fn write_whitespace_if_needed<W>(&self, i: usize, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    if !dest.minify && i != self.0.len() - 1 && !matches!(self.0[i + 1], TokenOrValue::Token(Token::Comma)) {
+      // Whitespace is removed during parsing, so add it back if we aren't minifying.
+      dest.write_char(' ')?;
+    }
+
+    Ok(())
+  }
+}
+
+/// A CSS [variable](https://drafts.csswg.org/css-variables/#using-variables) reference, i.e. `var()`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct Variable<'i> {
+  /// The variable name.
+  pub name: DashedIdentReference<'i>,
+  /// A fallback value in case the variable is not defined.
+  pub fallback: Option<TokenList<'i>>,
+}
+
+impl_visit!(Variable<'i> { name, fallback });
+impl_into_owned!(Variable<'i> { name, fallback });
+
+impl<'i> Variable<'i> {
+  fn parse<'t>(
+    input: &mut Parser<'i, 't>,
+    options: &ParserOptions,
+  ) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let name = DashedIdentReference::parse_with_options(input, options)?;
+
+    let fallback = if input.try_parse(|input| input.expect_comma()).is_ok() {
+      input.skip_whitespace();
+      Some(TokenList::parse_raw(input, options, false)?)
+    } else {
+      None
+    };
+
+    Ok(Variable { name, fallback })
+  }
+
+  fn to_css<W>(&self, dest: &mut Printer<W>, is_custom_property: bool) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    dest.write_str("var(")?;
+    self.name.to_css(dest)?;
+    if let Some(fallback) = &self.fallback {
+      dest.delim(',', false)?;
+      fallback.to_css(dest, is_custom_property)?;
+    }
+    dest.write_char(')')
+  }
 }
 
 /// A raw CSS token.
 // Copied from cssparser to change CowRcStr to CowArcStr
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Token<'a> {
   /// A [`<ident-token>`](https://drafts.csswg.org/css-syntax/#ident-token-diagram)
   Ident(CowArcStr<'a>),
@@ -328,6 +488,7 @@ pub enum Token<'a> {
   /// A [`<hash-token>`](https://drafts.csswg.org/css-syntax/#hash-token-diagram) with the type flag set to "id"
   ///
   /// The value does not include the `#` marker.
+  #[cfg_attr(feature = "serde", serde(rename = "id-hash"))]
   IDHash(CowArcStr<'a>), // Hash that is a valid ID selector.
 
   /// A [`<string-token>`](https://drafts.csswg.org/css-syntax/#string-token-diagram)
@@ -345,6 +506,7 @@ pub enum Token<'a> {
   Delim(char),
 
   /// A [`<number-token>`](https://drafts.csswg.org/css-syntax/#number-token-diagram)
+  #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
   Number {
     /// Whether the number had a `+` or `-` sign.
     ///
@@ -359,6 +521,7 @@ pub enum Token<'a> {
   },
 
   /// A [`<percentage-token>`](https://drafts.csswg.org/css-syntax/#percentage-token-diagram)
+  #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
   Percentage {
     /// Whether the number had a `+` or `-` sign.
     has_sign: bool,
@@ -372,6 +535,7 @@ pub enum Token<'a> {
   },
 
   /// A [`<dimension-token>`](https://drafts.csswg.org/css-syntax/#dimension-token-diagram)
+  #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
   Dimension {
     /// Whether the number had a `+` or `-` sign.
     ///
@@ -389,15 +553,15 @@ pub enum Token<'a> {
   },
 
   /// A [`<whitespace-token>`](https://drafts.csswg.org/css-syntax/#whitespace-token-diagram)
-  WhiteSpace(&'a str),
+  WhiteSpace(CowArcStr<'a>),
 
   /// A comment.
   ///
   /// The CSS Syntax spec does not generate tokens for comments,
-  /// But we do, because we can (borrowed &str makes it cheap).
+  /// But we do, because we can (a borrowed string makes it cheap).
   ///
   /// The value does not include the `/*` `*/` markers.
-  Comment(&'a str),
+  Comment(CowArcStr<'a>),
 
   /// A `:` `<colon-token>`
   Colon, // :
@@ -424,9 +588,11 @@ pub enum Token<'a> {
   SubstringMatch,
 
   /// A `<!--` [`<CDO-token>`](https://drafts.csswg.org/css-syntax/#CDO-token-diagram)
+  #[cfg_attr(feature = "serde", serde(rename = "cdo"))]
   CDO,
 
   /// A `-->` [`<CDC-token>`](https://drafts.csswg.org/css-syntax/#CDC-token-diagram)
+  #[cfg_attr(feature = "serde", serde(rename = "cdc"))]
   CDC,
 
   /// A [`<function-token>`](https://drafts.csswg.org/css-syntax/#function-token-diagram)
@@ -472,6 +638,40 @@ pub enum Token<'a> {
   CloseCurlyBracket,
 }
 
+impl_into_owned!(Token<'i> match {
+  Ident(v),
+  AtKeyword(v),
+  Hash(v),
+  IDHash(v),
+  QuotedString(v),
+  UnquotedUrl(v),
+  Delim(c),
+  Number { has_sign, value, int_value },
+  Percentage { has_sign, unit_value, int_value },
+  Dimension { has_sign, value, int_value, unit },
+  WhiteSpace(v),
+  Comment(v),
+  Colon,
+  Semicolon,
+  Comma,
+  IncludeMatch,
+  DashMatch,
+  PrefixMatch,
+  SuffixMatch,
+  SubstringMatch,
+  CDO,
+  CDC,
+  Function(v),
+  ParenthesisBlock,
+  SquareBracketBlock,
+  CurlyBracketBlock,
+  BadUrl(v),
+  BadString(v),
+  CloseParenthesis,
+  CloseSquareBracket,
+  CloseCurlyBracket,
+});
+
 impl<'a> From<&cssparser::Token<'a>> for Token<'a> {
   #[inline]
   fn from(t: &cssparser::Token<'a>) -> Token<'a> {
@@ -515,8 +715,8 @@ impl<'a> From<&cssparser::Token<'a>> for Token<'a> {
         unit_value: *unit_value,
         int_value: *int_value,
       },
-      cssparser::Token::WhiteSpace(w) => Token::WhiteSpace(w),
-      cssparser::Token::Comment(c) => Token::Comment(c),
+      cssparser::Token::WhiteSpace(w) => Token::WhiteSpace((*w).into()),
+      cssparser::Token::Comment(c) => Token::Comment((*c).into()),
       cssparser::Token::Colon => Token::Colon,
       cssparser::Token::Semicolon => Token::Semicolon,
       cssparser::Token::Comma => Token::Comma,
@@ -587,8 +787,8 @@ impl<'a> ToCss for Token<'a> {
         int_value: *int_value,
       }
       .to_css(dest)?,
-      Token::WhiteSpace(w) => cssparser::Token::WhiteSpace(w).to_css(dest)?,
-      Token::Comment(c) => cssparser::Token::Comment(c).to_css(dest)?,
+      Token::WhiteSpace(w) => cssparser::Token::WhiteSpace(w.as_ref()).to_css(dest)?,
+      Token::Comment(c) => cssparser::Token::Comment(c.as_ref()).to_css(dest)?,
       Token::Colon => cssparser::Token::Colon.to_css(dest)?,
       Token::Semicolon => cssparser::Token::Semicolon.to_css(dest)?,
       Token::Comma => cssparser::Token::Comma.to_css(dest)?,
@@ -615,8 +815,13 @@ impl<'i> TokenList<'i> {
   pub(crate) fn get_necessary_fallbacks(&self, targets: Browsers) -> ColorFallbackKind {
     let mut fallbacks = ColorFallbackKind::empty();
     for token in &self.0 {
-      if let TokenOrValue::Color(color) = token {
-        fallbacks |= color.get_possible_fallbacks(targets);
+      match token {
+        TokenOrValue::Color(color) => fallbacks |= color.get_possible_fallbacks(targets),
+        TokenOrValue::Var(Variable {
+          fallback: Some(fallback),
+          ..
+        }) => fallbacks |= fallback.get_necessary_fallbacks(targets),
+        _ => {}
       }
     }
 
@@ -629,6 +834,10 @@ impl<'i> TokenList<'i> {
       .iter()
       .map(|token| match token {
         TokenOrValue::Color(color) => TokenOrValue::Color(color.get_fallback(kind)),
+        TokenOrValue::Var(var) => TokenOrValue::Var(Variable {
+          name: var.name.clone(),
+          fallback: var.fallback.as_ref().map(|fallback| fallback.get_fallback(kind)),
+        }),
         _ => token.clone(),
       })
       .collect();
@@ -658,11 +867,7 @@ impl<'i> TokenList<'i> {
     }
 
     if !lowest_fallback.is_empty() {
-      for token in self.0.iter_mut() {
-        if let TokenOrValue::Color(color) = token {
-          *color = color.get_fallback(lowest_fallback);
-        }
-      }
+      *self = self.get_fallback(lowest_fallback);
     }
 
     res
@@ -5,7 +5,7 @@ use super::{Property, PropertyId};
 use crate::context::PropertyHandlerContext;
 use crate::declaration::DeclarationList;
 use crate::error::{ParserError, PrinterError};
-use crate::macros::enum_property;
+use crate::macros::{enum_property, impl_into_owned, impl_visit};
 use crate::prefixes::{is_flex_2009, Feature};
 use crate::printer::Printer;
 use crate::targets::Browsers;
@@ -25,6 +25,11 @@ enum_property! {
 
 /// A [`<display-inside>`](https://drafts.csswg.org/css-display-3/#typedef-display-inside) value.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 #[allow(missing_docs)]
 pub enum DisplayInside {
   Flow,
@@ -36,6 +41,9 @@ pub enum DisplayInside {
   Ruby,
 }
 
+impl_visit!(DisplayInside);
+impl_into_owned!(DisplayInside);
+
 impl<'i> Parse<'i> for DisplayInside {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let location = input.current_source_location();
@@ -101,6 +109,11 @@ impl DisplayInside {
 ///
 /// See [Display](Display).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct DisplayPair {
   /// The outside display value.
   pub outside: DisplayOutside,
@@ -110,6 +123,9 @@ pub struct DisplayPair {
   pub is_list_item: bool,
 }
 
+impl_visit!(DisplayPair);
+impl_into_owned!(DisplayPair);
+
 impl<'i> Parse<'i> for DisplayPair {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let mut list_item = false;
@@ -313,6 +329,11 @@ enum_property! {
 
 /// A value for the [display](https://drafts.csswg.org/css-display-3/#the-display-properties) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Display {
   /// A display keyword.
   Keyword(DisplayKeyword),
@@ -320,6 +341,9 @@ pub enum Display {
   Pair(DisplayPair),
 }
 
+impl_visit!(Display);
+impl_into_owned!(Display);
+
 impl<'i> Parse<'i> for Display {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if let Ok(pair) = input.try_parse(DisplayPair::parse) {
@@ -1,6 +1,7 @@
 //! CSS properties related to filters and effects.
 
 use crate::error::{ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::targets::Browsers;
 use crate::traits::{FallbackValues, Parse, ToCss};
@@ -11,6 +12,11 @@ use smallvec::SmallVec;
 
 /// A [filter](https://drafts.fxtf.org/filter-effects-1/#filter-functions) function.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Filter<'i> {
   /// A `blur()` filter.
   Blur(Length),
@@ -36,6 +42,33 @@ pub enum Filter<'i> {
   Url(Url<'i>),
 }
 
+impl_visit!(Filter<'i> match {
+  Blur(l),
+  Brightness(_),
+  Contrast(_),
+  Grayscale(_),
+  HueRotate(_),
+  Invert(_),
+  Opacity(_),
+  Saturate(_),
+  Sepia(_),
+  DropShadow(d),
+  Url(u),
+});
+impl_into_owned!(Filter<'i> match {
+  Blur(l),
+  Brightness(n),
+  Contrast(n),
+  Grayscale(n),
+  HueRotate(a),
+  Invert(n),
+  Opacity(n),
+  Saturate(n),
+  Sepia(n),
+  DropShadow(d),
+  Url(u),
+});
+
 impl<'i> Parse<'i> for Filter<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if let Ok(url) = input.try_parse(Url::parse) {
@@ -192,6 +225,11 @@ impl<'i> Filter<'i> {
 
 /// A [`drop-shadow()`](https://drafts.fxtf.org/filter-effects-1/#funcdef-filter-drop-shadow) filter function.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct DropShadow {
   /// The color of the drop shadow.
   pub color: CssColor,
@@ -203,6 +241,14 @@ pub struct DropShadow {
   pub blur: Length,
 }
 
+impl_visit!(DropShadow {
+  color,
+  x_offset,
+  y_offset,
+  blur
+});
+impl_into_owned!(DropShadow);
+
 impl<'i> Parse<'i> for DropShadow {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let mut color = None;
@@ -278,6 +324,11 @@ impl DropShadow {
 /// A value for the [filter](https://drafts.fxtf.org/filter-effects-1/#FilterProperty) and
 /// [backdrop-filter](https://drafts.fxtf.org/filter-effects-2/#BackdropFilterProperty) properties.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum FilterList<'i> {
   /// The `none` keyword.
   None,
@@ -285,6 +336,9 @@ pub enum FilterList<'i> {
   Filters(SmallVec<[Filter<'i>; 1]>),
 }
 
+impl_visit!(FilterList<'i> match { None, Filters(f) });
+impl_into_owned!(FilterList<'i> match { None, Filters(s) });
+
 impl<'i> Parse<'i> for FilterList<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
@@ -66,6 +66,11 @@ impl FromStandard<FlexWrap> for FlexWrap {
 
 /// A value for the [flex-flow](https://www.w3.org/TR/2018/CR-css-flexbox-1-20181119/#flex-flow-property) shorthand property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct FlexFlow {
   /// The direction that flex items flow.
   pub direction: FlexDirection,
@@ -73,6 +78,9 @@ pub struct FlexFlow {
   pub wrap: FlexWrap,
 }
 
+impl_visit!(FlexFlow);
+impl_into_owned!(FlexFlow);
+
 impl<'i> Parse<'i> for FlexFlow {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let mut direction = None;
@@ -124,6 +132,11 @@ impl ToCss for FlexFlow {
 
 /// A value for the [flex](https://www.w3.org/TR/2018/CR-css-flexbox-1-20181119/#flex-property) shorthand property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Flex {
   /// The flex grow factor.
   pub grow: CSSNumber,
@@ -133,6 +146,13 @@ pub struct Flex {
   pub basis: LengthPercentageOrAuto,
 }
 
+impl_visit!(Flex {
+  grow: _,
+  shrink: _,
+  basis
+});
+impl_into_owned!(Flex);
+
 impl<'i> Parse<'i> for Flex {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
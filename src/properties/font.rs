@@ -14,6 +14,11 @@ use cssparser::*;
 
 /// A value for the [font-weight](https://www.w3.org/TR/css-fonts-4/#font-weight-prop) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum FontWeight {
   /// An absolute font weight.
   Absolute(AbsoluteFontWeight),
@@ -23,6 +28,9 @@ pub enum FontWeight {
   Lighter,
 }
 
+impl_visit!(FontWeight);
+impl_into_owned!(FontWeight);
+
 impl Default for FontWeight {
   fn default() -> FontWeight {
     FontWeight::Absolute(AbsoluteFontWeight::default())
@@ -66,6 +74,11 @@ impl ToCss for FontWeight {
 ///
 /// See [FontWeight](FontWeight).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum AbsoluteFontWeight {
   /// An explicit weight.
   Weight(CSSNumber),
@@ -75,6 +88,9 @@ pub enum AbsoluteFontWeight {
   Bold,
 }
 
+impl_visit!(AbsoluteFontWeight);
+impl_into_owned!(AbsoluteFontWeight);
+
 impl Default for AbsoluteFontWeight {
   fn default() -> AbsoluteFontWeight {
     AbsoluteFontWeight::Normal
@@ -144,6 +160,11 @@ enum_property! {
 
 /// A value for the [font-size](https://www.w3.org/TR/css-fonts-4/#font-size-prop) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum FontSize {
   /// An explicit size.
   Length(LengthPercentage),
@@ -153,6 +174,9 @@ pub enum FontSize {
   Relative(RelativeFontSize),
 }
 
+impl_visit!(FontSize match { Length(l), Absolute(_), Relative(_) });
+impl_into_owned!(FontSize);
+
 impl<'i> Parse<'i> for FontSize {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if let Ok(val) = input.try_parse(LengthPercentage::parse) {
@@ -235,6 +259,11 @@ impl Into<Percentage> for &FontStretchKeyword {
 
 /// A value for the [font-stretch](https://www.w3.org/TR/css-fonts-4/#font-stretch-prop) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum FontStretch {
   /// A font stretch keyword.
   Keyword(FontStretchKeyword),
@@ -242,6 +271,9 @@ pub enum FontStretch {
   Percentage(Percentage),
 }
 
+impl_visit!(FontStretch);
+impl_into_owned!(FontStretch);
+
 impl Default for FontStretch {
   fn default() -> FontStretch {
     FontStretch::Keyword(FontStretchKeyword::default())
@@ -325,6 +357,11 @@ enum_property! {
 
 /// A value for the [font-family](https://www.w3.org/TR/css-fonts-4/#font-family-prop) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum FontFamily<'i> {
   /// A custom family name.
   FamilyName(CowArcStr<'i>),
@@ -332,6 +369,9 @@ pub enum FontFamily<'i> {
   Generic(GenericFontFamily),
 }
 
+impl_visit!(FontFamily<'i>);
+impl_into_owned!(FontFamily<'i> match { FamilyName(c), Generic(g) });
+
 impl<'i> Parse<'i> for FontFamily<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if let Ok(value) = input.try_parse(|i| i.expect_string_cloned()) {
@@ -400,6 +440,11 @@ impl<'i> ToCss for FontFamily<'i> {
 
 /// A value for the [font-style](https://www.w3.org/TR/css-fonts-4/#font-style-prop) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum FontStyle {
   /// Normal font style.
   Normal,
@@ -409,6 +454,9 @@ pub enum FontStyle {
   Oblique(Angle),
 }
 
+impl_visit!(FontStyle);
+impl_into_owned!(FontStyle);
+
 impl Default for FontStyle {
   fn default() -> FontStyle {
     FontStyle::Normal
@@ -512,6 +560,11 @@ impl FontVariantCapsCSS2 {
 
 /// A value for the [line-height](https://www.w3.org/TR/2020/WD-css-inline-3-20200827/#propdef-line-height) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum LineHeight {
   /// The UA sets the line height based on the font.
   Normal,
@@ -521,6 +574,9 @@ pub enum LineHeight {
   Length(LengthPercentage),
 }
 
+impl_visit!(LineHeight match { Normal, Number(_), Length(l) });
+impl_into_owned!(LineHeight);
+
 impl Default for LineHeight {
   fn default() -> LineHeight {
     LineHeight::Normal
@@ -579,6 +635,11 @@ enum_property! {
 /// A value for the [vertical align](https://drafts.csswg.org/css2/#propdef-vertical-align) property.
 // TODO: there is a more extensive spec in CSS3 but it doesn't seem any browser implements it? https://www.w3.org/TR/css-inline-3/#transverse-alignment
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum VerticalAlign {
   /// A vertical align keyword.
   Keyword(VerticalAlignKeyword),
@@ -586,6 +647,9 @@ pub enum VerticalAlign {
   Length(LengthPercentage),
 }
 
+impl_visit!(VerticalAlign match { Keyword(_), Length(l) });
+impl_into_owned!(VerticalAlign);
+
 impl<'i> Parse<'i> for VerticalAlign {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if let Ok(len) = input.try_parse(LengthPercentage::parse) {
@@ -611,6 +675,11 @@ impl ToCss for VerticalAlign {
 
 /// A value for the [font](https://www.w3.org/TR/css-fonts-4/#font-prop) shorthand property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Font<'i> {
   /// The font family.
   pub family: Vec<FontFamily<'i>>,
@@ -628,6 +697,9 @@ pub struct Font<'i> {
   pub variant_caps: FontVariantCapsCSS2,
 }
 
+impl_visit!(Font<'i> { family: _, size, style: _, weight: _, stretch: _, line_height, variant_caps: _ });
+impl_into_owned!(Font<'i> { family, size, style, weight, stretch, line_height, variant_caps });
+
 impl<'i> Parse<'i> for Font<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let mut style = None;
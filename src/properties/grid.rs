@@ -5,6 +5,7 @@
 use crate::context::PropertyHandlerContext;
 use crate::declaration::DeclarationList;
 use crate::error::{ParserError, PrinterError};
+use crate::macros::{impl_bitflags_serde, impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::properties::{Property, PropertyId};
 use crate::traits::{Parse, PropertyHandler, ToCss};
@@ -19,6 +20,11 @@ use smallvec::SmallVec;
 /// A [track sizing](https://drafts.csswg.org/css-grid-2/#track-sizing) value
 /// for the `grid-template-rows` and `grid-template-columns` properties.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum TrackSizing<'i> {
   /// No explicit grid tracks.
   None,
@@ -26,11 +32,19 @@ pub enum TrackSizing<'i> {
   TrackList(TrackList<'i>),
 }
 
+impl_visit!(TrackSizing<'i> match { None, TrackList(t) });
+impl_into_owned!(TrackSizing<'i> match { None, TrackList(t) });
+
 /// A [`<track-list>`](https://drafts.csswg.org/css-grid-2/#typedef-track-list) value,
 /// as used in the `grid-template-rows` and `grid-template-columns` properties.
 ///
 /// See [TrackSizing](TrackSizing).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct TrackList<'i> {
   /// A list of line names.
   pub line_names: Vec<CustomIdentList<'i>>,
@@ -38,10 +52,18 @@ pub struct TrackList<'i> {
   pub items: Vec<TrackListItem<'i>>,
 }
 
+impl_visit!(TrackList<'i> { line_names, items });
+impl_into_owned!(TrackList<'i> { line_names, items });
+
 /// Either a track size or `repeat()` function.
 ///
 /// See [TrackList](TrackList).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum TrackListItem<'i> {
   /// A track size.
   TrackSize(TrackSize),
@@ -49,11 +71,19 @@ pub enum TrackListItem<'i> {
   TrackRepeat(TrackRepeat<'i>),
 }
 
+impl_visit!(TrackListItem<'i> match { TrackSize(s), TrackRepeat(r) });
+impl_into_owned!(TrackListItem<'i> match { TrackSize(t), TrackRepeat(t) });
+
 /// A [`<track-size>`](https://drafts.csswg.org/css-grid-2/#typedef-track-size) value,
 /// as used in the `grid-template-rows` and `grid-template-columns` properties.
 ///
 /// See [TrackListItem](TrackListItem).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum TrackSize {
   /// An explicit track breadth.
   TrackBreadth(TrackBreadth),
@@ -63,6 +93,9 @@ pub enum TrackSize {
   FitContent(LengthPercentage),
 }
 
+impl_visit!(TrackSize match { TrackBreadth(b), MinMax(min, max), FitContent(l) });
+impl_into_owned!(TrackSize);
+
 impl Default for TrackSize {
   fn default() -> TrackSize {
     TrackSize::TrackBreadth(TrackBreadth::Auto)
@@ -72,12 +105,21 @@ impl Default for TrackSize {
 /// A [track size list](https://drafts.csswg.org/css-grid-2/#auto-tracks), as used
 /// in the `grid-auto-rows` and `grid-auto-columns` properties.
 #[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TrackSizeList(pub SmallVec<[TrackSize; 1]>);
 
+impl_visit!(TrackSizeList { 0 });
+impl_into_owned!(TrackSizeList);
+
 /// A [`<track-breadth>`](https://drafts.csswg.org/css-grid-2/#typedef-track-breadth) value.
 ///
 /// See [TrackSize](TrackSize).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum TrackBreadth {
   /// An explicit length.
   Length(LengthPercentage),
@@ -91,11 +133,19 @@ pub enum TrackBreadth {
   Auto,
 }
 
+impl_visit!(TrackBreadth match { Length(l), Flex(_), MinContent, MaxContent, Auto });
+impl_into_owned!(TrackBreadth);
+
 /// A [`<track-repeat>`](https://drafts.csswg.org/css-grid-2/#typedef-track-repeat) value,
 /// representing the `repeat()` function in a track list.
 ///
 /// See [TrackListItem](TrackListItem).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct TrackRepeat<'i> {
   /// The repeat count.
   count: RepeatCount,
@@ -105,11 +155,19 @@ pub struct TrackRepeat<'i> {
   track_sizes: Vec<TrackSize>,
 }
 
+impl_visit!(TrackRepeat<'i> { count: _, line_names, track_sizes });
+impl_into_owned!(TrackRepeat<'i> { count, line_names, track_sizes });
+
 /// A [`<repeat-count>`](https://drafts.csswg.org/css-grid-2/#typedef-track-repeat) value,
 /// used in the `repeat()` function.
 ///
 /// See [TrackRepeat](TrackRepeat).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum RepeatCount {
   /// The number of times to repeat.
   Number(CSSInteger),
@@ -119,6 +177,9 @@ pub enum RepeatCount {
   AutoFit,
 }
 
+impl_visit!(RepeatCount);
+impl_into_owned!(RepeatCount);
+
 impl<'i> Parse<'i> for TrackSize {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if let Ok(breadth) = input.try_parse(TrackBreadth::parse) {
@@ -341,11 +402,32 @@ where
     } else {
       dest.write_char(' ')?;
     }
-    name.to_css(dest)?;
+    write_grid_ident(&name.0, dest)?;
   }
   dest.write_char(']')
 }
 
+/// Writes a grid line or area name. Grid lines may implicitly reference an area with a
+/// `-start` or `-end` suffix. If the CSS modules pattern does not end with the local name,
+/// the suffix is written after the compiled name of the area so that these still match.
+fn write_grid_ident<W>(ident: &str, dest: &mut Printer<W>) -> Result<(), PrinterError>
+where
+  W: std::fmt::Write,
+{
+  if let Some(css_module) = &dest.css_module {
+    if !css_module.config.pattern.ends_with_local() {
+      for suffix in ["-start", "-end"] {
+        if let Some(area) = ident.strip_suffix(suffix).filter(|area| !area.is_empty()) {
+          dest.write_ident(area)?;
+          return dest.write_str(suffix);
+        }
+      }
+    }
+  }
+
+  dest.write_ident(ident)
+}
+
 impl<'i> Parse<'i> for TrackList<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let mut line_names = Vec::new();
@@ -483,6 +565,11 @@ impl ToCss for TrackSizeList {
 
 /// A value for the [grid-template-areas](https://drafts.csswg.org/css-grid-2/#grid-template-areas-property) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum GridTemplateAreas {
   /// No named grid areas.
   None,
@@ -496,6 +583,9 @@ pub enum GridTemplateAreas {
   },
 }
 
+impl_visit!(GridTemplateAreas);
+impl_into_owned!(GridTemplateAreas);
+
 impl<'i> Parse<'i> for GridTemplateAreas {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
@@ -625,7 +715,7 @@ impl GridTemplateAreas {
           if i > 0 && (!last_was_null || !dest.minify) {
             dest.write_char(' ')?;
           }
-          dest.write_ident(string)?;
+          write_grid_ident(string, dest)?;
           last_was_null = false;
         } else {
           if i > 0 && (last_was_null || !dest.minify) {
@@ -647,6 +737,11 @@ impl GridTemplateAreas {
 ///
 /// If `areas` is not `None`, then `rows` must also not be `None`.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct GridTemplate<'i> {
   /// The grid template rows.
   pub rows: TrackSizing<'i>,
@@ -656,6 +751,9 @@ pub struct GridTemplate<'i> {
   pub areas: GridTemplateAreas,
 }
 
+impl_visit!(GridTemplate<'i> { rows, columns, areas: _ });
+impl_into_owned!(GridTemplate<'i> { rows, columns, areas });
+
 impl<'i> Parse<'i> for GridTemplate<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
@@ -861,6 +959,14 @@ bitflags! {
   }
 }
 
+impl_visit!(GridAutoFlow);
+impl_into_owned!(GridAutoFlow);
+
+impl_bitflags_serde!(GridAutoFlow {
+  "column": Column,
+  "dense": Dense,
+});
+
 impl Default for GridAutoFlow {
   fn default() -> GridAutoFlow {
     GridAutoFlow::Row
@@ -950,6 +1056,11 @@ impl ToCss for GridAutoFlow {
 ///
 /// Explicit and implicit values may not be combined.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Grid<'i> {
   /// Explicit grid template rows.
   pub rows: TrackSizing<'i>,
@@ -965,6 +1076,9 @@ pub struct Grid<'i> {
   pub auto_flow: GridAutoFlow,
 }
 
+impl_visit!(Grid<'i> { rows, columns, areas: _, auto_rows, auto_columns, auto_flow: _ });
+impl_into_owned!(Grid<'i> { rows, columns, areas, auto_rows, auto_columns, auto_flow });
+
 impl<'i> Parse<'i> for Grid<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     // <'grid-template'>
@@ -1087,6 +1201,11 @@ impl ToCss for Grid<'_> {
 /// A [`<grid-line>`](https://drafts.csswg.org/css-grid-2/#typedef-grid-row-start-grid-line) value,
 /// used in the `grid-row-start`, `grid-row-end`, `grid-column-start`, and `grid-column-end` properties.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum GridLine<'i> {
   /// Automatic placement.
   Auto,
@@ -1098,6 +1217,9 @@ pub enum GridLine<'i> {
   Span(CSSInteger, Option<CustomIdent<'i>>),
 }
 
+impl_visit!(GridLine<'i> match { Auto, Ident(i), Line(n, i), Span(n, i) });
+impl_into_owned!(GridLine<'i> match { Auto, Ident(c), Line(c, o), Span(c, o) });
+
 impl<'i> Parse<'i> for GridLine<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|input| input.expect_ident_matching("auto")).is_ok() {
@@ -1150,12 +1272,12 @@ impl ToCss for GridLine<'_> {
   {
     match self {
       GridLine::Auto => dest.write_str("auto"),
-      GridLine::Ident(id) => id.to_css(dest),
+      GridLine::Ident(id) => write_grid_ident(&id.0, dest),
       GridLine::Line(line_number, id) => {
         line_number.to_css(dest)?;
         if let Some(id) = id {
           dest.write_char(' ')?;
-          id.to_css(dest)?;
+          write_grid_ident(&id.0, dest)?;
         }
         Ok(())
       }
@@ -1169,7 +1291,7 @@ impl ToCss for GridLine<'_> {
         }
 
         if let Some(id) = id {
-          id.to_css(dest)?;
+          write_grid_ident(&id.0, dest)?;
         }
         Ok(())
       }
@@ -1200,6 +1322,11 @@ impl<'i> GridLine<'i> {
 /// A [grid placement](https://drafts.csswg.org/css-grid-2/#placement-shorthands) value for the
 /// `grid-row` and `grid-column` shorthand properties.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct GridPlacement<'i> {
   /// The starting line.
   pub start: GridLine<'i>,
@@ -1207,6 +1334,9 @@ pub struct GridPlacement<'i> {
   pub end: GridLine<'i>,
 }
 
+impl_visit!(GridPlacement<'i> { start, end });
+impl_into_owned!(GridPlacement<'i> { start, end });
+
 impl<'i> Parse<'i> for GridPlacement<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let start = GridLine::parse(input)?;
@@ -1237,6 +1367,11 @@ impl ToCss for GridPlacement<'_> {
 
 /// A value for the [grid-area](https://drafts.csswg.org/css-grid-2/#propdef-grid-area) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct GridArea<'i> {
   /// The grid row start placement.
   pub row_start: GridLine<'i>,
@@ -1248,6 +1383,9 @@ pub struct GridArea<'i> {
   pub column_end: GridLine<'i>,
 }
 
+impl_visit!(GridArea<'i> { row_start, column_start, row_end, column_end });
+impl_into_owned!(GridArea<'i> { row_start, column_start, row_end, column_end });
+
 impl<'i> Parse<'i> for GridArea<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let row_start = GridLine::parse(input)?;
@@ -4,7 +4,7 @@ use super::{Property, PropertyId};
 use crate::context::PropertyHandlerContext;
 use crate::declaration::DeclarationList;
 use crate::error::{ParserError, PrinterError};
-use crate::macros::{enum_property, shorthand_handler, shorthand_property};
+use crate::macros::{enum_property, impl_into_owned, impl_visit, shorthand_handler, shorthand_property};
 use crate::printer::Printer;
 use crate::targets::Browsers;
 use crate::traits::{FallbackValues, Parse, PropertyHandler, ToCss};
@@ -14,6 +14,11 @@ use cssparser::*;
 
 /// A value for the [list-style-type](https://www.w3.org/TR/2020/WD-css-lists-3-20201117/#text-markers) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum ListStyleType<'i> {
   /// No marker.
   None,
@@ -23,6 +28,9 @@ pub enum ListStyleType<'i> {
   CounterStyle(CounterStyle<'i>),
 }
 
+impl_visit!(ListStyleType<'i> match { None, String(_), CounterStyle(c) });
+impl_into_owned!(ListStyleType<'i> match { None, String(c), CounterStyle(c) });
+
 impl Default for ListStyleType<'_> {
   fn default() -> Self {
     ListStyleType::CounterStyle(CounterStyle::Predefined(PredefinedCounterStyle::Disc))
@@ -62,6 +70,11 @@ impl ToCss for ListStyleType<'_> {
 
 /// A [counter-style](https://www.w3.org/TR/css-counter-styles-3/#typedef-counter-style) name.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum CounterStyle<'i> {
   /// A predefined counter style name.
   Predefined(PredefinedCounterStyle),
@@ -71,6 +84,9 @@ pub enum CounterStyle<'i> {
   Symbols(SymbolsType, Vec<Symbol<'i>>),
 }
 
+impl_visit!(CounterStyle<'i> match { Predefined(_), Name(n), Symbols(t, s) });
+impl_into_owned!(CounterStyle<'i> match { Predefined(p), Name(c), Symbols(s, v) });
+
 enum_property! {
   /// A [predefined counter](https://www.w3.org/TR/css-counter-styles-3/#predefined-counters) style.
   #[allow(missing_docs)]
@@ -174,7 +190,7 @@ impl ToCss for CounterStyle<'_> {
       CounterStyle::Predefined(style) => style.to_css(dest),
       CounterStyle::Name(name) => {
         if let Some(css_module) = &mut dest.css_module {
-          css_module.reference(&name.0)
+          css_module.reference(&name.0, dest.loc.source_index)
         }
         name.to_css(dest)
       }
@@ -219,6 +235,11 @@ enum_property! {
 ///
 /// See [CounterStyle](CounterStyle).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Symbol<'i> {
   /// A string.
   String(CowArcStr<'i>),
@@ -226,6 +247,9 @@ pub enum Symbol<'i> {
   Image(Image<'i>),
 }
 
+impl_visit!(Symbol<'i> match { String(_), Image(i) });
+impl_into_owned!(Symbol<'i> match { String(c), Image(i) });
+
 impl<'i> Parse<'i> for Symbol<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if let Ok(img) = input.try_parse(Image::parse) {
@@ -6,7 +6,7 @@ use super::PropertyId;
 use crate::context::PropertyHandlerContext;
 use crate::declaration::DeclarationList;
 use crate::error::{ParserError, PrinterError};
-use crate::macros::enum_property;
+use crate::macros::{enum_property, impl_into_owned, impl_visit};
 use crate::prefixes::Feature;
 use crate::printer::Printer;
 use crate::properties::Property;
@@ -102,6 +102,11 @@ impl Default for GeometryBox {
 
 /// A value for the [mask-clip](https://www.w3.org/TR/css-masking-1/#the-mask-clip) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum MaskClip {
   /// A geometry box.
   GeometryBox(GeometryBox),
@@ -109,6 +114,9 @@ pub enum MaskClip {
   NoClip,
 }
 
+impl_visit!(MaskClip);
+impl_into_owned!(MaskClip);
+
 impl<'i> Parse<'i> for MaskClip {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if let Ok(b) = input.try_parse(GeometryBox::parse) {
@@ -196,6 +204,11 @@ impl From<MaskComposite> for WebKitMaskComposite {
 
 /// A value for the [mask](https://www.w3.org/TR/css-masking-1/#the-mask) shorthand property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Mask<'i> {
   /// The mask image.
   pub image: Image<'i>,
@@ -215,6 +228,9 @@ pub struct Mask<'i> {
   pub mode: MaskMode,
 }
 
+impl_visit!(Mask<'i> { image, position, size, repeat: _, clip: _, origin: _, composite: _, mode: _ });
+impl_into_owned!(Mask<'i> { image, position, size, repeat, clip, origin, composite, mode });
+
 impl<'i> Parse<'i> for Mask<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let mut image: Option<Image> = None;
@@ -365,6 +381,11 @@ impl<'i> ImageFallback<'i> for Mask<'i> {
 
 /// A value for the [clip-path](https://www.w3.org/TR/css-masking-1/#the-clip-path) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum ClipPath<'i> {
   /// No clip path.
   None,
@@ -376,6 +397,9 @@ pub enum ClipPath<'i> {
   Box(GeometryBox),
 }
 
+impl_visit!(ClipPath<'i> match { None, Url(u), Shape(s, b), Box(_) });
+impl_into_owned!(ClipPath<'i> match { None, Url(u), Shape(b, g), Box(g) });
+
 impl<'i> Parse<'i> for ClipPath<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if let Ok(url) = input.try_parse(Url::parse) {
@@ -438,6 +462,11 @@ impl Default for MaskBorderMode {
 
 /// A value for the [mask-border](https://www.w3.org/TR/css-masking-1/#the-mask-border) shorthand property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct MaskBorder<'i> {
   /// The border image shorthand.
   pub border_image: BorderImage<'i>,
@@ -445,6 +474,9 @@ pub struct MaskBorder<'i> {
   pub mode: MaskBorderMode,
 }
 
+impl_visit!(MaskBorder<'i> { border_image, mode: _ });
+impl_into_owned!(MaskBorder<'i> { border_image, mode });
+
 impl<'i> Parse<'i> for MaskBorder<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let mut mode: Option<MaskBorderMode> = None;
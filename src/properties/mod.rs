@@ -96,6 +96,7 @@ pub mod border;
 pub mod border_image;
 pub mod border_radius;
 pub mod box_shadow;
+pub mod contain;
 pub mod css_modules;
 pub mod custom;
 pub mod display;
@@ -119,17 +120,18 @@ pub mod transition;
 pub mod ui;
 
 use crate::error::{ParserError, PrinterError};
+use crate::macros::impl_visit;
 use crate::parser::starts_with_ignore_ascii_case;
 use crate::parser::ParserOptions;
 use crate::prefixes::Feature;
 use crate::printer::{Printer, PrinterOptions};
 use crate::targets::Browsers;
-use crate::traits::{Parse, ToCss};
+use crate::traits::{IntoOwned, Parse, ToCss, Visit, Visitor};
 use crate::values::number::{CSSInteger, CSSNumber};
 use crate::values::string::CowArcStr;
 use crate::values::{
-  alpha::*, color::*, easing::EasingFunction, ident::DashedIdent, image::*, length::*, position::*, rect::*,
-  shape::FillRule, size::Size2D, time::Time,
+  alpha::*, color::*, easing::EasingFunction, ident::DashedIdentReference, image::*, length::*, position::*,
+  rect::*, shape::FillRule, size::Size2D, time::Time,
 };
 use crate::vendor_prefix::VendorPrefix;
 use align::*;
@@ -139,6 +141,7 @@ use border::*;
 use border_image::*;
 use border_radius::*;
 use box_shadow::*;
+use contain::*;
 use css_modules::*;
 use cssparser::*;
 use custom::*;
@@ -169,10 +172,16 @@ macro_rules! define_properties {
   ) => {
     /// A CSS property id.
     #[derive(Debug, Clone, PartialEq)]
+    #[cfg_attr(
+      feature = "serde",
+      derive(serde::Serialize, serde::Deserialize),
+      serde(tag = "property", content = "value", rename_all = "kebab-case")
+    )]
     pub enum PropertyId<'i> {
       $(
         #[doc=concat!("The `", $name, "` property.")]
         $(#[$meta])*
+        #[cfg_attr(feature = "serde", serde(rename = $name))]
         $property$(($vp))?,
       )+
       /// The `all` property.
@@ -181,12 +190,31 @@ macro_rules! define_properties {
       Custom(CowArcStr<'i>)
     }
 
+    impl_visit!(PropertyId<'i>);
+
     macro_rules! vp_name {
       ($x: ty, $n: ident) => {
         $n
       };
     }
 
+    impl<'i, 'any> IntoOwned<'any> for PropertyId<'i> {
+      type Owned = PropertyId<'any>;
+
+      fn into_owned(self) -> Self::Owned {
+        use PropertyId::*;
+
+        match self {
+          $(
+            $(#[$meta])*
+            $property$((vp_name!($vp, prefix)))? => $property$((vp_name!($vp, prefix)))?,
+          )+
+          All => All,
+          Custom(name) => Custom(name.into_owned()),
+        }
+      }
+    }
+
     impl<'i> Parse<'i> for PropertyId<'i> {
       fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
         let name = input.expect_ident()?;
@@ -381,10 +409,16 @@ macro_rules! define_properties {
 
     /// A CSS property.
     #[derive(Debug, Clone, PartialEq)]
+    #[cfg_attr(
+      feature = "serde",
+      derive(serde::Serialize, serde::Deserialize),
+      serde(tag = "property", content = "value", rename_all = "kebab-case")
+    )]
     pub enum Property<'i> {
       $(
         #[doc=concat!("The `", $name, "` property.")]
         $(#[$meta])*
+        #[cfg_attr(feature = "serde", serde(rename = $name))]
         $property($type, $($vp)?),
       )+
       /// An unparsed property.
@@ -422,7 +456,7 @@ macro_rules! define_properties {
         let property_id = match_ignore_ascii_case! { name_ref,
           $(
             $(#[$meta])*
-            $name $(if options.$condition)? => {
+            $name $(if options.$condition.is_some())? => {
               let allowed_prefixes = get_allowed_prefixes!($($unprefixed)?) $(| VendorPrefix::$prefix)*;
               if allowed_prefixes.contains(prefix) {
                 if let Ok(c) = <$type>::parse(input) {
@@ -451,11 +485,11 @@ macro_rules! define_properties {
 
                 get_propertyid!($($vp)?)
               } else {
-                return Ok(Property::Custom(CustomProperty::parse(name, input)?))
+                return Ok(Property::Custom(CustomProperty::parse(name, input, options)?))
               }
             },
           )+
-          _ => return Ok(Property::Custom(CustomProperty::parse(name, input)?))
+          _ => return Ok(Property::Custom(CustomProperty::parse(name, input, options)?))
         };
 
         // If a value was unable to be parsed, treat as an unparsed property.
@@ -463,7 +497,7 @@ macro_rules! define_properties {
         // and stored as an enum rather than a string. This lets property handlers more easily deal with it.
         // Ideally we'd only do this if var() or env() references were seen, but err on the safe side for now.
         input.reset(&state);
-        return Ok(Property::Unparsed(UnparsedProperty::parse(property_id, input)?))
+        return Ok(Property::Unparsed(UnparsedProperty::parse(property_id, input, options)?))
       }
 
       #[allow(dead_code)]
@@ -480,6 +514,20 @@ macro_rules! define_properties {
         }
       }
 
+      /// Returns the property id for this property, including its vendor prefix.
+      pub fn property_id(&self) -> PropertyId<'i> {
+        use Property::*;
+
+        match self {
+          $(
+            $(#[$meta])*
+            $property(_, $(vp_name!($vp, p))?) => PropertyId::$property$((*vp_name!($vp, p)))?,
+          )+
+          Unparsed(unparsed) => unparsed.property_id.clone(),
+          Custom(custom) => PropertyId::Custom(custom.name.clone()),
+        }
+      }
+
       /// Parses a CSS property from a string.
       pub fn parse_string(name: &'i str, input: &'i str, options: ParserOptions) -> Result<Self, ParseError<'i, ParserError<'i>>> {
         let mut input = ParserInput::new(input);
@@ -552,7 +600,11 @@ macro_rules! define_properties {
           Unparsed(unparsed) => (unparsed.property_id.name(), unparsed.property_id.prefix()),
           Custom(custom) => {
             // Ensure custom property names are escaped.
-            serialize_name(custom.name.as_ref(), dest)?;
+            if custom.name.starts_with("--") {
+              dest.write_dashed_ident(&custom.name)?;
+            } else {
+              serialize_name(custom.name.as_ref(), dest)?;
+            }
             dest.delim(':', false)?;
             self.value_to_css(dest)?;
             write_important!();
@@ -589,6 +641,42 @@ macro_rules! define_properties {
         Ok(s)
       }
     }
+
+    impl<'i> Visit<'i> for Property<'i> {
+      fn visit<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+        visitor.visit_property(self)
+      }
+
+      fn visit_children<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+        use Property::*;
+
+        match self {
+          $(
+            $(#[$meta])*
+            $property(val, $(vp_name!($vp, _p))?) => val.visit(visitor),
+          )+
+          Unparsed(unparsed) => unparsed.visit(visitor),
+          Custom(custom) => custom.visit(visitor),
+        }
+      }
+    }
+
+    impl<'i, 'any> IntoOwned<'any> for Property<'i> {
+      type Owned = Property<'any>;
+
+      fn into_owned(self) -> Self::Owned {
+        use Property::*;
+
+        match self {
+          $(
+            $(#[$meta])*
+            $property(val, $(vp_name!($vp, prefix))?) => $property(val.into_owned(), $(vp_name!($vp, prefix))?),
+          )+
+          Unparsed(unparsed) => Unparsed(unparsed.into_owned()),
+          Custom(custom) => Custom(custom.into_owned()),
+        }
+      }
+    }
   };
 }
 
@@ -854,7 +942,7 @@ define_properties! {
   "line-height": LineHeight(LineHeight),
   "font": Font(Font<'i>),
   "vertical-align": VerticalAlign(VerticalAlign),
-  "font-palette": FontPalette(DashedIdent<'i>),
+  "font-palette": FontPalette(DashedIdentReference<'i>),
 
   "transition-property": TransitionProperty(SmallVec<[PropertyId<'i>; 1]>, VendorPrefix) / WebKit / Moz / Ms,
   "transition-duration": TransitionDuration(SmallVec<[Time; 1]>, VendorPrefix) / WebKit / Moz / Ms,
@@ -930,6 +1018,11 @@ define_properties! {
   "list-style": ListStyle(ListStyle<'i>),
   "marker-side": MarkerSide(MarkerSide),
 
+  // https://drafts.csswg.org/css-contain-3/
+  "container-type": ContainerType(ContainerType),
+  "container-name": ContainerName(ContainerNameList<'i>),
+  "container": Container(Container<'i>),
+
   // CSS modules
   "composes": Composes(Composes<'i>) if css_modules,
 
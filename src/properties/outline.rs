@@ -5,7 +5,7 @@ use super::{Property, PropertyId};
 use crate::context::PropertyHandlerContext;
 use crate::declaration::DeclarationList;
 use crate::error::{ParserError, PrinterError};
-use crate::macros::shorthand_handler;
+use crate::macros::{impl_into_owned, impl_visit, shorthand_handler};
 use crate::printer::Printer;
 use crate::targets::Browsers;
 use crate::traits::{FallbackValues, Parse, PropertyHandler, ToCss};
@@ -14,6 +14,11 @@ use cssparser::*;
 
 /// A value for the [outline-style](https://drafts.csswg.org/css-ui/#outline-style) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum OutlineStyle {
   /// The `auto` keyword.
   Auto,
@@ -21,6 +26,9 @@ pub enum OutlineStyle {
   BorderStyle(BorderStyle),
 }
 
+impl_visit!(OutlineStyle);
+impl_into_owned!(OutlineStyle);
+
 impl<'i> Parse<'i> for OutlineStyle {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if let Ok(border_style) = input.try_parse(BorderStyle::parse) {
@@ -5,7 +5,7 @@ use crate::compat::Feature;
 use crate::context::PropertyHandlerContext;
 use crate::declaration::DeclarationList;
 use crate::error::{ParserError, PrinterError};
-use crate::macros::enum_property;
+use crate::macros::{enum_property, impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::targets::Browsers;
 use crate::traits::{Parse, PropertyHandler, ToCss};
@@ -30,6 +30,11 @@ enum_property! {
 
 /// A value for the [overflow](https://www.w3.org/TR/css-overflow-3/#overflow-properties) shorthand property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Overflow {
   /// The overflow mode for the x direction.
   pub x: OverflowKeyword,
@@ -37,6 +42,9 @@ pub struct Overflow {
   pub y: OverflowKeyword,
 }
 
+impl_visit!(Overflow);
+impl_into_owned!(Overflow);
+
 impl<'i> Parse<'i> for Overflow {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let x = OverflowKeyword::parse(input)?;
@@ -4,6 +4,7 @@ use super::Property;
 use crate::context::PropertyHandlerContext;
 use crate::declaration::DeclarationList;
 use crate::error::{ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::prefixes::Feature;
 use crate::printer::Printer;
 use crate::targets::Browsers;
@@ -13,6 +14,11 @@ use cssparser::*;
 
 /// A value for the [position](https://www.w3.org/TR/css-position-3/#position-property) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Position {
   /// The box is laid in the document flow.
   Static,
@@ -26,6 +32,9 @@ pub enum Position {
   Fixed,
 }
 
+impl_visit!(Position);
+impl_into_owned!(Position);
+
 impl<'i> Parse<'i> for Position {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let location = input.current_source_location();
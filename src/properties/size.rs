@@ -4,7 +4,7 @@ use crate::compat::Feature;
 use crate::context::PropertyHandlerContext;
 use crate::declaration::DeclarationList;
 use crate::error::{ParserError, PrinterError};
-use crate::macros::enum_property;
+use crate::macros::{enum_property, impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::properties::{Property, PropertyId};
 use crate::traits::{Parse, PropertyHandler, ToCss};
@@ -16,6 +16,11 @@ use cssparser::*;
 /// A value for the [preferred size properties](https://drafts.csswg.org/css-sizing-3/#preferred-size-properties),
 /// i.e. `width` and `height.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Size {
   /// The `auto` keyword.
   Auto,
@@ -29,6 +34,9 @@ pub enum Size {
   FitContent(LengthPercentage),
 }
 
+impl_visit!(Size match { Auto, LengthPercentage(l), MinContent, MaxContent, FitContent(l) });
+impl_into_owned!(Size);
+
 impl<'i> Parse<'i> for Size {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|i| i.expect_ident_matching("auto")).is_ok() {
@@ -79,6 +87,11 @@ impl ToCss for Size {
 /// and [maximum](https://drafts.csswg.org/css-sizing-3/#max-size-properties) size properties,
 /// e.g. `min-width` and `max-height`.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum MinMaxSize {
   /// The `none` keyword.
   None,
@@ -92,6 +105,9 @@ pub enum MinMaxSize {
   FitContent(LengthPercentage),
 }
 
+impl_visit!(MinMaxSize match { None, LengthPercentage(l), MinContent, MaxContent, FitContent(l) });
+impl_into_owned!(MinMaxSize);
+
 impl<'i> Parse<'i> for MinMaxSize {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|i| i.expect_ident_matching("none")).is_ok() {
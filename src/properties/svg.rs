@@ -1,7 +1,7 @@
 //! CSS properties used in SVG.
 
 use crate::error::{ParserError, PrinterError};
-use crate::macros::enum_property;
+use crate::macros::{enum_property, impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::targets::Browsers;
 use crate::traits::{FallbackValues, Parse, ToCss};
@@ -12,6 +12,11 @@ use cssparser::*;
 /// An SVG [`<paint>`](https://www.w3.org/TR/SVG2/painting.html#SpecifyingPaint) value
 /// used in the `fill` and `stroke` properties.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum SVGPaint<'i> {
   /// No paint.
   None,
@@ -26,10 +31,18 @@ pub enum SVGPaint<'i> {
   ContextStroke,
 }
 
+impl_visit!(SVGPaint<'i> match { None, Url(u, f), Color(c), ContextFill, ContextStroke });
+impl_into_owned!(SVGPaint<'i> match { None, Url(u, o), Color(c), ContextFill, ContextStroke });
+
 /// A fallback for an SVG paint in case a paint server `url()` cannot be resolved.
 ///
 /// See [SVGPaint](SVGPaint).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum SVGPaintFallback {
   /// No fallback.
   None,
@@ -37,6 +50,9 @@ pub enum SVGPaintFallback {
   Color(CssColor),
 }
 
+impl_visit!(SVGPaintFallback match { None, Color(c) });
+impl_into_owned!(SVGPaintFallback);
+
 impl<'i> Parse<'i> for SVGPaint<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if let Ok(url) = input.try_parse(Url::parse) {
@@ -153,6 +169,11 @@ enum_property! {
 
 /// A value for the [stroke-dasharray](https://www.w3.org/TR/SVG2/painting.html#StrokeDashing) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum StrokeDasharray {
   /// No dashing is used.
   None,
@@ -160,6 +181,9 @@ pub enum StrokeDasharray {
   Values(Vec<LengthPercentage>),
 }
 
+impl_visit!(StrokeDasharray match { None, Values(v) });
+impl_into_owned!(StrokeDasharray);
+
 impl<'i> Parse<'i> for StrokeDasharray {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
@@ -210,6 +234,11 @@ impl ToCss for StrokeDasharray {
 
 /// A value for the [marker](https://www.w3.org/TR/SVG2/painting.html#VertexMarkerProperties) shorthand property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Marker<'i> {
   /// No marker.
   None,
@@ -217,6 +246,9 @@ pub enum Marker<'i> {
   Url(Url<'i>),
 }
 
+impl_visit!(Marker<'i> match { None, Url(u) });
+impl_into_owned!(Marker<'i> match { None, Url(u) });
+
 impl<'i> Parse<'i> for Marker<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if let Ok(url) = input.try_parse(Url::parse) {
@@ -7,7 +7,7 @@ use crate::compat;
 use crate::context::PropertyHandlerContext;
 use crate::declaration::DeclarationList;
 use crate::error::{ParserError, PrinterError};
-use crate::macros::enum_property;
+use crate::macros::{enum_property, impl_bitflags_serde, impl_into_owned, impl_visit};
 use crate::prefixes::Feature;
 use crate::printer::Printer;
 use crate::targets::Browsers;
@@ -55,6 +55,14 @@ bitflags! {
   }
 }
 
+impl_visit!(TextTransformOther);
+impl_into_owned!(TextTransformOther);
+
+impl_bitflags_serde!(TextTransformOther {
+  "full-width": FullWidth,
+  "full-size-kana": FullSizeKana,
+});
+
 impl<'i> Parse<'i> for TextTransformOther {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let location = input.current_source_location();
@@ -93,6 +101,11 @@ impl ToCss for TextTransformOther {
 
 /// A value for the [text-transform](https://www.w3.org/TR/2021/CRD-css-text-3-20210422/#text-transform-property) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct TextTransform {
   /// How case should be transformed.
   pub case: TextTransformCase,
@@ -100,6 +113,9 @@ pub struct TextTransform {
   pub other: TextTransformOther,
 }
 
+impl_visit!(TextTransform);
+impl_into_owned!(TextTransform);
+
 impl<'i> Parse<'i> for TextTransform {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let mut case = None;
@@ -286,6 +302,11 @@ enum_property! {
 /// A value for the [word-spacing](https://www.w3.org/TR/2021/CRD-css-text-3-20210422/#word-spacing-property)
 /// and [letter-spacing](https://www.w3.org/TR/2021/CRD-css-text-3-20210422/#letter-spacing-property) properties.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Spacing {
   /// No additional spacing is applied.
   Normal,
@@ -293,6 +314,9 @@ pub enum Spacing {
   Length(Length),
 }
 
+impl_visit!(Spacing match { Normal, Length(l) });
+impl_into_owned!(Spacing);
+
 impl<'i> Parse<'i> for Spacing {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|input| input.expect_ident_matching("normal")).is_ok() {
@@ -318,6 +342,11 @@ impl ToCss for Spacing {
 
 /// A value for the [text-indent](https://www.w3.org/TR/2021/CRD-css-text-3-20210422/#text-indent-property) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct TextIndent {
   /// The amount to indent.
   pub value: LengthPercentage,
@@ -327,6 +356,13 @@ pub struct TextIndent {
   pub each_line: bool,
 }
 
+impl_visit!(TextIndent {
+  value,
+  hanging: _,
+  each_line: _
+});
+impl_into_owned!(TextIndent);
+
 impl<'i> Parse<'i> for TextIndent {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let mut value = None;
@@ -406,6 +442,18 @@ bitflags! {
   }
 }
 
+impl_visit!(TextDecorationLine);
+impl_into_owned!(TextDecorationLine);
+
+impl_bitflags_serde!(TextDecorationLine {
+  "underline": Underline,
+  "overline": Overline,
+  "line-through": LineThrough,
+  "blink": Blink,
+  "spelling-error": SpellingError,
+  "grammar-error": GrammarError,
+});
+
 impl Default for TextDecorationLine {
   fn default() -> TextDecorationLine {
     TextDecorationLine::empty()
@@ -514,6 +562,11 @@ impl Default for TextDecorationStyle {
 
 /// A value for the [text-decoration-thickness](https://www.w3.org/TR/2020/WD-css-text-decor-4-20200506/#text-decoration-width-property) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum TextDecorationThickness {
   /// The UA chooses an appropriate thickness for text decoration lines.
   Auto,
@@ -523,6 +576,9 @@ pub enum TextDecorationThickness {
   LengthPercentage(LengthPercentage),
 }
 
+impl_visit!(TextDecorationThickness match { Auto, FromFont, LengthPercentage(l) });
+impl_into_owned!(TextDecorationThickness);
+
 impl Default for TextDecorationThickness {
   fn default() -> TextDecorationThickness {
     TextDecorationThickness::Auto
@@ -559,6 +615,11 @@ impl ToCss for TextDecorationThickness {
 
 /// A value for the [text-decoration](https://www.w3.org/TR/2020/WD-css-text-decor-4-20200506/#text-decoration-property) shorthand property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct TextDecoration {
   /// The lines to display.
   pub line: TextDecorationLine,
@@ -570,6 +631,14 @@ pub struct TextDecoration {
   pub color: CssColor,
 }
 
+impl_visit!(TextDecoration {
+  line: _,
+  thickness,
+  style: _,
+  color
+});
+impl_into_owned!(TextDecoration);
+
 impl<'i> Parse<'i> for TextDecoration {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let mut line = None;
@@ -696,6 +765,11 @@ enum_property! {
 
 /// A value for the [text-emphasis-style](https://www.w3.org/TR/2020/WD-css-text-decor-4-20200506/#text-emphasis-style-property) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum TextEmphasisStyle<'i> {
   /// No emphasis.
   None,
@@ -710,6 +784,9 @@ pub enum TextEmphasisStyle<'i> {
   String(CowArcStr<'i>),
 }
 
+impl_visit!(TextEmphasisStyle<'i>);
+impl_into_owned!(TextEmphasisStyle<'i> match { None, Keyword { fill, shape }, String(c) });
+
 impl<'i> Default for TextEmphasisStyle<'i> {
   fn default() -> TextEmphasisStyle<'i> {
     TextEmphasisStyle::None
@@ -773,6 +850,11 @@ impl<'i> ToCss for TextEmphasisStyle<'i> {
 
 /// A value for the [text-emphasis](https://www.w3.org/TR/2020/WD-css-text-decor-4-20200506/#text-emphasis-property) shorthand property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct TextEmphasis<'i> {
   /// The text emphasis style.
   pub style: TextEmphasisStyle<'i>,
@@ -780,6 +862,9 @@ pub struct TextEmphasis<'i> {
   pub color: CssColor,
 }
 
+impl_visit!(TextEmphasis<'i> { style, color });
+impl_into_owned!(TextEmphasis<'i> { style, color });
+
 impl<'i> Parse<'i> for TextEmphasis<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let mut style = None;
@@ -863,6 +948,11 @@ enum_property! {
 
 /// A value for the [text-emphasis-position](https://www.w3.org/TR/2020/WD-css-text-decor-4-20200506/#text-emphasis-position-property) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct TextEmphasisPosition {
   /// The vertical position.
   pub vertical: TextEmphasisPositionVertical,
@@ -870,6 +960,9 @@ pub struct TextEmphasisPosition {
   pub horizontal: TextEmphasisPositionHorizontal,
 }
 
+impl_visit!(TextEmphasisPosition);
+impl_into_owned!(TextEmphasisPosition);
+
 impl<'i> Parse<'i> for TextEmphasisPosition {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if let Ok(horizontal) = input.try_parse(TextEmphasisPositionHorizontal::parse) {
@@ -1195,6 +1288,11 @@ impl<'i> PropertyHandler<'i> for TextDecorationHandler<'i> {
 
 /// A value for the [text-shadow](https://www.w3.org/TR/2020/WD-css-text-decor-4-20200506/#text-shadow-property) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct TextShadow {
   /// The color of the text shadow.
   pub color: CssColor,
@@ -1208,6 +1306,15 @@ pub struct TextShadow {
   pub spread: Length, // added in Level 4 spec
 }
 
+impl_visit!(TextShadow {
+  color,
+  x_offset,
+  y_offset,
+  blur,
+  spread
+});
+impl_into_owned!(TextShadow);
+
 impl<'i> Parse<'i> for TextShadow {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let mut color = None;
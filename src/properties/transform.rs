@@ -4,7 +4,7 @@ use super::{Property, PropertyId};
 use crate::context::PropertyHandlerContext;
 use crate::declaration::DeclarationList;
 use crate::error::{ParserError, PrinterError};
-use crate::macros::enum_property;
+use crate::macros::{enum_property, impl_into_owned, impl_visit};
 use crate::prefixes::Feature;
 use crate::printer::Printer;
 use crate::stylesheet::PrinterOptions;
@@ -21,8 +21,12 @@ use std::f32::consts::PI;
 
 /// A value for the [transform](https://www.w3.org/TR/2019/CR-css-transforms-1-20190214/#propdef-transform) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransformList(pub Vec<Transform>);
 
+impl_visit!(TransformList { 0 });
+impl_into_owned!(TransformList);
+
 impl<'i> Parse<'i> for TransformList {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
@@ -142,6 +146,11 @@ impl TransformList {
 
 /// An individual [transform function](https://www.w3.org/TR/2019/CR-css-transforms-1-20190214/#two-d-transform-functions).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Transform {
   /// A 2D translation.
   Translate(LengthPercentage, LengthPercentage),
@@ -187,8 +196,38 @@ pub enum Transform {
   Matrix3d(Matrix3d<f32>),
 }
 
+impl_visit!(Transform match {
+  Translate(x, y),
+  TranslateX(x),
+  TranslateY(y),
+  TranslateZ(z),
+  Translate3d(x, y, z),
+  Scale(_, _),
+  ScaleX(_),
+  ScaleY(_),
+  ScaleZ(_),
+  Scale3d(_, _, _),
+  Rotate(_),
+  RotateX(_),
+  RotateY(_),
+  RotateZ(_),
+  Rotate3d(_, _, _, _),
+  Skew(_, _),
+  SkewX(_),
+  SkewY(_),
+  Perspective(l),
+  Matrix(_),
+  Matrix3d(_),
+});
+impl_into_owned!(Transform);
+
 /// A 2D matrix.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 #[allow(missing_docs)]
 pub struct Matrix<T> {
   pub a: T,
@@ -225,6 +264,11 @@ impl Matrix<f32> {
 
 /// A 3D matrix.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 #[allow(missing_docs)]
 pub struct Matrix3d<T> {
   pub m11: T,
@@ -1373,6 +1417,11 @@ enum_property! {
 
 /// A value for the [perspective](https://drafts.csswg.org/css-transforms-2/#perspective-property) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Perspective {
   /// No perspective transform is applied.
   None,
@@ -1380,6 +1429,9 @@ pub enum Perspective {
   Length(Length),
 }
 
+impl_visit!(Perspective match { None, Length(l) });
+impl_into_owned!(Perspective);
+
 impl<'i> Parse<'i> for Perspective {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|input| input.expect_ident_matching("none")).is_ok() {
@@ -1404,6 +1456,11 @@ impl ToCss for Perspective {
 
 /// A value for the [translate](https://drafts.csswg.org/css-transforms-2/#propdef-translate) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Translate {
   /// The x translation.
   pub x: LengthPercentage,
@@ -1413,6 +1470,9 @@ pub struct Translate {
   pub z: Length,
 }
 
+impl_visit!(Translate { x, y, z });
+impl_into_owned!(Translate);
+
 impl<'i> Parse<'i> for Translate {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|i| i.expect_ident_matching("none")).is_ok() {
@@ -1466,6 +1526,11 @@ impl Translate {
 
 /// A value for the [rotate](https://drafts.csswg.org/css-transforms-2/#propdef-rotate) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Rotate {
   /// Rotation around the x axis.
   pub x: f32,
@@ -1477,6 +1542,9 @@ pub struct Rotate {
   pub angle: Angle,
 }
 
+impl_visit!(Rotate);
+impl_into_owned!(Rotate);
+
 impl<'i> Parse<'i> for Rotate {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|i| i.expect_ident_matching("none")).is_ok() {
@@ -1549,6 +1617,11 @@ impl Rotate {
 
 /// A value for the [scale](https://drafts.csswg.org/css-transforms-2/#propdef-scale) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Scale {
   /// Scale on the x axis.
   pub x: NumberOrPercentage,
@@ -1558,6 +1631,9 @@ pub struct Scale {
   pub z: NumberOrPercentage,
 }
 
+impl_visit!(Scale);
+impl_into_owned!(Scale);
+
 impl<'i> Parse<'i> for Scale {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|i| i.expect_ident_matching("none")).is_ok() {
@@ -5,6 +5,7 @@ use crate::compat;
 use crate::context::PropertyHandlerContext;
 use crate::declaration::DeclarationList;
 use crate::error::{ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::prefixes::Feature;
 use crate::printer::Printer;
 use crate::properties::masking::get_webkit_mask_property;
@@ -17,6 +18,11 @@ use smallvec::SmallVec;
 
 /// A value for the [transition](https://www.w3.org/TR/2018/WD-css-transitions-1-20181011/#transition-shorthand-property) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Transition<'i> {
   /// The property to transition.
   pub property: PropertyId<'i>,
@@ -28,6 +34,9 @@ pub struct Transition<'i> {
   pub timing_function: EasingFunction,
 }
 
+impl_visit!(Transition<'i>);
+impl_into_owned!(Transition<'i> { property, duration, delay, timing_function });
+
 impl<'i> Parse<'i> for Transition<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let mut property = None;
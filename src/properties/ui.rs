@@ -1,7 +1,7 @@
 //! CSS properties related to user interface.
 
 use crate::error::{ParserError, PrinterError};
-use crate::macros::{enum_property, shorthand_property};
+use crate::macros::{enum_property, impl_into_owned, impl_visit, shorthand_property};
 use crate::printer::Printer;
 use crate::targets::Browsers;
 use crate::traits::{FallbackValues, Parse, ToCss};
@@ -34,6 +34,11 @@ enum_property! {
 ///
 /// See [Cursor](Cursor).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct CursorImage<'i> {
   /// A url to the cursor image.
   pub url: Url<'i>,
@@ -41,6 +46,9 @@ pub struct CursorImage<'i> {
   pub hotspot: Option<(CSSNumber, CSSNumber)>,
 }
 
+impl_visit!(CursorImage<'i> { url, hotspot: _ });
+impl_into_owned!(CursorImage<'i> { url, hotspot });
+
 impl<'i> Parse<'i> for CursorImage<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let url = Url::parse(input)?;
@@ -120,6 +128,11 @@ enum_property! {
 
 /// A value for the [cursor](https://www.w3.org/TR/2021/WD-css-ui-4-20210316/#cursor) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Cursor<'i> {
   /// A list of cursor images.
   pub images: SmallVec<[CursorImage<'i>; 1]>,
@@ -127,6 +140,9 @@ pub struct Cursor<'i> {
   pub keyword: CursorKeyword,
 }
 
+impl_visit!(Cursor<'i> { images, keyword: _ });
+impl_into_owned!(Cursor<'i> { images, keyword });
+
 impl<'i> Parse<'i> for Cursor<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let mut images = SmallVec::new();
@@ -160,6 +176,11 @@ impl<'i> ToCss for Cursor<'i> {
 
 /// A value for the [caret-color](https://www.w3.org/TR/2021/WD-css-ui-4-20210316/#caret-color) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum ColorOrAuto {
   /// The `currentColor`, adjusted by the UA to ensure contrast against the background.
   Auto,
@@ -167,6 +188,9 @@ pub enum ColorOrAuto {
   Color(CssColor),
 }
 
+impl_visit!(ColorOrAuto match { Auto, Color(c) });
+impl_into_owned!(ColorOrAuto);
+
 impl Default for ColorOrAuto {
   fn default() -> ColorOrAuto {
     ColorOrAuto::Auto
@@ -271,6 +295,11 @@ enum_property! {
 
 /// A value for the [appearance](https://www.w3.org/TR/2021/WD-css-ui-4-20210316/#appearance-switching) property.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 #[allow(missing_docs)]
 pub enum Appearance<'i> {
   None,
@@ -292,6 +321,27 @@ pub enum Appearance<'i> {
   NonStandard(CowArcStr<'i>),
 }
 
+impl_visit!(Appearance<'i>);
+impl_into_owned!(Appearance<'i> match {
+  None,
+  Auto,
+  Textfield,
+  MenulistButton,
+  Button,
+  Checkbox,
+  Listbox,
+  Menulist,
+  Meter,
+  ProgressBar,
+  PushButton,
+  Radio,
+  Searchfield,
+  SliderHorizontal,
+  SquareButton,
+  Textarea,
+  NonStandard(c),
+});
+
 impl<'i> Parse<'i> for Appearance<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let ident = input.expect_ident()?;
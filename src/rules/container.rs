@@ -0,0 +1,287 @@
+//! The `@container` rule.
+
+use super::Location;
+use super::{CssRuleList, MinifyContext};
+use crate::error::{MinifyError, ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
+use crate::media_query::{MediaFeature, Operator};
+use crate::printer::Printer;
+use crate::properties::Property;
+use crate::rules::{StyleContext, ToCssWithContext};
+use crate::stylesheet::ParserOptions;
+use crate::traits::{Parse, ToCss};
+use crate::values::ident::CustomIdent;
+use cssparser::*;
+
+/// A [@container](https://drafts.csswg.org/css-contain-3/#container-rule) rule.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct ContainerRule<'i> {
+  /// The name of the container.
+  pub name: Option<CustomIdent<'i>>,
+  /// The container condition.
+  pub condition: ContainerCondition<'i>,
+  /// The rules within the `@container` rule.
+  pub rules: CssRuleList<'i>,
+  /// The location of the rule in the source file.
+  pub loc: Location,
+}
+
+impl_visit!(ContainerRule<'i> { name, condition, rules, loc: _ });
+impl_into_owned!(ContainerRule<'i> { name, condition, rules, loc });
+
+impl<'i> ContainerRule<'i> {
+  pub(crate) fn minify(
+    &mut self,
+    context: &mut MinifyContext<'_, 'i>,
+    parent_is_unused: bool,
+  ) -> Result<bool, MinifyError> {
+    self.rules.minify(context, parent_is_unused)?;
+    Ok(self.rules.0.is_empty())
+  }
+}
+
+impl<'a, 'i> ToCssWithContext<'a, 'i> for ContainerRule<'i> {
+  fn to_css_with_context<W>(
+    &self,
+    dest: &mut Printer<W>,
+    context: Option<&StyleContext<'a, 'i>>,
+  ) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    dest.add_mapping(self.loc);
+    dest.write_str("@container ")?;
+    if let Some(name) = &self.name {
+      name.to_css(dest)?;
+      dest.write_char(' ')?;
+    }
+    self.condition.to_css(dest)?;
+    dest.whitespace()?;
+    dest.write_char('{')?;
+    dest.indent();
+    dest.newline()?;
+    self.rules.to_css_with_context(dest, context)?;
+    dest.dedent();
+    dest.newline()?;
+    dest.write_char('}')
+  }
+}
+
+/// A [`<container-condition>`](https://drafts.csswg.org/css-contain-3/#typedef-container-condition),
+/// as used in the `@container` rule.
+///
+/// Size features are represented as [media features](MediaFeature), and support the same
+/// range syntax. Unlike media queries, range syntax is never lowered to `min-` and `max-`
+/// prefixed features, since all browsers that support container queries support it.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+pub enum ContainerCondition<'i> {
+  /// A size container feature, implicitly parenthesized.
+  Feature(MediaFeature<'i>),
+  /// A negation of a condition.
+  Not(Box<ContainerCondition<'i>>),
+  /// A set of joint operations.
+  Operation(Vec<ContainerCondition<'i>>, Operator),
+  /// A condition wrapped in parenthesis.
+  InParens(Box<ContainerCondition<'i>>),
+  /// A style query, i.e. `style(...)`.
+  Style(StyleQuery<'i>),
+}
+
+impl_visit!(ContainerCondition<'i> match { Feature(f), Not(c), Operation(c, o), InParens(c), Style(s) });
+impl_into_owned!(ContainerCondition<'i> match { Feature(m), Not(b), Operation(v, o), InParens(b), Style(s) });
+
+impl<'i> Parse<'i> for ContainerCondition<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if input.try_parse(|input| input.expect_ident_matching("not")).is_ok() {
+      let inner_condition = Self::parse_in_parens(input)?;
+      return Ok(ContainerCondition::Not(Box::new(inner_condition)));
+    }
+
+    let first_condition = Self::parse_in_parens(input)?;
+    let operator = match input.try_parse(Operator::parse) {
+      Ok(op) => op,
+      Err(..) => return Ok(first_condition),
+    };
+
+    let mut conditions = vec![first_condition, Self::parse_in_parens(input)?];
+    let delim = match operator {
+      Operator::And => "and",
+      Operator::Or => "or",
+    };
+
+    loop {
+      if input.try_parse(|i| i.expect_ident_matching(delim)).is_err() {
+        return Ok(ContainerCondition::Operation(conditions, operator));
+      }
+
+      conditions.push(Self::parse_in_parens(input)?);
+    }
+  }
+}
+
+impl<'i> ContainerCondition<'i> {
+  fn parse_in_parens<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let location = input.current_source_location();
+    match *input.next()? {
+      Token::ParenthesisBlock => input.parse_nested_block(|input| {
+        if let Ok(inner) = input.try_parse(Self::parse) {
+          return Ok(ContainerCondition::InParens(Box::new(inner)));
+        }
+
+        let feature = MediaFeature::parse(input)?;
+        Ok(ContainerCondition::Feature(feature))
+      }),
+      Token::Function(ref f) if f.eq_ignore_ascii_case("style") => {
+        input.parse_nested_block(|input| Ok(ContainerCondition::Style(StyleQuery::parse(input)?)))
+      }
+      ref t => Err(location.new_unexpected_token_error(t.clone())),
+    }
+  }
+}
+
+impl<'i> ToCss for ContainerCondition<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match *self {
+      ContainerCondition::Feature(ref f) => f.to_css_with_lowering(dest, false),
+      ContainerCondition::Not(ref c) => {
+        dest.write_str("not ")?;
+        c.to_css(dest)
+      }
+      ContainerCondition::InParens(ref c) => {
+        dest.write_char('(')?;
+        c.to_css(dest)?;
+        dest.write_char(')')
+      }
+      ContainerCondition::Operation(ref list, op) => {
+        let mut iter = list.iter();
+        iter.next().unwrap().to_css(dest)?;
+        for item in iter {
+          dest.write_char(' ')?;
+          op.to_css(dest)?;
+          dest.write_char(' ')?;
+          item.to_css(dest)?;
+        }
+        Ok(())
+      }
+      ContainerCondition::Style(ref query) => {
+        dest.write_str("style(")?;
+        query.to_css(dest)?;
+        dest.write_char(')')
+      }
+    }
+  }
+}
+
+/// A [`<style-query>`](https://drafts.csswg.org/css-contain-3/#typedef-style-query),
+/// as used within the `style()` function of a container condition.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
+pub enum StyleQuery<'i> {
+  /// A property declaration to compare against the computed value of the container.
+  Declaration(Box<Property<'i>>),
+  /// A negation of a condition.
+  Not(Box<StyleQuery<'i>>),
+  /// A set of joint operations.
+  Operation(Vec<StyleQuery<'i>>, Operator),
+  /// A condition wrapped in parenthesis.
+  InParens(Box<StyleQuery<'i>>),
+}
+
+impl_visit!(StyleQuery<'i> match { Declaration(d), Not(q), Operation(q, o), InParens(q) });
+impl_into_owned!(StyleQuery<'i> match { Declaration(b), Not(b), Operation(v, o), InParens(b) });
+
+impl<'i> Parse<'i> for StyleQuery<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    if let Ok(declaration) = input.try_parse(Self::parse_declaration) {
+      return Ok(declaration);
+    }
+
+    if input.try_parse(|input| input.expect_ident_matching("not")).is_ok() {
+      let inner_condition = Self::parse_in_parens(input)?;
+      return Ok(StyleQuery::Not(Box::new(inner_condition)));
+    }
+
+    let first_condition = Self::parse_in_parens(input)?;
+    let operator = match input.try_parse(Operator::parse) {
+      Ok(op) => op,
+      Err(..) => return Ok(first_condition),
+    };
+
+    let mut conditions = vec![first_condition, Self::parse_in_parens(input)?];
+    let delim = match operator {
+      Operator::And => "and",
+      Operator::Or => "or",
+    };
+
+    loop {
+      if input.try_parse(|i| i.expect_ident_matching(delim)).is_err() {
+        return Ok(StyleQuery::Operation(conditions, operator));
+      }
+
+      conditions.push(Self::parse_in_parens(input)?);
+    }
+  }
+}
+
+impl<'i> StyleQuery<'i> {
+  fn parse_in_parens<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    input.expect_parenthesis_block()?;
+    input.parse_nested_block(|input| Ok(StyleQuery::InParens(Box::new(Self::parse(input)?))))
+  }
+
+  fn parse_declaration<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let name = input.expect_ident()?.clone();
+    input.expect_colon()?;
+    let property = Property::parse(name, input, &ParserOptions::default())?;
+    input.expect_exhausted()?;
+    Ok(StyleQuery::Declaration(Box::new(property)))
+  }
+}
+
+impl<'i> ToCss for StyleQuery<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    match *self {
+      StyleQuery::Declaration(ref property) => property.to_css(dest, false),
+      StyleQuery::Not(ref c) => {
+        dest.write_str("not ")?;
+        c.to_css(dest)
+      }
+      StyleQuery::InParens(ref c) => {
+        dest.write_char('(')?;
+        c.to_css(dest)?;
+        dest.write_char(')')
+      }
+      StyleQuery::Operation(ref list, op) => {
+        let mut iter = list.iter();
+        iter.next().unwrap().to_css(dest)?;
+        for item in iter {
+          dest.write_char(' ')?;
+          op.to_css(dest)?;
+          dest.write_char(' ')?;
+          item.to_css(dest)?;
+        }
+        Ok(())
+      }
+    }
+  }
+}
@@ -3,12 +3,18 @@
 use super::Location;
 use crate::declaration::DeclarationBlock;
 use crate::error::PrinterError;
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::traits::ToCss;
 use crate::values::ident::CustomIdent;
 
 /// A [@counter-style](https://drafts.csswg.org/css-counter-styles/#the-counter-style-rule) rule.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct CounterStyleRule<'i> {
   /// The name of the counter style to declare.
   pub name: CustomIdent<'i>,
@@ -19,6 +25,9 @@ pub struct CounterStyleRule<'i> {
   pub loc: Location,
 }
 
+impl_visit!(CounterStyleRule<'i> { name, declarations, loc: _ });
+impl_into_owned!(CounterStyleRule<'i> { name, declarations, loc });
+
 impl<'i> ToCss for CounterStyleRule<'i> {
   fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
   where
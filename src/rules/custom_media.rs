@@ -2,6 +2,7 @@
 
 use super::Location;
 use crate::error::PrinterError;
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::media_query::MediaList;
 use crate::printer::Printer;
 use crate::traits::ToCss;
@@ -9,6 +10,11 @@ use crate::values::ident::DashedIdent;
 
 /// A [@custom-media](https://drafts.csswg.org/mediaqueries-5/#custom-mq) rule.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct CustomMediaRule<'i> {
   /// The name of the declared media query.
   pub name: DashedIdent<'i>,
@@ -18,6 +24,9 @@ pub struct CustomMediaRule<'i> {
   pub loc: Location,
 }
 
+impl_visit!(CustomMediaRule<'i> { name, query, loc: _ });
+impl_into_owned!(CustomMediaRule<'i> { name, query, loc });
+
 impl<'i> ToCss for CustomMediaRule<'i> {
   fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
   where
@@ -27,6 +36,7 @@ impl<'i> ToCss for CustomMediaRule<'i> {
     dest.write_str("@custom-media ")?;
     self.name.to_css(dest)?;
     dest.write_char(' ')?;
-    self.query.to_css(dest)
+    self.query.to_css(dest)?;
+    dest.write_char(';')
   }
 }
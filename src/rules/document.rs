@@ -3,6 +3,7 @@
 use super::Location;
 use super::{CssRuleList, MinifyContext};
 use crate::error::{MinifyError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::traits::ToCss;
 
@@ -11,6 +12,11 @@ use crate::traits::ToCss;
 /// Note that only the `url-prefix()` function with no arguments is supported, and only the `-moz` prefix
 /// is allowed since Firefox was the only browser that ever implemented this rule.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct MozDocumentRule<'i> {
   /// Nested rules within the `@-moz-document` rule.
   pub rules: CssRuleList<'i>,
@@ -18,6 +24,9 @@ pub struct MozDocumentRule<'i> {
   pub loc: Location,
 }
 
+impl_visit!(MozDocumentRule<'i> { rules, loc: _ });
+impl_into_owned!(MozDocumentRule<'i> { rules, loc });
+
 impl<'i> MozDocumentRule<'i> {
   pub(crate) fn minify(&mut self, context: &mut MinifyContext<'_, 'i>) -> Result<(), MinifyError> {
     self.rules.minify(context, false)
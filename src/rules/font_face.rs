@@ -2,7 +2,8 @@
 
 use super::Location;
 use crate::error::{ParserError, PrinterError};
-use crate::macros::enum_property;
+use crate::macros::{enum_property, impl_into_owned, impl_visit};
+use crate::parser::ParserOptions;
 use crate::printer::Printer;
 use crate::properties::custom::CustomProperty;
 use crate::properties::font::{FontFamily, FontStretch, FontStyle, FontWeight};
@@ -15,6 +16,11 @@ use std::fmt::Write;
 
 /// A [@font-face](https://drafts.csswg.org/css-fonts/#font-face-rule) rule.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct FontFaceRule<'i> {
   /// Declarations in the `@font-face` rule.
   pub properties: Vec<FontFaceProperty<'i>>,
@@ -22,10 +28,18 @@ pub struct FontFaceRule<'i> {
   pub loc: Location,
 }
 
+impl_visit!(FontFaceRule<'i> { properties, loc: _ });
+impl_into_owned!(FontFaceRule<'i> { properties, loc });
+
 /// A property within an `@font-face` rule.
 ///
 /// See [FontFaceRule](FontFaceRule).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum FontFaceProperty<'i> {
   /// The `src` property.
   Source(Vec<Source<'i>>),
@@ -38,14 +52,39 @@ pub enum FontFaceProperty<'i> {
   /// The `font-stretch` property.
   FontStretch(Size2D<FontStretch>),
   /// The `unicode-range` property.
+  #[cfg_attr(feature = "serde", serde(with = "crate::serialization::unicode_ranges"))]
   UnicodeRange(Vec<UnicodeRange>),
   /// An unknown or unsupported property.
   Custom(CustomProperty<'i>),
 }
 
+impl_visit!(FontFaceProperty<'i> match {
+  Source(s),
+  FontFamily(_),
+  FontStyle(_),
+  FontWeight(_),
+  FontStretch(_),
+  UnicodeRange(_),
+  Custom(c),
+});
+impl_into_owned!(FontFaceProperty<'i> match {
+  Source(v),
+  FontFamily(f),
+  FontStyle(f),
+  FontWeight(s),
+  FontStretch(s),
+  UnicodeRange(v),
+  Custom(c),
+});
+
 /// A value for the [src](https://drafts.csswg.org/css-fonts/#src-desc)
 /// property in an `@font-face` rule.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Source<'i> {
   /// A `url()` with optional format metadata.
   Url(UrlSource<'i>),
@@ -53,6 +92,9 @@ pub enum Source<'i> {
   Local(FontFamily<'i>),
 }
 
+impl_visit!(Source<'i> match { Url(u), Local(_) });
+impl_into_owned!(Source<'i> match { Url(u), Local(f) });
+
 impl<'i> Parse<'i> for Source<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if let Ok(url) = input.try_parse(UrlSource::parse) {
@@ -84,6 +126,11 @@ impl<'i> ToCss for Source<'i> {
 /// A `url()` value for the [src](https://drafts.csswg.org/css-fonts/#src-desc)
 /// property in an `@font-face` rule.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct UrlSource<'i> {
   /// The URL.
   pub url: Url<'i>,
@@ -91,6 +138,9 @@ pub struct UrlSource<'i> {
   pub format: Option<Format<'i>>,
 }
 
+impl_visit!(UrlSource<'i> { url, format: _ });
+impl_into_owned!(UrlSource<'i> { url, format });
+
 impl<'i> Parse<'i> for UrlSource<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let url = Url::parse(input)?;
@@ -124,6 +174,11 @@ impl<'i> ToCss for UrlSource<'i> {
 /// The `format()` function within the [src](https://drafts.csswg.org/css-fonts/#src-desc)
 /// property of an `@font-face` rule.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Format<'i> {
   /// A font format name.
   pub format: FontFormat<'i>,
@@ -132,6 +187,8 @@ pub struct Format<'i> {
   pub supports: Vec<FontTechnology>,
 }
 
+impl_into_owned!(Format<'i> { format, supports });
+
 impl<'i> Parse<'i> for Format<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let format = FontFormat::parse(input)?;
@@ -175,10 +232,17 @@ impl<'i> ToCss for Format<'i> {
 /// [src](https://drafts.csswg.org/css-fonts/#src-desc)
 /// property of an `@font-face` rule.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum FontFormat<'i> {
   /// A WOFF font.
+  #[cfg_attr(feature = "serde", serde(rename = "woff"))]
   WOFF,
   /// A WOFF v2 font.
+  #[cfg_attr(feature = "serde", serde(rename = "woff2"))]
   WOFF2,
   /// A TrueType font.
   TrueType,
@@ -189,11 +253,23 @@ pub enum FontFormat<'i> {
   /// A font collection.
   Collection,
   /// An SVG font.
+  #[cfg_attr(feature = "serde", serde(rename = "svg"))]
   SVG,
   /// An unknown format.
   String(CowArcStr<'i>),
 }
 
+impl_into_owned!(FontFormat<'i> match {
+  WOFF,
+  WOFF2,
+  TrueType,
+  OpenType,
+  EmbeddedOpenType,
+  Collection,
+  SVG,
+  String(c),
+});
+
 impl<'i> Parse<'i> for FontFormat<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let s = input.expect_ident_or_string()?;
@@ -269,6 +345,11 @@ enum_property! {
 /// [src](https://drafts.csswg.org/css-fonts/#src-desc)
 /// property of an `@font-face` rule.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum FontTechnology {
   /// Supports font features.
   Features(FontFeatureTechnology),
@@ -280,6 +361,8 @@ pub enum FontTechnology {
   Palettes,
 }
 
+impl_into_owned!(FontTechnology);
+
 impl<'i> Parse<'i> for FontTechnology {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let location = input.current_source_location();
@@ -422,7 +505,11 @@ impl<'i> cssparser::DeclarationParser<'i> for FontFaceDeclarationParser {
     }
 
     input.reset(&state);
-    return Ok(FontFaceProperty::Custom(CustomProperty::parse(name, input)?));
+    return Ok(FontFaceProperty::Custom(CustomProperty::parse(
+      name,
+      input,
+      &ParserOptions::default(),
+    )?));
   }
 }
 
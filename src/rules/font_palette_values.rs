@@ -3,6 +3,8 @@
 use super::supports::SupportsRule;
 use super::{CssRule, CssRuleList, Location, MinifyContext};
 use crate::error::{ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
+use crate::parser::ParserOptions;
 use crate::printer::Printer;
 use crate::properties::custom::CustomProperty;
 use crate::properties::font::FontFamily;
@@ -15,6 +17,11 @@ use cssparser::*;
 
 /// A [@font-palette-values](https://drafts.csswg.org/css-fonts-4/#font-palette-values) rule.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct FontPaletteValuesRule<'i> {
   /// The name of the font palette.
   pub name: DashedIdent<'i>,
@@ -24,10 +31,18 @@ pub struct FontPaletteValuesRule<'i> {
   pub loc: Location,
 }
 
+impl_visit!(FontPaletteValuesRule<'i> { name, properties, loc: _ });
+impl_into_owned!(FontPaletteValuesRule<'i> { name, properties, loc });
+
 /// A property within an `@font-palette-values` rule.
 ///
 ///  See [FontPaletteValuesRule](FontPaletteValuesRule).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum FontPaletteValuesProperty<'i> {
   /// The `font-family` property.
   FontFamily(FontFamily<'i>),
@@ -39,9 +54,22 @@ pub enum FontPaletteValuesProperty<'i> {
   Custom(CustomProperty<'i>),
 }
 
+impl_visit!(FontPaletteValuesProperty<'i> match { FontFamily(_), BasePalette(_), OverrideColors(o), Custom(c) });
+impl_into_owned!(FontPaletteValuesProperty<'i> match {
+  FontFamily(f),
+  BasePalette(b),
+  OverrideColors(v),
+  Custom(c),
+});
+
 /// A value for the [base-palette](https://drafts.csswg.org/css-fonts-4/#base-palette-desc)
 /// property in an `@font-palette-values` rule.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum BasePalette {
   /// A light color palette as defined within the font.
   Light,
@@ -51,9 +79,16 @@ pub enum BasePalette {
   Integer(u16),
 }
 
+impl_into_owned!(BasePalette);
+
 /// A value for the [override-colors](https://drafts.csswg.org/css-fonts-4/#override-color)
 /// property in an `@font-palette-values` rule.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct OverrideColors {
   /// The index of the color within the palette to override.
   index: u16,
@@ -61,6 +96,9 @@ pub struct OverrideColors {
   color: CssColor,
 }
 
+impl_visit!(OverrideColors { index: _, color });
+impl_into_owned!(OverrideColors);
+
 pub(crate) struct FontPaletteValuesDeclarationParser;
 
 impl<'i> cssparser::DeclarationParser<'i> for FontPaletteValuesDeclarationParser {
@@ -99,7 +137,11 @@ impl<'i> cssparser::DeclarationParser<'i> for FontPaletteValuesDeclarationParser
     }
 
     input.reset(&state);
-    return Ok(FontPaletteValuesProperty::Custom(CustomProperty::parse(name, input)?));
+    return Ok(FontPaletteValuesProperty::Custom(CustomProperty::parse(
+      name,
+      input,
+      &ParserOptions::default(),
+    )?));
   }
 }
 
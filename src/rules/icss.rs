@@ -0,0 +1,430 @@
+//! The ICSS `:import` and `:export` rules, and the CSS modules `@value` rule.
+//!
+//! These are only parsed when the `css_modules` option is enabled. They are not printed,
+//! but are added to the CSS module exports instead. Any references to values declared
+//! via `@value` or imported from other files are substituted into property values and
+//! media queries after parsing.
+
+use super::{CssRule, CssRuleList, Location};
+use crate::css_modules::import_placeholder;
+use crate::error::{ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
+use crate::media_query::MediaQuery;
+use crate::parser::ParserOptions;
+use crate::printer::{Printer, PrinterOptions};
+use crate::properties::Property;
+use crate::traits::{IntoOwned, Parse, ToCss, Visit, Visitor};
+use crate::values::string::CowArcStr;
+use cssparser::*;
+use std::collections::HashMap;
+
+/// An ICSS [:import](https://github.com/css-modules/icss#import) rule, or an `@value` rule
+/// that imports values from another file, e.g. `@value primary, secondary as accent from "./colors.css"`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct IcssImportRule<'i> {
+  /// The specifier of the file to import from.
+  pub specifier: CowArcStr<'i>,
+  /// The imported names.
+  pub imports: Vec<IcssImport<'i>>,
+  /// The location of the rule in the source file.
+  pub loc: Location,
+}
+
+impl_visit!(IcssImportRule<'i>);
+impl_into_owned!(IcssImportRule<'i> { specifier, imports, loc });
+
+/// A name imported by an [IcssImportRule](IcssImportRule).
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct IcssImport<'i> {
+  /// The local name that the imported value is referenced by.
+  pub local: CowArcStr<'i>,
+  /// The name exported by the imported file.
+  pub imported: CowArcStr<'i>,
+}
+
+impl_into_owned!(IcssImport<'i> { local, imported });
+
+/// An ICSS [:export](https://github.com/css-modules/icss#export) rule.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct IcssExportRule<'i> {
+  /// The exported names and values.
+  pub exports: Vec<IcssExport<'i>>,
+  /// The location of the rule in the source file.
+  pub loc: Location,
+}
+
+impl_visit!(IcssExportRule<'i>);
+impl_into_owned!(IcssExportRule<'i> { exports, loc });
+
+/// A name and value exported by an [IcssExportRule](IcssExportRule).
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct IcssExport<'i> {
+  /// The exported name.
+  pub name: CowArcStr<'i>,
+  /// The exported value, as written in the source.
+  pub value: CowArcStr<'i>,
+}
+
+impl_into_owned!(IcssExport<'i> { name, value });
+
+/// A CSS modules [@value](https://github.com/css-modules/postcss-modules-values) rule,
+/// e.g. `@value primary: #BF4040`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct ValueRule<'i> {
+  /// The name of the value.
+  pub name: CowArcStr<'i>,
+  /// The value, as written in the source.
+  pub value: CowArcStr<'i>,
+  /// The location of the rule in the source file.
+  pub loc: Location,
+}
+
+impl_visit!(ValueRule<'i>);
+impl_into_owned!(ValueRule<'i> { name, value, loc });
+
+impl<'i> Parse<'i> for IcssImport<'i> {
+  /// Parses an imported name within an `@value` rule, e.g. `primary` or `primary as accent`.
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let imported: CowArcStr = input.expect_ident()?.into();
+    let local = if input.try_parse(|input| input.expect_ident_matching("as")).is_ok() {
+      input.expect_ident()?.into()
+    } else {
+      imported.clone()
+    };
+
+    Ok(IcssImport { local, imported })
+  }
+}
+
+impl<'i> IcssImportRule<'i> {
+  pub(crate) fn parse<'t>(
+    specifier: CowArcStr<'i>,
+    input: &mut Parser<'i, 't>,
+    loc: Location,
+  ) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let imports = DeclarationListParser::new(input, IcssDeclarationParser)
+      .flatten()
+      .map(|(local, imported)| IcssImport {
+        local: local.into(),
+        imported: imported.into(),
+      })
+      .collect();
+
+    Ok(IcssImportRule {
+      specifier,
+      imports,
+      loc,
+    })
+  }
+}
+
+impl<'i> IcssExportRule<'i> {
+  pub(crate) fn parse<'t>(
+    input: &mut Parser<'i, 't>,
+    loc: Location,
+  ) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let exports = DeclarationListParser::new(input, IcssDeclarationParser)
+      .flatten()
+      .map(|(name, value)| IcssExport {
+        name: name.into(),
+        value: value.into(),
+      })
+      .collect();
+
+    Ok(IcssExportRule { exports, loc })
+  }
+}
+
+/// Consumes the remaining tokens in the input, and returns the source text they were parsed from.
+pub(crate) fn parse_raw<'i, 't>(input: &mut Parser<'i, 't>) -> Result<&'i str, ParseError<'i, ParserError<'i>>> {
+  input.skip_whitespace();
+  let start = input.position();
+  while input.next().is_ok() {}
+
+  let raw = input.slice_from(start).trim();
+  if raw.is_empty() {
+    return Err(input.new_custom_error(ParserError::InvalidValue));
+  }
+
+  Ok(raw)
+}
+
+/// Parses the declarations within `:import` and `:export` rules as raw name and value pairs.
+struct IcssDeclarationParser;
+
+impl<'i> DeclarationParser<'i> for IcssDeclarationParser {
+  type Declaration = (CowRcStr<'i>, &'i str);
+  type Error = ParserError<'i>;
+
+  fn parse_value<'t>(
+    &mut self,
+    name: CowRcStr<'i>,
+    input: &mut Parser<'i, 't>,
+  ) -> Result<Self::Declaration, ParseError<'i, Self::Error>> {
+    Ok((name, parse_raw(input)?))
+  }
+}
+
+/// Default methods reject all at rules.
+impl<'i> AtRuleParser<'i> for IcssDeclarationParser {
+  type Prelude = ();
+  type AtRule = (CowRcStr<'i>, &'i str);
+  type Error = ParserError<'i>;
+}
+
+impl<'i> ToCss for IcssImportRule<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    if let Some(css_module) = &mut dest.css_module {
+      for import in &self.imports {
+        css_module.add_import(&import.local, &import.imported, &self.specifier, self.loc.source_index);
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl<'i> ToCss for IcssExportRule<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    if let Some(css_module) = &mut dest.css_module {
+      for export in &self.exports {
+        css_module.add_value(&export.name, &export.value, self.loc.source_index);
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl<'i> ToCss for ValueRule<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    if let Some(css_module) = &mut dest.css_module {
+      css_module.add_value(&self.name, &self.value, self.loc.source_index);
+    }
+
+    Ok(())
+  }
+}
+
+/// Substitutes references to values declared via `@value`, or imported via `:import` or `@value`,
+/// into the property values and media queries of the given rules, as well as into other values.
+///
+/// Imported values are unknown until the imported file is compiled, so they are replaced with
+/// a placeholder, which is included in the CSS module references. When bundling, the placeholders
+/// are substituted with the values exported by the imported file via [replace_imported_values].
+pub(crate) fn replace_values<'i>(rules: &mut CssRuleList<'i>, options: &ParserOptions) {
+  let mut values = HashMap::new();
+  for rule in &mut rules.0 {
+    match rule {
+      CssRule::Value(rule) => {
+        if let Some(value) = replace_in_str(&rule.value, &values) {
+          rule.value = value.into();
+        }
+        values.insert(rule.name.to_string(), rule.value.to_string());
+      }
+      CssRule::IcssImport(rule) => {
+        for import in &rule.imports {
+          values.insert(
+            import.local.to_string(),
+            import_placeholder(&rule.specifier, &import.imported),
+          );
+        }
+      }
+      _ => {}
+    }
+  }
+
+  if values.is_empty() {
+    return;
+  }
+
+  replace_in_rules(rules, &values, options);
+}
+
+/// Substitutes the placeholders of imported values with the given values, keyed by placeholder,
+/// once the files they are imported from have been compiled, e.g. when bundling.
+pub(crate) fn replace_imported_values<'i>(
+  rules: &mut CssRuleList<'i>,
+  values: &HashMap<String, String>,
+  options: &ParserOptions,
+) {
+  for rule in &mut rules.0 {
+    if let CssRule::Value(rule) = rule {
+      if let Some(value) = replace_in_str(&rule.value, values) {
+        rule.value = value.into();
+      }
+    }
+  }
+
+  replace_in_rules(rules, values, options);
+}
+
+fn replace_in_rules<'i>(rules: &mut CssRuleList<'i>, values: &HashMap<String, String>, options: &ParserOptions) {
+  let mut replacer = ValueReplacer { values, options };
+
+  for rule in &mut rules.0 {
+    match rule {
+      CssRule::IcssExport(rule) => {
+        for export in &mut rule.exports {
+          if let Some(value) = replace_in_str(&export.value, values) {
+            export.value = value.into();
+          }
+        }
+      }
+      CssRule::Value(_) | CssRule::IcssImport(_) => {}
+      rule => rule.visit(&mut replacer),
+    }
+  }
+}
+
+struct ValueReplacer<'a> {
+  values: &'a HashMap<String, String>,
+  options: &'a ParserOptions,
+}
+
+impl<'a> ValueReplacer<'a> {
+  /// Serializes the given value, and returns the serialized string with values substituted,
+  /// or `None` if the value does not reference any values.
+  fn replace<F>(&self, to_css: F) -> Option<String>
+  where
+    F: FnOnce(&mut Printer<String>) -> Result<(), PrinterError>,
+  {
+    let mut s = String::new();
+    let mut printer = Printer::new(&mut s, PrinterOptions::default());
+    to_css(&mut printer).ok()?;
+    replace_in_str(&s, self.values)
+  }
+}
+
+impl<'a, 'i> Visitor<'i> for ValueReplacer<'a> {
+  fn visit_property(&mut self, property: &mut Property<'i>) {
+    if matches!(property, Property::Composes(_)) {
+      return;
+    }
+
+    let value = match self.replace(|dest| property.value_to_css(dest)) {
+      Some(value) => value,
+      None => return,
+    };
+
+    let name = match property.property_id().to_css_string(PrinterOptions::default()) {
+      Ok(name) => name,
+      Err(_) => return,
+    };
+
+    let mut input = ParserInput::new(&value);
+    let mut parser = Parser::new(&mut input);
+    let replaced = Property::parse(name.as_str().into(), &mut parser, self.options).ok();
+    if let Some(replaced) = replaced {
+      *property = replaced.into_owned();
+    }
+  }
+
+  fn visit_media_query(&mut self, query: &mut MediaQuery<'i>) {
+    let value = match self.replace(|dest| query.to_css(dest)) {
+      Some(value) => value,
+      None => return,
+    };
+
+    let mut input = ParserInput::new(&value);
+    let mut parser = Parser::new(&mut input);
+    let replaced = parser.parse_entirely(MediaQuery::parse).ok();
+    if let Some(replaced) = replaced {
+      *query = replaced.into_owned();
+    }
+  }
+}
+
+/// Returns the given string with any identifiers that reference a value replaced,
+/// or `None` if there were no references.
+fn replace_in_str(s: &str, values: &HashMap<String, String>) -> Option<String> {
+  if values.is_empty() {
+    return None;
+  }
+
+  let mut input = ParserInput::new(s);
+  let mut parser = Parser::new(&mut input);
+  let mut res = String::with_capacity(s.len());
+  if replace_tokens(&mut parser, values, &mut res) {
+    Some(res)
+  } else {
+    None
+  }
+}
+
+fn replace_tokens<'i, 't>(
+  input: &mut Parser<'i, 't>,
+  values: &HashMap<String, String>,
+  dest: &mut String,
+) -> bool {
+  let mut replaced = false;
+  loop {
+    let start = input.position();
+    let token = match input.next_including_whitespace_and_comments() {
+      Ok(token) => token.clone(),
+      Err(_) => break,
+    };
+
+    let closing = match &token {
+      Token::Ident(name) => {
+        if let Some(value) = values.get(name.as_ref()) {
+          dest.push_str(value);
+          replaced = true;
+        } else {
+          dest.push_str(input.slice_from(start));
+        }
+        continue;
+      }
+      Token::Function(_) | Token::ParenthesisBlock => ')',
+      Token::SquareBracketBlock => ']',
+      Token::CurlyBracketBlock => '}',
+      _ => {
+        dest.push_str(input.slice_from(start));
+        continue;
+      }
+    };
+
+    dest.push_str(input.slice_from(start));
+    let _ = input.parse_nested_block(|input| -> Result<(), ParseError<'i, ()>> {
+      replaced |= replace_tokens(input, values, dest);
+      Ok(())
+    });
+    dest.push(closing);
+  }
+
+  replaced
+}
@@ -4,6 +4,7 @@ use super::layer::LayerName;
 use super::supports::SupportsCondition;
 use super::Location;
 use crate::error::PrinterError;
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::media_query::MediaList;
 use crate::printer::Printer;
 use crate::traits::ToCss;
@@ -12,6 +13,11 @@ use cssparser::*;
 
 /// A [@import](https://drafts.csswg.org/css-cascade/#at-import) rule.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct ImportRule<'i> {
   /// The url to import.
   pub url: CowArcStr<'i>,
@@ -25,6 +31,9 @@ pub struct ImportRule<'i> {
   pub loc: Location,
 }
 
+impl_visit!(ImportRule<'i> { url: _, layer: _, supports: _, media, loc: _ });
+impl_into_owned!(ImportRule<'i> { url, layer, supports, media, loc });
+
 impl<'i> ToCss for ImportRule<'i> {
   fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
   where
@@ -5,7 +5,8 @@ use super::MinifyContext;
 use super::{CssRule, CssRuleList, Location};
 use crate::context::DeclarationContext;
 use crate::declaration::DeclarationBlock;
-use crate::error::{ParserError, PrinterError};
+use crate::error::{MinifyWarning, ParserError, PrinterError, WarningKind};
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::parser::ParserOptions;
 use crate::printer::Printer;
 use crate::properties::custom::{CustomProperty, UnparsedProperty};
@@ -20,6 +21,11 @@ use cssparser::*;
 
 /// A [@keyframes](https://drafts.csswg.org/css-animations/#keyframes) rule.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct KeyframesRule<'i> {
   /// The animation name.
   pub name: CustomIdent<'i>,
@@ -31,11 +37,26 @@ pub struct KeyframesRule<'i> {
   pub loc: Location,
 }
 
+impl_visit!(KeyframesRule<'i> { name, keyframes, vendor_prefix: _, loc: _ });
+impl_into_owned!(KeyframesRule<'i> { name, keyframes, vendor_prefix, loc });
+
 impl<'i> KeyframesRule<'i> {
   pub(crate) fn minify(&mut self, context: &mut MinifyContext<'_, 'i>) {
     context.handler_context.context = DeclarationContext::Keyframes;
 
     for keyframe in &mut self.keyframes {
+      // Declarations marked `!important` are ignored within keyframes, so they are removed for the browser targets.
+      if context.targets.is_some() {
+        for property in keyframe.declarations.important_declarations.drain(..) {
+          context.warnings.push(MinifyWarning {
+            kind: WarningKind::ImportantInKeyframes {
+              property: property.name().into(),
+            },
+            loc: keyframe.loc,
+          });
+        }
+      }
+
       keyframe
         .declarations
         .minify(context.handler, context.important_handler, context.handler_context)
@@ -44,6 +65,16 @@ impl<'i> KeyframesRule<'i> {
     context.handler_context.context = DeclarationContext::None;
   }
 
+  /// Returns whether both rules contain the same keyframes, regardless of their source locations.
+  pub(crate) fn has_same_keyframes(&self, other: &KeyframesRule<'i>) -> bool {
+    self.keyframes.len() == other.keyframes.len()
+      && self
+        .keyframes
+        .iter()
+        .zip(&other.keyframes)
+        .all(|(a, b)| a.selectors == b.selectors && a.declarations == b.declarations)
+  }
+
   pub(crate) fn get_fallbacks(&mut self, targets: Browsers) -> Vec<CssRule<'i>> {
     let mut fallbacks = ColorFallbackKind::empty();
     for keyframe in &self.keyframes {
@@ -94,6 +125,7 @@ impl<'i> KeyframesRule<'i> {
       .iter()
       .map(|keyframe| Keyframe {
         selectors: keyframe.selectors.clone(),
+        loc: keyframe.loc,
         declarations: DeclarationBlock {
           important_declarations: vec![],
           declarations: keyframe
@@ -112,6 +144,7 @@ impl<'i> KeyframesRule<'i> {
               _ => property.clone(),
             })
             .collect(),
+          at_rules: keyframe.declarations.at_rules.clone(),
         },
       })
       .collect();
@@ -183,6 +216,11 @@ impl<'i> ToCss for KeyframesRule<'i> {
 /// A [keyframe selector](https://drafts.csswg.org/css-animations/#typedef-keyframe-selector)
 /// within an `@keyframes` rule.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum KeyframeSelector {
   /// An explicit percentage.
   Percentage(Percentage),
@@ -192,6 +230,8 @@ pub enum KeyframeSelector {
   To,
 }
 
+impl_into_owned!(KeyframeSelector);
+
 impl<'i> Parse<'i> for KeyframeSelector {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if let Ok(val) = input.try_parse(Percentage::parse) {
@@ -239,13 +279,23 @@ impl ToCss for KeyframeSelector {
 ///
 /// See [KeyframesRule](KeyframesRule).
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Keyframe<'i> {
   /// A list of keyframe selectors to associate with the declarations in this keyframe.
   pub selectors: Vec<KeyframeSelector>,
   /// The declarations for this keyframe.
   pub declarations: DeclarationBlock<'i>,
+  /// The location of the keyframe in the source file.
+  pub loc: Location,
 }
 
+impl_visit!(Keyframe<'i> { selectors: _, declarations, loc: _ });
+impl_into_owned!(Keyframe<'i> { selectors, declarations, loc });
+
 impl<'i> ToCss for Keyframe<'i> {
   fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
   where
@@ -264,7 +314,9 @@ impl<'i> ToCss for Keyframe<'i> {
   }
 }
 
-pub(crate) struct KeyframeListParser;
+pub(crate) struct KeyframeListParser {
+  pub source_index: u32,
+}
 
 impl<'a, 'i> AtRuleParser<'i> for KeyframeListParser {
   type Prelude = ();
@@ -287,14 +339,23 @@ impl<'a, 'i> QualifiedRuleParser<'i> for KeyframeListParser {
   fn parse_block<'t>(
     &mut self,
     selectors: Self::Prelude,
-    _: &ParserState,
+    start: &ParserState,
     input: &mut Parser<'i, 't>,
   ) -> Result<Self::QualifiedRule, ParseError<'i, ParserError<'i>>> {
     // For now there are no options that apply within @keyframes
-    let options = ParserOptions::default();
+    let options = ParserOptions {
+      source_index: self.source_index,
+      ..ParserOptions::default()
+    };
+    let loc = start.source_location();
     Ok(Keyframe {
       selectors,
       declarations: DeclarationBlock::parse(input, &options)?,
+      loc: Location {
+        source_index: self.source_index,
+        line: loc.line,
+        column: loc.column,
+      },
     })
   }
 }
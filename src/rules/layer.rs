@@ -2,6 +2,7 @@
 
 use super::{CssRuleList, Location};
 use crate::error::{ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::traits::{Parse, ToCss};
 use crate::values::string::CowArcStr;
@@ -13,8 +14,11 @@ use smallvec::SmallVec;
 ///
 /// Nested layers are represented using a list of identifiers. In CSS syntax, these are dot-separated.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LayerName<'i>(pub SmallVec<[CowArcStr<'i>; 1]>);
 
+impl_into_owned!(LayerName<'i> { 0 });
+
 macro_rules! expect_non_whitespace {
   ($parser: ident, $($branches: tt)+) => {{
     let start_location = $parser.current_source_location();
@@ -78,6 +82,11 @@ impl<'i> ToCss for LayerName<'i> {
 ///
 /// See also [LayerBlockRule](LayerBlockRule).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct LayerStatementRule<'i> {
   /// The layer names to declare.
   pub names: Vec<LayerName<'i>>,
@@ -85,6 +94,9 @@ pub struct LayerStatementRule<'i> {
   pub loc: Location,
 }
 
+impl_visit!(LayerStatementRule<'i>);
+impl_into_owned!(LayerStatementRule<'i> { names, loc });
+
 impl<'i> ToCss for LayerStatementRule<'i> {
   fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
   where
@@ -99,6 +111,11 @@ impl<'i> ToCss for LayerStatementRule<'i> {
 
 /// A [@layer block](https://drafts.csswg.org/css-cascade-5/#layer-block) rule.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct LayerBlockRule<'i> {
   /// The name of the layer to declare, or `None` to declare an anonymous layer.
   pub name: Option<LayerName<'i>>,
@@ -108,6 +125,9 @@ pub struct LayerBlockRule<'i> {
   pub loc: Location,
 }
 
+impl_visit!(LayerBlockRule<'i> { name: _, rules, loc: _ });
+impl_into_owned!(LayerBlockRule<'i> { name, rules, loc });
+
 impl<'i> ToCss for LayerBlockRule<'i> {
   fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
   where
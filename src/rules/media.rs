@@ -2,14 +2,20 @@
 
 use super::Location;
 use super::{CssRuleList, MinifyContext};
-use crate::error::{MinifyError, PrinterError};
-use crate::media_query::MediaList;
+use crate::error::{MinifyError, MinifyWarning, PrinterError, WarningKind};
+use crate::macros::{impl_into_owned, impl_visit};
+use crate::media_query::{CustomMediaError, MediaList};
 use crate::printer::Printer;
 use crate::rules::{StyleContext, ToCssWithContext};
 use crate::traits::ToCss;
 
 /// A [@media](https://drafts.csswg.org/css-conditional-3/#at-media) rule.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct MediaRule<'i> {
   /// The media query list.
   pub query: MediaList<'i>,
@@ -19,6 +25,9 @@ pub struct MediaRule<'i> {
   pub loc: Location,
 }
 
+impl_visit!(MediaRule<'i> { query, rules, loc: _ });
+impl_into_owned!(MediaRule<'i> { query, rules, loc });
+
 impl<'i> MediaRule<'i> {
   pub(crate) fn minify(
     &mut self,
@@ -28,7 +37,17 @@ impl<'i> MediaRule<'i> {
     self.rules.minify(context, parent_is_unused)?;
 
     if let Some(custom_media) = &context.custom_media {
-      self.query.transform_custom_media(self.loc, custom_media)?;
+      let mut query = self.query.clone();
+      match query.transform_custom_media(self.loc, custom_media) {
+        Ok(()) => self.query = query,
+        Err(CustomMediaError::UnsupportedBooleanLogic(custom_media_loc)) => {
+          context.warnings.push(MinifyWarning {
+            kind: WarningKind::UnsupportedCustomMediaBooleanLogic { custom_media_loc },
+            loc: self.loc,
+          });
+        }
+        Err(CustomMediaError::Error(err)) => return Err(err),
+      }
     }
 
     Ok(self.rules.0.is_empty() || self.query.never_matches())
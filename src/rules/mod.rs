@@ -36,11 +36,13 @@
 
 #![deny(missing_docs)]
 
+pub mod container;
 pub mod counter_style;
 pub mod custom_media;
 pub mod document;
 pub mod font_face;
 pub mod font_palette_values;
+pub mod icss;
 pub mod import;
 pub mod keyframes;
 pub mod layer;
@@ -49,41 +51,49 @@ pub mod namespace;
 pub mod nesting;
 pub mod page;
 pub mod property;
+pub mod scope;
 pub mod style;
 pub mod supports;
+pub mod unknown;
 pub mod viewport;
 
 use self::font_palette_values::FontPaletteValuesRule;
 use self::layer::{LayerBlockRule, LayerStatementRule};
 use self::property::PropertyRule;
+use crate::compat;
 use crate::context::PropertyHandlerContext;
 use crate::declaration::DeclarationHandler;
 use crate::dependencies::{Dependency, ImportDependency};
-use crate::error::{MinifyError, ParserError, PrinterError};
+use crate::error::{MinifyError, MinifyWarning, ParserError, PrinterError, WarningKind};
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::parser::TopLevelRuleParser;
 use crate::prefixes::Feature;
 use crate::printer::Printer;
 use crate::selector::{downlevel_selectors, get_prefix, is_equivalent};
 use crate::stylesheet::ParserOptions;
 use crate::targets::Browsers;
-use crate::traits::ToCss;
+use crate::traits::{ToCss, Visit, Visitor};
 use crate::values::string::CowArcStr;
 use crate::vendor_prefix::VendorPrefix;
+use container::ContainerRule;
 use counter_style::CounterStyleRule;
 use cssparser::{parse_one_rule, ParseError, Parser, ParserInput};
 use custom_media::CustomMediaRule;
 use document::MozDocumentRule;
 use font_face::FontFaceRule;
+use icss::{IcssExportRule, IcssImportRule, ValueRule};
 use import::ImportRule;
 use keyframes::KeyframesRule;
 use media::MediaRule;
 use namespace::NamespaceRule;
 use nesting::NestingRule;
 use page::PageRule;
+use scope::ScopeRule;
 use serde::Serialize;
 use std::collections::{HashMap, HashSet};
 use style::StyleRule;
 use supports::SupportsRule;
+use unknown::UnknownAtRule;
 use viewport::ViewportRule;
 
 pub(crate) trait ToCssWithContext<'a, 'i> {
@@ -96,13 +106,22 @@ pub(crate) trait ToCssWithContext<'a, 'i> {
     W: std::fmt::Write;
 }
 
-pub(crate) struct StyleContext<'a, 'i> {
-  pub rule: &'a StyleRule<'i>,
-  pub parent: Option<&'a StyleContext<'a, 'i>>,
+pub(crate) enum StyleContext<'a, 'i> {
+  /// Rules nested within a style rule, where `&` refers to the parent selectors.
+  Style {
+    rule: &'a StyleRule<'i>,
+    parent: Option<&'a StyleContext<'a, 'i>>,
+  },
+  /// Rules within a `@scope` rule that is being lowered to plain style rules.
+  Scope {
+    rule: &'a ScopeRule<'i>,
+    parent: Option<&'a StyleContext<'a, 'i>>,
+  },
 }
 
 /// A source location.
 #[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
 pub struct Location {
   /// The index of the source file within the source map.
   pub source_index: u32,
@@ -115,6 +134,11 @@ pub struct Location {
 
 /// A CSS rule.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum CssRule<'i> {
   /// A `@media` rule.
   Media(MediaRule<'i>),
@@ -150,10 +174,84 @@ pub enum CssRule<'i> {
   LayerBlock(LayerBlockRule<'i>),
   /// A `@property` rule.
   Property(PropertyRule<'i>),
+  /// A `@container` rule.
+  Container(ContainerRule<'i>),
+  /// A `@scope` rule.
+  Scope(ScopeRule<'i>),
+  /// An unknown at-rule.
+  Unknown(UnknownAtRule<'i>),
+  /// A CSS modules `@value` rule.
+  Value(ValueRule<'i>),
+  /// An ICSS `:import` rule, or an `@value` rule that imports from another file.
+  IcssImport(IcssImportRule<'i>),
+  /// An ICSS `:export` rule.
+  IcssExport(IcssExportRule<'i>),
   /// A placeholder for a rule that was removed.
   Ignored,
 }
 
+impl<'i> Visit<'i> for CssRule<'i> {
+  fn visit<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    visitor.visit_rule(self)
+  }
+
+  fn visit_children<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    match self {
+      CssRule::Media(media) => media.visit(visitor),
+      CssRule::Import(import) => import.visit(visitor),
+      CssRule::Style(style) => style.visit(visitor),
+      CssRule::Keyframes(keyframes) => keyframes.visit(visitor),
+      CssRule::FontFace(font_face) => font_face.visit(visitor),
+      CssRule::FontPaletteValues(f) => f.visit(visitor),
+      CssRule::Page(page) => page.visit(visitor),
+      CssRule::Supports(supports) => supports.visit(visitor),
+      CssRule::CounterStyle(counter_style) => counter_style.visit(visitor),
+      CssRule::Namespace(namespace) => namespace.visit(visitor),
+      CssRule::MozDocument(document) => document.visit(visitor),
+      CssRule::Nesting(nesting) => nesting.visit(visitor),
+      CssRule::Viewport(viewport) => viewport.visit(visitor),
+      CssRule::CustomMedia(custom_media) => custom_media.visit(visitor),
+      CssRule::LayerStatement(layer) => layer.visit(visitor),
+      CssRule::LayerBlock(layer) => layer.visit(visitor),
+      CssRule::Property(property) => property.visit(visitor),
+      CssRule::Container(container) => container.visit(visitor),
+      CssRule::Scope(scope) => scope.visit(visitor),
+      CssRule::Unknown(unknown) => unknown.visit(visitor),
+      CssRule::Value(value) => value.visit(visitor),
+      CssRule::IcssImport(import) => import.visit(visitor),
+      CssRule::IcssExport(export) => export.visit(visitor),
+      CssRule::Ignored => {}
+    }
+  }
+}
+
+impl_into_owned!(CssRule<'i> match {
+  Media(m),
+  Import(i),
+  Style(s),
+  Keyframes(k),
+  FontFace(f),
+  FontPaletteValues(f),
+  Page(p),
+  Supports(s),
+  CounterStyle(c),
+  Namespace(n),
+  MozDocument(m),
+  Nesting(n),
+  Viewport(v),
+  CustomMedia(c),
+  LayerStatement(l),
+  LayerBlock(l),
+  Property(p),
+  Container(c),
+  Scope(s),
+  Unknown(u),
+  Value(v),
+  IcssImport(i),
+  IcssExport(e),
+  Ignored,
+});
+
 impl<'a, 'i> ToCssWithContext<'a, 'i> for CssRule<'i> {
   fn to_css_with_context<W>(
     &self,
@@ -181,6 +279,12 @@ impl<'a, 'i> ToCssWithContext<'a, 'i> for CssRule<'i> {
       CssRule::LayerStatement(layer) => layer.to_css(dest),
       CssRule::LayerBlock(layer) => layer.to_css(dest),
       CssRule::Property(property) => property.to_css(dest),
+      CssRule::Container(container) => container.to_css_with_context(dest, context),
+      CssRule::Scope(scope) => scope.to_css_with_context(dest, context),
+      CssRule::Unknown(unknown) => unknown.to_css(dest),
+      CssRule::Value(value) => value.to_css(dest),
+      CssRule::IcssImport(import) => import.to_css(dest),
+      CssRule::IcssExport(export) => export.to_css(dest),
       CssRule::Ignored => Ok(()),
     }
   }
@@ -215,8 +319,12 @@ impl<'i> ToCss for CssRule<'i> {
 
 /// A list of CSS rules.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CssRuleList<'i>(pub Vec<CssRule<'i>>);
 
+impl_visit!(CssRuleList<'i> { 0 });
+impl_into_owned!(CssRuleList<'i> { 0 });
+
 pub(crate) struct MinifyContext<'a, 'i> {
   pub targets: &'a Option<Browsers>,
   pub handler: &'a mut DeclarationHandler<'i>,
@@ -224,6 +332,7 @@ pub(crate) struct MinifyContext<'a, 'i> {
   pub handler_context: &'a mut PropertyHandlerContext<'i>,
   pub unused_symbols: &'a HashSet<String>,
   pub custom_media: Option<HashMap<CowArcStr<'i>, CustomMediaRule<'i>>>,
+  pub warnings: &'a mut Vec<MinifyWarning>,
 }
 
 impl<'i> CssRuleList<'i> {
@@ -246,7 +355,23 @@ impl<'i> CssRuleList<'i> {
             ($keyframes: ident) => {
               if $keyframes.vendor_prefix.contains(VendorPrefix::None) {
                 if let Some(targets) = context.targets {
-                  $keyframes.vendor_prefix = Feature::AtKeyframes.prefixes_for(*targets)
+                  let prefixes = Feature::AtKeyframes.prefixes_for(*targets);
+                  for prefix in [
+                    VendorPrefix::WebKit,
+                    VendorPrefix::Moz,
+                    VendorPrefix::Ms,
+                    VendorPrefix::O,
+                  ] {
+                    if $keyframes.vendor_prefix.contains(prefix) && !prefixes.contains(prefix) {
+                      context.warnings.push(MinifyWarning {
+                        kind: WarningKind::VendorPrefixRemoved {
+                          name: format!("@{}keyframes", cssparser::ToCss::to_css_string(&prefix)),
+                        },
+                        loc: $keyframes.loc,
+                      });
+                    }
+                  }
+                  $keyframes.vendor_prefix = prefixes
                 }
               }
             };
@@ -256,7 +381,7 @@ impl<'i> CssRuleList<'i> {
           // merge the vendor prefixes from this rule into it.
           if let Some(existing_idx) = keyframe_rules.get(&keyframes.name) {
             if let Some(CssRule::Keyframes(existing)) = &mut rules.get_mut(*existing_idx) {
-              if existing.keyframes == keyframes.keyframes {
+              if existing.has_same_keyframes(keyframes) {
                 existing.vendor_prefix |= keyframes.vendor_prefix;
                 set_prefix!(existing);
                 continue;
@@ -279,11 +404,37 @@ impl<'i> CssRuleList<'i> {
             continue;
           }
         }
+        CssRule::Unknown(unknown) => {
+          // @custom-media rules are only parsed when the draft is enabled, so they cannot be lowered otherwise.
+          if let Some(targets) = context.targets {
+            if unknown.name.eq_ignore_ascii_case("custom-media")
+              && !compat::Feature::CustomMediaQueries.is_compatible(*targets)
+            {
+              context.warnings.push(MinifyWarning {
+                kind: WarningKind::DraftNotEnabled {
+                  feature: "@custom-media".into(),
+                  draft: "custom_media".into(),
+                },
+                loc: unknown.loc,
+              });
+            }
+          }
+        }
         CssRule::Media(media) => {
           if media.minify(context, parent_is_unused)? {
             continue;
           }
         }
+        CssRule::Container(container) => {
+          if container.minify(context, parent_is_unused)? {
+            continue;
+          }
+        }
+        CssRule::Scope(scope) => {
+          if scope.minify(context, parent_is_unused)? {
+            continue;
+          }
+        }
         CssRule::Supports(supports) => {
           supports.minify(context, parent_is_unused)?;
           if supports.rules.0.is_empty() {
@@ -310,6 +461,8 @@ impl<'i> CssRuleList<'i> {
               && last_style_rule.is_compatible(*context.targets)
               && style.rules.0.is_empty()
               && last_style_rule.rules.0.is_empty()
+              && style.declarations.at_rules.is_empty()
+              && last_style_rule.declarations.at_rules.is_empty()
             {
               last_style_rule
                 .declarations
@@ -426,6 +579,12 @@ impl<'a, 'i> ToCssWithContext<'a, 'i> for CssRuleList<'i> {
         continue;
       }
 
+      // ICSS rules are not printed, but are added to the CSS module exports.
+      if let CssRule::Value(..) | CssRule::IcssImport(..) | CssRule::IcssExport(..) = &rule {
+        rule.to_css_with_context(dest, context)?;
+        continue;
+      }
+
       // Skip @import rules if collecting dependencies.
       if let CssRule::Import(rule) = &rule {
         let dep = if dest.dependencies.is_some() {
@@ -2,6 +2,7 @@
 
 use super::Location;
 use crate::error::PrinterError;
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::traits::ToCss;
 use crate::values::string::CowArcStr;
@@ -9,6 +10,11 @@ use cssparser::*;
 
 /// A [@namespace](https://drafts.csswg.org/css-namespaces/#declaration) rule.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct NamespaceRule<'i> {
   /// An optional namespace prefix to declare, or `None` to declare the default namespace.
   pub prefix: Option<CowArcStr<'i>>,
@@ -18,6 +24,9 @@ pub struct NamespaceRule<'i> {
   pub loc: Location,
 }
 
+impl_visit!(NamespaceRule<'i>);
+impl_into_owned!(NamespaceRule<'i> { prefix, url, loc });
+
 impl<'i> ToCss for NamespaceRule<'i> {
   fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
   where
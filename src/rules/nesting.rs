@@ -4,11 +4,17 @@ use super::style::StyleRule;
 use super::Location;
 use super::MinifyContext;
 use crate::error::{MinifyError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::rules::{StyleContext, ToCssWithContext};
 
 /// A [@nest](https://www.w3.org/TR/css-nesting-1/#at-nest) rule.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct NestingRule<'i> {
   /// The style rule that defines the selector and declarations for the `@nest` rule.
   pub style: StyleRule<'i>,
@@ -16,6 +22,9 @@ pub struct NestingRule<'i> {
   pub loc: Location,
 }
 
+impl_visit!(NestingRule<'i> { style, loc: _ });
+impl_into_owned!(NestingRule<'i> { style, loc });
+
 impl<'i> NestingRule<'i> {
   pub(crate) fn minify(
     &mut self,
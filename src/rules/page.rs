@@ -3,7 +3,7 @@
 use super::Location;
 use crate::declaration::DeclarationBlock;
 use crate::error::{ParserError, PrinterError};
-use crate::macros::enum_property;
+use crate::macros::{enum_property, impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::traits::{Parse, ToCss};
 use crate::values::string::CowArcStr;
@@ -14,6 +14,11 @@ use cssparser::*;
 ///
 /// Either a name or at least one pseudo class is required.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct PageSelector<'i> {
   /// An optional named page type.
   pub name: Option<CowArcStr<'i>>,
@@ -21,6 +26,8 @@ pub struct PageSelector<'i> {
   pub pseudo_classes: Vec<PagePseudoClass>,
 }
 
+impl_into_owned!(PageSelector<'i> { name, pseudo_classes });
+
 enum_property! {
   /// A page pseudo class within an `@page` selector.
   ///
@@ -68,6 +75,11 @@ impl<'i> Parse<'i> for PageSelector<'i> {
 
 /// A [@page](https://www.w3.org/TR/css-page-3/#at-page-rule) rule.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct PageRule<'i> {
   /// A list of page selectors.
   pub selectors: Vec<PageSelector<'i>>,
@@ -77,6 +89,9 @@ pub struct PageRule<'i> {
   pub loc: Location,
 }
 
+impl_visit!(PageRule<'i> { selectors: _, declarations, loc: _ });
+impl_into_owned!(PageRule<'i> { selectors, declarations, loc });
+
 impl<'i> ToCss for PageRule<'i> {
   fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
   where
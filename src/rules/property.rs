@@ -1,6 +1,7 @@
 //! The `@property` rule.
 
 use super::Location;
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::{
   error::{ParserError, PrinterError},
   printer::Printer,
@@ -14,6 +15,11 @@ use cssparser::*;
 
 /// A [@property](https://drafts.css-houdini.org/css-properties-values-api/#at-property-rule) rule.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct PropertyRule<'i> {
   /// The name of the custom property to declare.
   name: DashedIdent<'i>,
@@ -27,6 +33,9 @@ pub struct PropertyRule<'i> {
   loc: Location,
 }
 
+impl_visit!(PropertyRule<'i> { name, syntax: _, inherits: _, initial_value, loc: _ });
+impl_into_owned!(PropertyRule<'i> { name, syntax, inherits, initial_value, loc });
+
 impl<'i> PropertyRule<'i> {
   pub(crate) fn parse<'t>(
     name: DashedIdent<'i>,
@@ -0,0 +1,112 @@
+//! The `@scope` rule.
+
+use super::Location;
+use super::{CssRuleList, MinifyContext};
+use crate::compat::Feature;
+use crate::error::{MinifyError, PrinterError, WarningKind};
+use crate::macros::{impl_into_owned, impl_visit};
+use crate::printer::Printer;
+use crate::rules::{StyleContext, ToCssWithContext};
+use crate::selector::{is_unused, Selectors};
+use parcel_selectors::SelectorList;
+
+/// A [@scope](https://drafts.csswg.org/css-cascade-6/#scope-atrule) rule.
+///
+/// When the targets do not support `@scope`, the rule is lowered into plain style rules
+/// at print time. The scoping root is added as a `:where()` ancestor of each selector,
+/// and the scoping limit is excluded using `:not()`. A rule without a scope start cannot
+/// be lowered, since its scoping root depends on the owner node of the style sheet.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct ScopeRule<'i> {
+  /// A selector list used to identify the scoping root(s).
+  pub scope_start: Option<SelectorList<'i, Selectors>>,
+  /// A selector list used to identify any scoping limits.
+  pub scope_end: Option<SelectorList<'i, Selectors>>,
+  /// The rules within the `@scope` rule.
+  pub rules: CssRuleList<'i>,
+  /// The location of the rule in the source file.
+  pub loc: Location,
+}
+
+impl_visit!(ScopeRule<'i> { scope_start, scope_end, rules, loc: _ });
+impl_into_owned!(ScopeRule<'i> { scope_start, scope_end, rules, loc });
+
+impl<'i> ScopeRule<'i> {
+  pub(crate) fn minify(
+    &mut self,
+    context: &mut MinifyContext<'_, 'i>,
+    parent_is_unused: bool,
+  ) -> Result<bool, MinifyError> {
+    // If the scoping root is unused, nothing within the scope can match.
+    let is_unused = parent_is_unused
+      || match &self.scope_start {
+        Some(scope_start) => is_unused(&mut scope_start.0.iter(), context.unused_symbols, parent_is_unused),
+        None => false,
+      };
+
+    self.rules.minify(context, is_unused)?;
+    Ok(self.rules.0.is_empty())
+  }
+}
+
+impl<'a, 'i> ToCssWithContext<'a, 'i> for ScopeRule<'i> {
+  fn to_css_with_context<W>(
+    &self,
+    dest: &mut Printer<W>,
+    context: Option<&StyleContext<'a, 'i>>,
+  ) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    let is_supported = match dest.targets {
+      Some(targets) => Feature::CssScope.is_compatible(targets),
+      None => true,
+    };
+
+    if !is_supported {
+      if self.scope_start.is_some() {
+        return self.rules.to_css_with_context(
+          dest,
+          Some(&StyleContext::Scope {
+            rule: self,
+            parent: context,
+          }),
+        );
+      }
+
+      dest.warn(
+        WarningKind::UnsupportedFeature {
+          feature: "@scope without a scope start".into(),
+        },
+        self.loc,
+      );
+    }
+
+    dest.add_mapping(self.loc);
+    dest.write_str("@scope")?;
+    if let Some(scope_start) = &self.scope_start {
+      dest.whitespace()?;
+      dest.write_char('(')?;
+      scope_start.to_css_with_context(dest, context)?;
+      dest.write_char(')')?;
+    }
+    if let Some(scope_end) = &self.scope_end {
+      dest.write_str(" to (")?;
+      scope_end.to_css_with_context(dest, context)?;
+      dest.write_char(')')?;
+    }
+    dest.whitespace()?;
+    dest.write_char('{')?;
+    dest.indent();
+    dest.newline()?;
+    self.rules.to_css_with_context(dest, context)?;
+    dest.dedent();
+    dest.newline()?;
+    dest.write_char('}')
+  }
+}
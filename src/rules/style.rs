@@ -5,17 +5,24 @@ use super::MinifyContext;
 use crate::compat::Feature;
 use crate::context::DeclarationContext;
 use crate::declaration::DeclarationBlock;
-use crate::error::{MinifyError, PrinterError, PrinterErrorKind};
-use crate::printer::Printer;
+use crate::error::{MinifyError, MinifyWarning, PrinterError, PrinterErrorKind, WarningKind};
+use crate::macros::{impl_into_owned, impl_visit};
+use crate::printer::{Printer, PrinterOptions};
 use crate::rules::{CssRuleList, StyleContext, ToCssWithContext};
 use crate::selector::{is_compatible, is_unused, Selectors};
 use crate::targets::Browsers;
-use crate::traits::ToCss;
+use crate::traits::{ToCss, Visit, Visitor};
+use crate::values::color::CssColor;
 use crate::vendor_prefix::VendorPrefix;
 use parcel_selectors::SelectorList;
 
 /// A CSS [style rule](https://drafts.csswg.org/css-syntax/#style-rules).
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct StyleRule<'i> {
   /// The selectors for the style rule.
   pub selectors: SelectorList<'i, Selectors>,
@@ -29,6 +36,9 @@ pub struct StyleRule<'i> {
   pub loc: Location,
 }
 
+impl_visit!(StyleRule<'i> { selectors, vendor_prefix: _, declarations, rules, loc: _ });
+impl_into_owned!(StyleRule<'i> { selectors, vendor_prefix, declarations, rules, loc });
+
 impl<'i> StyleRule<'i> {
   pub(crate) fn minify(
     &mut self,
@@ -44,10 +54,20 @@ impl<'i> StyleRule<'i> {
 
         self.declarations.declarations.clear();
         self.declarations.important_declarations.clear();
+        self.declarations.at_rules.clear();
         unused = true;
       }
     }
 
+    if let Some(targets) = *context.targets {
+      let mut visitor = LossyColorVisitor {
+        targets,
+        loc: self.loc,
+        warnings: context.warnings,
+      };
+      self.declarations.visit(&mut visitor);
+    }
+
     context.handler_context.context = DeclarationContext::StyleRule;
     self
       .declarations
@@ -132,7 +152,7 @@ impl<'a, 'i> StyleRule<'i> {
     let supports_nesting = self.rules.0.is_empty()
       || dest.targets.is_none()
       || Feature::CssNesting.is_compatible(dest.targets.unwrap());
-    let len = self.declarations.declarations.len() + self.declarations.important_declarations.len();
+    let len = self.declarations.len();
     let has_declarations = supports_nesting || len > 0 || self.rules.0.is_empty();
 
     if has_declarations {
@@ -143,9 +163,22 @@ impl<'a, 'i> StyleRule<'i> {
       dest.indent();
 
       let mut i = 0;
+      let mut at_rules = self.declarations.at_rules.iter().peekable();
+      macro_rules! write_at_rules {
+        ($index: expr) => {
+          while let Some(at_rule) = at_rules.next_if(|at_rule| at_rule.index <= $index) {
+            dest.newline()?;
+            at_rule.rule.to_css(dest)?;
+            i += 1;
+          }
+        };
+      }
+
       macro_rules! write {
         ($decls: ident, $important: literal) => {
-          for decl in &self.declarations.$decls {
+          for (index, decl) in self.declarations.$decls.iter().enumerate() {
+            write_at_rules!(index);
+
             // The CSS modules `composes` property is handled specially, and omitted during printing.
             // We need to add the classes it references to the list for the selectors in this rule.
             if let crate::properties::Property::Composes(composes) = &decl {
@@ -155,7 +188,7 @@ impl<'a, 'i> StyleRule<'i> {
 
               if let Some(css_module) = &mut dest.css_module {
                 css_module
-                  .handle_composes(&self.selectors, &composes)
+                  .handle_composes(&self.selectors, &composes, self.loc.source_index)
                   .map_err(|e| dest.error(e, composes.loc))?;
                 continue;
               }
@@ -173,6 +206,7 @@ impl<'a, 'i> StyleRule<'i> {
       }
 
       write!(declarations, false);
+      write_at_rules!(usize::MAX);
       write!(important_declarations, true);
     }
 
@@ -207,7 +241,7 @@ impl<'a, 'i> StyleRule<'i> {
       newline!();
       self.rules.to_css_with_context(
         dest,
-        Some(&StyleContext {
+        Some(&StyleContext::Style {
           rule: self,
           parent: context,
         }),
@@ -217,3 +251,23 @@ impl<'a, 'i> StyleRule<'i> {
     Ok(())
   }
 }
+
+/// Emits a warning for each color that will be gamut mapped when converted to an RGB fallback.
+struct LossyColorVisitor<'a> {
+  targets: Browsers,
+  loc: Location,
+  warnings: &'a mut Vec<MinifyWarning>,
+}
+
+impl<'a, 'i> Visitor<'i> for LossyColorVisitor<'a> {
+  fn visit_color(&mut self, color: &mut CssColor) {
+    if color.has_lossy_fallback(self.targets) {
+      self.warnings.push(MinifyWarning {
+        kind: WarningKind::LossyColorConversion {
+          color: color.to_css_string(PrinterOptions::default()).unwrap_or_default(),
+        },
+        loc: self.loc,
+      });
+    }
+  }
+}
@@ -3,6 +3,7 @@
 use super::Location;
 use super::{CssRuleList, MinifyContext};
 use crate::error::{MinifyError, ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::rules::{StyleContext, ToCssWithContext};
 use crate::traits::{Parse, ToCss};
@@ -11,6 +12,11 @@ use cssparser::*;
 
 /// A [@supports](https://drafts.csswg.org/css-conditional-3/#at-supports) rule.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct SupportsRule<'i> {
   /// The supports condition.
   pub condition: SupportsCondition<'i>,
@@ -20,6 +26,9 @@ pub struct SupportsRule<'i> {
   pub loc: Location,
 }
 
+impl_visit!(SupportsRule<'i> { condition: _, rules, loc: _ });
+impl_into_owned!(SupportsRule<'i> { condition, rules, loc });
+
 impl<'i> SupportsRule<'i> {
   pub(crate) fn minify(
     &mut self,
@@ -56,6 +65,11 @@ impl<'a, 'i> ToCssWithContext<'a, 'i> for SupportsRule<'i> {
 /// A [`<supports-condition>`](https://drafts.csswg.org/css-conditional-3/#typedef-supports-condition),
 /// as used in the `@supports` and `@import` rules.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum SupportsCondition<'i> {
   /// A `not` expression.
   Not(Box<SupportsCondition<'i>>),
@@ -74,6 +88,16 @@ pub enum SupportsCondition<'i> {
   Unknown(CowArcStr<'i>),
 }
 
+impl_into_owned!(SupportsCondition<'i> match {
+  Not(b),
+  And(v),
+  Or(v),
+  Declaration(c),
+  Selector(c),
+  Parens(b),
+  Unknown(c),
+});
+
 impl<'i> SupportsCondition<'i> {
   /// Combines the given supports condition into this one with an `and` expression.
   pub fn and(&mut self, b: &SupportsCondition<'i>) {
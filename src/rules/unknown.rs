@@ -0,0 +1,67 @@
+//! An unknown at-rule.
+
+use super::Location;
+use crate::error::PrinterError;
+use crate::macros::{impl_into_owned, impl_visit};
+use crate::printer::Printer;
+use crate::properties::custom::TokenList;
+use crate::traits::ToCss;
+use crate::values::string::CowArcStr;
+use cssparser::*;
+
+/// An unknown at-rule, stored as raw tokens.
+///
+/// This allows style sheets containing at-rules that are handled by other tools
+/// (e.g. `@tailwind` or `@apply`) to be parsed and printed back unchanged.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct UnknownAtRule<'i> {
+  /// The name of the at-rule (without the @).
+  pub name: CowArcStr<'i>,
+  /// The prelude of the rule.
+  pub prelude: TokenList<'i>,
+  /// The contents of the block, if any.
+  pub block: Option<TokenList<'i>>,
+  /// The location of the rule in the source file.
+  pub loc: Location,
+}
+
+impl_visit!(UnknownAtRule<'i> { name: _, prelude, block, loc: _ });
+impl_into_owned!(UnknownAtRule<'i> { name, prelude, block, loc });
+
+impl<'i> ToCss for UnknownAtRule<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    dest.add_mapping(self.loc);
+    dest.write_char('@')?;
+    serialize_identifier(&self.name, dest)?;
+
+    if !self.prelude.0.is_empty() {
+      dest.write_char(' ')?;
+      self.prelude.to_css_unknown_at_rule(dest)?;
+    }
+
+    if let Some(block) = &self.block {
+      dest.whitespace()?;
+      if block.0.is_empty() {
+        return dest.write_str("{}");
+      }
+
+      dest.write_char('{')?;
+      dest.indent();
+      dest.newline()?;
+      block.to_css_unknown_at_rule(dest)?;
+      dest.dedent();
+      dest.newline()?;
+      dest.write_char('}')
+    } else {
+      dest.write_char(';')
+    }
+  }
+}
@@ -3,12 +3,18 @@
 use super::Location;
 use crate::declaration::DeclarationBlock;
 use crate::error::PrinterError;
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::traits::ToCss;
 use crate::vendor_prefix::VendorPrefix;
 
 /// A [@viewport](https://drafts.csswg.org/css-device-adapt/#atviewport-rule) rule.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct ViewportRule<'i> {
   /// The vendor prefix for this rule, e.g. `@-ms-viewport`.
   pub vendor_prefix: VendorPrefix,
@@ -18,6 +24,9 @@ pub struct ViewportRule<'i> {
   pub loc: Location,
 }
 
+impl_visit!(ViewportRule<'i> { vendor_prefix: _, declarations, loc: _ });
+impl_into_owned!(ViewportRule<'i> { vendor_prefix, declarations, loc });
+
 impl<'i> ToCss for ViewportRule<'i> {
   fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
   where
@@ -1,16 +1,20 @@
 use crate::compat::Feature;
 use crate::error::{ParserError, PrinterError};
+use crate::macros::{enum_property, impl_into_owned};
 use crate::printer::Printer;
-use crate::rules::{StyleContext, ToCssWithContext};
+use crate::rules::{scope::ScopeRule, StyleContext, ToCssWithContext};
 use crate::stylesheet::PrinterOptions;
 use crate::targets::Browsers;
-use crate::traits::{Parse, ToCss};
+use crate::traits::{IntoOwned, Parse, ToCss, Visit, Visitor};
+use crate::values::string::CowArcStr;
 use crate::vendor_prefix::VendorPrefix;
-use crate::{macros::enum_property, values::string::CowArcStr};
 use cssparser::*;
 use parcel_selectors::{
-  attr::{AttrSelectorOperator, ParsedAttrSelectorOperation, ParsedCaseSensitivity},
-  parser::{Combinator, Component, Selector, SelectorImpl},
+  attr::{
+    AttrSelectorOperator, AttrSelectorWithOptionalNamespace, NamespaceConstraint, ParsedAttrSelectorOperation,
+    ParsedCaseSensitivity,
+  },
+  parser::{Combinator, Component, LocalName, Selector, SelectorImpl},
   SelectorList,
 };
 use std::collections::HashMap;
@@ -22,8 +26,11 @@ use std::fmt::Write;
 pub struct Selectors;
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SelectorString<'a>(pub CowArcStr<'a>);
 
+impl_into_owned!(SelectorString<'i> { 0 });
+
 impl<'a> std::convert::From<CowRcStr<'a>> for SelectorString<'a> {
   fn from(s: CowRcStr<'a>) -> SelectorString<'a> {
     SelectorString(s.into())
@@ -50,8 +57,11 @@ impl<'a> SelectorString<'a> {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SelectorIdent<'i>(pub CowArcStr<'i>);
 
+impl_into_owned!(SelectorIdent<'i> { 0 });
+
 impl<'a> std::convert::From<CowRcStr<'a>> for SelectorIdent<'a> {
   fn from(s: CowRcStr<'a>) -> SelectorIdent {
     SelectorIdent(s.into())
@@ -307,6 +317,11 @@ enum_property! {
 
 /// https://drafts.csswg.org/selectors-4/#structural-pseudos
 #[derive(Clone, Eq, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum PseudoClass<'i> {
   // https://drafts.csswg.org/selectors-4/#linguistic-pseudos
   Lang(Vec<CowArcStr<'i>>),
@@ -379,8 +394,63 @@ pub enum PseudoClass<'i> {
   Custom(CowArcStr<'i>),
 }
 
+impl_into_owned!(PseudoClass<'i> match {
+  Lang(v),
+  Dir(d),
+  Hover,
+  Active,
+  Focus,
+  FocusVisible,
+  FocusWithin,
+  Current,
+  Past,
+  Future,
+  Playing,
+  Paused,
+  Seeking,
+  Buffering,
+  Stalled,
+  Muted,
+  VolumeLocked,
+  Fullscreen(v),
+  Defined,
+  AnyLink(v),
+  Link,
+  LocalLink,
+  Target,
+  TargetWithin,
+  Visited,
+  Enabled,
+  Disabled,
+  ReadOnly(v),
+  ReadWrite(v),
+  PlaceholderShown(v),
+  Default,
+  Checked,
+  Indeterminate,
+  Blank,
+  Valid,
+  Invalid,
+  InRange,
+  OutOfRange,
+  Required,
+  Optional,
+  UserValid,
+  UserInvalid,
+  Autofill(v),
+  Local(b),
+  Global(b),
+  WebKitScrollbar(w),
+  Custom(c),
+});
+
 /// https://webkit.org/blog/363/styling-scrollbars/
 #[derive(Clone, Eq, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "kebab-case")
+)]
 pub enum WebKitScrollbarPseudoClass {
   Horizontal,
   Vertical,
@@ -395,6 +465,8 @@ pub enum WebKitScrollbarPseudoClass {
   WindowInactive,
 }
 
+impl_into_owned!(WebKitScrollbarPseudoClass);
+
 impl<'i> parcel_selectors::parser::NonTSPseudoClass<'i> for PseudoClass<'i> {
   type Impl = Selectors;
 
@@ -648,6 +720,11 @@ impl<'i> PseudoClass<'i> {
 }
 
 #[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum PseudoElement<'i> {
   After,
   Before,
@@ -662,7 +739,26 @@ pub enum PseudoElement<'i> {
   Custom(CowArcStr<'i>),
 }
 
+impl_into_owned!(PseudoElement<'i> match {
+  After,
+  Before,
+  FirstLine,
+  FirstLetter,
+  Selection(v),
+  Placeholder(v),
+  Marker,
+  Backdrop(v),
+  FileSelectorButton(v),
+  WebKitScrollbar(w),
+  Custom(c),
+});
+
 #[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "kebab-case")
+)]
 pub enum WebKitScrollbarPseudoElement {
   /// ::-webkit-scrollbar
   Scrollbar,
@@ -680,6 +776,8 @@ pub enum WebKitScrollbarPseudoElement {
   Resizer,
 }
 
+impl_into_owned!(WebKitScrollbarPseudoElement);
+
 impl<'i> cssparser::ToCss for PseudoElement<'i> {
   fn to_css<W>(&self, _: &mut W) -> std::fmt::Result
   where
@@ -837,7 +935,158 @@ impl<'a, 'i> ToCssWithContext<'a, 'i> for SelectorList<'i, Selectors> {
   where
     W: fmt::Write,
   {
-    serialize_selector_list(self.0.iter(), dest, context, false)
+    if let Some(StyleContext::Scope { .. }) = context {
+      // Selectors directly within a lowered @scope rule are restricted to the scope.
+      let mut first = true;
+      for selector in self.0.iter() {
+        if !first {
+          dest.delim(',', false)?;
+        }
+        first = false;
+        serialize_scoped_selector(selector, dest, context, true)?;
+      }
+      Ok(())
+    } else {
+      serialize_selector_list(self.0.iter(), dest, context, false)
+    }
+  }
+}
+
+impl<'i> Visit<'i> for SelectorList<'i, Selectors> {
+  fn visit<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    visitor.visit_selector_list(self)
+  }
+
+  fn visit_children<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    for selector in self.0.iter_mut() {
+      visit_selector(selector, visitor);
+    }
+  }
+}
+
+/// Visits the class names and ids of a selector, including within nested selectors.
+fn visit_selector<'i, V: Visitor<'i>>(selector: &mut Selector<'i, Selectors>, visitor: &mut V) {
+  for component in selector.iter_mut_raw_match_order() {
+    match component {
+      Component::Class(ident) | Component::ID(ident) => visitor.visit_selector_ident(ident),
+      Component::Is(list)
+      | Component::Where(list)
+      | Component::Any(_, list)
+      | Component::Negation(list)
+      | Component::Has(list) => {
+        for selector in list.iter_mut() {
+          visit_selector(selector, visitor);
+        }
+      }
+      Component::Slotted(selector) | Component::Host(Some(selector)) => visit_selector(selector, visitor),
+      Component::NonTSPseudoClass(PseudoClass::Local(selector) | PseudoClass::Global(selector)) => {
+        visit_selector(selector, visitor)
+      }
+      _ => {}
+    }
+  }
+}
+
+impl<'i, 'any> IntoOwned<'any> for SelectorList<'i, Selectors> {
+  type Owned = SelectorList<'any, Selectors>;
+
+  fn into_owned(self) -> Self::Owned {
+    SelectorList(self.0.into_owned())
+  }
+}
+
+impl<'i, 'any> IntoOwned<'any> for Selector<'i, Selectors> {
+  type Owned = Selector<'any, Selectors>;
+
+  fn into_owned(self) -> Self::Owned {
+    let (spec, components) = self.into_raw_parts();
+    Selector::from_raw_parts(spec, components.into_owned())
+  }
+}
+
+impl<'i, 'any> IntoOwned<'any> for Component<'i, Selectors> {
+  type Owned = Component<'any, Selectors>;
+
+  fn into_owned(self) -> Self::Owned {
+    match self {
+      Component::Combinator(c) => Component::Combinator(c),
+      Component::ExplicitAnyNamespace => Component::ExplicitAnyNamespace,
+      Component::ExplicitNoNamespace => Component::ExplicitNoNamespace,
+      Component::DefaultNamespace(url) => Component::DefaultNamespace(url.into_owned()),
+      Component::Namespace(prefix, url) => Component::Namespace(prefix.into_owned(), url.into_owned()),
+      Component::ExplicitUniversalType => Component::ExplicitUniversalType,
+      Component::LocalName(name) => Component::LocalName(LocalName {
+        name: name.name.into_owned(),
+        lower_name: name.lower_name.into_owned(),
+      }),
+      Component::ID(id) => Component::ID(id.into_owned()),
+      Component::Class(class) => Component::Class(class.into_owned()),
+      Component::AttributeInNoNamespaceExists {
+        local_name,
+        local_name_lower,
+      } => Component::AttributeInNoNamespaceExists {
+        local_name: local_name.into_owned(),
+        local_name_lower: local_name_lower.into_owned(),
+      },
+      Component::AttributeInNoNamespace {
+        local_name,
+        operator,
+        value,
+        case_sensitivity,
+        never_matches,
+      } => Component::AttributeInNoNamespace {
+        local_name: local_name.into_owned(),
+        operator,
+        value: value.into_owned(),
+        case_sensitivity,
+        never_matches,
+      },
+      Component::AttributeOther(attr) => Component::AttributeOther(Box::new(AttrSelectorWithOptionalNamespace {
+        namespace: attr.namespace.map(|namespace| match namespace {
+          NamespaceConstraint::Any => NamespaceConstraint::Any,
+          NamespaceConstraint::Specific(namespace) => NamespaceConstraint::Specific(namespace.into_owned()),
+        }),
+        local_name: attr.local_name.into_owned(),
+        local_name_lower: attr.local_name_lower.into_owned(),
+        operation: match attr.operation {
+          ParsedAttrSelectorOperation::Exists => ParsedAttrSelectorOperation::Exists,
+          ParsedAttrSelectorOperation::WithValue {
+            operator,
+            case_sensitivity,
+            expected_value,
+          } => ParsedAttrSelectorOperation::WithValue {
+            operator,
+            case_sensitivity,
+            expected_value: expected_value.into_owned(),
+          },
+        },
+        never_matches: attr.never_matches,
+      })),
+      Component::Negation(selectors) => Component::Negation(selectors.into_owned()),
+      Component::FirstChild => Component::FirstChild,
+      Component::LastChild => Component::LastChild,
+      Component::OnlyChild => Component::OnlyChild,
+      Component::Root => Component::Root,
+      Component::Empty => Component::Empty,
+      Component::Scope => Component::Scope,
+      Component::NthChild(a, b) => Component::NthChild(a, b),
+      Component::NthLastChild(a, b) => Component::NthLastChild(a, b),
+      Component::NthOfType(a, b) => Component::NthOfType(a, b),
+      Component::NthLastOfType(a, b) => Component::NthLastOfType(a, b),
+      Component::FirstOfType => Component::FirstOfType,
+      Component::LastOfType => Component::LastOfType,
+      Component::OnlyOfType => Component::OnlyOfType,
+      Component::NonTSPseudoClass(pseudo_class) => Component::NonTSPseudoClass(pseudo_class.into_owned()),
+      Component::Slotted(selector) => Component::Slotted(selector.into_owned()),
+      Component::Part(names) => Component::Part(names.into_owned()),
+      Component::Host(selector) => Component::Host(selector.into_owned()),
+      Component::Where(selectors) => Component::Where(selectors.into_owned()),
+      Component::Is(selectors) => Component::Is(selectors.into_owned()),
+      Component::Any(prefix, selectors) => Component::Any(prefix, selectors.into_owned()),
+      Component::Has(selectors) => Component::Has(selectors.into_owned()),
+      Component::PseudoElement(pseudo_element) => Component::PseudoElement(pseudo_element.into_owned()),
+      Component::Nesting => Component::Nesting,
+    }
   }
 }
 
@@ -866,7 +1115,7 @@ impl<'a, 'i> ToCssWithContext<'a, 'i> for parcel_selectors::parser::Selector<'i,
   where
     W: fmt::Write,
   {
-    serialize_selector(self, dest, context, false)
+    serialize_selector(self, dest, context, false, false)
   }
 }
 
@@ -875,6 +1124,7 @@ fn serialize_selector<'a, 'i, W>(
   dest: &mut Printer<W>,
   context: Option<&StyleContext<'a, 'i>>,
   mut is_relative: bool,
+  mut with_scope_limit: bool,
 ) -> Result<(), PrinterError>
 where
   W: fmt::Write,
@@ -910,6 +1160,11 @@ where
 
     // https://drafts.csswg.org/cssom/#serializing-selectors
     if compound.is_empty() {
+      // A leading pseudo element (e.g. ::before) still needs to be excluded from the scope limit.
+      if with_scope_limit {
+        serialize_scope_limit(dest, context)?;
+        with_scope_limit = false;
+      }
       continue;
     }
 
@@ -1015,6 +1270,13 @@ where
       }
     }
 
+    // When lowering @scope, exclude elements within the scope limit from the subject
+    // of the selector. This must come before any pseudo element.
+    if with_scope_limit && matches!(next_combinator, None | Some(Combinator::PseudoElement)) {
+      serialize_scope_limit(dest, context)?;
+      with_scope_limit = false;
+    }
+
     // 3. If this is not the last part of the chain of the selector
     //    append a single SPACE (U+0020), followed by the combinator
     //    ">", "+", "~", ">>", "||", as appropriate, followed by another
@@ -1120,6 +1382,10 @@ impl<'a, 'i> ToCssWithContext<'a, 'i> for Component<'i, Selectors> {
       NonTSPseudoClass(pseudo) => pseudo.to_css_with_context(dest, context),
       PseudoElement(pseudo) => pseudo.to_css(dest),
       Nesting => serialize_nesting(dest, context, false),
+      Scope => match find_scope(context) {
+        Some(scope) => serialize_scope_root(dest, scope),
+        None => dest.write_str(":scope"),
+      },
       Class(ref class) => {
         dest.write_char('.')?;
         dest.write_ident(&class.0)
@@ -1144,23 +1410,146 @@ fn serialize_nesting<W>(
 where
   W: fmt::Write,
 {
-  if let Some(ctx) = context {
-    // If there's only one simple selector, just serialize it directly.
-    // Otherwise, use an :is() pseudo class.
-    // Type selectors are only allowed at the start of a compound selector,
-    // so use :is() if that is not the case.
-    if ctx.rule.selectors.0.len() == 1
-      && (first || (!has_type_selector(&ctx.rule.selectors.0[0]) && is_simple(&ctx.rule.selectors.0[0])))
-    {
-      ctx.rule.selectors.0.first().unwrap().to_css_with_context(dest, ctx.parent)
-    } else {
-      dest.write_str(":is(")?;
-      serialize_selector_list(ctx.rule.selectors.0.iter(), dest, ctx.parent, false)?;
-      dest.write_char(')')
+  match context {
+    Some(StyleContext::Style { rule, parent }) => {
+      // If there's only one simple selector, just serialize it directly.
+      // Otherwise, use an :is() pseudo class.
+      // Type selectors are only allowed at the start of a compound selector,
+      // so use :is() if that is not the case. Selectors within a lowered @scope
+      // rule are never simple, since they include the scoping root.
+      let is_scoped = matches!(parent, Some(StyleContext::Scope { .. }));
+      if rule.selectors.0.len() == 1
+        && (first || (!is_scoped && !has_type_selector(&rule.selectors.0[0]) && is_simple(&rule.selectors.0[0])))
+      {
+        rule.selectors.to_css_with_context(dest, *parent)
+      } else {
+        dest.write_str(":is(")?;
+        rule.selectors.to_css_with_context(dest, *parent)?;
+        dest.write_char(')')
+      }
+    }
+    // Within @scope, & refers to the scoping root.
+    Some(StyleContext::Scope { rule, parent }) => serialize_scope_root(dest, (*rule, *parent)),
+    None => dest.write_char('&'),
+  }
+}
+
+/// Returns the nearest @scope rule that is being lowered, along with its parent context.
+fn find_scope<'a, 'i>(
+  mut context: Option<&'a StyleContext<'a, 'i>>,
+) -> Option<(&'a ScopeRule<'i>, Option<&'a StyleContext<'a, 'i>>)> {
+  while let Some(ctx) = context {
+    match ctx {
+      StyleContext::Style { parent, .. } => context = *parent,
+      StyleContext::Scope { rule, parent } => return Some((*rule, *parent)),
     }
+  }
+
+  None
+}
+
+/// Serializes a selector within a lowered @scope rule, optionally excluding elements within the scope limit.
+fn serialize_scoped_selector<'a, 'i, W>(
+  selector: &Selector<'i, Selectors>,
+  dest: &mut Printer<W>,
+  context: Option<&StyleContext<'a, 'i>>,
+  with_scope_limit: bool,
+) -> Result<(), PrinterError>
+where
+  W: fmt::Write,
+{
+  // Selectors that don't reference the scoping root via :scope or & are implicitly descendants of it.
+  if !references_scope(selector) {
+    if let Some(scope) = find_scope(context) {
+      serialize_scope_root(dest, scope)?;
+      dest.write_char(' ')?;
+    }
+  }
+
+  serialize_selector(selector, dest, context, false, with_scope_limit)
+}
+
+/// Serializes the scoping root of a lowered @scope rule, e.g. `:where(.card)`.
+fn serialize_scope_root<'a, 'i, W>(
+  dest: &mut Printer<W>,
+  (scope, parent): (&ScopeRule<'i>, Option<&StyleContext<'a, 'i>>),
+) -> Result<(), PrinterError>
+where
+  W: fmt::Write,
+{
+  dest.write_str(":where(")?;
+  if let Some(scope_start) = &scope.scope_start {
+    scope_start.to_css_with_context(dest, parent)?;
+  }
+  dest.write_char(')')
+}
+
+/// Serializes a :not() pseudo class that excludes the scope limit of a lowered @scope rule
+/// and all of its descendants, e.g. `:not(:where(.card) .content, :where(.card) .content *)`.
+fn serialize_scope_limit<'a, 'i, W>(
+  dest: &mut Printer<W>,
+  context: Option<&StyleContext<'a, 'i>>,
+) -> Result<(), PrinterError>
+where
+  W: fmt::Write,
+{
+  let scope_end = match context {
+    Some(StyleContext::Scope {
+      rule: ScopeRule {
+        scope_end: Some(scope_end),
+        ..
+      },
+      ..
+    }) => scope_end,
+    _ => return Ok(()),
+  };
+
+  // Downlevel :not(.a, .b) -> :not(.a):not(.b) if not list is unsupported.
+  let is_supported = if let Some(targets) = dest.targets {
+    Feature::CssNotSelList.is_compatible(targets)
   } else {
-    dest.write_char('&')
+    true
+  };
+
+  if is_supported {
+    dest.write_str(":not(")?;
   }
+
+  let mut first = true;
+  for selector in scope_end.0.iter() {
+    for descendants in [false, true] {
+      if !is_supported {
+        dest.write_str(":not(")?;
+      } else if !first {
+        dest.delim(',', false)?;
+      }
+      first = false;
+      serialize_scoped_selector(selector, dest, context, false)?;
+      if descendants {
+        dest.write_str(" *")?;
+      }
+      if !is_supported {
+        dest.write_char(')')?;
+      }
+    }
+  }
+
+  if is_supported {
+    dest.write_char(')')?;
+  }
+
+  Ok(())
+}
+
+/// Returns whether a selector references the scoping root via :scope or &.
+fn references_scope(selector: &Selector<Selectors>) -> bool {
+  selector.iter_raw_match_order().any(|component| match component {
+    Component::Scope | Component::Nesting => true,
+    Component::Is(list) | Component::Where(list) | Component::Negation(list) | Component::Any(_, list) => {
+      list.iter().any(references_scope)
+    }
+    _ => false,
+  })
 }
 
 #[inline]
@@ -1214,7 +1603,7 @@ where
       dest.delim(',', false)?;
     }
     first = false;
-    serialize_selector(selector, dest, context, is_relative)?;
+    serialize_selector(selector, dest, context, is_relative, false)?;
   }
   Ok(())
 }
@@ -1596,3 +1985,29 @@ pub fn is_unused(
     false
   })
 }
+
+/// Returns the first selector in the list that is not pure, as required by the CSS modules `pure` option,
+/// serialized without CSS modules applied.
+pub(crate) fn find_impure_css_modules_selector(selectors: &SelectorList<Selectors>) -> Option<String> {
+  let selector = selectors.0.iter().find(|selector| !is_pure_css_modules_selector(selector))?;
+  let mut s = String::new();
+  let mut printer = Printer::new(&mut s, PrinterOptions::default());
+  selector.to_css_with_context(&mut printer, None).ok()?;
+  Some(s)
+}
+
+/// Determines whether a selector is pure, as required by the CSS modules `pure` option.
+/// A selector is pure if it contains at least one local class or id component. Nesting
+/// selectors are also considered pure, since the parent rule is checked separately.
+fn is_pure_css_modules_selector(selector: &Selector<Selectors>) -> bool {
+  selector.iter_raw_match_order().any(|component| match component {
+    Component::Class(_) | Component::ID(_) | Component::Nesting => true,
+    Component::NonTSPseudoClass(PseudoClass::Local(selector)) => is_pure_css_modules_selector(selector),
+    Component::Is(list)
+    | Component::Where(list)
+    | Component::Any(_, list)
+    | Component::Negation(list)
+    | Component::Has(list) => list.iter().all(is_pure_css_modules_selector),
+    _ => false,
+  })
+}
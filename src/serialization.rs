@@ -0,0 +1,49 @@
+//! Serde support for types defined in external crates.
+
+use cssparser::{SourceLocation, UnicodeRange};
+
+/// Serializes a [SourceLocation](cssparser::SourceLocation) as an object with `line` and `column` fields.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(remote = "SourceLocation")]
+pub(crate) struct SourceLocationDef {
+  line: u32,
+  column: u32,
+}
+
+/// Serializes a list of [UnicodeRange](cssparser::UnicodeRange) values as objects with `start` and `end` fields.
+pub(crate) mod unicode_ranges {
+  use super::UnicodeRange;
+  use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+  #[derive(Serialize, Deserialize)]
+  struct UnicodeRangeDef {
+    start: u32,
+    end: u32,
+  }
+
+  pub fn serialize<S>(ranges: &[UnicodeRange], serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    serializer.collect_seq(ranges.iter().map(|range| UnicodeRangeDef {
+      start: range.start,
+      end: range.end,
+    }))
+  }
+
+  pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<UnicodeRange>, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    let ranges = Vec::<UnicodeRangeDef>::deserialize(deserializer)?;
+    Ok(
+      ranges
+        .into_iter()
+        .map(|range| UnicodeRange {
+          start: range.start,
+          end: range.end,
+        })
+        .collect(),
+    )
+  }
+}
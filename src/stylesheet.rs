@@ -5,12 +5,14 @@
 
 use crate::compat::Feature;
 use crate::context::{DeclarationContext, PropertyHandlerContext};
-use crate::css_modules::{hash, CssModule, CssModuleExports};
+use crate::css_modules::{hash, CssModule, CssModuleExports, CssModuleReferences};
 use crate::declaration::{DeclarationBlock, DeclarationHandler};
 use crate::dependencies::Dependency;
-use crate::error::{Error, ErrorLocation, MinifyErrorKind, ParserError, PrinterError, PrinterErrorKind};
-use crate::parser::TopLevelRuleParser;
+use crate::error::{Error, ErrorLocation, MinifyErrorKind, ParserError, PrinterError, PrinterErrorKind, Warning};
+use crate::macros::{impl_into_owned, impl_visit};
+use crate::parser::{collect_warnings, warn, TopLevelRuleParser};
 use crate::printer::Printer;
+use crate::rules::icss::replace_values;
 use crate::rules::{CssRule, CssRuleList, MinifyContext};
 use crate::targets::Browsers;
 use crate::traits::ToCss;
@@ -57,16 +59,53 @@ pub use crate::printer::PseudoClasses;
 /// assert_eq!(res.code, ".foo, .bar {\n  color: red;\n}\n");
 /// ```
 #[derive(Debug)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct StyleSheet<'i> {
   /// A list of top-level rules within the style sheet.
   pub rules: CssRuleList<'i>,
   /// A list of file names for all source files included within the style sheet.
   /// Sources are referenced by index in the `loc` property of each rule.
   pub sources: Vec<String>,
+  /// A list of content hashes for all source files included within the style sheet.
+  /// This is only set if the CSS modules pattern includes the `[content-hash]` placeholder.
+  #[cfg_attr(feature = "serde", serde(skip))]
+  pub(crate) content_hashes: Option<Vec<String>>,
+  /// For each source file, the source index of the file that defines its CSS module scope.
+  /// This is only set when bundling CSS modules, where files referenced via `composes` are
+  /// compiled with their own scope rather than the scope of the entry file.
+  #[cfg_attr(feature = "serde", serde(skip))]
+  pub(crate) css_module_scopes: Option<Vec<u32>>,
+  /// The source index of the file whose CSS module exports are returned from `to_css`.
+  /// This is the entry file of a bundle.
+  #[cfg_attr(feature = "serde", serde(skip))]
+  pub(crate) css_module_entry: u32,
   /// The options the style sheet was originally parsed with.
+  #[cfg_attr(feature = "serde", serde(skip))]
   options: ParserOptions,
+  /// Invalid rules and declarations that were skipped while parsing, due to the `error_recovery` option.
+  #[cfg_attr(feature = "serde", serde(skip))]
+  pub parser_warnings: Vec<Error<ParserError<'i>>>,
+  /// Warnings emitted while bundling and minifying the style sheet.
+  #[cfg_attr(feature = "serde", serde(skip))]
+  pub(crate) warnings: Vec<Warning>,
 }
 
+impl_visit!(StyleSheet<'i> {
+  rules,
+  sources: _,
+  content_hashes: _,
+  css_module_scopes: _,
+  css_module_entry: _,
+  options: _,
+  parser_warnings: _,
+  warnings: _
+});
+impl_into_owned!(StyleSheet<'i> { rules, sources, content_hashes, css_module_scopes, css_module_entry, options, parser_warnings, warnings });
+
 /// Options for the `minify` function of a [StyleSheet](StyleSheet)
 /// or [StyleAttribute](StyleAttribute).
 #[derive(Default)]
@@ -86,22 +125,37 @@ pub struct ToCssResult {
   /// A map of CSS module exports, if the `css_modules` option was
   /// enabled during parsing.
   pub exports: Option<CssModuleExports>,
+  /// A map of placeholders to references in other files, if the `css_modules`
+  /// option was enabled during parsing.
+  pub references: Option<CssModuleReferences>,
   /// A list of dependencies (e.g. `@import` or `url()`) found in
   /// the style sheet, if the `analyze_dependencies` option is enabled.
   pub dependencies: Option<Vec<Dependency>>,
+  /// A list of warnings emitted while bundling, minifying and printing, e.g. for
+  /// features that could not be lowered for the browser targets.
+  pub warnings: Vec<Warning>,
 }
 
 impl<'i> StyleSheet<'i> {
   /// Creates a new style sheet with the given source filenames and rules.
-  pub fn new(sources: Vec<String>, rules: CssRuleList, options: ParserOptions) -> StyleSheet {
+  pub fn new(sources: Vec<String>, rules: CssRuleList<'i>, options: ParserOptions) -> StyleSheet<'i> {
     StyleSheet {
       sources,
       rules,
+      content_hashes: None,
+      css_module_scopes: None,
+      css_module_entry: 0,
       options,
+      parser_warnings: Vec::new(),
+      warnings: Vec::new(),
     }
   }
 
   /// Parse a style sheet from a string.
+  ///
+  /// If the `error_recovery` option is enabled, invalid rules and declarations are skipped,
+  /// and the errors are returned as the [parser_warnings](StyleSheet::parser_warnings) of the
+  /// style sheet rather than as an error.
   pub fn parse(
     filename: &str,
     code: &'i str,
@@ -110,23 +164,49 @@ impl<'i> StyleSheet<'i> {
     let filename = String::from(filename);
     let mut input = ParserInput::new(&code);
     let mut parser = Parser::new(&mut input);
-    let rule_list_parser = RuleListParser::new_for_stylesheet(&mut parser, TopLevelRuleParser::new(&options));
-
-    let mut rules = vec![];
-    for rule in rule_list_parser {
-      let rule = match rule {
-        Ok((_, CssRule::Ignored)) => continue,
-        Ok((_, rule)) => rule,
-        Err((e, _)) => return Err(Error::from(e, filename)),
-      };
+    let (rules, parser_warnings) = collect_warnings(&filename, || {
+      let rule_list_parser = RuleListParser::new_for_stylesheet(&mut parser, TopLevelRuleParser::new(&options));
+
+      let mut rules = vec![];
+      for rule in rule_list_parser {
+        let rule = match rule {
+          Ok((_, CssRule::Ignored)) => continue,
+          Ok((_, rule)) => rule,
+          Err((e, _)) => {
+            if options.error_recovery {
+              warn(e);
+              continue;
+            }
 
-      rules.push(rule)
+            return Err(e);
+          }
+        };
+
+        rules.push(rule)
+      }
+
+      Ok(rules)
+    });
+
+    let mut rules = CssRuleList(rules.map_err(|e| Error::from(e, filename.clone()))?);
+    if options.css_modules.is_some() {
+      replace_values(&mut rules, &options);
     }
 
+    let content_hashes = match &options.css_modules {
+      Some(config) if config.pattern.has_content_hash() => Some(vec![hash(code)]),
+      _ => None,
+    };
+
     Ok(StyleSheet {
       sources: vec![filename],
-      rules: CssRuleList(rules),
+      rules,
+      content_hashes,
+      css_module_scopes: None,
+      css_module_entry: 0,
       options,
+      parser_warnings,
+      warnings: Vec::new(),
     })
   }
 
@@ -153,6 +233,7 @@ impl<'i> StyleSheet<'i> {
       None
     };
 
+    let mut warnings = Vec::new();
     let mut ctx = MinifyContext {
       targets: &options.targets,
       handler: &mut handler,
@@ -160,6 +241,7 @@ impl<'i> StyleSheet<'i> {
       handler_context: &mut context,
       unused_symbols: &options.unused_symbols,
       custom_media,
+      warnings: &mut warnings,
     };
 
     self.rules.minify(&mut ctx, false).map_err(|e| Error {
@@ -170,10 +252,18 @@ impl<'i> StyleSheet<'i> {
       )),
     })?;
 
+    let sources = &self.sources;
+    self.warnings.extend(warnings.into_iter().map(|w| Warning {
+      kind: w.kind,
+      loc: Some(ErrorLocation::new(w.loc, sources[w.loc.source_index as usize].clone())),
+    }));
+
     Ok(())
   }
 
   /// Serialize the style sheet to a CSS string.
+  ///
+  /// Any warnings emitted during minification or printing are returned as part of the result.
   pub fn to_css(&self, options: PrinterOptions) -> Result<ToCssResult, Error<PrinterErrorKind>> {
     // Make sure we always have capacity > 0: https://github.com/napi-rs/napi-rs/issues/1124.
     let mut dest = String::with_capacity(1);
@@ -181,29 +271,38 @@ impl<'i> StyleSheet<'i> {
 
     printer.sources = Some(&self.sources);
 
-    if self.options.css_modules {
-      let h = hash(printer.filename());
+    if let Some(config) = &self.options.css_modules {
       let mut exports = HashMap::new();
-      printer.css_module = Some(CssModule {
-        hash: &h,
-        exports: &mut exports,
-      });
+      let mut references = HashMap::new();
+      printer.css_module = Some(CssModule::new(
+        config,
+        &self.sources,
+        self.css_module_scopes.as_ref(),
+        self.content_hashes.as_ref(),
+        self.css_module_entry,
+        &mut exports,
+        &mut references,
+      ));
 
       self.rules.to_css(&mut printer)?;
       printer.newline()?;
 
       Ok(ToCssResult {
         dependencies: printer.dependencies,
+        warnings: self.warnings.iter().cloned().chain(printer.warnings).collect(),
         code: dest,
         exports: Some(exports),
+        references: Some(references),
       })
     } else {
       self.rules.to_css(&mut printer)?;
       printer.newline()?;
       Ok(ToCssResult {
         dependencies: printer.dependencies,
+        warnings: self.warnings.iter().cloned().chain(printer.warnings).collect(),
         code: dest,
         exports: None,
+        references: None,
       })
     }
   }
@@ -233,11 +332,15 @@ impl<'i> StyleSheet<'i> {
 /// let res = style.to_css(PrinterOptions::default()).unwrap();
 /// assert_eq!(res.code, "color: #ff0; font-family: Helvetica");
 /// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StyleAttribute<'i> {
   /// The declarations in the style attribute.
   pub declarations: DeclarationBlock<'i>,
 }
 
+impl_visit!(StyleAttribute<'i> { declarations });
+impl_into_owned!(StyleAttribute<'i> { declarations });
+
 impl<'i> StyleAttribute<'i> {
   /// Parses a style attribute from a string.
   pub fn parse(code: &'i str) -> Result<StyleAttribute, Error<ParserError<'i>>> {
@@ -269,12 +372,26 @@ impl<'i> StyleAttribute<'i> {
     let mut dest = String::with_capacity(1);
     let mut printer = Printer::new(&mut dest, options);
 
-    let len = self.declarations.declarations.len() + self.declarations.important_declarations.len();
+    let len = self.declarations.len();
     let mut i = 0;
+    let mut at_rules = self.declarations.at_rules.iter().peekable();
+
+    macro_rules! write_at_rules {
+      ($index: expr) => {
+        while let Some(at_rule) = at_rules.next_if(|at_rule| at_rule.index <= $index) {
+          at_rule.rule.to_css(&mut printer)?;
+          if i != len - 1 {
+            printer.whitespace()?;
+          }
+          i += 1;
+        }
+      };
+    }
 
     macro_rules! write {
       ($decls: expr, $important: literal) => {
-        for decl in &$decls {
+        for (index, decl) in $decls.iter().enumerate() {
+          write_at_rules!(index);
           decl.to_css(&mut printer, $important)?;
           if i != len - 1 {
             printer.write_char(';')?;
@@ -286,12 +403,15 @@ impl<'i> StyleAttribute<'i> {
     }
 
     write!(self.declarations.declarations, false);
+    write_at_rules!(usize::MAX);
     write!(self.declarations.important_declarations, true);
 
     Ok(ToCssResult {
       dependencies: printer.dependencies,
+      warnings: printer.warnings,
       code: dest,
       exports: None,
+      references: None,
     })
   }
 }
@@ -1,13 +1,25 @@
 //! Traits for parsing and serializing CSS.
 
 use crate::context::PropertyHandlerContext;
-use crate::declaration::DeclarationList;
+use crate::declaration::{DeclarationBlock, DeclarationList};
 use crate::error::{ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
+use crate::media_query::MediaQuery;
 use crate::printer::Printer;
 use crate::properties::Property;
+use crate::rules::{CssRule, Location};
+use crate::selector::{SelectorIdent, Selectors};
 use crate::stylesheet::PrinterOptions;
 use crate::targets::Browsers;
+use crate::values::color::CssColor;
+use crate::values::ident::{CustomIdent, DashedIdent};
+use crate::values::length::LengthValue;
+use crate::values::string::CowArcStr;
+use crate::values::url::Url;
+use crate::vendor_prefix::VendorPrefix;
 use cssparser::*;
+use parcel_selectors::SelectorList;
+use smallvec::SmallVec;
 
 /// Trait for things that can be parsed from CSS syntax.
 pub trait Parse<'i>: Sized {
@@ -80,3 +92,240 @@ pub(crate) trait FromStandard<T>: Sized {
 pub(crate) trait FallbackValues: Sized {
   fn get_fallbacks(&mut self, targets: Browsers) -> Vec<Self>;
 }
+
+/// Trait for visiting and mutating the nodes of a CSS AST.
+///
+/// Each method is a hook that is called when a node of the corresponding type is reached.
+/// The default implementations continue the traversal into the node's children, so
+/// implementors only need to override the hooks they are interested in. Overridden hooks
+/// can call `visit_children` on the node to keep descending.
+///
+/// # Example
+///
+/// This example rewrites every `url()` in a style sheet to an absolute URL.
+///
+/// ```
+/// use parcel_css::{
+///   stylesheet::{StyleSheet, ParserOptions, PrinterOptions},
+///   traits::{Visit, Visitor},
+///   values::url::Url,
+/// };
+///
+/// struct UrlRewriter;
+///
+/// impl<'i> Visitor<'i> for UrlRewriter {
+///   fn visit_url(&mut self, url: &mut Url<'i>) {
+///     url.url = format!("https://example.com/{}", url.url).into();
+///   }
+/// }
+///
+/// let mut stylesheet = StyleSheet::parse(
+///   "test.css",
+///   ".foo { background: url(img.png) }",
+///   ParserOptions::default()
+/// ).unwrap();
+///
+/// stylesheet.visit(&mut UrlRewriter);
+///
+/// let res = stylesheet.to_css(PrinterOptions { minify: true, ..PrinterOptions::default() }).unwrap();
+/// assert_eq!(res.code, ".foo{background:url(https://example.com/img.png)}");
+/// ```
+#[allow(unused_variables)]
+pub trait Visitor<'i>: Sized {
+  /// Visits a rule. Rules can be removed by replacing them with [CssRule::Ignored](CssRule::Ignored).
+  fn visit_rule(&mut self, rule: &mut CssRule<'i>) {
+    rule.visit_children(self)
+  }
+
+  /// Visits the selectors of a style rule.
+  fn visit_selector_list(&mut self, selectors: &mut SelectorList<'i, Selectors>) {
+    selectors.visit_children(self)
+  }
+
+  /// Visits a class name or id in a selector, including within nested selectors such as `:not()`.
+  fn visit_selector_ident(&mut self, ident: &mut SelectorIdent<'i>) {}
+
+  /// Visits a declaration block, e.g. the body of a style rule.
+  fn visit_declaration_block(&mut self, declarations: &mut DeclarationBlock<'i>) {
+    declarations.visit_children(self)
+  }
+
+  /// Visits a property declaration.
+  fn visit_property(&mut self, property: &mut Property<'i>) {
+    property.visit_children(self)
+  }
+
+  /// Visits a media query, e.g. in a `@media` or `@import` rule.
+  fn visit_media_query(&mut self, query: &mut MediaQuery<'i>) {
+    query.visit_children(self)
+  }
+
+  /// Visits a length value.
+  fn visit_length(&mut self, length: &mut LengthValue) {}
+
+  /// Visits a color value.
+  fn visit_color(&mut self, color: &mut CssColor) {}
+
+  /// Visits a `url()` value.
+  fn visit_url(&mut self, url: &mut Url<'i>) {}
+
+  /// Visits a `<custom-ident>`, e.g. an animation or counter style name.
+  fn visit_custom_ident(&mut self, ident: &mut CustomIdent<'i>) {}
+
+  /// Visits a `<dashed-ident>`, e.g. a custom media query or font palette name.
+  fn visit_dashed_ident(&mut self, ident: &mut DashedIdent<'i>) {}
+}
+
+/// Trait for AST nodes that can be traversed by a [Visitor](Visitor).
+pub trait Visit<'i> {
+  /// Visits this node, calling the matching hook of the visitor if there is one,
+  /// and otherwise visiting its children.
+  fn visit<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    self.visit_children(visitor)
+  }
+
+  /// Visits the children of this node.
+  fn visit_children<V: Visitor<'i>>(&mut self, visitor: &mut V);
+}
+
+impl<'i, T: Visit<'i>> Visit<'i> for Vec<T> {
+  fn visit_children<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    for item in self.iter_mut() {
+      item.visit(visitor)
+    }
+  }
+}
+
+impl<'i, T: Visit<'i>, A: smallvec::Array<Item = T>> Visit<'i> for SmallVec<A> {
+  fn visit_children<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    for item in self.iter_mut() {
+      item.visit(visitor)
+    }
+  }
+}
+
+impl<'i, T: Visit<'i>> Visit<'i> for Option<T> {
+  fn visit_children<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    if let Some(value) = self {
+      value.visit(visitor)
+    }
+  }
+}
+
+impl<'i, T: Visit<'i>> Visit<'i> for Box<T> {
+  fn visit_children<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    self.as_mut().visit(visitor)
+  }
+}
+
+impl<'i, A: Visit<'i>, B: Visit<'i>> Visit<'i> for (A, B) {
+  fn visit_children<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    self.0.visit(visitor);
+    self.1.visit(visitor);
+  }
+}
+
+impl_visit!(
+  f32,
+  i32,
+  u16,
+  u32,
+  usize,
+  bool,
+  String,
+  CowArcStr<'i>,
+  VendorPrefix,
+  Location,
+  SourceLocation
+);
+
+/// Trait for AST nodes that can be converted into a version that does not borrow from the
+/// source string, e.g. in order to cache a parsed style sheet after the source is dropped.
+///
+/// # Example
+///
+/// ```
+/// use parcel_css::{
+///   stylesheet::{StyleSheet, ParserOptions, PrinterOptions},
+///   traits::IntoOwned,
+/// };
+///
+/// let source = String::from(".foo { color: red }");
+/// let stylesheet: StyleSheet<'static> =
+///   StyleSheet::parse("test.css", &source, ParserOptions::default()).unwrap().into_owned();
+/// drop(source);
+///
+/// let res = stylesheet.to_css(PrinterOptions { minify: true, ..PrinterOptions::default() }).unwrap();
+/// assert_eq!(res.code, ".foo{color:red}");
+/// ```
+pub trait IntoOwned<'any> {
+  /// The owned version of the type.
+  type Owned: 'any;
+
+  /// Converts the value into an owned version, copying any strings that are borrowed
+  /// from the source.
+  fn into_owned(self) -> Self::Owned;
+}
+
+impl<'any, T: IntoOwned<'any>> IntoOwned<'any> for Vec<T> {
+  type Owned = Vec<T::Owned>;
+
+  fn into_owned(self) -> Self::Owned {
+    self.into_iter().map(|item| item.into_owned()).collect()
+  }
+}
+
+impl<'any, T: IntoOwned<'any>> IntoOwned<'any> for SmallVec<[T; 1]> {
+  type Owned = SmallVec<[T::Owned; 1]>;
+
+  fn into_owned(self) -> Self::Owned {
+    self.into_iter().map(|item| item.into_owned()).collect()
+  }
+}
+
+impl<'any, T: IntoOwned<'any>> IntoOwned<'any> for Option<T> {
+  type Owned = Option<T::Owned>;
+
+  fn into_owned(self) -> Self::Owned {
+    self.map(|value| value.into_owned())
+  }
+}
+
+impl<'any, T: IntoOwned<'any>> IntoOwned<'any> for Box<T> {
+  type Owned = Box<T::Owned>;
+
+  fn into_owned(self) -> Self::Owned {
+    Box::new((*self).into_owned())
+  }
+}
+
+impl<'any, T: IntoOwned<'any>> IntoOwned<'any> for Box<[T]> {
+  type Owned = Box<[T::Owned]>;
+
+  fn into_owned(self) -> Self::Owned {
+    self.into_vec().into_owned().into_boxed_slice()
+  }
+}
+
+impl<'any, A: IntoOwned<'any>, B: IntoOwned<'any>> IntoOwned<'any> for (A, B) {
+  type Owned = (A::Owned, B::Owned);
+
+  fn into_owned(self) -> Self::Owned {
+    (self.0.into_owned(), self.1.into_owned())
+  }
+}
+
+impl_into_owned!(
+  f32,
+  i32,
+  u16,
+  u32,
+  usize,
+  bool,
+  char,
+  String,
+  VendorPrefix,
+  Location,
+  SourceLocation,
+  UnicodeRange
+);
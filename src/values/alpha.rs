@@ -2,6 +2,7 @@
 
 use super::percentage::NumberOrPercentage;
 use crate::error::{ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::traits::{Parse, ToCss};
 use cssparser::*;
@@ -11,8 +12,12 @@ use cssparser::*;
 ///
 /// Parses either a `<number>` or `<percentage>`, but is always stored and serialized as a number.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AlphaValue(pub f32);
 
+impl_visit!(AlphaValue);
+impl_into_owned!(AlphaValue);
+
 impl<'i> Parse<'i> for AlphaValue {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     match NumberOrPercentage::parse(input)? {
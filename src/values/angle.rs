@@ -5,6 +5,7 @@ use super::length::serialize_dimension;
 use super::number::CSSNumber;
 use super::percentage::DimensionPercentage;
 use crate::error::{ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::traits::{private::TryAdd, Parse, ToCss};
 use cssparser::*;
@@ -15,6 +16,11 @@ use std::f32::consts::PI;
 /// Angles may be explicit or computed by `calc()`, but are always stored and serialized
 /// as their computed value.
 #[derive(Debug, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Angle {
   /// An angle in degrees. There are 360 degrees in a full circle.
   Deg(CSSNumber),
@@ -26,6 +32,9 @@ pub enum Angle {
   Turn(CSSNumber),
 }
 
+impl_visit!(Angle);
+impl_into_owned!(Angle);
+
 impl<'i> Parse<'i> for Angle {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     match input.try_parse(Calc::parse) {
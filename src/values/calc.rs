@@ -3,7 +3,7 @@
 use crate::compat::Feature;
 use crate::error::{ParserError, PrinterError};
 use crate::printer::Printer;
-use crate::traits::{Parse, ToCss};
+use crate::traits::{Parse, ToCss, Visit, Visitor};
 use cssparser::*;
 
 use super::number::CSSNumber;
@@ -13,6 +13,11 @@ use super::number::CSSNumber;
 /// Math functions may be used in most properties and values that accept numeric
 /// values, including lengths, percentages, angles, times, etc.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum MathFunction<V> {
   /// The [`calc()`](https://www.w3.org/TR/css-values-4/#calc-func) function.
   Calc(Calc<V>),
@@ -24,6 +29,20 @@ pub enum MathFunction<V> {
   Clamp(Calc<V>, Calc<V>, Calc<V>),
 }
 
+impl<'i, T: Visit<'i>> Visit<'i> for MathFunction<T> {
+  fn visit_children<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    match self {
+      MathFunction::Calc(calc) => calc.visit(visitor),
+      MathFunction::Min(values) | MathFunction::Max(values) => values.visit(visitor),
+      MathFunction::Clamp(a, b, c) => {
+        a.visit(visitor);
+        b.visit(visitor);
+        c.visit(visitor);
+      }
+    }
+  }
+}
+
 impl<V: ToCss + std::cmp::PartialOrd<f32> + std::ops::Mul<f32, Output = V> + Clone + std::fmt::Debug> ToCss
   for MathFunction<V>
 {
@@ -96,6 +115,11 @@ impl<V: ToCss + std::cmp::PartialOrd<f32> + std::ops::Mul<f32, Output = V> + Clo
 /// This type supports generic value types. Values such as [Length](super::length::Length), [Percentage](super::percentage::Percentage),
 /// [Time](super::time::Time), and [Angle](super::angle::Angle) support `calc()` expressions.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Calc<V> {
   /// A literal value.
   Value(Box<V>),
@@ -109,6 +133,21 @@ pub enum Calc<V> {
   Function(Box<MathFunction<V>>),
 }
 
+impl<'i, T: Visit<'i>> Visit<'i> for Calc<T> {
+  fn visit_children<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    match self {
+      Calc::Value(v) => v.visit(visitor),
+      Calc::Number(_) => {}
+      Calc::Sum(a, b) => {
+        a.visit(visitor);
+        b.visit(visitor);
+      }
+      Calc::Product(_, v) => v.visit(visitor),
+      Calc::Function(f) => f.visit(visitor),
+    }
+  }
+}
+
 impl<
     'i,
     V: Parse<'i>
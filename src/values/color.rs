@@ -3,11 +3,11 @@
 use super::percentage::Percentage;
 use crate::compat::Feature;
 use crate::error::{ParserError, PrinterError};
-use crate::macros::enum_property;
+use crate::macros::{enum_property, impl_into_owned};
 use crate::printer::Printer;
 use crate::rules::supports::SupportsCondition;
 use crate::targets::Browsers;
-use crate::traits::{FallbackValues, Parse, ToCss};
+use crate::traits::{FallbackValues, Parse, ToCss, Visit, Visitor};
 use bitflags::bitflags;
 use cssparser::*;
 use std::any::TypeId;
@@ -24,12 +24,19 @@ use std::fmt::Write;
 /// for all other color spaces, so it is possible to convert between color spaces easily.
 /// In addition, colors support [interpolation](#method.interpolate) as in the `color-mix()` function.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum CssColor {
   /// The [`currentColor`](https://www.w3.org/TR/css-color-4/#currentcolor-color) keyword.
   CurrentColor,
   /// An value in the RGB color space, including values parsed as hex colors, or the `rgb()`, `hsl()`, and `hwb()` functions.
+  #[cfg_attr(feature = "serde", serde(rename = "rgba"))]
   RGBA(RGBA),
   /// A value in a LAB color space, including the `lab()`, `lch()`, `oklab()`, and `oklch()` functions.
+  #[cfg_attr(feature = "serde", serde(rename = "lab"))]
   LAB(Box<LABColor>),
   /// A value in a predefined color space, e.g. `display-p3`.
   Predefined(Box<PredefinedColor>),
@@ -37,25 +44,51 @@ pub enum CssColor {
   Float(Box<FloatColor>),
 }
 
+impl<'i> Visit<'i> for CssColor {
+  fn visit<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    visitor.visit_color(self)
+  }
+
+  fn visit_children<V: Visitor<'i>>(&mut self, _: &mut V) {}
+}
+
+impl_into_owned!(CssColor);
+
 /// A color in a LAB color space, including the `lab()`, `lch()`, `oklab()`, and `oklch()` functions.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum LABColor {
   /// A `lab()` color.
+  #[cfg_attr(feature = "serde", serde(rename = "lab"))]
   LAB(LAB),
   /// An `lch()` color.
+  #[cfg_attr(feature = "serde", serde(rename = "lch"))]
   LCH(LCH),
   /// An `oklab()` color.
+  #[cfg_attr(feature = "serde", serde(rename = "oklab"))]
   OKLAB(OKLAB),
   /// An `oklch()` color.
+  #[cfg_attr(feature = "serde", serde(rename = "oklch"))]
   OKLCH(OKLCH),
 }
 
 /// A color in a predefined color space, e.g. `display-p3`.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum PredefinedColor {
   /// A color in the `srgb` color space.
+  #[cfg_attr(feature = "serde", serde(rename = "srgb"))]
   SRGB(SRGB),
   /// A color in the `srgb-linear` color space.
+  #[cfg_attr(feature = "serde", serde(rename = "srgb-linear"))]
   SRGBLinear(SRGBLinear),
   /// A color in the `display-p3` color space.
   DisplayP3(P3),
@@ -66,8 +99,10 @@ pub enum PredefinedColor {
   /// A color in the `rec2020` color space.
   Rec2020(Rec2020),
   /// A color in the `xyz-d50` color space.
+  #[cfg_attr(feature = "serde", serde(rename = "xyz-d50"))]
   XYZd50(XYZd50),
   /// A color in the `xyz-d65` color space.
+  #[cfg_attr(feature = "serde", serde(rename = "xyz-d65"))]
   XYZd65(XYZd65),
 }
 
@@ -75,12 +110,20 @@ pub enum PredefinedColor {
 /// are usually stored as RGBA. These are used when there
 /// are any `none` components, which are represented as NaN.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum FloatColor {
   /// An RGB color.
+  #[cfg_attr(feature = "serde", serde(rename = "rgb"))]
   RGB(SRGB),
   /// An HSL color.
+  #[cfg_attr(feature = "serde", serde(rename = "hsl"))]
   HSL(HSL),
   /// An HWB color.
+  #[cfg_attr(feature = "serde", serde(rename = "hwb"))]
   HWB(HWB),
 }
 
@@ -254,6 +297,16 @@ impl CssColor {
     fallbacks - fallbacks.highest()
   }
 
+  /// Returns whether the RGB fallback needed for the given browser targets is lossy,
+  /// i.e. the color is outside the sRGB gamut and must be gamut mapped.
+  pub(crate) fn has_lossy_fallback(&self, targets: Browsers) -> bool {
+    if !self.get_possible_fallbacks(targets).contains(ColorFallbackKind::RGB) {
+      return false;
+    }
+
+    !SRGB::from(self).resolve_missing().in_gamut()
+  }
+
   /// Returns a fallback color for the given fallback type.
   pub fn get_fallback(&self, kind: ColorFallbackKind) -> CssColor {
     if matches!(self, CssColor::RGBA(_)) {
@@ -784,6 +837,7 @@ macro_rules! define_colorspace {
   ) => {
     $(#[$outer])*
     #[derive(Debug, Clone, Copy, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct $name {
       $(#[$a_meta])*
       pub $a: f32,
@@ -1,6 +1,7 @@
 //! CSS easing functions.
 
 use crate::error::{ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::traits::{Parse, ToCss};
 use crate::values::number::{CSSInteger, CSSNumber};
@@ -9,6 +10,11 @@ use std::fmt::Write;
 
 /// A CSS [easing function](https://www.w3.org/TR/css-easing-1/#easing-functions).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum EasingFunction {
   /// A linear easing function.
   Linear,
@@ -31,6 +37,9 @@ pub enum EasingFunction {
   Steps(CSSInteger, StepPosition),
 }
 
+impl_visit!(EasingFunction);
+impl_into_owned!(EasingFunction);
+
 impl<'i> Parse<'i> for EasingFunction {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let location = input.current_source_location();
@@ -126,6 +135,11 @@ impl EasingFunction {
 
 /// A [step position](https://www.w3.org/TR/css-easing-1/#step-position), used within the `steps()` function.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "kebab-case")
+)]
 pub enum StepPosition {
   /// The first rise occurs at input progress value of 0.
   Start,
@@ -137,6 +151,9 @@ pub enum StepPosition {
   JumpBoth,
 }
 
+impl_visit!(StepPosition);
+impl_into_owned!(StepPosition);
+
 impl<'i> Parse<'i> for StepPosition {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let location = input.current_source_location();
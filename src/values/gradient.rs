@@ -9,16 +9,21 @@ use super::position::{HorizontalPositionKeyword, VerticalPositionKeyword};
 use super::position::{Position, PositionComponent};
 use crate::compat;
 use crate::error::{ParserError, PrinterError};
-use crate::macros::enum_property;
+use crate::macros::{enum_property, impl_into_owned, impl_visit};
 use crate::prefixes::Feature;
 use crate::printer::Printer;
 use crate::targets::Browsers;
-use crate::traits::{Parse, ToCss};
+use crate::traits::{Parse, ToCss, Visit, Visitor};
 use crate::vendor_prefix::VendorPrefix;
 use cssparser::*;
 
 /// A CSS [`<gradient>`](https://www.w3.org/TR/css-images-3/#gradients) value.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Gradient {
   /// A `linear-gradient()`, and its vendor prefix.
   Linear(LinearGradient, VendorPrefix),
@@ -36,6 +41,17 @@ pub enum Gradient {
   WebKitGradient(WebKitGradient),
 }
 
+impl_visit!(Gradient match {
+  Linear(g, p),
+  RepeatingLinear(g, p),
+  Radial(g, p),
+  RepeatingRadial(g, p),
+  Conic(g),
+  RepeatingConic(g),
+  WebKitGradient(g),
+});
+impl_into_owned!(Gradient);
+
 impl Gradient {
   /// Returns the vendor prefix of the gradient.
   pub fn get_vendor_prefix(&self) -> VendorPrefix {
@@ -194,6 +210,11 @@ impl ToCss for Gradient {
 
 /// A CSS [`linear-gradient()`](https://www.w3.org/TR/css-images-3/#linear-gradients) or `repeating-linear-gradient()`.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct LinearGradient {
   /// The direction of the gradient.
   pub direction: LineDirection,
@@ -201,6 +222,9 @@ pub struct LinearGradient {
   pub items: Vec<GradientItem<LengthPercentage>>,
 }
 
+impl_visit!(LinearGradient { direction, items });
+impl_into_owned!(LinearGradient);
+
 impl LinearGradient {
   fn parse<'i, 't>(
     input: &mut Parser<'i, 't>,
@@ -290,6 +314,11 @@ impl LinearGradient {
 
 /// A CSS [`radial-gradient()`](https://www.w3.org/TR/css-images-3/#radial-gradients) or `repeating-radial-gradient()`.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct RadialGradient {
   /// The shape of the gradient.
   pub shape: EndingShape,
@@ -299,6 +328,9 @@ pub struct RadialGradient {
   pub items: Vec<GradientItem<LengthPercentage>>,
 }
 
+impl_visit!(RadialGradient { shape, position, items });
+impl_into_owned!(RadialGradient);
+
 impl<'i> Parse<'i> for RadialGradient {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<RadialGradient, ParseError<'i, ParserError<'i>>> {
     let shape = input.try_parse(EndingShape::parse).ok();
@@ -360,6 +392,11 @@ impl RadialGradient {
 ///
 /// See [LinearGradient](LinearGradient).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum LineDirection {
   /// An angle.
   Angle(Angle),
@@ -371,6 +408,9 @@ pub enum LineDirection {
   Corner(HorizontalPositionKeyword, VerticalPositionKeyword),
 }
 
+impl_visit!(LineDirection);
+impl_into_owned!(LineDirection);
+
 impl LineDirection {
   fn parse<'i, 't>(
     input: &mut Parser<'i, 't>,
@@ -446,6 +486,11 @@ impl LineDirection {
 ///
 /// See [RadialGradient](RadialGradient).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum EndingShape {
   /// A circle.
   Circle(Circle),
@@ -453,6 +498,9 @@ pub enum EndingShape {
   Ellipse(Ellipse),
 }
 
+impl_visit!(EndingShape match { Circle(c), Ellipse(e) });
+impl_into_owned!(EndingShape);
+
 impl Default for EndingShape {
   fn default() -> EndingShape {
     EndingShape::Ellipse(Ellipse::Extent(ShapeExtent::FarthestCorner))
@@ -490,6 +538,11 @@ impl ToCss for EndingShape {
 ///
 /// See [RadialGradient](RadialGradient).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Circle {
   /// A circle with a specified radius.
   Radius(Length),
@@ -497,6 +550,9 @@ pub enum Circle {
   Extent(ShapeExtent),
 }
 
+impl_visit!(Circle match { Radius(r), Extent(_) });
+impl_into_owned!(Circle);
+
 impl<'i> Parse<'i> for Circle {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if let Ok(extent) = input.try_parse(ShapeExtent::parse) {
@@ -552,6 +608,11 @@ impl ToCss for Circle {
 ///
 /// See [RadialGradient](RadialGradient).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Ellipse {
   /// An ellipse with a specified horizontal and vertical radius.
   Size(LengthPercentage, LengthPercentage),
@@ -559,6 +620,9 @@ pub enum Ellipse {
   Extent(ShapeExtent),
 }
 
+impl_visit!(Ellipse match { Size(x, y), Extent(_) });
+impl_into_owned!(Ellipse);
+
 impl<'i> Parse<'i> for Ellipse {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if let Ok(extent) = input.try_parse(ShapeExtent::parse) {
@@ -631,6 +695,11 @@ enum_property! {
 
 /// A CSS [`conic-gradient()`](https://www.w3.org/TR/css-images-4/#conic-gradients) or `repeating-conic-gradient()`.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct ConicGradient {
   /// The angle of the gradient.
   pub angle: Angle,
@@ -640,6 +709,9 @@ pub struct ConicGradient {
   pub items: Vec<GradientItem<AnglePercentage>>,
 }
 
+impl_visit!(ConicGradient { angle, position, items });
+impl_into_owned!(ConicGradient);
+
 impl ConicGradient {
   fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let angle = input.try_parse(|input| {
@@ -706,6 +778,11 @@ impl ConicGradient {
 /// This type is generic, and may be either a [LengthPercentage](super::length::LengthPercentage)
 /// or [Angle](super::angle::Angle) depending on what type of gradient it is within.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct ColorStop<D> {
   /// The color of the color stop.
   pub color: CssColor,
@@ -713,6 +790,13 @@ pub struct ColorStop<D> {
   pub position: Option<D>,
 }
 
+impl<'i, D: Visit<'i>> Visit<'i> for ColorStop<D> {
+  fn visit_children<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    self.color.visit(visitor);
+    self.position.visit(visitor);
+  }
+}
+
 impl<'i, D: Parse<'i>> Parse<'i> for ColorStop<D> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let color = CssColor::parse(input)?;
@@ -740,6 +824,11 @@ impl<D: ToCss> ToCss for ColorStop<D> {
 /// This type is generic, and items may be either a [LengthPercentage](super::length::LengthPercentage)
 /// or [Angle](super::angle::Angle) depending on what type of gradient it is within.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum GradientItem<D> {
   /// A color stop.
   ColorStop(ColorStop<D>),
@@ -747,6 +836,15 @@ pub enum GradientItem<D> {
   Hint(D),
 }
 
+impl<'i, D: Visit<'i>> Visit<'i> for GradientItem<D> {
+  fn visit_children<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    match self {
+      GradientItem::ColorStop(stop) => stop.visit(visitor),
+      GradientItem::Hint(hint) => hint.visit(visitor),
+    }
+  }
+}
+
 impl<D: ToCss> ToCss for GradientItem<D> {
   fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
   where
@@ -884,6 +982,11 @@ where
 
 /// A legacy `-webkit-gradient()`.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum WebKitGradient {
   /// A linear `-webkit-gradient()`.
   Linear {
@@ -909,6 +1012,12 @@ pub enum WebKitGradient {
   },
 }
 
+impl_visit!(WebKitGradient match {
+  Linear { from: _, to: _, stops },
+  Radial { from: _, r0: _, to: _, r1: _, stops },
+});
+impl_into_owned!(WebKitGradient);
+
 impl<'i> Parse<'i> for WebKitGradient {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let location = input.current_source_location();
@@ -1023,6 +1132,11 @@ impl WebKitGradient {
 
 /// A color stop within a legacy `-webkit-gradient()`.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct WebKitColorStop {
   /// The color of the color stop.
   pub color: CssColor,
@@ -1030,6 +1144,9 @@ pub struct WebKitColorStop {
   pub position: CSSNumber,
 }
 
+impl_visit!(WebKitColorStop { color, position: _ });
+impl_into_owned!(WebKitColorStop);
+
 impl<'i> Parse<'i> for WebKitColorStop {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let location = input.current_source_location();
@@ -1083,6 +1200,11 @@ impl WebKitColorStop {
 
 /// An x/y position within a legacy `-webkit-gradient()`.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct WebKitGradientPoint {
   /// The x-position.
   pub x: WebKitGradientPointComponent<HorizontalPositionKeyword>,
@@ -1111,6 +1233,11 @@ impl ToCss for WebKitGradientPoint {
 
 /// A keyword or number within a [WebKitGradientPoint](WebKitGradientPoint).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum WebKitGradientPointComponent<S> {
   /// The `center` keyword.
   Center,
@@ -1,8 +1,11 @@
 //! CSS identifiers.
 
 use crate::error::{ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
+use crate::parser::ParserOptions;
 use crate::printer::Printer;
-use crate::traits::{Parse, ToCss};
+use crate::properties::css_modules::ComposesFrom;
+use crate::traits::{Parse, ToCss, Visit, Visitor};
 use crate::values::string::CowArcStr;
 use cssparser::*;
 use smallvec::SmallVec;
@@ -13,8 +16,19 @@ use smallvec::SmallVec;
 /// [CSS-wide keywords](https://www.w3.org/TR/css-values-4/#css-wide-keywords).
 /// They may be renamed to include a hash when compiled as part of a CSS module.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CustomIdent<'i>(pub CowArcStr<'i>);
 
+impl<'i> Visit<'i> for CustomIdent<'i> {
+  fn visit<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    visitor.visit_custom_ident(self)
+  }
+
+  fn visit_children<V: Visitor<'i>>(&mut self, _: &mut V) {}
+}
+
+impl_into_owned!(CustomIdent<'i> { 0 });
+
 impl<'i> Parse<'i> for CustomIdent<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let location = input.current_source_location();
@@ -49,8 +63,19 @@ pub type CustomIdentList<'i> = SmallVec<[CustomIdent<'i>; 1]>;
 /// Dashed idents are used in cases where an identifier can be either author defined _or_ CSS-defined.
 /// Author defined idents must start with two dash characters ("--") or parsing will fail.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DashedIdent<'i>(pub CowArcStr<'i>);
 
+impl<'i> Visit<'i> for DashedIdent<'i> {
+  fn visit<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    visitor.visit_dashed_ident(self)
+  }
+
+  fn visit_children<V: Visitor<'i>>(&mut self, _: &mut V) {}
+}
+
+impl_into_owned!(DashedIdent<'i> { 0 });
+
 impl<'i> Parse<'i> for DashedIdent<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let location = input.current_source_location();
@@ -68,6 +93,66 @@ impl<'i> ToCss for DashedIdent<'i> {
   where
     W: std::fmt::Write,
   {
-    dest.write_ident(&self.0)
+    dest.write_dashed_ident(&self.0)
+  }
+}
+
+/// A reference to a CSS [`<dashed-ident>`](https://www.w3.org/TR/css-values-4/#dashed-idents), e.g. within `var()`.
+///
+/// When the `dashed_idents` CSS modules option is enabled, the referenced name may be declared in another
+/// file, using the same `from` syntax as the `composes` property, e.g. `--foo from "colors.css"` or `--foo from global`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct DashedIdentReference<'i> {
+  /// The referenced identifier.
+  pub ident: DashedIdent<'i>,
+  /// Where the identifier is declared, if not in the current file.
+  pub from: Option<ComposesFrom<'i>>,
+}
+
+impl_visit!(DashedIdentReference<'i> { ident, from: _ });
+impl_into_owned!(DashedIdentReference<'i> { ident, from });
+
+impl<'i> DashedIdentReference<'i> {
+  /// Parses a dashed ident reference. The `from` syntax is only accepted if the
+  /// `dashed_idents` CSS modules option is enabled.
+  pub fn parse_with_options<'t>(
+    input: &mut Parser<'i, 't>,
+    options: &ParserOptions,
+  ) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let ident = DashedIdent::parse(input)?;
+
+    let from = match &options.css_modules {
+      Some(config) if config.dashed_idents => {
+        if input.try_parse(|input| input.expect_ident_matching("from")).is_ok() {
+          Some(ComposesFrom::parse(input)?)
+        } else {
+          None
+        }
+      }
+      _ => None,
+    };
+
+    Ok(DashedIdentReference { ident, from })
+  }
+}
+
+impl<'i> Parse<'i> for DashedIdentReference<'i> {
+  fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
+    let ident = DashedIdent::parse(input)?;
+    Ok(DashedIdentReference { ident, from: None })
+  }
+}
+
+impl<'i> ToCss for DashedIdentReference<'i> {
+  fn to_css<W>(&self, dest: &mut Printer<W>) -> Result<(), PrinterError>
+  where
+    W: std::fmt::Write,
+  {
+    dest.write_dashed_ident_reference(&self.ident.0, &self.from)
   }
 }
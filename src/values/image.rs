@@ -5,6 +5,7 @@ use super::gradient::*;
 use super::resolution::Resolution;
 use crate::dependencies::{Dependency, UrlDependency};
 use crate::error::{ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::prefixes::{is_webkit_gradient, Feature};
 use crate::printer::Printer;
 use crate::targets::Browsers;
@@ -17,6 +18,11 @@ use smallvec::SmallVec;
 
 /// A CSS [`<image>`](https://www.w3.org/TR/css-images-3/#image-values) value.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Image<'i> {
   /// The `none` keyword.
   None,
@@ -28,6 +34,9 @@ pub enum Image<'i> {
   ImageSet(ImageSet<'i>),
 }
 
+impl_visit!(Image<'i> match { None, Url(url), Gradient(gradient), ImageSet(image_set) });
+impl_into_owned!(Image<'i> match { None, Url(u), Gradient(b), ImageSet(i) });
+
 impl<'i> Default for Image<'i> {
   fn default() -> Image<'i> {
     Image::None
@@ -309,6 +318,11 @@ impl<'i> ToCss for Image<'i> {
 /// `image-set()` allows the user agent to choose between multiple versions of an image to
 /// display the most appropriate resolution or file type that it supports.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct ImageSet<'i> {
   /// The image options to choose from.
   pub options: Vec<ImageSetOption<'i>>,
@@ -316,6 +330,9 @@ pub struct ImageSet<'i> {
   pub vendor_prefix: VendorPrefix,
 }
 
+impl_visit!(ImageSet<'i> { options, vendor_prefix: _ });
+impl_into_owned!(ImageSet<'i> { options, vendor_prefix });
+
 impl<'i> ImageSet<'i> {
   /// Returns the vendor prefix for the `image-set()`.
   pub fn get_vendor_prefix(&self) -> VendorPrefix {
@@ -379,6 +396,11 @@ impl<'i> ToCss for ImageSet<'i> {
 
 /// An image option within the `image-set()` function. See [ImageSet](ImageSet).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct ImageSetOption<'i> {
   /// The image for this option.
   pub image: Image<'i>,
@@ -388,6 +410,9 @@ pub struct ImageSetOption<'i> {
   pub file_type: Option<CowArcStr<'i>>,
 }
 
+impl_visit!(ImageSetOption<'i> { image, resolution: _, file_type: _ });
+impl_into_owned!(ImageSetOption<'i> { image, resolution, file_type });
+
 impl<'i> Parse<'i> for ImageSetOption<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let loc = input.current_source_location();
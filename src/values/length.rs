@@ -4,8 +4,9 @@ use super::calc::Calc;
 use super::number::CSSNumber;
 use super::percentage::DimensionPercentage;
 use crate::error::{ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::printer::Printer;
-use crate::traits::{private::TryAdd, Parse, ToCss};
+use crate::traits::{private::TryAdd, Parse, ToCss, Visit, Visitor};
 use const_str;
 use cssparser::*;
 
@@ -37,6 +38,11 @@ impl LengthPercentage {
 
 /// Either a [`<length-percentage>`](https://www.w3.org/TR/css-values-4/#typedef-length-percentage), or the `auto` keyword.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum LengthPercentageOrAuto {
   /// The `auto` keyword.
   Auto,
@@ -44,6 +50,9 @@ pub enum LengthPercentageOrAuto {
   LengthPercentage(LengthPercentage),
 }
 
+impl_visit!(LengthPercentageOrAuto match { Auto, LengthPercentage(l) });
+impl_into_owned!(LengthPercentageOrAuto);
+
 impl<'i> Parse<'i> for LengthPercentageOrAuto {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if input.try_parse(|i| i.expect_ident_matching("auto")).is_ok() {
@@ -88,6 +97,11 @@ macro_rules! define_length_units {
     /// A CSS [`<length>`](https://www.w3.org/TR/css-values-4/#lengths) value,
     /// without support for `calc()`. See also: [Length](Length).
     #[derive(Debug, Clone, PartialEq)]
+    #[cfg_attr(
+      feature = "serde",
+      derive(serde::Serialize, serde::Deserialize),
+      serde(tag = "unit", content = "value", rename_all = "lowercase")
+    )]
     pub enum LengthValue {
       $(
         $(#[$meta])*
@@ -95,6 +109,14 @@ macro_rules! define_length_units {
       )+
     }
 
+    impl<'i> Visit<'i> for LengthValue {
+      fn visit<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+        visitor.visit_length(self)
+      }
+
+      fn visit_children<V: Visitor<'i>>(&mut self, _: &mut V) {}
+    }
+
     impl<'i> Parse<'i> for LengthValue {
       fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
         let location = input.current_source_location();
@@ -386,6 +408,11 @@ impl LengthValue {
 
 /// A CSS [`<length>`](https://www.w3.org/TR/css-values-4/#lengths) value, with support for `calc()`.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Length {
   /// An explicitly specified length value.
   Value(LengthValue),
@@ -393,6 +420,9 @@ pub enum Length {
   Calc(Box<Calc<Length>>),
 }
 
+impl_visit!(Length match { Value(v), Calc(c) });
+impl_into_owned!(Length);
+
 impl<'i> Parse<'i> for Length {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     match input.try_parse(Calc::parse) {
@@ -585,6 +615,11 @@ impl std::cmp::PartialOrd<Length> for Length {
 
 /// Either a [`<length>`](https://www.w3.org/TR/css-values-4/#lengths) or a [`<number>`](https://www.w3.org/TR/css-values-4/#numbers).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum LengthOrNumber {
   /// A length.
   Length(Length),
@@ -592,6 +627,9 @@ pub enum LengthOrNumber {
   Number(CSSNumber),
 }
 
+impl_visit!(LengthOrNumber match { Length(l), Number(_) });
+impl_into_owned!(LengthOrNumber);
+
 impl Default for LengthOrNumber {
   fn default() -> LengthOrNumber {
     LengthOrNumber::Number(0.0)
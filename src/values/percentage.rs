@@ -3,8 +3,9 @@
 use super::calc::Calc;
 use super::number::CSSNumber;
 use crate::error::{ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::printer::Printer;
-use crate::traits::{private::TryAdd, Parse, ToCss};
+use crate::traits::{private::TryAdd, Parse, ToCss, Visit, Visitor};
 use cssparser::*;
 
 /// A CSS [`<percentage>`](https://www.w3.org/TR/css-values-4/#percentages) value.
@@ -12,8 +13,12 @@ use cssparser::*;
 /// Percentages may be explicit or computed by `calc()`, but are always stored and serialized
 /// as their computed value.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Percentage(pub CSSNumber);
 
+impl_visit!(Percentage);
+impl_into_owned!(Percentage);
+
 impl<'i> Parse<'i> for Percentage {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     match input.try_parse(Calc::parse) {
@@ -111,6 +116,11 @@ impl std::cmp::PartialOrd<Percentage> for Percentage {
 
 /// Either a `<number>` or `<percentage>`.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum NumberOrPercentage {
   /// A percentage.
   Percentage(Percentage),
@@ -118,6 +128,9 @@ pub enum NumberOrPercentage {
   Number(CSSNumber),
 }
 
+impl_visit!(NumberOrPercentage);
+impl_into_owned!(NumberOrPercentage);
+
 impl<'i> Parse<'i> for NumberOrPercentage {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     if let Ok(number) = input.try_parse(CSSNumber::parse) {
@@ -167,6 +180,11 @@ impl std::convert::Into<CSSNumber> for &NumberOrPercentage {
 ///
 /// <https://drafts.csswg.org/css-values-4/#mixed-percentages>
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum DimensionPercentage<D> {
   /// An explicit dimension value.
   Dimension(D),
@@ -176,6 +194,18 @@ pub enum DimensionPercentage<D> {
   Calc(Box<Calc<DimensionPercentage<D>>>),
 }
 
+impl<'i, D: Visit<'i>> Visit<'i> for DimensionPercentage<D> {
+  fn visit_children<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    match self {
+      DimensionPercentage::Dimension(d) => d.visit(visitor),
+      DimensionPercentage::Percentage(_) => {}
+      DimensionPercentage::Calc(c) => c.visit(visitor),
+    }
+  }
+}
+
+impl_into_owned!(DimensionPercentage<D>);
+
 impl<
     'i,
     D: Parse<'i>
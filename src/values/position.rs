@@ -3,14 +3,19 @@
 use super::length::LengthPercentage;
 use super::percentage::Percentage;
 use crate::error::{ParserError, PrinterError};
-use crate::macros::enum_property;
+use crate::macros::{enum_property, impl_into_owned, impl_visit};
 use crate::printer::Printer;
-use crate::traits::{Parse, ToCss};
+use crate::traits::{Parse, ToCss, Visit, Visitor};
 use cssparser::*;
 
 /// A CSS [`<position>`](https://www.w3.org/TR/css3-values/#position) value,
 /// as used in the `background-position` property, gradients, masks, etc.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Position {
   /// The x-position.
   pub x: HorizontalPosition,
@@ -18,6 +23,9 @@ pub struct Position {
   pub y: VerticalPosition,
 }
 
+impl_visit!(Position { x, y });
+impl_into_owned!(Position);
+
 impl Position {
   /// Returns a `Position` with both the x and y set to `center`.
   pub fn center() -> Position {
@@ -236,6 +244,11 @@ impl ToCss for Position {
 ///
 /// This type is generic over side keywords.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum PositionComponent<S> {
   /// The `center` keyword.
   Center,
@@ -245,6 +258,18 @@ pub enum PositionComponent<S> {
   Side(S, Option<LengthPercentage>),
 }
 
+impl<'i, S> Visit<'i> for PositionComponent<S> {
+  fn visit_children<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    match self {
+      PositionComponent::Center => {}
+      PositionComponent::Length(l) => l.visit(visitor),
+      PositionComponent::Side(_, l) => l.visit(visitor),
+    }
+  }
+}
+
+impl_into_owned!(PositionComponent<S>);
+
 impl<S> PositionComponent<S> {
   fn is_center(&self) -> bool {
     match self {
@@ -2,6 +2,7 @@
 
 use super::number::CSSNumber;
 use crate::error::{ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::traits::{Parse, ToCss};
 use cssparser::*;
@@ -9,8 +10,12 @@ use cssparser::*;
 /// A CSS [`<ratio>`](https://www.w3.org/TR/css-values-4/#ratios) value,
 /// representing the ratio of two numeric values.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ratio(pub CSSNumber, pub CSSNumber);
 
+impl_visit!(Ratio);
+impl_into_owned!(Ratio);
+
 impl<'i> Parse<'i> for Ratio {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let first = CSSNumber::parse(input)?;
@@ -1,8 +1,9 @@
 //! Generic values for four sided properties.
 
 use crate::error::{ParserError, PrinterError};
+use crate::macros::impl_into_owned;
 use crate::printer::Printer;
-use crate::traits::{Parse, ToCss};
+use crate::traits::{Parse, ToCss, Visit, Visitor};
 use cssparser::*;
 
 /// A generic value that represents a value for four sides of a box,
@@ -11,6 +12,7 @@ use cssparser::*;
 /// When serialized, as few components as possible are written when
 /// there are duplicate values.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rect<T>(
   /// The top component.
   pub T,
@@ -22,6 +24,17 @@ pub struct Rect<T>(
   pub T,
 );
 
+impl<'i, T: Visit<'i>> Visit<'i> for Rect<T> {
+  fn visit_children<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    self.0.visit(visitor);
+    self.1.visit(visitor);
+    self.2.visit(visitor);
+    self.3.visit(visitor);
+  }
+}
+
+impl_into_owned!(Rect<T>);
+
 impl<T> Rect<T> {
   /// Returns a new `Rect<T>` value.
   pub fn new(first: T, second: T, third: T, fourth: T) -> Self {
@@ -3,12 +3,18 @@
 use super::length::serialize_dimension;
 use super::number::CSSNumber;
 use crate::error::{ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::traits::{Parse, ToCss};
 use cssparser::*;
 
 /// A CSS [`<resolution>`](https://www.w3.org/TR/css-values-4/#resolution) value.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Resolution {
   /// A resolution in dots per inch.
   Dpi(CSSNumber),
@@ -18,6 +24,9 @@ pub enum Resolution {
   Dppx(CSSNumber),
 }
 
+impl_visit!(Resolution);
+impl_into_owned!(Resolution);
+
 impl<'i> Parse<'i> for Resolution {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     // TODO: calc?
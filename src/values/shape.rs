@@ -4,7 +4,7 @@ use super::length::LengthPercentage;
 use super::position::Position;
 use super::rect::Rect;
 use crate::error::{ParserError, PrinterError};
-use crate::macros::enum_property;
+use crate::macros::{enum_property, impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::properties::border_radius::BorderRadius;
 use crate::traits::{Parse, ToCss};
@@ -12,6 +12,11 @@ use cssparser::*;
 
 /// A CSS [`<basic-shape>`](https://www.w3.org/TR/css-shapes-1/#basic-shape-functions) value.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum BasicShape {
   /// An inset rectangle.
   Inset(InsetRect),
@@ -23,8 +28,16 @@ pub enum BasicShape {
   Polygon(Polygon),
 }
 
+impl_visit!(BasicShape match { Inset(r), Circle(c), Ellipse(e), Polygon(p) });
+impl_into_owned!(BasicShape);
+
 /// An [`inset()`](https://www.w3.org/TR/css-shapes-1/#funcdef-inset) rectangle shape.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct InsetRect {
   /// The rectangle.
   pub rect: Rect<LengthPercentage>,
@@ -32,8 +45,16 @@ pub struct InsetRect {
   pub radius: BorderRadius,
 }
 
+impl_visit!(InsetRect { rect, radius });
+impl_into_owned!(InsetRect);
+
 /// A [`circle()`](https://www.w3.org/TR/css-shapes-1/#funcdef-circle) shape.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Circle {
   /// The radius of the circle.
   pub radius: ShapeRadius,
@@ -41,9 +62,17 @@ pub struct Circle {
   pub position: Position,
 }
 
+impl_visit!(Circle { radius, position });
+impl_into_owned!(Circle);
+
 /// A [`<shape-radius>`](https://www.w3.org/TR/css-shapes-1/#typedef-shape-radius) value
 /// that defines the radius of a `circle()` or `ellipse()` shape.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum ShapeRadius {
   /// An explicit length or percentage.
   LengthPercentage(LengthPercentage),
@@ -53,8 +82,16 @@ pub enum ShapeRadius {
   FarthestSide,
 }
 
+impl_visit!(ShapeRadius match { LengthPercentage(l), ClosestSide, FarthestSide });
+impl_into_owned!(ShapeRadius);
+
 /// An [`ellipse()`](https://www.w3.org/TR/css-shapes-1/#funcdef-ellipse) shape.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Ellipse {
   /// The x-radius of the ellipse.
   pub radius_x: ShapeRadius,
@@ -64,8 +101,20 @@ pub struct Ellipse {
   pub position: Position,
 }
 
+impl_visit!(Ellipse {
+  radius_x,
+  radius_y,
+  position
+});
+impl_into_owned!(Ellipse);
+
 /// A [`polygon()`](https://www.w3.org/TR/css-shapes-1/#funcdef-polygon) shape.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Polygon {
   /// The fill rule used to determine the interior of the polygon.
   pub fill_rule: FillRule,
@@ -73,10 +122,18 @@ pub struct Polygon {
   pub points: Vec<Point>,
 }
 
+impl_visit!(Polygon { fill_rule: _, points });
+impl_into_owned!(Polygon);
+
 /// A point within a `polygon()` shape.
 ///
 /// See [Polygon](Polygon).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Point {
   /// The x position of the point.
   x: LengthPercentage,
@@ -84,6 +141,9 @@ pub struct Point {
   y: LengthPercentage,
 }
 
+impl_visit!(Point { x, y });
+impl_into_owned!(Point);
+
 enum_property! {
   /// A [`<fill-rule>`](https://www.w3.org/TR/css-shapes-1/#typedef-fill-rule) used to
   /// determine the interior of a `polygon()` shape.
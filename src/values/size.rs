@@ -1,16 +1,27 @@
 //! Generic values for two component properties.
 
 use crate::error::{ParserError, PrinterError};
+use crate::macros::impl_into_owned;
 use crate::printer::Printer;
-use crate::traits::{Parse, ToCss};
+use crate::traits::{Parse, ToCss, Visit, Visitor};
 use cssparser::*;
 
 /// A generic value that represents a value with two components, e.g. a border radius.
 ///
 /// When serialized, only a single component will be written if both are equal.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Size2D<T>(pub T, pub T);
 
+impl<'i, T: Visit<'i>> Visit<'i> for Size2D<T> {
+  fn visit_children<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    self.0.visit(visitor);
+    self.1.visit(visitor);
+  }
+}
+
+impl_into_owned!(Size2D<T>);
+
 impl<'i, T> Parse<'i> for Size2D<T>
 where
   T: Parse<'i> + Clone,
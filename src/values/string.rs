@@ -1,5 +1,6 @@
 //! Types used to represent strings.
 
+use crate::traits::IntoOwned;
 use cssparser::CowRcStr;
 use serde::{Serialize, Serializer};
 use std::borrow::Borrow;
@@ -139,6 +140,26 @@ impl<'a> Drop for CowArcStr<'a> {
   }
 }
 
+impl<'a, 'any> IntoOwned<'any> for CowArcStr<'a> {
+  type Owned = CowArcStr<'any>;
+
+  /// Copies a borrowed string into a shared one. Strings that are already shared are not copied.
+  fn into_owned(self) -> Self::Owned {
+    match self.unpack() {
+      Ok(s) => CowArcStr::from(s.to_owned()),
+      Err(_) => {
+        let ptr = self.ptr;
+        std::mem::forget(self);
+        CowArcStr {
+          ptr,
+          borrowed_len_or_max: usize::MAX,
+          phantom: PhantomData,
+        }
+      }
+    }
+  }
+}
+
 impl<'a> Deref for CowArcStr<'a> {
   type Target = str;
 
@@ -220,3 +241,15 @@ impl<'a> Serialize for CowArcStr<'a> {
     self.as_ref().serialize(serializer)
   }
 }
+
+// Deserialized strings are always owned, so that they can be used with any lifetime
+// rather than being tied to the lifetime of the deserializer's input.
+#[cfg(feature = "serde")]
+impl<'a, 'de> serde::Deserialize<'de> for CowArcStr<'a> {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: serde::Deserializer<'de>,
+  {
+    String::deserialize(deserializer).map(CowArcStr::from)
+  }
+}
@@ -3,6 +3,7 @@
 use super::number::{CSSInteger, CSSNumber};
 use super::string::CowArcStr;
 use crate::error::{ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::traits::{Parse, ToCss};
 use crate::values;
@@ -11,6 +12,11 @@ use cssparser::*;
 /// A CSS [syntax string](https://drafts.css-houdini.org/css-properties-values-api/#syntax-strings)
 /// used to define the grammar for a registered custom property.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum SyntaxString {
   /// A list of syntax components.
   Components(Vec<SyntaxComponent>),
@@ -18,12 +24,19 @@ pub enum SyntaxString {
   Universal,
 }
 
+impl_into_owned!(SyntaxString);
+
 /// A [syntax component](https://drafts.css-houdini.org/css-properties-values-api/#syntax-component)
 /// within a [SyntaxString](SyntaxString).
 ///
 /// A syntax component consists of a component kind an a multiplier, which indicates how the component
 /// may repeat during parsing.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct SyntaxComponent {
   /// The kind of component.
   pub kind: SyntaxComponentKind,
@@ -33,6 +46,11 @@ pub struct SyntaxComponent {
 
 /// A [syntax component component name](https://drafts.css-houdini.org/css-properties-values-api/#supported-names).
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum SyntaxComponentKind {
   /// A `<length>` component.
   Length,
@@ -69,6 +87,11 @@ pub enum SyntaxComponentKind {
 /// A [multiplier](https://drafts.css-houdini.org/css-properties-values-api/#multipliers) for a
 /// [SyntaxComponent](SyntaxComponent). Indicates whether and how the component may be repeated.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "kebab-case")
+)]
 pub enum Multiplier {
   /// The component may not be repeated.
   None,
@@ -78,8 +101,16 @@ pub enum Multiplier {
   Comma,
 }
 
+impl_visit!(Multiplier);
+impl_into_owned!(Multiplier);
+
 /// A parsed value for a [SyntaxComponent](SyntaxComponent).
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum ParsedComponent<'i> {
   /// A `<length>` value.
   Length(values::length::Length),
@@ -117,6 +148,45 @@ pub enum ParsedComponent<'i> {
   Token(crate::properties::custom::Token<'i>),
 }
 
+impl_visit!(ParsedComponent<'i> match {
+  Length(v),
+  Number(_),
+  Percentage(_),
+  LengthPercentage(v),
+  Color(v),
+  Image(v),
+  Url(v),
+  Integer(_),
+  Angle(_),
+  Time(_),
+  Resolution(_),
+  TransformFunction(v),
+  TransformList(v),
+  CustomIdent(v),
+  Literal(_),
+  Repeated(v, m),
+  Token(_),
+});
+impl_into_owned!(ParsedComponent<'i> match {
+  Length(l),
+  Number(c),
+  Percentage(p),
+  LengthPercentage(l),
+  Color(c),
+  Image(i),
+  Url(u),
+  Integer(c),
+  Angle(a),
+  Time(t),
+  Resolution(r),
+  TransformFunction(t),
+  TransformList(t),
+  CustomIdent(c),
+  Literal(c),
+  Repeated(v, m),
+  Token(t),
+});
+
 impl<'i> SyntaxString {
   /// Parses a syntax string.
   pub fn parse_string(input: &'i str) -> Result<SyntaxString, ()> {
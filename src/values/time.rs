@@ -3,6 +3,7 @@
 use super::calc::Calc;
 use super::number::CSSNumber;
 use crate::error::{ParserError, PrinterError};
+use crate::macros::{impl_into_owned, impl_visit};
 use crate::printer::Printer;
 use crate::traits::{Parse, ToCss};
 use cssparser::*;
@@ -13,6 +14,11 @@ use cssparser::*;
 /// Time values may be explicit or computed by `calc()`, but are always stored and serialized
 /// as their computed value.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", content = "value", rename_all = "kebab-case")
+)]
 pub enum Time {
   /// A time in seconds.
   Seconds(CSSNumber),
@@ -20,6 +26,9 @@ pub enum Time {
   Milliseconds(CSSNumber),
 }
 
+impl_visit!(Time);
+impl_into_owned!(Time);
+
 impl Time {
   /// Returns the time in milliseconds.
   pub fn to_ms(&self) -> CSSNumber {
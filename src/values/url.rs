@@ -2,20 +2,37 @@
 
 use crate::dependencies::{Dependency, UrlDependency};
 use crate::error::{ParserError, PrinterError};
+use crate::macros::impl_into_owned;
 use crate::printer::Printer;
-use crate::traits::{Parse, ToCss};
+use crate::traits::{Parse, ToCss, Visit, Visitor};
 use crate::values::string::CowArcStr;
 use cssparser::*;
 
 /// A CSS [url()](https://www.w3.org/TR/css-values-4/#urls) value and its source location.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Url<'i> {
   /// The url string.
   pub url: CowArcStr<'i>,
   /// The location where the `url()` was seen in the CSS source file.
+  #[cfg_attr(feature = "serde", serde(with = "crate::serialization::SourceLocationDef"))]
   pub loc: SourceLocation,
 }
 
+impl<'i> Visit<'i> for Url<'i> {
+  fn visit<V: Visitor<'i>>(&mut self, visitor: &mut V) {
+    visitor.visit_url(self)
+  }
+
+  fn visit_children<V: Visitor<'i>>(&mut self, _: &mut V) {}
+}
+
+impl_into_owned!(Url<'i> { url, loc });
+
 impl<'i> Parse<'i> for Url<'i> {
   fn parse<'t>(input: &mut Parser<'i, 't>) -> Result<Self, ParseError<'i, ParserError<'i>>> {
     let loc = input.current_source_location();
@@ -3,6 +3,7 @@
 #![allow(non_upper_case_globals)]
 
 use crate::error::PrinterError;
+use crate::macros::impl_bitflags_serde;
 use crate::printer::Printer;
 use crate::traits::ToCss;
 use bitflags::bitflags;
@@ -29,6 +30,14 @@ bitflags! {
   }
 }
 
+impl_bitflags_serde!(VendorPrefix {
+  "none": None,
+  "webkit": WebKit,
+  "moz": Moz,
+  "ms": Ms,
+  "o": O,
+});
+
 impl Default for VendorPrefix {
   fn default() -> VendorPrefix {
     VendorPrefix::None
@@ -62,3 +62,21 @@ let res = css.transform({
 console.log(res.code.toString());
 console.log(res.exports);
 console.log(require('util').inspect(res.dependencies, { colors: true, depth: 50 }));
+
+const os = require('os');
+const path = require('path');
+const assert = require('assert');
+let dir = fs.mkdtempSync(path.join(os.tmpdir(), 'parcel-css-'));
+fs.writeFileSync(path.join(dir, 'a.css'), '@import "b.css" print;\n.a { color: red }');
+fs.writeFileSync(path.join(dir, 'b.css'), '.b { color: green }');
+
+let bundled = css.bundle({
+  filename: path.join(dir, 'a.css'),
+  graph: true
+});
+
+assert.deepStrictEqual(bundled.graph.files.map(file => path.basename(file.path)), ['a.css', 'b.css']);
+assert.strictEqual(bundled.graph.files[1].conditions.media, 'print');
+assert.deepStrictEqual(bundled.graph.order, [1, 0]);
+assert.strictEqual(css.bundle({filename: path.join(dir, 'a.css')}).graph, null);
+fs.rmSync(dir, {recursive: true});
@@ -278,6 +278,82 @@ fn css_modules_output_target_option() -> Result<(), Box<dyn std::error::Error>>
   Ok(())
 }
 
+#[test]
+fn css_modules_pattern_option() -> Result<(), Box<dyn std::error::Error>> {
+  let (input, _, _) = css_module_test_vals();
+  let infile = assert_fs::NamedTempFile::new("test.css")?;
+  let outfile = assert_fs::NamedTempFile::new("out.css")?;
+  infile.write_str(&input)?;
+  let mut cmd = Command::cargo_bin("parcel_css")?;
+  cmd.current_dir(infile.path().parent().unwrap());
+  cmd.arg(infile.path());
+  cmd.arg("-o").arg(outfile.path());
+  cmd.arg("--css-modules");
+  cmd.arg("--css-modules-pattern").arg("[name]__[local]");
+  cmd.assert().success();
+
+  outfile.assert(predicate::str::contains(".test__foo {"));
+  outfile.assert(predicate::str::contains("animation: test__test 2s;"));
+
+  let mut cmd = Command::cargo_bin("parcel_css")?;
+  cmd.arg(infile.path());
+  cmd.arg("-o").arg(outfile.path());
+  cmd.arg("--css-modules");
+  cmd.arg("--css-modules-pattern").arg("[name]__[hash]");
+  cmd
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("the [local] placeholder is required"));
+
+  Ok(())
+}
+
+#[test]
+fn css_modules_dts_option() -> Result<(), Box<dyn std::error::Error>> {
+  let infile = assert_fs::NamedTempFile::new("test.css")?;
+  let outfile = assert_fs::NamedTempFile::new("out.css")?;
+  infile.write_str(
+    r#"
+    .foo-bar {
+      color: red;
+    }
+
+    .baz {
+      composes: foo-bar;
+    }
+  "#,
+  )?;
+  let mut cmd = Command::cargo_bin("parcel_css")?;
+  cmd.current_dir(infile.path().parent().unwrap());
+  cmd.arg(infile.path());
+  cmd.arg("-o").arg(outfile.path());
+  cmd.arg("--css-modules");
+  cmd.arg("--css-modules-pattern").arg("[local]");
+  cmd.arg("--css-modules-dts");
+  cmd.arg("--css-modules-camel-case");
+  cmd.assert().success();
+
+  let dts = std::fs::read_to_string(format!("{}.d.ts", outfile.path().display()))?;
+  assert_eq!(
+    dts,
+    indoc! {r#"
+      declare const styles: {
+        /** Composes `foo-bar`. */
+        readonly "baz": string;
+        readonly "foo-bar": string;
+        readonly "fooBar": string;
+      };
+      export default styles;
+    "#}
+  );
+
+  let json: serde_json::Value =
+    serde_json::from_str(&std::fs::read_to_string(outfile.path().with_extension("json"))?)?;
+  assert_eq!(json["fooBar"], json["foo-bar"]);
+
+  Ok(())
+}
+
 #[test]
 fn sourcemap() -> Result<(), Box<dyn std::error::Error>> {
   let (input, _, _) = css_module_test_vals();
@@ -331,3 +407,46 @@ fn targets() -> Result<(), Box<dyn std::error::Error>> {
 
   Ok(())
 }
+
+#[test]
+fn watch_option() -> Result<(), Box<dyn std::error::Error>> {
+  let dir = assert_fs::TempDir::new()?;
+  let infile = dir.child("test.css");
+  infile.write_str(r#"@import "colors.css"; .a { color: red }"#)?;
+  let colors = dir.child("colors.css");
+  colors.write_str(".b { color: green }")?;
+  let outfile = dir.child("out.css");
+
+  let mut child = Command::cargo_bin("parcel_css")?
+    .arg(infile.path())
+    .arg("--bundle")
+    .arg("--watch")
+    .arg("--minify")
+    .arg("--output-file")
+    .arg(outfile.path())
+    .stderr(std::process::Stdio::null())
+    .spawn()?;
+
+  // Waits for the output file to contain the expected code, polling it until a timeout.
+  let wait_for = |expected: &str| {
+    let start = std::time::Instant::now();
+    while start.elapsed() < std::time::Duration::from_secs(10) {
+      if std::fs::read_to_string(outfile.path()).map_or(false, |code| code == expected) {
+        return true;
+      }
+      std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    false
+  };
+
+  let built = wait_for(".b{color:green}.a{color:red}");
+  // Imported files are watched as well, so changing them rewrites the output.
+  colors.write_str(".b { color: yellow; background: blue }")?;
+  let rebuilt = built && wait_for(".b{color:#ff0;background:#00f}.a{color:red}");
+  child.kill()?;
+  child.wait()?;
+  assert!(built, "the output was not written");
+  assert!(rebuilt, "the output was not rewritten after a change");
+
+  Ok(())
+}